@@ -12,7 +12,7 @@ fn benchmark_agent_creation(c: &mut Criterion) {
             world.register::<agent::components::Skills>();
             for _ in 0..100 {
                 world.create_entity()
-                    .with(agent::components::Needs { thirst: black_box(0.5), hunger: black_box(0.8) })
+                    .with(agent::components::Needs { thirst: black_box(0.5), hunger: black_box(0.8), tiredness: black_box(0.2) })
                     .with(agent::components::Inventory { items: std::collections::HashMap::new() })
                     .with(agent::components::Wallet { currency: black_box(100.0) })
                     .with(agent::components::Skills { skills: std::collections::HashMap::new() })
@@ -22,5 +22,27 @@ fn benchmark_agent_creation(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, benchmark_agent_creation);
+fn benchmark_snapshot(c: &mut Criterion) {
+    let mut world = World::new();
+    register_core_components(&mut world);
+    create_agents(&mut world, 1000, &AgentTemplate::default());
+
+    c.bench_function("snapshot 1000 agents to JSON", |b| {
+        b.iter(|| world_to_json(black_box(&world)))
+    });
+    c.bench_function("snapshot 1000 agents to bytes", |b| {
+        b.iter(|| world_to_bytes(black_box(&world)))
+    });
+
+    let json = world_to_json(&world);
+    let bytes = world_to_bytes(&world);
+    c.bench_function("restore 1000 agents from JSON", |b| {
+        b.iter(|| world_from_json(black_box(&json)).unwrap())
+    });
+    c.bench_function("restore 1000 agents from bytes", |b| {
+        b.iter(|| world_from_bytes(black_box(&bytes)).unwrap())
+    });
+}
+
+criterion_group!(benches, benchmark_agent_creation, benchmark_snapshot);
 criterion_main!(benches);
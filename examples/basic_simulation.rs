@@ -37,7 +37,7 @@ fn main() {
 	}
 
 	// Create agent with custom needs (very thirsty)
-	let agent2 = create_agent_with_needs(&mut world, Needs::new(90.0, 40.0));
+	let agent2 = create_agent_with_needs(&mut world, Needs::new(90.0, 40.0, 50.0));
 	println!("\nCreated agent2 (thirsty): {:?}", agent2);
 	{
 		let agents = world.read_storage::<Agent>();
@@ -62,7 +62,7 @@ fn main() {
 	custom_inventory.add("food", 3);
 	let agent4 = create_agent_custom(
 		&mut world,
-		Needs::new(20.0, 30.0), // satisfied
+		Needs::new(20.0, 30.0, 50.0), // satisfied
 		custom_inventory,
 		Wallet::new(500.0),
 	);
@@ -87,7 +87,7 @@ fn main() {
 	};
 	let test_agent = world.create_entity()
 		.with(Agent { id: test_id })
-		.with(Needs::new(-1.0, 200.0)) // intentionally out of bounds
+		.with(Needs::new(-1.0, 200.0, 50.0)) // intentionally out of bounds
 		.with(Inventory::default())
 		.with(Wallet::new(-10.0)) // negative becomes zero
 		.with(Skills::default())
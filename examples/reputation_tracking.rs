@@ -147,7 +147,7 @@ fn main() {
 
     // Process all transactions
     println!("\n3. Processing reputation updates...");
-    let mut reputation_system = ReputationUpdateSystem;
+    let mut reputation_system = ReputationUpdateSystem::default();
     reputation_system.run_now(&world);
     world.maintain();
 
@@ -132,12 +132,12 @@ fn setup_world_and_agents() -> (World, GridWorld) {
     println!("Creating agents...");
 
     // Agent 1: Very thirsty agent at (5, 5)
-    let agent1 = create_agent_with_needs(&mut world, Needs::new(90.0, 30.0));
+    let agent1 = create_agent_with_needs(&mut world, Needs::new(90.0, 30.0, 50.0));
     grid_world.place_agent(agent1, 5, 5);
     println!("  Agent 1 created at (5, 5) - Very thirsty!");
 
     // Agent 2: Hungry agent at (8, 8)
-    let agent2 = create_agent_with_needs(&mut world, Needs::new(20.0, 85.0));
+    let agent2 = create_agent_with_needs(&mut world, Needs::new(20.0, 85.0, 50.0));
     grid_world.place_agent(agent2, 8, 8);
     println!("  Agent 2 created at (8, 8) - Very hungry!");
 
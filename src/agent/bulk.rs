@@ -0,0 +1,288 @@
+//! Bulk agent creation
+//! Spawns many agents from one shared template with a single id allocation and maintain
+
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+use super::components::{
+    Agent, Inventory, NeedDecayRates, Needs, ReputationKnowledge, Skills, SpeciesComponent, Wallet,
+};
+use super::creation::{
+    CreationError, DEFAULT_CURRENCY, DEFAULT_HUNGER, DEFAULT_THIRST, DEFAULT_TIREDNESS,
+};
+use super::identity::AgentIdAllocator;
+
+/// Starting state shared by agents created with [`create_agents`]
+///
+/// Every agent gets its own clone of each component. Optional components are
+/// only added when set, and their storages only need registering then.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let mut inventory = Inventory::default();
+/// inventory.add("food", 2);
+/// let template = AgentTemplate::default()
+///     .with_inventory(inventory)
+///     .with_species(SpeciesComponent::rabbit());
+/// assert_eq!(template.wallet.currency, 100.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentTemplate {
+    pub needs: Needs,
+    pub inventory: Inventory,
+    pub wallet: Wallet,
+    pub species: Option<SpeciesComponent>,
+    pub skills: Option<Skills>,
+    pub need_decay_rates: Option<NeedDecayRates>,
+    pub reputation: Option<ReputationKnowledge>,
+}
+
+impl Default for AgentTemplate {
+    fn default() -> Self {
+        Self {
+            needs: Needs::new(DEFAULT_THIRST, DEFAULT_HUNGER, DEFAULT_TIREDNESS),
+            inventory: Inventory::default(),
+            wallet: Wallet::new(DEFAULT_CURRENCY),
+            species: None,
+            skills: None,
+            need_decay_rates: None,
+            reputation: None,
+        }
+    }
+}
+
+impl AgentTemplate {
+    /// Set starting needs
+    pub fn with_needs(mut self, needs: Needs) -> Self {
+        self.needs = needs;
+        self
+    }
+
+    /// Set the starting inventory
+    pub fn with_inventory(mut self, inventory: Inventory) -> Self {
+        self.inventory = inventory;
+        self
+    }
+
+    /// Set the starting wallet
+    pub fn with_wallet(mut self, wallet: Wallet) -> Self {
+        self.wallet = wallet;
+        self
+    }
+
+    /// Add a `SpeciesComponent`
+    pub fn with_species(mut self, species: SpeciesComponent) -> Self {
+        self.species = Some(species);
+        self
+    }
+
+    /// Add `Skills`
+    pub fn with_skills(mut self, skills: Skills) -> Self {
+        self.skills = Some(skills);
+        self
+    }
+
+    /// Add `NeedDecayRates`
+    pub fn with_need_decay_rates(mut self, rates: NeedDecayRates) -> Self {
+        self.need_decay_rates = Some(rates);
+        self
+    }
+
+    /// Add a `ReputationKnowledge`
+    pub fn with_reputation(mut self, reputation: ReputationKnowledge) -> Self {
+        self.reputation = Some(reputation);
+        self
+    }
+}
+
+fn ensure_registered<T: Component>(world: &World, name: &'static str) -> Result<(), CreationError> {
+    if world.has_value::<MaskedStorage<T>>() {
+        Ok(())
+    } else {
+        Err(CreationError::MissingComponentRegistration(name))
+    }
+}
+
+/// Insert `value` for every entity if the template sets it
+fn insert_all<T: Component + Clone>(world: &World, entities: &[Entity], value: &Option<T>) {
+    if let Some(value) = value {
+        let mut storage = world.write_storage::<T>();
+        for &entity in entities {
+            // Entities were just created, so insertion cannot fail
+            let _ = storage.insert(entity, value.clone());
+        }
+    }
+}
+
+/// Create `count` agents from a template
+///
+/// # Panics
+/// Panics if the `AgentIdAllocator` resource is missing, a needed component
+/// is not registered, or ids run out. Use [`try_create_agents`] to get an
+/// error instead.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// register_core_components(&mut world);
+///
+/// let agents = create_agents(&mut world, 100, &AgentTemplate::default());
+/// assert_eq!(agents.len(), 100);
+/// assert_eq!(world.read_storage::<Agent>().join().count(), 100);
+/// ```
+pub fn create_agents(world: &mut World, count: usize, template: &AgentTemplate) -> Vec<Entity> {
+    try_create_agents(world, count, template).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Create `count` agents from a template, without panicking
+///
+/// Ids are allocated in one batch and the world is maintained once at the
+/// end. All checks run before anything is created, so a failed call leaves
+/// the world unchanged.
+///
+/// # Errors
+/// [`CreationError::MissingResource`], [`CreationError::MissingComponentRegistration`]
+/// or [`CreationError::IdExhausted`]
+pub fn try_create_agents(
+    world: &mut World,
+    count: usize,
+    template: &AgentTemplate,
+) -> Result<Vec<Entity>, CreationError> {
+    ensure_registered::<Agent>(world, "Agent")?;
+    ensure_registered::<Needs>(world, "Needs")?;
+    ensure_registered::<Inventory>(world, "Inventory")?;
+    ensure_registered::<Wallet>(world, "Wallet")?;
+    if template.species.is_some() {
+        ensure_registered::<SpeciesComponent>(world, "SpeciesComponent")?;
+    }
+    if template.skills.is_some() {
+        ensure_registered::<Skills>(world, "Skills")?;
+    }
+    if template.need_decay_rates.is_some() {
+        ensure_registered::<NeedDecayRates>(world, "NeedDecayRates")?;
+    }
+    if template.reputation.is_some() {
+        ensure_registered::<ReputationKnowledge>(world, "ReputationKnowledge")?;
+    }
+
+    let ids = world
+        .try_fetch_mut::<AgentIdAllocator>()
+        .ok_or(CreationError::MissingResource("AgentIdAllocator"))?
+        .allocate_many(count)?;
+
+    let entities: Vec<Entity> = {
+        let all = world.entities();
+        ids.iter().map(|_| all.create()).collect()
+    };
+
+    {
+        let mut agents = world.write_storage::<Agent>();
+        let mut needs = world.write_storage::<Needs>();
+        let mut inventories = world.write_storage::<Inventory>();
+        let mut wallets = world.write_storage::<Wallet>();
+        for (&entity, &id) in entities.iter().zip(&ids) {
+            // Entities were just created, so insertion cannot fail
+            let _ = agents.insert(entity, Agent { id });
+            let _ = needs.insert(entity, template.needs.clone());
+            let _ = inventories.insert(entity, template.inventory.clone());
+            let _ = wallets.insert(entity, template.wallet.clone());
+        }
+    }
+    insert_all(world, &entities, &template.species);
+    insert_all(world, &entities, &template.skills);
+    insert_all(world, &entities, &template.need_decay_rates);
+    insert_all(world, &entities, &template.reputation);
+
+    world.maintain();
+    Ok(entities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::registration::register_core_components;
+    use crate::agent::identity::AgentId;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_create_thousand_agents_from_template() {
+        let mut world = World::new();
+        register_core_components(&mut world);
+        world.register::<SpeciesComponent>();
+
+        let mut inventory = Inventory::default();
+        inventory.add("food", 3);
+        let template = AgentTemplate::default()
+            .with_needs(Needs::new(10.0, 20.0, 30.0))
+            .with_inventory(inventory)
+            .with_wallet(Wallet::new(7.0))
+            .with_species(SpeciesComponent::human());
+
+        let entities = create_agents(&mut world, 1000, &template);
+        assert_eq!(entities.len(), 1000);
+
+        let agents = world.read_storage::<Agent>();
+        let ids: HashSet<AgentId> = entities.iter().map(|e| agents.get(*e).unwrap().id).collect();
+        assert_eq!(ids.len(), 1000);
+
+        let needs = world.read_storage::<Needs>();
+        let inventories = world.read_storage::<Inventory>();
+        let wallets = world.read_storage::<Wallet>();
+        let species = world.read_storage::<SpeciesComponent>();
+        for &entity in &entities {
+            assert!(world.entities().is_alive(entity));
+            assert_eq!(needs.get(entity), Some(&template.needs));
+            assert_eq!(inventories.get(entity), Some(&template.inventory));
+            assert_eq!(wallets.get(entity), Some(&template.wallet));
+            assert_eq!(species.get(entity), Some(&SpeciesComponent::human()));
+        }
+    }
+
+    #[test]
+    fn test_template_inventory_is_cloned_per_agent() {
+        let mut world = World::new();
+        register_core_components(&mut world);
+        let entities = create_agents(&mut world, 2, &AgentTemplate::default());
+
+        world
+            .write_storage::<Inventory>()
+            .get_mut(entities[0])
+            .unwrap()
+            .add("wood", 5);
+
+        let inventories = world.read_storage::<Inventory>();
+        assert_eq!(inventories.get(entities[0]).unwrap().quantity("wood"), 5);
+        assert_eq!(inventories.get(entities[1]).unwrap().quantity("wood"), 0);
+    }
+
+    #[test]
+    fn test_bulk_ids_continue_allocator_sequence() {
+        let mut world = World::new();
+        register_core_components(&mut world);
+        crate::agent::creation::create_agent(&mut world);
+        create_agents(&mut world, 3, &AgentTemplate::default());
+
+        assert_eq!(world.read_resource::<AgentIdAllocator>().peek(), AgentId(5));
+    }
+
+    #[test]
+    fn test_unregistered_optional_component_leaves_world_unchanged() {
+        let mut world = World::new();
+        register_core_components(&mut world);
+        let template = AgentTemplate::default().with_skills(Skills::default());
+
+        assert_eq!(
+            try_create_agents(&mut world, 10, &template),
+            Err(CreationError::MissingComponentRegistration("Skills"))
+        );
+        assert_eq!(world.read_storage::<Agent>().join().count(), 0);
+        assert_eq!(world.read_resource::<AgentIdAllocator>().peek(), AgentId(1));
+    }
+}
@@ -1,6 +1,10 @@
 //! Agent ECS components
-use specs::prelude::{Component, VecStorage};
+use specs::prelude::{Component, Entities, Join, Read, ReadStorage, System, VecStorage, WriteStorage};
 use super::identity::AgentId;
+use super::overflow::{OverflowBuffer, OverflowConfig};
+use crate::systems::OptionalWriteStorage;
+use super::currency::CurrencyAmount;
+use crate::items::{ItemRegistry, NeedType};
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
@@ -9,9 +13,19 @@ pub const MAX_NEEDS: f32 = 100.0;
 /// Minimum bound for needs values
 pub const MIN_NEEDS: f32 = 0.0;
 
+/// Clamp a need to [MIN_NEEDS, MAX_NEEDS]; NaN becomes MIN_NEEDS
+fn clamp_need(value: f32) -> f32 {
+    if value.is_nan() {
+        MIN_NEEDS
+    } else {
+        value.clamp(MIN_NEEDS, MAX_NEEDS)
+    }
+}
+
 /// Agent needs component tracking thirst, hunger, and tiredness
 ///
 /// Values are automatically clamped between [`MIN_NEEDS`] and [`MAX_NEEDS`].
+/// Infinities clamp to the nearest bound and NaN becomes [`MIN_NEEDS`].
 ///
 /// # Example
 ///
@@ -39,19 +53,62 @@ pub struct Needs {
 impl Needs {
     /// Creates a new Needs clamped to [MIN_NEEDS, MAX_NEEDS]
     pub fn new(thirst: f32, hunger: f32, tiredness: f32) -> Self {
-        fn clamp(v: f32) -> f32 { v.max(MIN_NEEDS).min(MAX_NEEDS) }
         Self {
-            thirst: clamp(thirst),
-            hunger: clamp(hunger),
-            tiredness: clamp(tiredness),
+            thirst: clamp_need(thirst),
+            hunger: clamp_need(hunger),
+            tiredness: clamp_need(tiredness),
         }
     }
 
-    /// Clamp the current needs in place.
+    /// Clamp the current needs in place (NaN becomes MIN_NEEDS).
     pub fn clamp(&mut self) {
-        self.thirst = self.thirst.max(MIN_NEEDS).min(MAX_NEEDS);
-        self.hunger = self.hunger.max(MIN_NEEDS).min(MAX_NEEDS);
-        self.tiredness = self.tiredness.max(MIN_NEEDS).min(MAX_NEEDS);
+        self.thirst = clamp_need(self.thirst);
+        self.hunger = clamp_need(self.hunger);
+        self.tiredness = clamp_need(self.tiredness);
+    }
+
+    /// Current level of one need
+    pub fn get(&self, need: NeedType) -> f32 {
+        match need {
+            NeedType::Thirst => self.thirst,
+            NeedType::Hunger => self.hunger,
+            NeedType::Tiredness => self.tiredness,
+        }
+    }
+
+    /// Mutable access to one need
+    pub fn get_mut(&mut self, need: NeedType) -> &mut f32 {
+        match need {
+            NeedType::Thirst => &mut self.thirst,
+            NeedType::Hunger => &mut self.hunger,
+            NeedType::Tiredness => &mut self.tiredness,
+        }
+    }
+
+    /// The most pressing need and its value.
+    ///
+    /// Ties resolve in survival order: thirst, then hunger, then tiredness.
+    ///
+    /// ```rust
+    /// use libreconomy::{Needs, NeedType};
+    ///
+    /// assert_eq!(Needs::new(20.0, 70.0, 40.0).max_need(), (NeedType::Hunger, 70.0));
+    /// assert_eq!(Needs::new(50.0, 50.0, 50.0).max_need(), (NeedType::Thirst, 50.0));
+    /// ```
+    pub fn max_need(&self) -> (NeedType, f32) {
+        let mut top = (NeedType::Thirst, self.thirst);
+        for candidate in [(NeedType::Hunger, self.hunger), (NeedType::Tiredness, self.tiredness)] {
+            if candidate.1 > top.1 {
+                top = candidate;
+            }
+        }
+        top
+    }
+
+    /// Overall need pressure: mean of all needs, normalized to 0-1.
+    pub fn pressure(&self) -> f32 {
+        let mean = (self.thirst + self.hunger + self.tiredness) / 3.0;
+        ((mean - MIN_NEEDS) / (MAX_NEEDS - MIN_NEEDS)).clamp(0.0, 1.0)
     }
 }
 
@@ -63,6 +120,7 @@ impl Component for Needs {
 ///
 /// Energy represents overall health/fitness and affects movement speed and decision-making.
 /// Values are automatically clamped between 0.0 and the maximum energy.
+/// A non-finite maximum falls back to 1.0; NaN energy becomes 0.0.
 ///
 /// # Example
 ///
@@ -82,16 +140,21 @@ pub struct EnergyComponent {
 impl EnergyComponent {
     /// Creates a new Energy component with current and max values
     pub fn new(current: f32, max: f32) -> Self {
-        let max = max.max(1.0); // Ensure max is at least 1
-        Self {
-            current: current.max(0.0).min(max),
-            max,
-        }
+        let mut energy = Self { current, max };
+        energy.clamp();
+        energy
     }
 
     /// Clamp the current energy to [0, max]
+    ///
+    /// Also repairs a non-finite or below-1 max (to 1.0) and NaN energy (to 0.0).
     pub fn clamp(&mut self) {
-        self.current = self.current.max(0.0).min(self.max);
+        self.max = if self.max.is_finite() { self.max.max(1.0) } else { 1.0 };
+        self.current = if self.current.is_nan() {
+            0.0
+        } else {
+            self.current.clamp(0.0, self.max)
+        };
     }
 }
 
@@ -126,6 +189,7 @@ impl Component for EnergyComponent {
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct Inventory {
+    #[serde(serialize_with = "crate::snapshot::sorted_map")]
     pub items: HashMap<String, u32>, // item_id -> quantity
 }
 
@@ -160,12 +224,216 @@ impl Inventory {
         self.set_quantity(item_id, current.saturating_sub(removed));
         removed
     }
+
+    /// Add every item from `other` into this inventory, saturating at u32::MAX.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libreconomy::Inventory;
+    ///
+    /// let mut pool = Inventory::default();
+    /// pool.add("water", 2);
+    ///
+    /// let mut loot = Inventory::default();
+    /// loot.add("water", 3);
+    /// loot.add("food", 1);
+    ///
+    /// pool.merge(&loot);
+    /// assert_eq!(pool.quantity("water"), 5);
+    /// assert_eq!(pool.quantity("food"), 1);
+    /// ```
+    pub fn merge(&mut self, other: &Inventory) {
+        for (item_id, quantity) in &other.items {
+            self.add(item_id, *quantity);
+        }
+    }
+
+    /// Combine two inventories into a new one without modifying either.
+    pub fn merged(a: &Inventory, b: &Inventory) -> Inventory {
+        let mut result = a.clone();
+        result.merge(b);
+        result
+    }
+
+    /// Whether every item in `requirements` is held in at least the given quantity.
+    ///
+    /// An empty requirement set is always met.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libreconomy::Inventory;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut inv = Inventory::default();
+    /// inv.add("wood", 3);
+    /// inv.add("stone", 1);
+    ///
+    /// let recipe = HashMap::from([("wood".to_string(), 2), ("stone".to_string(), 2)]);
+    /// assert!(!inv.has_all(&recipe));
+    /// assert_eq!(inv.missing(&recipe), HashMap::from([("stone".to_string(), 1)]));
+    /// ```
+    pub fn has_all(&self, requirements: &HashMap<String, u32>) -> bool {
+        requirements
+            .iter()
+            .all(|(item_id, needed)| self.quantity(item_id) >= *needed)
+    }
+
+    /// Shortfall per item against `requirements`; items already covered are omitted.
+    ///
+    /// Empty exactly when [`has_all`](Self::has_all) holds.
+    pub fn missing(&self, requirements: &HashMap<String, u32>) -> HashMap<String, u32> {
+        requirements
+            .iter()
+            .filter_map(|(item_id, needed)| {
+                let short = needed.saturating_sub(self.quantity(item_id));
+                (short > 0).then(|| (item_id.clone(), short))
+            })
+            .collect()
+    }
+
+    /// Total number of units across all items, saturating at u32::MAX.
+    pub fn total_quantity(&self) -> u32 {
+        self.items.values().fold(0u32, |acc, q| acc.saturating_add(*q))
+    }
+
+    /// Total weight of all items according to `registry`.
+    pub fn total_weight(&self, registry: &ItemRegistry) -> f32 {
+        self.items
+            .iter()
+            .map(|(item_id, quantity)| registry.weight_of(item_id) * *quantity as f32)
+            .sum()
+    }
+
+    /// Which of the `offered` items fit within `capacity`, best value per weight first.
+    ///
+    /// Capacity is measured in registry weight (`max_weight`, with
+    /// items defaulting to a weight of 1.0). Offers are taken greedily in
+    /// order of value per weight; ties are broken by item id so the selection
+    /// is deterministic. Weightless items always fit. Returns `(item_id,
+    /// quantity)` pairs in the order they were selected, omitting items none
+    /// of which fit.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libreconomy::{CarryCapacity, Inventory, ItemRegistry, ItemType};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut registry = ItemRegistry::new();
+    /// registry.register(ItemType::new("gem".into(), HashMap::new(), false).with_value(50.0));
+    /// registry.register(ItemType::new("rock".into(), HashMap::new(), false).with_weight(5.0));
+    ///
+    /// let offered = vec![("rock".to_string(), 1), ("gem".to_string(), 3)];
+    /// let fits = Inventory::default().fittable(&offered, &registry, &CarryCapacity::new(4));
+    /// assert_eq!(fits, vec![("gem".to_string(), 3)]);
+    /// ```
+    pub fn fittable(
+        &self,
+        offered: &[(String, u32)],
+        registry: &ItemRegistry,
+        capacity: &CarryCapacity,
+    ) -> Vec<(String, u32)> {
+        let mut ranked: Vec<(&String, u32, f32, f32)> = offered
+            .iter()
+            .filter(|(_, quantity)| *quantity > 0)
+            .map(|(item_id, quantity)| {
+                let weight = registry.weight_of(item_id).max(0.0);
+                let value = registry.value_of(item_id);
+                let density = if weight > 0.0 { value / weight } else { f32::INFINITY };
+                (item_id, *quantity, weight, density)
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| {
+            b.3.partial_cmp(&a.3)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.cmp(b.0))
+        });
+
+        let mut remaining = capacity.remaining(self, registry);
+        let mut selected = Vec::new();
+        for (item_id, quantity, weight, _) in ranked {
+            let take = units_within(remaining, weight, quantity);
+            if take > 0 {
+                remaining = (remaining - weight * take as f32).max(0.0);
+                selected.push((item_id.clone(), take));
+            }
+        }
+        selected
+    }
 }
 
 impl Component for Inventory {
     type Storage = VecStorage<Self>;
 }
 
+/// Units of an item weighing `weight` each, up to `quantity`, that fit in `remaining`
+fn units_within(remaining: f32, weight: f32, quantity: u32) -> u32 {
+    if weight > 0.0 {
+        // Small epsilon so exact multiples aren't lost to rounding
+        (((remaining + 1e-4) / weight).floor() as u32).min(quantity)
+    } else {
+        quantity
+    }
+}
+
+/// Carry capacity component limiting how much weight an agent can hold
+///
+/// Agents without this component have unlimited capacity. `max_weight` is a
+/// budget of registry weight (see [`ItemType::weight`](crate::ItemType)), not
+/// a unit count: only with the default item weight of 1.0 do the two agree.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::{CarryCapacity, Inventory, ItemRegistry, ItemType};
+/// use std::collections::HashMap;
+///
+/// let mut registry = ItemRegistry::new();
+/// registry.register(ItemType::new("stone".into(), HashMap::new(), false).with_weight(3.0));
+///
+/// let capacity = CarryCapacity::new(10);
+/// let mut inv = Inventory::default();
+/// inv.add("wood", 4);
+///
+/// // 6 weight left: only 2 of the 5 stones fit
+/// assert_eq!(capacity.add_to(&mut inv, &registry, "stone", 5), 2);
+/// assert_eq!(inv.quantity("stone"), 2);
+/// ```
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct CarryCapacity {
+    /// Maximum total item weight the agent can carry
+    pub max_weight: u32,
+}
+
+impl CarryCapacity {
+    /// Create a capacity limit of `max_weight`
+    pub fn new(max_weight: u32) -> Self {
+        Self { max_weight }
+    }
+
+    /// Weight that still fits into `inventory`
+    pub fn remaining(&self, inventory: &Inventory, registry: &ItemRegistry) -> f32 {
+        (self.max_weight as f32 - inventory.total_weight(registry)).max(0.0)
+    }
+
+    /// Add as much of `quantity` as fits by weight; returns the amount actually added.
+    pub fn add_to(
+        &self,
+        inventory: &mut Inventory,
+        registry: &ItemRegistry,
+        item_id: &str,
+        quantity: u32,
+    ) -> u32 {
+        let weight = registry.weight_of(item_id).max(0.0);
+        let added = units_within(self.remaining(inventory, registry), weight, quantity);
+        inventory.add(item_id, added);
+        added
+    }
+}
+
+impl Component for CarryCapacity {
+    type Storage = VecStorage<Self>;
+}
+
 /// Agent wallet component for currency management
 ///
 /// Ensures non-negative balance at all times. Negative values are clamped to 0.
@@ -192,33 +460,56 @@ impl Component for Inventory {
 /// assert_eq!(withdrawn, 70.0);
 /// assert_eq!(wallet.currency, 0.0);
 /// ```
+///
+/// The balance is `f32` by default; `Wallet<Currency>` keeps exact
+/// fixed-point balances (see [`Currency`](crate::Currency)).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct Wallet {
-    pub currency: f32,
+pub struct Wallet<C = f32> {
+    pub currency: C,
 }
 
 impl Wallet {
-    /// Create a new wallet with non-negative balance
+    /// Create a new wallet with non-negative balance; NaN or infinity starts empty
     pub fn new(currency: f32) -> Self {
-        Self { currency: currency.max(0.0) }
+        Self::with_balance(currency)
     }
+}
 
-    /// Deposit non-negative amount; negative is treated as zero.
-    pub fn deposit(&mut self, amount: f32) {
-        if amount <= 0.0 { return; }
-        self.currency += amount;
+impl<C: CurrencyAmount> Wallet<C> {
+    /// Create a wallet of any currency type; negative or non-finite starts empty
+    pub fn with_balance(currency: C) -> Self {
+        Self {
+            currency: if currency.is_finite() && currency.is_positive() {
+                currency
+            } else {
+                C::default()
+            },
+        }
+    }
+
+    /// Deposit non-negative amount; negative, NaN or infinite is treated as zero.
+    ///
+    /// This creates currency and does not conserve the world's total. Pair it
+    /// with a [`withdraw`](Self::withdraw) of the same amount, or use
+    /// `transfer_currency` / `mint_currency`, which also respect a closed
+    /// `EconomyPolicy`.
+    pub fn deposit(&mut self, amount: C) {
+        if !amount.is_finite() || !amount.is_positive() { return; }
+        self.currency = self.currency.plus(amount);
     }
 
     /// Withdraw up to amount, not allowing negative balance; returns withdrawn.
-    pub fn withdraw(&mut self, amount: f32) -> f32 {
-        if amount <= 0.0 { return 0.0; }
-        let withdrawn = self.currency.min(amount);
-        self.currency -= withdrawn;
+    ///
+    /// NaN withdraws nothing; infinity withdraws the whole balance.
+    pub fn withdraw(&mut self, amount: C) -> C {
+        if !amount.is_positive() { return C::default(); }
+        let withdrawn = if amount < self.currency { amount } else { self.currency };
+        self.currency = self.currency.minus(withdrawn);
         withdrawn
     }
 }
 
-impl Component for Wallet {
+impl<C: CurrencyAmount> Component for Wallet<C> {
     type Storage = VecStorage<Self>;
 }
 
@@ -247,12 +538,24 @@ impl Component for Agent {
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct Skills {
+    #[serde(serialize_with = "crate::snapshot::sorted_map")]
     pub skills: HashMap<String, u32>, // skill_id -> level
 }
 impl Component for Skills {
     type Storage = VecStorage<Self>;
 }
 
+/// A price one trade partner quoted for an item
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PriceQuote {
+    /// Item the quote is for
+    pub item: String,
+    /// Partner who quoted it
+    pub partner: AgentId,
+    /// Quoted price per unit
+    pub price: f32,
+}
+
 /// Agent knowledge component.
 ///
 /// Stores observed prices and known trade partners.
@@ -267,33 +570,86 @@ impl Component for Skills {
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct Knowledge {
+    #[serde(serialize_with = "crate::snapshot::sorted_map")]
     pub known_prices: std::collections::HashMap<String, f32>,
     pub trade_partners: Vec<String>,
+    /// Latest quote per (item, partner), in the order first heard
+    #[serde(default)]
+    pub partner_quotes: Vec<PriceQuote>,
+    /// Tick each known price was last observed (see `observe_price`)
+    #[serde(default, serialize_with = "crate::snapshot::sorted_map")]
+    pub price_ticks: std::collections::HashMap<String, u64>,
+    /// Tick each trade partner was last met (see `meet_partner`)
+    #[serde(default, serialize_with = "crate::snapshot::sorted_map")]
+    pub partner_ticks: std::collections::HashMap<String, u64>,
 }
 impl Component for Knowledge {
     type Storage = VecStorage<Self>;
 }
 
+impl Knowledge {
+    /// Record `partner`'s quote for `item`, replacing its earlier one
+    ///
+    /// Also makes it the latest known price, as [`LearningSystem::update`] does,
+    /// and clears the price's tick so
+    /// [`KnowledgeDecaySystem`](crate::KnowledgeDecaySystem) counts it as
+    /// observed on its next run.
+    pub fn record_quote(&mut self, item: &str, partner: AgentId, price: f32) {
+        self.known_prices.insert(item.to_string(), price);
+        self.price_ticks.remove(item);
+        match self
+            .partner_quotes
+            .iter_mut()
+            .find(|q| q.partner == partner && q.item == item)
+        {
+            Some(quote) => quote.price = price,
+            None => self.partner_quotes.push(PriceQuote {
+                item: item.to_string(),
+                partner,
+                price,
+            }),
+        }
+    }
+    /// Record `item`'s price as observed at `tick`
+    ///
+    /// Like [`LearningSystem::update`], but also refreshes the entry so
+    /// [`KnowledgeDecaySystem`](crate::KnowledgeDecaySystem) keeps it.
+    pub fn observe_price(&mut self, item: &str, price: f32, tick: u64) {
+        self.known_prices.insert(item.to_string(), price);
+        self.price_ticks.insert(item.to_string(), tick);
+    }
+
+    /// Record meeting trade `partner` at `tick`, adding it if new
+    pub fn meet_partner(&mut self, partner: &str, tick: u64) {
+        if !self.trade_partners.iter().any(|p| p == partner) {
+            self.trade_partners.push(partner.to_string());
+        }
+        self.partner_ticks.insert(partner.to_string(), tick);
+    }
+}
+
 /// Agent employment component.
 ///
-/// Tracks job status, employer, and subordinates.
+/// Tracks job status, employer, and subordinates. Employer and employees are
+/// agent ids, so the org graph can be walked with `employer_of` and
+/// `employees_of`; the job itself is described by `job_status`.
 ///
 /// # Example
 /// ```rust
-/// use libreconomy::Employment;
+/// use libreconomy::{AgentId, Employment};
 /// let mut e = Employment::default();
 /// e.job_status = Some("employed".into());
-/// e.employer = Some("Acme Inc".into());
-/// e.employees.push("WorkerA".into());
+/// e.employer = Some(AgentId(7));
+/// e.employees.push(AgentId(12));
 /// assert_eq!(e.job_status.as_deref(), Some("employed"));
-/// assert_eq!(e.employer.as_deref(), Some("Acme Inc"));
+/// assert_eq!(e.employer, Some(AgentId(7)));
 /// assert_eq!(e.employees.len(), 1);
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct Employment {
     pub job_status: Option<String>,
-    pub employer: Option<String>,
-    pub employees: Vec<String>,
+    pub employer: Option<AgentId>,
+    pub employees: Vec<AgentId>,
 }
 impl Component for Employment {
     type Storage = VecStorage<Self>;
@@ -329,7 +685,8 @@ impl Component for Preferences {
 /// Resource source component for entities that produce items.
 ///
 /// Used to mark entities as resource providers (e.g., water sources, grass patches).
-/// Supports regeneration and stock tracking.
+/// Supports regeneration and stock tracking. A source may be gated behind a
+/// tool the harvester must hold and/or a minimum skill level.
 ///
 /// # Example
 /// ```rust
@@ -340,6 +697,9 @@ impl Component for Preferences {
 ///     item_produced: "grass".to_string(),
 ///     regeneration_rate: 0.1,
 ///     current_stock: 100,
+///     requires_item: None,
+///     requires_skill: None,
+///     yield_per_harvest: 1,
 /// };
 ///
 /// assert_eq!(grass_patch.item_produced, "grass");
@@ -355,6 +715,37 @@ pub struct ResourceSource {
     pub regeneration_rate: f32,
     /// Current available quantity
     pub current_stock: u32,
+    /// Tool the harvester must hold in its `Inventory` (e.g. "axe")
+    #[serde(default)]
+    pub requires_item: Option<String>,
+    /// Skill and minimum level the harvester needs
+    #[serde(default)]
+    pub requires_skill: Option<(String, u32)>,
+    /// Units one gather action takes from a fully productive harvester
+    #[serde(default = "default_yield_per_harvest")]
+    pub yield_per_harvest: u32,
+}
+
+fn default_yield_per_harvest() -> u32 {
+    1
+}
+
+/// Whether an agent meets a resource's tool and skill prerequisites
+///
+/// Missing components count as empty: an agent without an `Inventory` holds
+/// no tools, one without `Skills` has level 0 everywhere.
+pub fn harvest_requirements_met(
+    requires_item: Option<&str>,
+    requires_skill: Option<(&str, u32)>,
+    inventory: Option<&Inventory>,
+    skills: Option<&Skills>,
+) -> bool {
+    let has_tool = requires_item
+        .is_none_or(|tool| inventory.is_some_and(|inv| inv.quantity(tool) > 0));
+    let has_skill = requires_skill.is_none_or(|(skill, level)| {
+        skills.and_then(|s| s.skills.get(skill)).copied().unwrap_or(0) >= level
+    });
+    has_tool && has_skill
 }
 
 impl ResourceSource {
@@ -370,10 +761,58 @@ impl ResourceSource {
             item_produced,
             regeneration_rate,
             current_stock,
+            requires_item: None,
+            requires_skill: None,
+            yield_per_harvest: default_yield_per_harvest(),
         }
     }
 
+    /// Set how many units one gather action yields
+    pub fn with_yield_per_harvest(mut self, amount: u32) -> Self {
+        self.yield_per_harvest = amount;
+        self
+    }
+
+    /// Require the harvester to hold a tool
+    pub fn with_required_item(mut self, item: &str) -> Self {
+        self.requires_item = Some(item.to_string());
+        self
+    }
+
+    /// Require the harvester to have a skill at `level` or above
+    pub fn with_required_skill(mut self, skill: &str, level: u32) -> Self {
+        self.requires_skill = Some((skill.to_string(), level));
+        self
+    }
+
+    /// Whether an agent with this inventory and skills may harvest here
+    ///
+    /// # Example
+    /// ```rust
+    /// use libreconomy::{Inventory, ResourceSource};
+    ///
+    /// let forest = ResourceSource::new("tree".into(), "wood".into(), 0.0, 50)
+    ///     .with_required_item("axe");
+    /// let mut inventory = Inventory::default();
+    /// assert!(!forest.can_harvest(Some(&inventory), None));
+    ///
+    /// inventory.add("axe", 1);
+    /// assert!(forest.can_harvest(Some(&inventory), None));
+    /// ```
+    pub fn can_harvest(&self, inventory: Option<&Inventory>, skills: Option<&Skills>) -> bool {
+        harvest_requirements_met(
+            self.requires_item.as_deref(),
+            self.requires_skill.as_ref().map(|(s, l)| (s.as_str(), *l)),
+            inventory,
+            skills,
+        )
+    }
+
     /// Harvest from this resource source, returning amount actually harvested
+    ///
+    /// For one standard gather action use `harvest_action`, which takes
+    /// [`yield_per_harvest`](Self::yield_per_harvest) scaled by the
+    /// harvester's productivity.
     pub fn harvest(&mut self, amount: u32) -> u32 {
         let harvested = self.current_stock.min(amount);
         self.current_stock = self.current_stock.saturating_sub(harvested);
@@ -382,8 +821,16 @@ impl ResourceSource {
 
     /// Regenerate resource stock
     pub fn regenerate(&mut self) {
-        if self.regeneration_rate > 0.0 {
-            let regen_amount = self.regeneration_rate as u32;
+        self.regenerate_scaled(1.0);
+    }
+
+    /// Regenerate stock at `multiplier` times the normal rate
+    ///
+    /// Used for seasonal modifiers; a multiplier of 0 (or below) adds nothing.
+    pub fn regenerate_scaled(&mut self, multiplier: f32) {
+        let rate = self.regeneration_rate * multiplier;
+        if rate > 0.0 {
+            let regen_amount = rate as u32;
             self.current_stock = self.current_stock.saturating_add(regen_amount);
         }
     }
@@ -524,6 +971,17 @@ impl Component for SpeciesComponent {
     type Storage = VecStorage<Self>;
 }
 
+/// Host-assigned map region the agent is currently in
+///
+/// Regions are opaque ids; the application decides their layout and moves
+/// agents between them. Used with `RegionalScarcity` for migration decisions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Region(pub u32);
+
+impl Component for Region {
+    type Storage = VecStorage<Self>;
+}
+
 /// Reputation view of a single agent using Beta distribution
 ///
 /// Uses Beta(alpha, beta) to model reputation based on positive/negative interactions.
@@ -601,12 +1059,69 @@ impl ReputationView {
         self.interaction_count += 1;
     }
 
+    /// Add raw evidence to both sides of the distribution
+    ///
+    /// Unlike [`update`](Self::update), positive and negative evidence can be
+    /// added in the same interaction. Negative deltas are ignored so alpha and
+    /// beta never shrink below their prior.
+    pub fn add_evidence(&mut self, alpha_delta: f32, beta_delta: f32, current_tick: u64) {
+        self.alpha += alpha_delta.max(0.0);
+        self.beta += beta_delta.max(0.0);
+
+        self.last_interaction_tick = current_tick;
+        self.interaction_count += 1;
+    }
+
+    /// Forget up to `amount` of negative evidence (reconciliation)
+    ///
+    /// Beta never drops below the uniform prior of 1, so the score can rise
+    /// at most to what the same positive history without any bad interactions
+    /// would give. Positive evidence, the interaction count and tick are
+    /// unchanged. Returns the evidence actually forgiven.
+    pub fn forgive(&mut self, amount: f32) -> f32 {
+        if amount.is_nan() || amount <= 0.0 {
+            return 0.0;
+        }
+        let forgiven = amount.min((self.beta - 1.0).max(0.0));
+        self.beta -= forgiven;
+        forgiven
+    }
+
     /// Get confidence in this reputation (total evidence)
     ///
     /// Higher values indicate more interactions, thus more confident estimate
     pub fn confidence(&self) -> f32 {
         self.alpha + self.beta
     }
+
+    /// Variance of the Beta distribution
+    ///
+    /// Shrinks as evidence accumulates, whatever the mean.
+    pub fn variance(&self) -> f32 {
+        let total = self.alpha + self.beta;
+        self.alpha * self.beta / (total * total * (total + 1.0))
+    }
+
+    /// Pessimistic score: the mean minus `z` standard deviations
+    ///
+    /// Clamped to [0, 1]. With little evidence the margin is wide, so
+    /// cautious agents trust a partner with two good trades less than one
+    /// with twenty at the same mean. A `z` of 0 (or a negative or NaN `z`)
+    /// gives the plain [`score`](Self::score).
+    ///
+    /// # Example
+    /// ```rust
+    /// use libreconomy::ReputationView;
+    ///
+    /// let few = ReputationView::with_prior(3.0, 1.0);
+    /// let many = ReputationView::with_prior(30.0, 10.0);
+    /// assert_eq!(few.score(), many.score());
+    /// assert!(few.score_lower_bound(1.0) < many.score_lower_bound(1.0));
+    /// ```
+    pub fn score_lower_bound(&self, z: f32) -> f32 {
+        let z = z.max(0.0);
+        (self.score() - z * self.variance().sqrt()).clamp(0.0, 1.0)
+    }
 }
 
 impl Default for ReputationView {
@@ -615,9 +1130,34 @@ impl Default for ReputationView {
     }
 }
 
+/// How [`ReputationKnowledge`] turns a view into a trust score
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ReputationScoring {
+    /// Expected value of the Beta distribution
+    #[default]
+    Mean,
+    /// [`ReputationView::score_lower_bound`] with this many standard deviations
+    LowerBound {
+        /// Standard deviations subtracted from the mean
+        z: f32,
+    },
+}
+
+impl ReputationScoring {
+    /// Score of `view` under this mode
+    pub fn score(&self, view: &ReputationView) -> f32 {
+        match self {
+            ReputationScoring::Mean => view.score(),
+            ReputationScoring::LowerBound { z } => view.score_lower_bound(*z),
+        }
+    }
+}
+
 /// Agent reputation knowledge component
 ///
-/// Tracks first-hand reputation observations of other agents.
+/// Tracks first-hand reputation observations of other agents. `scoring`
+/// selects how [`trust_score`](Self::trust_score), `is_trusted` and
+/// `get_most_trusted` judge known agents; `get_score` is always the mean.
 ///
 /// # Example
 /// ```rust
@@ -637,18 +1177,19 @@ impl Default for ReputationView {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct ReputationKnowledge {
     /// First-hand reputation views of known agents
+    #[serde(serialize_with = "crate::snapshot::sorted_map")]
     pub first_hand: HashMap<AgentId, ReputationView>,
     /// Baseline trust level for unknown agents (0.0 = distrust, 1.0 = trust)
     pub trust_level: f32,
+    /// How known agents are scored for trust decisions
+    #[serde(default)]
+    pub scoring: ReputationScoring,
 }
 
 impl ReputationKnowledge {
     /// Create a new reputation knowledge with default trust level (0.5)
     pub fn new() -> Self {
-        Self {
-            first_hand: HashMap::new(),
-            trust_level: 0.5,
-        }
+        Self::with_trust_level(0.5)
     }
 
     /// Create a reputation knowledge with custom default trust level
@@ -656,9 +1197,16 @@ impl ReputationKnowledge {
         Self {
             first_hand: HashMap::new(),
             trust_level: trust_level.clamp(0.0, 1.0),
+            scoring: ReputationScoring::Mean,
         }
     }
 
+    /// Set how known agents are scored (builder)
+    pub fn with_scoring(mut self, scoring: ReputationScoring) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
     /// Get reputation score for an agent
     ///
     /// Returns the score if known, otherwise returns the default trust level
@@ -669,6 +1217,17 @@ impl ReputationKnowledge {
             .unwrap_or(self.trust_level)
     }
 
+    /// Get the trust score for an agent under the `scoring` mode
+    ///
+    /// Same as [`get_score`](Self::get_score) in `Mean` mode; unknown agents
+    /// get the default trust level in every mode.
+    pub fn trust_score(&self, agent: AgentId) -> f32 {
+        self.first_hand
+            .get(&agent)
+            .map(|view| self.scoring.score(view))
+            .unwrap_or(self.trust_level)
+    }
+
     /// Get reputation score with decay for an agent
     pub fn get_score_with_decay(&self, agent: AgentId, current_tick: u64, decay_rate: f32) -> f32 {
         self.first_hand
@@ -690,43 +1249,323 @@ impl ReputationKnowledge {
             .update(outcome_weight, current_tick);
     }
 
-    /// Check if an agent is trusted (score above threshold)
+    /// Add raw (alpha, beta) evidence about an agent
+    ///
+    /// See [`ReputationView::add_evidence`].
+    pub fn add_evidence(&mut self, agent: AgentId, alpha_delta: f32, beta_delta: f32, current_tick: u64) {
+        self.first_hand
+            .entry(agent)
+            .or_default()
+            .add_evidence(alpha_delta, beta_delta, current_tick);
+    }
+
+    /// Forgive `agent` up to `amount` of negative evidence
+    ///
+    /// For apologies, gifts or a make-good trade after a dispute. Unknown
+    /// agents are unaffected; see [`ReputationView::forgive`].
+    pub fn forgive(&mut self, agent: AgentId, amount: f32) {
+        if let Some(view) = self.first_hand.get_mut(&agent) {
+            view.forgive(amount);
+        }
+    }
+
+    /// Check if an agent is trusted ([`trust_score`](Self::trust_score) above threshold)
     pub fn is_trusted(&self, agent: AgentId, threshold: f32) -> bool {
-        self.get_score(agent) >= threshold
+        self.trust_score(agent) >= threshold
     }
 
     /// Get the most trusted agents
     ///
-    /// Returns up to `max_count` agents sorted by reputation score (highest first)
+    /// Returns up to `max_count` agents sorted by trust score (highest first)
     pub fn get_most_trusted(&self, max_count: usize) -> Vec<(AgentId, f32)> {
+        if max_count == 0 {
+            return Vec::new();
+        }
+
+        // Score each agent once, not once per comparison
         let mut scores: Vec<(AgentId, f32)> = self
             .first_hand
             .iter()
-            .map(|(id, view)| (*id, view.score()))
+            .map(|(id, view)| (*id, self.scoring.score(view)))
             .collect();
 
         scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         scores.truncate(max_count);
         scores
     }
+
+    /// Export first-hand trust data for transfer to another world
+    ///
+    /// Returns `(agent, alpha, beta, last_interaction_tick, interaction_count)`
+    /// for every known agent, sorted by agent id.
+    pub fn export(&self) -> Vec<(AgentId, f32, f32, u64, u32)> {
+        let mut data: Vec<(AgentId, f32, f32, u64, u32)> = self
+            .first_hand
+            .iter()
+            .map(|(id, view)| {
+                (*id, view.alpha, view.beta, view.last_interaction_tick, view.interaction_count)
+            })
+            .collect();
+        data.sort_by_key(|entry| entry.0 .0);
+        data
+    }
+
+    /// Import trust data produced by [`export`](Self::export)
+    ///
+    /// With `merge == false`, incoming views replace any existing view of the
+    /// same agent. With `merge == true`, evidence is summed: the incoming
+    /// alpha/beta above the uniform (1, 1) prior is added to the existing view,
+    /// so the prior is not counted twice and the merged score stays between
+    /// the two source scores. The later tick is kept and counts are added.
+    ///
+    /// Entries with non-finite or non-positive alpha/beta are skipped.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libreconomy::{ReputationKnowledge, AgentId};
+    ///
+    /// let mut elsewhere = ReputationKnowledge::new();
+    /// elsewhere.update_reputation(AgentId(7), 3.0, 50);
+    ///
+    /// let mut here = ReputationKnowledge::new();
+    /// here.import(elsewhere.export(), false);
+    /// assert_eq!(here.get_score(AgentId(7)), elsewhere.get_score(AgentId(7)));
+    /// ```
+    pub fn import(&mut self, data: Vec<(AgentId, f32, f32, u64, u32)>, merge: bool) {
+        for (agent, alpha, beta, tick, count) in data {
+            if !(alpha.is_finite() && beta.is_finite() && alpha > 0.0 && beta > 0.0) {
+                continue;
+            }
+
+            let incoming = ReputationView {
+                alpha,
+                beta,
+                last_interaction_tick: tick,
+                interaction_count: count,
+            };
+
+            match self.first_hand.get_mut(&agent) {
+                Some(existing) if merge => {
+                    existing.alpha += (incoming.alpha - 1.0).max(0.0);
+                    existing.beta += (incoming.beta - 1.0).max(0.0);
+                    existing.last_interaction_tick =
+                        existing.last_interaction_tick.max(incoming.last_interaction_tick);
+                    existing.interaction_count =
+                        existing.interaction_count.saturating_add(incoming.interaction_count);
+                }
+                _ => {
+                    self.first_hand.insert(agent, incoming);
+                }
+            }
+        }
+    }
 }
 
 impl Component for ReputationKnowledge {
     type Storage = VecStorage<Self>;
 }
 
+/// Reputation score below which no unsecured credit is extended
+pub const CREDIT_TRUST_FLOOR: f32 = 0.4;
+
+/// Observed interactions' worth of evidence at which credit certainty is 0.5
+pub const CREDIT_EVIDENCE_HALF: f32 = 10.0;
+
+/// Fraction of credit certainty granted with no evidence at all
+pub const STRANGER_CREDIT_FACTOR: f32 = 0.1;
+
+/// Unsecured balance `observer` would allow `debtor` to run up
+///
+/// Scales `base` by the debtor's reputation score and by how much evidence
+/// backs it: `base * score * certainty`, where certainty grows from
+/// [`STRANGER_CREDIT_FACTOR`] toward 1.0 as interactions accumulate.
+/// Debtors scoring below [`CREDIT_TRUST_FLOOR`] get no credit.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::{credit_limit, AgentId, ReputationKnowledge};
+///
+/// let mut rep = ReputationKnowledge::new();
+/// for tick in 0..20 {
+///     rep.update_reputation(AgentId(1), 1.0, tick);
+/// }
+///
+/// // A proven partner gets far more than a stranger
+/// assert!(credit_limit(&rep, AgentId(1), 100.0) > credit_limit(&rep, AgentId(2), 100.0));
+/// ```
+pub fn credit_limit(observer: &ReputationKnowledge, debtor: AgentId, base: f32) -> f32 {
+    if !base.is_finite() || base <= 0.0 {
+        return 0.0;
+    }
+
+    let score = observer.get_score(debtor);
+    if score < CREDIT_TRUST_FLOOR {
+        return 0.0;
+    }
+
+    // Evidence beyond the uniform prior (alpha = beta = 1)
+    let evidence = observer
+        .first_hand
+        .get(&debtor)
+        .map_or(0.0, |view| (view.confidence() - 2.0).max(0.0));
+    let certainty = evidence / (evidence + CREDIT_EVIDENCE_HALF);
+    let certainty = STRANGER_CREDIT_FACTOR + (1.0 - STRANGER_CREDIT_FACTOR) * certainty;
+
+    base * score * certainty
+}
+
+/// Per-agent multipliers on the base need decay rate
+///
+/// Agents without this component decay at the base rate (all multipliers 1.0).
+///
+/// # Example
+/// ```rust
+/// use libreconomy::{Needs, NeedDecayRates, NeedDecaySystem};
+///
+/// let rates = NeedDecayRates::new(1.0, 2.0, 1.0);
+/// let mut needs = Needs::new(0.0, 0.0, 0.0);
+/// NeedDecaySystem::tick_with_rates(&mut needs, &rates);
+/// assert!(needs.hunger > needs.thirst);
+/// ```
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct NeedDecayRates {
+    /// Multiplier on thirst growth
+    pub thirst: f32,
+    /// Multiplier on hunger growth
+    pub hunger: f32,
+    /// Multiplier on tiredness growth
+    pub tiredness: f32,
+}
+
+impl NeedDecayRates {
+    /// Creates decay multipliers; negative values are clamped to 0.0
+    pub fn new(thirst: f32, hunger: f32, tiredness: f32) -> Self {
+        Self {
+            thirst: thirst.max(0.0),
+            hunger: hunger.max(0.0),
+            tiredness: tiredness.max(0.0),
+        }
+    }
+}
+
+impl Default for NeedDecayRates {
+    fn default() -> Self {
+        Self::new(1.0, 1.0, 1.0)
+    }
+}
+
+impl Component for NeedDecayRates {
+    type Storage = VecStorage<Self>;
+}
+
+/// Grows every agent's needs by one tick
+///
+/// As an ECS system it applies each agent's [`NeedDecayRates`] (base rate if
+/// absent), pays the growth out of the agent's
+/// [`OverflowBuffer`](super::overflow::OverflowBuffer) first if it has one
+/// (and the `OverflowConfig` allows), and clamps the result; the associated
+/// functions tick a single `Needs` value directly.
 pub struct NeedDecaySystem;
 impl NeedDecaySystem {
+    /// Stable dispatcher name
+    pub const NAME: &'static str = "need_decay";
+
+    /// Base growth per tick for each need
+    pub const BASE_RATE: f32 = 0.01;
+
+    /// Advance needs by one tick: thirst, hunger and tiredness all grow
     pub fn tick(needs: &mut Needs) {
-        needs.thirst += 0.01;
-        needs.hunger += 0.01;
+        Self::tick_with_rates(needs, &NeedDecayRates::default());
+    }
+
+    /// Advance needs by one tick, scaling each need's growth by `rates`
+    pub fn tick_with_rates(needs: &mut Needs, rates: &NeedDecayRates) {
+        needs.thirst += Self::BASE_RATE * rates.thirst;
+        needs.hunger += Self::BASE_RATE * rates.hunger;
+        needs.tiredness += Self::BASE_RATE * rates.tiredness;
+    }
+
+    /// Advance needs by one tick, absorbing the growth into `buffer` first
+    pub fn tick_with_buffer(needs: &mut Needs, rates: &NeedDecayRates, buffer: &mut OverflowBuffer) {
+        for (need, rate) in [
+            (NeedType::Thirst, rates.thirst),
+            (NeedType::Hunger, rates.hunger),
+            (NeedType::Tiredness, rates.tiredness),
+        ] {
+            *needs.get_mut(need) += buffer.absorb(need, Self::BASE_RATE * rate);
+        }
+    }
+}
+
+impl<'a> System<'a> for NeedDecaySystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Needs>,
+        ReadStorage<'a, NeedDecayRates>,
+        OptionalWriteStorage<'a, OverflowBuffer>,
+        Option<Read<'a, OverflowConfig>>,
+    );
+
+    fn run(&mut self, (entities, mut needs, rates, mut buffers, config): Self::SystemData) {
+        let base = NeedDecayRates::default();
+        let buffered = config.is_none_or(|c| c.enabled);
+        for (entity, needs) in (&entities, &mut needs).join() {
+            let rates = rates.get(entity).unwrap_or(&base);
+            match buffers.as_mut().and_then(|b| b.get_mut(entity)).filter(|_| buffered) {
+                Some(buffer) => Self::tick_with_buffer(needs, rates, buffer),
+                None => Self::tick_with_rates(needs, rates),
+            }
+            needs.clamp();
+        }
     }
 }
 
+/// Price of `item` estimated from partners' quotes, trusting each partner
+/// by its reputation score
+///
+/// Each partner's latest quote is weighted by `reputation.get_score`, so a
+/// trusted partner's price pulls the estimate far more than a stranger's or a
+/// cheat's. A single quote is returned as is. Without any quotes, falls back
+/// to the latest known price; `None` if the item has never been priced.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::{estimate_price, AgentId, Knowledge, ReputationKnowledge};
+///
+/// let mut knowledge = Knowledge::default();
+/// let reputation = ReputationKnowledge::new();
+/// assert_eq!(estimate_price(&knowledge, &reputation, "water"), None);
+///
+/// knowledge.record_quote("water", AgentId(1), 4.0);
+/// assert_eq!(estimate_price(&knowledge, &reputation, "water"), Some(4.0));
+/// ```
+pub fn estimate_price(knowledge: &Knowledge, reputation: &ReputationKnowledge, item: &str) -> Option<f32> {
+    let quotes: Vec<(f32, f32)> = knowledge
+        .partner_quotes
+        .iter()
+        .filter(|q| q.item == item && q.price.is_finite())
+        .map(|q| (q.price, reputation.get_score(q.partner).max(0.0)))
+        .collect();
+    if quotes.is_empty() {
+        return knowledge.known_prices.get(item).copied();
+    }
+
+    let total_weight: f32 = quotes.iter().map(|(_, weight)| weight).sum();
+    if total_weight <= 0.0 {
+        // Trusting no one equally is the same as trusting everyone equally
+        return Some(quotes.iter().map(|(price, _)| price).sum::<f32>() / quotes.len() as f32);
+    }
+    Some(quotes.iter().map(|(price, weight)| price * weight).sum::<f32>() / total_weight)
+}
+
 pub struct LearningSystem;
 impl LearningSystem {
+    /// Record `item`'s latest price, clearing its tick so
+    /// [`KnowledgeDecaySystem`](crate::KnowledgeDecaySystem) counts it as
+    /// observed on its next run
     pub fn update(knowledge: &mut Knowledge, item: &str, price: f32) {
         knowledge.known_prices.insert(item.to_string(), price);
+        knowledge.price_ticks.remove(item);
     }
 }
 
@@ -741,6 +1580,7 @@ impl NegotiationSystem {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use crate::items::ItemType;
 
     #[test]
     fn test_needs_creation() {
@@ -774,6 +1614,40 @@ mod tests {
         assert!(grass.is_available());
     }
 
+    #[test]
+    fn test_non_finite_inputs_stay_finite_and_in_bounds() {
+        for bad in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let needs = Needs::new(bad, bad, bad);
+            for value in [needs.thirst, needs.hunger, needs.tiredness] {
+                assert!((MIN_NEEDS..=MAX_NEEDS).contains(&value));
+            }
+
+            let mut wallet = Wallet::new(bad);
+            assert_eq!(wallet.currency, 0.0);
+            wallet.deposit(10.0);
+            wallet.deposit(bad);
+            assert_eq!(wallet.currency, 10.0);
+
+            let energy = EnergyComponent::new(bad, bad);
+            assert_eq!(energy.max, 1.0);
+            assert!((0.0..=energy.max).contains(&energy.current));
+            let energy = EnergyComponent::new(bad, 100.0);
+            assert!((0.0..=100.0).contains(&energy.current));
+        }
+
+        assert_eq!(Needs::new(f32::NAN, f32::INFINITY, f32::NEG_INFINITY), Needs::new(0.0, 100.0, 0.0));
+
+        let mut needs = Needs::new(50.0, 50.0, 50.0);
+        needs.hunger = f32::NAN;
+        needs.clamp();
+        assert_eq!(needs.hunger, MIN_NEEDS);
+
+        let mut wallet = Wallet::new(10.0);
+        assert_eq!(wallet.withdraw(f32::NAN), 0.0);
+        assert_eq!(wallet.withdraw(f32::INFINITY), 10.0);
+        assert_eq!(wallet.currency, 0.0);
+    }
+
     #[test]
     fn test_resource_source_harvest() {
         let mut grass = ResourceSource::new(
@@ -901,6 +1775,97 @@ mod tests {
         assert!(carnivore.can_hunt(Species::Human));
     }
 
+    #[test]
+    fn test_max_need_hunger_on_top() {
+        let needs = Needs::new(30.0, 85.0, 60.0);
+        assert_eq!(needs.max_need(), (NeedType::Hunger, 85.0));
+        assert!((needs.pressure() - 175.0 / 300.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_max_need_all_equal() {
+        let needs = Needs::new(40.0, 40.0, 40.0);
+        assert_eq!(needs.max_need(), (NeedType::Thirst, 40.0));
+        assert_eq!(needs.pressure(), 0.4);
+
+        // Hunger beats tiredness on a tie
+        assert_eq!(Needs::new(10.0, 40.0, 40.0).max_need(), (NeedType::Hunger, 40.0));
+        assert_eq!(Needs::new(0.0, 0.0, 0.0).pressure(), 0.0);
+        assert_eq!(Needs::new(100.0, 100.0, 100.0).pressure(), 1.0);
+    }
+
+    #[test]
+    fn test_carry_capacity_limits_add() {
+        let registry = ItemRegistry::new();
+        let capacity = CarryCapacity::new(5);
+        let mut inv = Inventory::default();
+
+        assert_eq!(capacity.add_to(&mut inv, &registry, "water", 3), 3);
+        assert_eq!(capacity.remaining(&inv, &registry), 2.0);
+        assert_eq!(capacity.add_to(&mut inv, &registry, "food", 4), 2);
+        assert_eq!(capacity.add_to(&mut inv, &registry, "food", 1), 0);
+        assert_eq!(inv.total_quantity(), 5);
+    }
+
+    fn loot_registry() -> ItemRegistry {
+        let mut registry = ItemRegistry::new();
+        registry.register(
+            ItemType::new("anvil".to_string(), HashMap::new(), false)
+                .with_weight(8.0)
+                .with_value(4.0),
+        );
+        registry.register(
+            ItemType::new("ring".to_string(), HashMap::new(), false)
+                .with_weight(1.0)
+                .with_value(30.0),
+        );
+        registry
+    }
+
+    #[test]
+    fn test_fittable_prefers_light_valuable_items() {
+        let registry = loot_registry();
+        let mut inv = Inventory::default();
+        inv.add("water", 2);
+        let offered = vec![("anvil".to_string(), 1), ("ring".to_string(), 5)];
+
+        // 10 capacity, 2 used: the anvil (8) would fit alone but the rings go first
+        let fits = inv.fittable(&offered, &registry, &CarryCapacity::new(10));
+        assert_eq!(fits, vec![("ring".to_string(), 5)]);
+
+        // With room for both, everything is taken
+        let fits = inv.fittable(&offered, &registry, &CarryCapacity::new(20));
+        assert_eq!(fits, vec![("ring".to_string(), 5), ("anvil".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_capacity_weighs_heavy_and_light_items_alike() {
+        let registry = loot_registry();
+        let capacity = CarryCapacity::new(10);
+        let mut inv = Inventory::default();
+
+        // One anvil takes 8 of 10: two rings still fit, a second anvil doesn't
+        assert_eq!(capacity.add_to(&mut inv, &registry, "anvil", 2), 1);
+        assert_eq!(capacity.remaining(&inv, &registry), 2.0);
+        let offered = vec![("anvil".to_string(), 1), ("ring".to_string(), 5)];
+        assert_eq!(inv.fittable(&offered, &registry, &capacity), vec![("ring".to_string(), 2)]);
+        assert_eq!(capacity.add_to(&mut inv, &registry, "ring", 5), 2);
+
+        assert_eq!(inv.total_quantity(), 3);
+        assert_eq!(inv.total_weight(&registry), 10.0);
+        assert!(inv.fittable(&offered, &registry, &capacity).is_empty());
+    }
+
+    #[test]
+    fn test_fittable_ties_break_by_item_id() {
+        let registry = ItemRegistry::new();
+        let inv = Inventory::default();
+        let offered = vec![("wood".to_string(), 3), ("stone".to_string(), 3)];
+
+        let fits = inv.fittable(&offered, &registry, &CarryCapacity::new(4));
+        assert_eq!(fits, vec![("stone".to_string(), 3), ("wood".to_string(), 1)]);
+    }
+
     #[test]
     fn test_reputation_view_creation() {
         let view = ReputationView::new();
@@ -933,6 +1898,28 @@ mod tests {
         assert_eq!(view.interaction_count, 1);
     }
 
+    #[test]
+    fn test_lower_bound_distrusts_thin_evidence() {
+        let thin = ReputationView::with_prior(3.0, 1.0);
+        let thick = ReputationView::with_prior(30.0, 10.0);
+        assert_eq!(thin.score(), thick.score());
+        assert!(thin.score_lower_bound(1.0) < thick.score_lower_bound(1.0));
+        assert!(thick.score_lower_bound(1.0) < thick.score());
+        assert_eq!(thin.score_lower_bound(0.0), thin.score());
+        assert_eq!(ReputationView::new().score_lower_bound(100.0), 0.0);
+
+        let mut rep = ReputationKnowledge::new()
+            .with_scoring(ReputationScoring::LowerBound { z: 1.0 });
+        rep.first_hand.insert(AgentId(1), thin);
+        rep.first_hand.insert(AgentId(2), thick);
+        assert_eq!(rep.get_score(AgentId(1)), 0.75);
+        assert!(rep.trust_score(AgentId(1)) < rep.trust_score(AgentId(2)));
+        assert_eq!(rep.trust_score(AgentId(3)), 0.5);
+        assert!(rep.is_trusted(AgentId(2), 0.6));
+        assert!(!rep.is_trusted(AgentId(1), 0.6));
+        assert_eq!(rep.get_most_trusted(1)[0].0, AgentId(2));
+    }
+
     #[test]
     fn test_reputation_view_score_bounds() {
         let mut view = ReputationView::new();
@@ -964,6 +1951,73 @@ mod tests {
         assert!(score_decayed > 0.5);
     }
 
+    #[test]
+    fn test_credit_limit_favors_reputation() {
+        let mut rep = ReputationKnowledge::new();
+        for tick in 0..10 {
+            rep.update_reputation(AgentId(1), 1.0, tick);
+            rep.update_reputation(AgentId(2), if tick % 3 == 0 { -1.0 } else { 1.0 }, tick);
+        }
+
+        let trusted = credit_limit(&rep, AgentId(1), 100.0);
+        let mixed = credit_limit(&rep, AgentId(2), 100.0);
+        let stranger = credit_limit(&rep, AgentId(3), 100.0);
+
+        assert!(trusted > mixed);
+        assert!(mixed > stranger);
+        assert!(stranger > 0.0);
+        assert!(trusted <= 100.0);
+    }
+
+    #[test]
+    fn test_credit_limit_zero_below_trust_floor() {
+        let mut rep = ReputationKnowledge::new();
+        for tick in 0..10 {
+            rep.update_reputation(AgentId(1), -1.0, tick);
+        }
+        assert_eq!(credit_limit(&rep, AgentId(1), 100.0), 0.0);
+
+        let wary = ReputationKnowledge::with_trust_level(0.2);
+        assert_eq!(credit_limit(&wary, AgentId(2), 100.0), 0.0);
+    }
+
+    #[test]
+    fn test_trusted_quote_dominates_price_estimate() {
+        let mut rep = ReputationKnowledge::new();
+        for tick in 0..10 {
+            rep.update_reputation(AgentId(1), 1.0, tick);
+            rep.update_reputation(AgentId(2), -1.0, tick);
+        }
+        let mut knowledge = Knowledge::default();
+        knowledge.record_quote("water", AgentId(1), 10.0);
+        knowledge.record_quote("water", AgentId(2), 100.0);
+
+        let estimate = estimate_price(&knowledge, &rep, "water").unwrap();
+        assert!((estimate - 10.0).abs() < (estimate - 100.0).abs());
+        assert!(estimate < 20.0);
+
+        // A newer quote replaces the partner's old one
+        knowledge.record_quote("water", AgentId(2), 10.0);
+        assert_eq!(knowledge.partner_quotes.len(), 2);
+        assert_eq!(estimate_price(&knowledge, &rep, "water"), Some(10.0));
+    }
+
+    #[test]
+    fn test_price_estimate_without_quotes() {
+        let rep = ReputationKnowledge::with_trust_level(0.0);
+        let mut knowledge = Knowledge::default();
+        assert_eq!(estimate_price(&knowledge, &rep, "food"), None);
+
+        // Unattributed observations are still better than nothing
+        LearningSystem::update(&mut knowledge, "food", 3.0);
+        assert_eq!(estimate_price(&knowledge, &rep, "food"), Some(3.0));
+
+        // Distrusting every source falls back to a plain average
+        knowledge.record_quote("food", AgentId(1), 2.0);
+        knowledge.record_quote("food", AgentId(2), 4.0);
+        assert_eq!(estimate_price(&knowledge, &rep, "food"), Some(3.0));
+    }
+
     #[test]
     fn test_reputation_knowledge_creation() {
         let rep = ReputationKnowledge::new();
@@ -980,6 +2034,31 @@ mod tests {
         assert_eq!(score, 0.7); // Returns default trust level
     }
 
+    #[test]
+    fn test_forgiveness_raises_score_without_erasing_history() {
+        let mut rep = ReputationKnowledge::new();
+        let cheat = AgentId(7);
+        rep.update_reputation(cheat, 2.0, 10);
+        rep.update_reputation(cheat, -5.0, 20);
+        let distrusted = rep.get_score(cheat);
+        assert!(distrusted < 0.5);
+
+        rep.forgive(cheat, 2.0);
+        let forgiven = rep.get_score(cheat);
+        assert!(forgiven > distrusted && forgiven < 0.75);
+
+        // No amount of forgiveness beats a clean record with the same positives
+        rep.forgive(cheat, 100.0);
+        let view = &rep.first_hand[&cheat];
+        assert_eq!((view.alpha, view.beta), (3.0, 1.0));
+        assert_eq!(rep.get_score(cheat), 0.75);
+        assert_eq!((view.interaction_count, view.last_interaction_tick), (2, 20));
+
+        assert_eq!(rep.first_hand.get_mut(&cheat).unwrap().forgive(1.0), 0.0);
+        rep.forgive(AgentId(99), 1.0);
+        assert!(!rep.first_hand.contains_key(&AgentId(99)));
+    }
+
     #[test]
     fn test_reputation_knowledge_update() {
         let mut rep = ReputationKnowledge::new();
@@ -1035,4 +2114,174 @@ mod tests {
         assert_eq!(most_trusted[0].0, agent1); // Highest trust
         assert!(most_trusted[0].1 > most_trusted[1].1);
     }
+
+    #[test]
+    fn test_reputation_export_sorted_by_agent() {
+        let mut rep = ReputationKnowledge::new();
+        rep.update_reputation(AgentId(3), 1.0, 10);
+        rep.update_reputation(AgentId(1), -2.0, 20);
+
+        let data = rep.export();
+        assert_eq!(
+            data,
+            vec![(AgentId(1), 1.0, 3.0, 20, 1), (AgentId(3), 2.0, 1.0, 10, 1)]
+        );
+    }
+
+    #[test]
+    fn test_reputation_import_replace() {
+        let mut rep = ReputationKnowledge::new();
+        rep.update_reputation(AgentId(1), -5.0, 10);
+
+        rep.import(vec![(AgentId(1), 4.0, 1.0, 30, 3)], false);
+
+        let view = rep.first_hand.get(&AgentId(1)).unwrap();
+        assert_eq!(view.alpha, 4.0);
+        assert_eq!(view.beta, 1.0);
+        assert_eq!(view.last_interaction_tick, 30);
+        assert_eq!(view.interaction_count, 3);
+    }
+
+    #[test]
+    fn test_reputation_import_merge_sums_evidence() {
+        let mut rep = ReputationKnowledge::new();
+        rep.update_reputation(AgentId(1), 2.0, 50); // alpha 3, beta 1
+
+        rep.import(vec![(AgentId(1), 1.0, 3.0, 30, 2), (AgentId(2), 2.0, 1.0, 5, 1)], true);
+
+        // Prior is not double-counted: alpha 3 + 0, beta 1 + 2
+        let view = rep.first_hand.get(&AgentId(1)).unwrap();
+        assert_eq!(view.alpha, 3.0);
+        assert_eq!(view.beta, 3.0);
+        assert_eq!(view.last_interaction_tick, 50);
+        assert_eq!(view.interaction_count, 3);
+        assert_eq!(view.score(), 0.5);
+
+        // Unknown agents are inserted as-is
+        assert_eq!(rep.first_hand.get(&AgentId(2)).unwrap().alpha, 2.0);
+    }
+
+    #[test]
+    fn test_reputation_import_merge_same_direction_keeps_score_side() {
+        let mut rep = ReputationKnowledge::new();
+        rep.update_reputation(AgentId(1), 1.0, 10);
+        let before = rep.get_score(AgentId(1));
+
+        let exported = rep.export();
+        rep.import(exported, true);
+
+        // Agreeing evidence strengthens, never flips, the score
+        assert!(rep.get_score(AgentId(1)) >= before);
+        assert!(rep.get_score(AgentId(1)) < 1.0);
+    }
+
+    #[test]
+    fn test_reputation_import_skips_invalid() {
+        let mut rep = ReputationKnowledge::new();
+        rep.import(
+            vec![
+                (AgentId(1), f32::NAN, 1.0, 0, 0),
+                (AgentId(2), 0.0, 1.0, 0, 0),
+                (AgentId(3), 1.0, f32::INFINITY, 0, 0),
+            ],
+            false,
+        );
+        assert!(rep.first_hand.is_empty());
+    }
+
+    #[test]
+    fn test_inventory_merge_overlapping() {
+        let mut a = Inventory::default();
+        a.add("water", 4);
+        a.add("food", 1);
+
+        let mut b = Inventory::default();
+        b.add("water", 6);
+        b.add("food", 2);
+
+        a.merge(&b);
+        assert_eq!(a.quantity("water"), 10);
+        assert_eq!(a.quantity("food"), 3);
+        assert_eq!(a.items.len(), 2);
+        // Source is untouched
+        assert_eq!(b.quantity("water"), 6);
+    }
+
+    #[test]
+    fn test_inventory_merge_disjoint() {
+        let mut a = Inventory::default();
+        a.add("water", 4);
+
+        let mut b = Inventory::default();
+        b.add("wood", 7);
+
+        a.merge(&b);
+        assert_eq!(a.quantity("water"), 4);
+        assert_eq!(a.quantity("wood"), 7);
+        assert_eq!(a.items.len(), 2);
+    }
+
+    #[test]
+    fn test_inventory_merge_saturates() {
+        let mut a = Inventory::default();
+        a.add("gold", u32::MAX - 1);
+
+        let mut b = Inventory::default();
+        b.add("gold", 10);
+
+        a.merge(&b);
+        assert_eq!(a.quantity("gold"), u32::MAX);
+    }
+
+    #[test]
+    fn test_inventory_merged_is_non_mutating() {
+        let mut a = Inventory::default();
+        a.add("water", 1);
+        let mut b = Inventory::default();
+        b.add("water", 2);
+        b.add("food", 5);
+
+        let combined = Inventory::merged(&a, &b);
+        assert_eq!(combined.quantity("water"), 3);
+        assert_eq!(combined.quantity("food"), 5);
+        assert_eq!(a.quantity("water"), 1);
+        assert_eq!(a.quantity("food"), 0);
+
+        // Merging with an empty inventory is the identity
+        assert_eq!(Inventory::merged(&a, &Inventory::default()), a);
+    }
+
+    #[test]
+    fn test_inventory_has_all_satisfied() {
+        let mut inv = Inventory::default();
+        inv.add("wood", 4);
+        inv.add("nails", 10);
+
+        let plank_wall = HashMap::from([("wood".to_string(), 4), ("nails".to_string(), 6)]);
+        assert!(inv.has_all(&plank_wall));
+        assert!(inv.missing(&plank_wall).is_empty());
+
+        // Nothing required is always affordable, even with nothing held
+        assert!(Inventory::default().has_all(&HashMap::new()));
+        assert!(Inventory::default().missing(&HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn test_inventory_missing_reports_each_shortfall() {
+        let mut inv = Inventory::default();
+        inv.add("wood", 1);
+        inv.add("nails", 10);
+
+        let cabin = HashMap::from([
+            ("wood".to_string(), 5),
+            ("nails".to_string(), 8),
+            ("glass".to_string(), 2),
+            ("rope".to_string(), 0),
+        ]);
+        assert!(!inv.has_all(&cabin));
+        assert_eq!(
+            inv.missing(&cabin),
+            HashMap::from([("wood".to_string(), 4), ("glass".to_string(), 2)])
+        );
+    }
 }
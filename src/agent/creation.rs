@@ -2,21 +2,65 @@
 //! Functions for creating and managing agent entities with their components
 
 use specs::prelude::*;
+use specs::storage::MaskedStorage;
 use super::components::{Agent, Needs, Inventory, Wallet};
-use super::identity::AgentIdAllocator;
+use super::identity::{AgentId, AgentIdAllocator, AgentIdError};
 
-/// Default starting needs for a new agent (mid-range)
-const DEFAULT_THIRST: f32 = 50.0;
-const DEFAULT_HUNGER: f32 = 50.0;
-const DEFAULT_TIREDNESS: f32 = 50.0;
+/// Default starting thirst for a new agent (mid-range)
+pub const DEFAULT_THIRST: f32 = 50.0;
+/// Default starting hunger for a new agent (mid-range)
+pub const DEFAULT_HUNGER: f32 = 50.0;
+/// Default starting tiredness for a new agent (mid-range)
+pub const DEFAULT_TIREDNESS: f32 = 50.0;
 
 /// Default starting currency for a new agent
-const DEFAULT_CURRENCY: f32 = 100.0;
+pub const DEFAULT_CURRENCY: f32 = 100.0;
+
+/// Errors that can occur when creating an agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreationError {
+    /// The AgentIdAllocator has no more ids to hand out
+    IdExhausted,
+    /// A required resource is not inserted in the world (resource name)
+    MissingResource(&'static str),
+    /// A required component type is not registered (component name)
+    MissingComponentRegistration(&'static str),
+}
+
+impl core::fmt::Display for CreationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CreationError::IdExhausted => write!(f, "AgentId allocation failed: no more IDs available"),
+            CreationError::MissingResource(name) => write!(f, "missing world resource: {}", name),
+            CreationError::MissingComponentRegistration(name) => {
+                write!(f, "component not registered: {}", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CreationError {}
+
+impl From<AgentIdError> for CreationError {
+    fn from(err: AgentIdError) -> Self {
+        match err {
+            AgentIdError::Overflow => CreationError::IdExhausted,
+        }
+    }
+}
+
+fn ensure_registered<T: Component>(world: &World, name: &'static str) -> Result<(), CreationError> {
+    if world.has_value::<MaskedStorage<T>>() {
+        Ok(())
+    } else {
+        Err(CreationError::MissingComponentRegistration(name))
+    }
+}
 
 /// Create a new agent with default components
 ///
 /// # Default Components
-/// - Needs: thirst=50.0, hunger=50.0
+/// - Needs: thirst=50.0, hunger=50.0, tiredness=50.0
 /// - Inventory: empty
 /// - Wallet: currency=100.0
 ///
@@ -92,44 +136,165 @@ pub fn create_agent_with_wallet(world: &mut World, wallet: Wallet) -> Entity {
 /// Panics if AgentIdAllocator resource is not registered in the world
 /// Panics if required component types are not registered
 /// Panics if AgentId allocation fails (overflow)
+///
+/// Use [`try_create_agent_custom`] to get an error instead. In debug builds
+/// the panic lists every unregistered component (see
+/// [`ensure_components_registered`]).
+///
+/// [`ensure_components_registered`]: super::registration::ensure_components_registered
 pub fn create_agent_custom(
     world: &mut World,
     needs: Needs,
     inventory: Inventory,
     wallet: Wallet,
 ) -> Entity {
+    #[cfg(debug_assertions)]
+    if let Err(missing) = super::registration::ensure_components_registered(world) {
+        panic!(
+            "components not registered: {} (call register_core_components first)",
+            missing.join(", ")
+        );
+    }
+    try_create_agent_custom(world, needs, inventory, wallet)
+        .unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Create a new agent with fully custom components, without panicking
+///
+/// Checks that the `AgentIdAllocator` resource exists and that `Agent`,
+/// `Needs`, `Inventory` and `Wallet` are registered before allocating an id,
+/// so a failed call leaves the world unchanged.
+///
+/// # Example
+///
+/// ```rust
+/// use libreconomy::*;
+/// use libreconomy::agent::creation::{try_create_agent_custom, CreationError};
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Agent>();
+/// world.register::<Needs>();
+/// world.register::<Inventory>();
+/// world.register::<Wallet>();
+///
+/// let result = try_create_agent_custom(
+///     &mut world,
+///     Needs::new(50.0, 50.0, 50.0),
+///     Inventory::default(),
+///     Wallet::new(10.0),
+/// );
+/// assert_eq!(result, Err(CreationError::MissingResource("AgentIdAllocator")));
+/// ```
+pub fn try_create_agent_custom(
+    world: &mut World,
+    needs: Needs,
+    inventory: Inventory,
+    wallet: Wallet,
+) -> Result<Entity, CreationError> {
+    ensure_registered::<Agent>(world, "Agent")?;
+    ensure_registered::<Needs>(world, "Needs")?;
+    ensure_registered::<Inventory>(world, "Inventory")?;
+    ensure_registered::<Wallet>(world, "Wallet")?;
+
     // Allocate unique AgentId
     let agent_id = {
-        let mut allocator = world.write_resource::<AgentIdAllocator>();
-        allocator.allocate().expect("AgentId allocation failed")
+        let mut allocator = world
+            .try_fetch_mut::<AgentIdAllocator>()
+            .ok_or(CreationError::MissingResource("AgentIdAllocator"))?;
+        allocator.allocate()?
     };
 
     // Create entity with all components
-    world
+    Ok(world
         .create_entity()
         .with(Agent { id: agent_id })
         .with(needs)
         .with(inventory)
         .with(wallet)
-        .build()
+        .build())
+}
+
+/// Reasons an agent could not be removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoveError {
+    /// The entity was already deleted (or never existed)
+    AlreadyDead,
+    /// The entity is alive but carries no `Agent` component
+    NotAnAgent,
+}
+
+impl core::fmt::Display for RemoveError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RemoveError::AlreadyDead => write!(f, "entity is already dead"),
+            RemoveError::NotAnAgent => write!(f, "entity is not an agent"),
+        }
+    }
+}
+
+impl std::error::Error for RemoveError {}
+
+/// Remove an agent entity and all its components, reporting what happened
+///
+/// Returns the removed agent's id and releases it to the world's
+/// `AgentIdAllocator`, which hands it to the next agent created. Reputation,
+/// memories and households that still name the departed agent will then
+/// refer to its successor; clear them first if that matters. Non-agent
+/// entities are left alone.
+///
+/// # Errors
+/// [`RemoveError::AlreadyDead`] or [`RemoveError::NotAnAgent`]
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// register_core_components(&mut world);
+/// let agent = create_agent(&mut world);
+///
+/// assert_eq!(try_remove_agent(&mut world, agent), Ok(AgentId(1)));
+/// assert_eq!(try_remove_agent(&mut world, agent), Err(RemoveError::AlreadyDead));
+///
+/// // The id is free again
+/// let newcomer = create_agent(&mut world);
+/// assert_eq!(world.read_storage::<Agent>().get(newcomer).unwrap().id, AgentId(1));
+/// ```
+pub fn try_remove_agent(world: &mut World, entity: Entity) -> Result<AgentId, RemoveError> {
+    // Entities rebuilt from a bare index (`Entities::entity`) carry the dead
+    // generation of a deleted slot, which `is_alive` alone accepts
+    if !entity.gen().is_alive() || !world.entities().is_alive(entity) {
+        return Err(RemoveError::AlreadyDead);
+    }
+    let id = if world.has_value::<MaskedStorage<Agent>>() {
+        world.read_storage::<Agent>().get(entity).map(|agent| agent.id)
+    } else {
+        None
+    };
+    let id = id.ok_or(RemoveError::NotAnAgent)?;
+
+    // Deleting the entity drops every component it carries
+    world
+        .delete_entity(entity)
+        .map_err(|_| RemoveError::AlreadyDead)?;
+    if let Some(mut allocator) = world.try_fetch_mut::<AgentIdAllocator>() {
+        allocator.release(id);
+    }
+    Ok(id)
 }
 
 /// Remove an agent entity and all its components from the ECS world
 ///
+/// Does nothing if `entity` is dead or not an agent; use
+/// [`try_remove_agent`] to tell those cases apart.
+///
 /// # Arguments
 /// * `world` - ECS world
 /// * `entity` - Agent entity to remove
-///
-/// # Panics
-/// Panics if entity does not exist
 pub fn remove_agent(world: &mut World, entity: Entity) {
-    // Remove all components
-    world.write_storage::<Agent>().remove(entity);
-    world.write_storage::<Needs>().remove(entity);
-    world.write_storage::<Inventory>().remove(entity);
-    world.write_storage::<Wallet>().remove(entity);
-    // Remove entity itself
-    world.delete_entity(entity).expect("Failed to delete agent entity");
+    let _ = try_remove_agent(world, entity);
 }
 
 #[cfg(test)]
@@ -164,6 +329,7 @@ mod tests {
         let needs = needs_storage.get(entity).expect("should have Needs component");
         assert_eq!(needs.thirst, DEFAULT_THIRST);
         assert_eq!(needs.hunger, DEFAULT_HUNGER);
+        assert_eq!(needs.tiredness, DEFAULT_TIREDNESS);
 
         // Assert - has Inventory component (empty)
         let inventory_storage = world.read_storage::<Inventory>();
@@ -372,4 +538,100 @@ mod tests {
         assert_eq!(agents.get(entity1).unwrap().id, AgentId(1));
         assert_eq!(agents.get(entity3).unwrap().id, AgentId(3));
     }
+
+    #[test]
+    fn test_try_remove_agent_reports_outcome() {
+        let mut world = registered_world();
+        world.insert(AgentIdAllocator::new());
+        let first = create_agent(&mut world);
+        let second = create_agent(&mut world);
+        let rock = world.create_entity().build();
+
+        assert_eq!(try_remove_agent(&mut world, second), Ok(AgentId(2)));
+        assert!(!world.entities().is_alive(second));
+        assert_eq!(try_remove_agent(&mut world, second), Err(RemoveError::AlreadyDead));
+
+        assert_eq!(try_remove_agent(&mut world, rock), Err(RemoveError::NotAnAgent));
+        assert!(world.entities().is_alive(rock));
+
+        // The legacy call ignores the error instead of panicking
+        remove_agent(&mut world, second);
+        assert!(world.entities().is_alive(first));
+
+        // The removed id is recycled before a new one is issued
+        let third = create_agent(&mut world);
+        let fourth = create_agent(&mut world);
+        let agents = world.read_storage::<Agent>();
+        assert_eq!(agents.get(third).unwrap().id, AgentId(2));
+        assert_eq!(agents.get(fourth).unwrap().id, AgentId(3));
+    }
+
+    fn registered_world() -> World {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Needs>();
+        world.register::<Inventory>();
+        world.register::<Wallet>();
+        world
+    }
+
+    fn default_parts() -> (Needs, Inventory, Wallet) {
+        (
+            Needs::new(DEFAULT_THIRST, DEFAULT_HUNGER, DEFAULT_TIREDNESS),
+            Inventory::default(),
+            Wallet::new(DEFAULT_CURRENCY),
+        )
+    }
+
+    #[test]
+    fn test_try_create_agent_custom_ok() {
+        let mut world = registered_world();
+        world.insert(AgentIdAllocator::new());
+
+        let (needs, inventory, wallet) = default_parts();
+        let entity = try_create_agent_custom(&mut world, needs, inventory, wallet).unwrap();
+
+        assert_eq!(world.read_storage::<Agent>().get(entity).unwrap().id, AgentId(1));
+    }
+
+    #[test]
+    fn test_try_create_agent_custom_id_exhausted() {
+        let mut world = registered_world();
+        let exhausted: AgentIdAllocator =
+            serde_json::from_str(&format!("{{\"next\":{}}}", u64::MAX)).unwrap();
+        world.insert(exhausted);
+
+        let (needs, inventory, wallet) = default_parts();
+        let result = try_create_agent_custom(&mut world, needs, inventory, wallet);
+
+        assert_eq!(result, Err(CreationError::IdExhausted));
+        // Nothing was created
+        assert_eq!(world.read_storage::<Agent>().join().count(), 0);
+    }
+
+    #[test]
+    fn test_try_create_agent_custom_missing_allocator() {
+        let mut world = registered_world();
+
+        let (needs, inventory, wallet) = default_parts();
+        let result = try_create_agent_custom(&mut world, needs, inventory, wallet);
+
+        assert_eq!(result, Err(CreationError::MissingResource("AgentIdAllocator")));
+    }
+
+    #[test]
+    fn test_try_create_agent_custom_missing_registration() {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Needs>();
+        world.register::<Inventory>();
+        world.insert(AgentIdAllocator::new());
+
+        let (needs, inventory, wallet) = default_parts();
+        let result = try_create_agent_custom(&mut world, needs, inventory, wallet);
+
+        assert_eq!(result, Err(CreationError::MissingComponentRegistration("Wallet")));
+        // The allocator was not consumed
+        assert_eq!(world.read_resource::<AgentIdAllocator>().peek(), AgentId(1));
+    }
 }
@@ -0,0 +1,182 @@
+//! Currency amounts for wallets and transactions
+//!
+//! `Wallet` and `Transaction` default to `f32` amounts, which is fine for
+//! games but rounds on every operation. Closed-economy audits that need
+//! totals to balance exactly over millions of transactions can use
+//! `Wallet<Currency>` and `Transaction<Currency>` instead: [`Currency`]
+//! counts whole minor units (cents) in an `i64`, so addition and subtraction
+//! are exact.
+
+use serde::{Deserialize, Serialize};
+
+/// Minor units (cents) in one major unit of [`Currency`]
+pub const MINOR_UNITS_PER_MAJOR: i64 = 100;
+
+/// Amount type usable as a wallet balance or transaction price
+///
+/// Implemented for `f32` (the default) and [`Currency`].
+pub trait CurrencyAmount:
+    Copy + PartialOrd + Default + core::fmt::Debug + Send + Sync + 'static
+{
+    /// Whether the amount is finite (always true for fixed-point amounts)
+    fn is_finite(self) -> bool;
+
+    /// Whether the amount is greater than zero (false for NaN)
+    fn is_positive(self) -> bool;
+
+    /// Sum of two amounts
+    fn plus(self, other: Self) -> Self;
+
+    /// Difference of two amounts
+    fn minus(self, other: Self) -> Self;
+
+    /// Amount multiplied by a whole quantity
+    fn times(self, quantity: u32) -> Self;
+
+    /// Approximate value in major units
+    fn to_f32(self) -> f32;
+}
+
+impl CurrencyAmount for f32 {
+    fn is_finite(self) -> bool {
+        f32::is_finite(self)
+    }
+
+    fn is_positive(self) -> bool {
+        self > 0.0
+    }
+
+    fn plus(self, other: Self) -> Self {
+        self + other
+    }
+
+    fn minus(self, other: Self) -> Self {
+        self - other
+    }
+
+    fn times(self, quantity: u32) -> Self {
+        quantity as f32 * self
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+}
+
+/// Fixed-point currency amount in whole minor units
+///
+/// Arithmetic is exact and saturates at the `i64` limits instead of
+/// wrapping.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::{Currency, Wallet};
+///
+/// let price = Currency::from_f32(0.1);
+/// assert_eq!(price.minor_units(), 10);
+///
+/// let mut wallet = Wallet::with_balance(Currency::from_minor(100));
+/// for _ in 0..10 {
+///     wallet.withdraw(price);
+/// }
+/// assert_eq!(wallet.currency, Currency::ZERO);
+/// ```
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Currency(i64);
+
+impl Currency {
+    /// No money
+    pub const ZERO: Currency = Currency(0);
+
+    /// Amount of `minor` units (cents)
+    pub const fn from_minor(minor: i64) -> Self {
+        Currency(minor)
+    }
+
+    /// Nearest whole number of minor units to `major`; NaN becomes zero
+    pub fn from_f32(major: f32) -> Self {
+        // Round in f64 so amounts like 0.1 land on the intended cent
+        Currency((f64::from(major) * MINOR_UNITS_PER_MAJOR as f64).round() as i64)
+    }
+
+    /// Amount in minor units
+    pub const fn minor_units(self) -> i64 {
+        self.0
+    }
+}
+
+impl CurrencyAmount for Currency {
+    fn is_finite(self) -> bool {
+        true
+    }
+
+    fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    fn plus(self, other: Self) -> Self {
+        Currency(self.0.saturating_add(other.0))
+    }
+
+    fn minus(self, other: Self) -> Self {
+        Currency(self.0.saturating_sub(other.0))
+    }
+
+    fn times(self, quantity: u32) -> Self {
+        Currency(self.0.saturating_mul(i64::from(quantity)))
+    }
+
+    fn to_f32(self) -> f32 {
+        (self.0 as f64 / MINOR_UNITS_PER_MAJOR as f64) as f32
+    }
+}
+
+impl core::ops::Add for Currency {
+    type Output = Currency;
+
+    fn add(self, other: Currency) -> Currency {
+        self.plus(other)
+    }
+}
+
+impl core::ops::Sub for Currency {
+    type Output = Currency;
+
+    fn sub(self, other: Currency) -> Currency {
+        self.minus(other)
+    }
+}
+
+impl core::iter::Sum for Currency {
+    fn sum<I: Iterator<Item = Currency>>(iter: I) -> Currency {
+        iter.fold(Currency::ZERO, Currency::plus)
+    }
+}
+
+impl core::fmt::Display for Currency {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let minor = self.0.unsigned_abs();
+        let per_major = MINOR_UNITS_PER_MAJOR as u64;
+        write!(f, "{}{}.{:02}", sign, minor / per_major, minor % per_major)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_conversions_round_to_nearest_cent() {
+        assert_eq!(Currency::from_f32(12.345).minor_units(), 1235);
+        assert_eq!(Currency::from_f32(-0.5).minor_units(), -50);
+        assert_eq!(Currency::from_f32(f32::NAN), Currency::ZERO);
+        assert_eq!(Currency::from_minor(250).to_f32(), 2.5);
+        assert_eq!(Currency::from_minor(-1205).to_string(), "-12.05");
+        assert_eq!(Currency::from_minor(i64::MAX) + Currency::from_minor(1), Currency::from_minor(i64::MAX));
+    }
+}
@@ -0,0 +1,168 @@
+//! Employer and employee relationships between agents
+//! Resolves the agent ids stored in `Employment` to entities through an `AgentIndex`
+
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+use super::components::Employment;
+use super::identity::AgentId;
+use super::index::AgentIndex;
+
+/// Run `f` with the world's `AgentIndex` resource, or one built on the spot
+fn with_index<R>(world: &World, f: impl FnOnce(&AgentIndex) -> R) -> R {
+    match world.try_fetch::<AgentIndex>() {
+        Some(index) => f(&index),
+        None => f(&AgentIndex::from_world(world)),
+    }
+}
+
+/// Entity of the agent employing `entity`, if any
+///
+/// Ids resolve through the world's `AgentIndex` resource when there is one,
+/// otherwise through an index built from the `Agent` storage. Returns `None`
+/// if `Employment` is not registered or the employer no longer exists.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// register_core_components(&mut world);
+/// world.register::<Employment>();
+/// let boss = create_agent(&mut world);
+/// let worker = create_agent(&mut world);
+/// let boss_id = world.read_storage::<Agent>().get(boss).unwrap().id;
+/// let employment = Employment { employer: Some(boss_id), ..Default::default() };
+/// world.write_storage::<Employment>().insert(worker, employment).unwrap();
+///
+/// assert_eq!(employer_of(&world, worker), Some(boss));
+/// assert_eq!(employer_of(&world, boss), None);
+/// ```
+pub fn employer_of(world: &World, entity: Entity) -> Option<Entity> {
+    if !world.has_value::<MaskedStorage<Employment>>() {
+        return None;
+    }
+    let employer = world.read_storage::<Employment>().get(entity)?.employer?;
+    with_index(world, |index| index.get(employer))
+}
+
+/// Entities of the agents `entity` employs, in the order they were listed
+///
+/// Employees that no longer exist are skipped. Resolves ids like
+/// [`employer_of`].
+pub fn employees_of(world: &World, entity: Entity) -> Vec<Entity> {
+    if !world.has_value::<MaskedStorage<Employment>>() {
+        return Vec::new();
+    }
+    let employees = match world.read_storage::<Employment>().get(entity) {
+        Some(employment) => employment.employees.clone(),
+        None => return Vec::new(),
+    };
+    with_index(world, |index| employees.iter().filter_map(|&id| index.get(id)).collect())
+}
+
+/// `Employment` as it was stored before employers and employees were agent ids
+///
+/// Deserialize old saves into this and call [`migrate`](Self::migrate) to
+/// get the current component.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LegacyEmployment {
+    pub job_status: Option<String>,
+    pub employer: Option<String>,
+    pub employees: Vec<String>,
+}
+
+impl LegacyEmployment {
+    /// Convert to `Employment`, mapping names to ids with `resolve`
+    ///
+    /// `job_status` carries over unchanged. Names `resolve` does not know are
+    /// dropped, since there is no agent to link them to.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libreconomy::*;
+    ///
+    /// let legacy = LegacyEmployment {
+    ///     job_status: Some("employed".into()),
+    ///     employer: Some("7".into()),
+    ///     employees: vec!["12".into(), "Acme Inc".into()],
+    /// };
+    /// let employment = legacy.migrate(|name| name.parse().ok().map(AgentId));
+    /// assert_eq!(employment.employer, Some(AgentId(7)));
+    /// assert_eq!(employment.employees, vec![AgentId(12)]);
+    /// ```
+    pub fn migrate(&self, mut resolve: impl FnMut(&str) -> Option<AgentId>) -> Employment {
+        Employment {
+            job_status: self.job_status.clone(),
+            employer: self.employer.as_deref().and_then(&mut resolve),
+            employees: self.employees.iter().filter_map(|name| resolve(name)).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::components::Agent;
+    use pretty_assertions::assert_eq;
+
+    /// Agent `id` employed by `employer`, employing `employees`
+    fn hire(world: &mut World, id: u64, employer: Option<u64>, employees: &[u64]) -> Entity {
+        let employment = Employment {
+            job_status: Some("employed".into()),
+            employer: employer.map(AgentId),
+            employees: employees.iter().copied().map(AgentId).collect(),
+        };
+        world
+            .create_entity()
+            .with(Agent { id: AgentId(id) })
+            .with(employment)
+            .build()
+    }
+
+    #[test]
+    fn test_org_graph_traverses_both_ways() {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Employment>();
+        let owner = hire(&mut world, 1, None, &[2, 3]);
+        let foreman = hire(&mut world, 2, Some(1), &[4, 5]);
+        let clerk = hire(&mut world, 3, Some(1), &[]);
+        let hand_a = hire(&mut world, 4, Some(2), &[]);
+        let hand_b = hire(&mut world, 5, Some(2), &[]);
+
+        assert_eq!(employees_of(&world, owner), vec![foreman, clerk]);
+        assert_eq!(employees_of(&world, foreman), vec![hand_a, hand_b]);
+        assert_eq!(employees_of(&world, hand_a), Vec::new());
+
+        // Walk from the bottom of the chart back to the top
+        let mut chain = vec![hand_b];
+        while let Some(boss) = employer_of(&world, *chain.last().unwrap()) {
+            chain.push(boss);
+        }
+        assert_eq!(chain, vec![hand_b, foreman, owner]);
+        assert_eq!(employer_of(&world, clerk), Some(owner));
+    }
+
+    #[test]
+    fn test_departed_agents_are_skipped() {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Employment>();
+        let owner = hire(&mut world, 1, None, &[2, 3]);
+        let quitter = hire(&mut world, 2, Some(1), &[]);
+        let stayer = hire(&mut world, 3, Some(1), &[]);
+        world.delete_entity(quitter).unwrap();
+        world.maintain();
+
+        assert_eq!(employees_of(&world, owner), vec![stayer]);
+
+        // A stale AgentIndex resource is trusted as given
+        let mut index = AgentIndex::from_world(&world);
+        index.remove(AgentId(1));
+        world.insert(index);
+        assert_eq!(employer_of(&world, stayer), None);
+    }
+}
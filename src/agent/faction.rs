@@ -0,0 +1,338 @@
+//! Faction membership and group reputation
+//! Lets an observer's views of a faction's members spill over onto members it hasn't met
+
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+use super::components::{Agent, ReputationKnowledge, ReputationView};
+use super::identity::AgentId;
+
+/// Evidence weight given to a member the observer has never met
+///
+/// Matches the confidence of a fresh `ReputationView` (the uniform prior), so
+/// an unknown member counts as much as a known one with no interactions yet.
+const UNKNOWN_MEMBER_WEIGHT: f32 = 2.0;
+
+/// Fraction of the gap between `trust_level` and the faction's reputation
+/// that [`stranger_trust`] applies to unknown members
+pub const FACTION_SPILLOVER: f32 = 0.5;
+
+/// Faction (group, clan, company) an agent belongs to
+///
+/// Agents without this component belong to no faction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Faction(pub u32);
+
+impl Component for Faction {
+    type Storage = VecStorage<Self>;
+}
+
+/// Aggregate reputation `observer` holds of a group of agents
+///
+/// Confidence-weighted mean of the observer's scores for `members`: members
+/// with more interactions behind them count for more. Members the observer
+/// has never met contribute its `trust_level` with the weight of a fresh
+/// view. An empty faction has no evidence either way and yields `trust_level`.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let mut rep = ReputationKnowledge::new();
+/// for tick in 0..10 {
+///     rep.update_reputation(AgentId(1), 1.0, tick);
+///     rep.update_reputation(AgentId(2), 1.0, tick);
+/// }
+///
+/// assert!(faction_reputation(&rep, &[AgentId(1), AgentId(2), AgentId(3)]) > 0.7);
+/// assert_eq!(faction_reputation(&rep, &[]), rep.trust_level);
+/// ```
+pub fn faction_reputation(observer: &ReputationKnowledge, members: &[AgentId]) -> f32 {
+    let (weighted, total) = members
+        .iter()
+        .map(|member| match observer.first_hand.get(member) {
+            Some(view) => (view.score(), view.confidence()),
+            None => (observer.trust_level, UNKNOWN_MEMBER_WEIGHT),
+        })
+        .fold((0.0, 0.0), |(sum, weight), (score, confidence)| {
+            (sum + score * confidence, weight + confidence)
+        });
+
+    if total > 0.0 && weighted.is_finite() {
+        weighted / total
+    } else {
+        observer.trust_level
+    }
+}
+
+/// Score `observer` should assume for `target`, given `target`'s faction
+///
+/// Known agents keep their first-hand score. An unknown agent starts at
+/// `trust_level` moved [`FACTION_SPILLOVER`] of the way toward the faction's
+/// aggregate reputation, so strangers from a trusted faction are somewhat
+/// trusted and strangers from a distrusted one somewhat distrusted.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let mut rep = ReputationKnowledge::new();
+/// for tick in 0..10 {
+///     rep.update_reputation(AgentId(1), 1.0, tick);
+/// }
+///
+/// let stranger = AgentId(9);
+/// assert!(stranger_trust(&rep, stranger, &[AgentId(1), stranger]) > rep.get_score(stranger));
+/// ```
+pub fn stranger_trust(observer: &ReputationKnowledge, target: AgentId, members: &[AgentId]) -> f32 {
+    if let Some(view) = observer.first_hand.get(&target) {
+        return view.score();
+    }
+
+    let group = faction_reputation(observer, members);
+    observer.trust_level + FACTION_SPILLOVER * (group - observer.trust_level)
+}
+
+/// Trust `observer` places in `target`, judging strangers by their faction
+///
+/// Agents the observer has met get [`ReputationKnowledge::trust_score`].
+/// A stranger with a [`Faction`] in `world` gets [`stranger_trust`] toward
+/// that faction's members; one without gets `trust_level`.
+pub fn trust_in(world: &World, observer: &ReputationKnowledge, target: AgentId) -> f32 {
+    if observer.first_hand.contains_key(&target) || !world.has_value::<MaskedStorage<Faction>>() {
+        return observer.trust_score(target);
+    }
+
+    let faction = (&world.read_storage::<Agent>(), &world.read_storage::<Faction>())
+        .join()
+        .find(|(agent, _)| agent.id == target)
+        .map(|(_, faction)| *faction);
+    match faction {
+        Some(faction) => stranger_trust(observer, target, &faction_members(world, faction)),
+        None => observer.trust_level,
+    }
+}
+
+/// Ids of every agent in `faction`, sorted
+///
+/// Returns an empty list if the `Faction` component is not registered.
+pub fn faction_members(world: &World, faction: Faction) -> Vec<AgentId> {
+    if !world.has_value::<MaskedStorage<Faction>>() {
+        return Vec::new();
+    }
+
+    let agents = world.read_storage::<Agent>();
+    let factions = world.read_storage::<Faction>();
+    let mut members: Vec<AgentId> = (&agents, &factions)
+        .join()
+        .filter(|(_, member_of)| **member_of == faction)
+        .map(|(agent, _)| agent.id)
+        .collect();
+    members.sort();
+    members
+}
+
+/// Give a newly spawned agent mild trust toward its own faction
+///
+/// Adds a view of each of `faction_members` to `new_agent`'s
+/// `ReputationKnowledge`, as if it had already had `prior` positive
+/// interactions with them (`ReputationView::with_prior(1.0 + prior, 1.0)`).
+/// Agents outside the faction get no view and stay at `trust_level`. Views
+/// the agent already holds, and its own id, are left alone; an agent without
+/// `ReputationKnowledge` gets a default one.
+///
+/// Returns how many views were added: none if `faction_members` is empty,
+/// `prior` is not positive, or `ReputationKnowledge` is not registered.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// register_core_components(&mut world);
+/// world.register::<ReputationKnowledge>();
+/// let child = create_agent(&mut world);
+///
+/// assert_eq!(seed_reputation_from_faction(&world, child, &[AgentId(40), AgentId(41)], 1.0), 2);
+/// let rep = world.read_storage::<ReputationKnowledge>();
+/// assert!(rep.get(child).unwrap().trust_score(AgentId(40)) > 0.5);
+/// ```
+pub fn seed_reputation_from_faction(
+    world: &World,
+    new_agent: Entity,
+    faction_members: &[AgentId],
+    prior: f32,
+) -> usize {
+    if faction_members.is_empty()
+        || !(prior > 0.0 && prior.is_finite())
+        || !world.has_value::<MaskedStorage<ReputationKnowledge>>()
+    {
+        return 0;
+    }
+
+    let own_id = if world.has_value::<MaskedStorage<Agent>>() {
+        world.read_storage::<Agent>().get(new_agent).map(|a| a.id)
+    } else {
+        None
+    };
+    let mut storage = world.write_storage::<ReputationKnowledge>();
+    let Ok(entry) = storage.entry(new_agent) else {
+        return 0;
+    };
+    let knowledge = entry.or_insert_with(ReputationKnowledge::new);
+
+    let mut seeded = 0;
+    for &member in faction_members {
+        if Some(member) == own_id || knowledge.first_hand.contains_key(&member) {
+            continue;
+        }
+        knowledge
+            .first_hand
+            .insert(member, ReputationView::with_prior(1.0 + prior, 1.0));
+        seeded += 1;
+    }
+    seeded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn trusting(partners: &[(u64, f32)]) -> ReputationKnowledge {
+        let mut rep = ReputationKnowledge::new();
+        for &(id, outcome) in partners {
+            for tick in 0..10 {
+                rep.update_reputation(AgentId(id), outcome, tick);
+            }
+        }
+        rep
+    }
+
+    #[test]
+    fn test_mostly_trusted_faction_has_high_aggregate() {
+        let rep = trusting(&[(1, 1.0), (2, 1.0), (3, 1.0), (4, -1.0)]);
+        let members = [AgentId(1), AgentId(2), AgentId(3), AgentId(4)];
+
+        let aggregate = faction_reputation(&rep, &members);
+        assert!(aggregate > 0.65, "aggregate {aggregate}");
+
+        // Unknown members pull toward trust_level, but only with a prior's weight
+        let with_stranger = [AgentId(1), AgentId(2), AgentId(3), AgentId(4), AgentId(5)];
+        let with_strangers = faction_reputation(&rep, &with_stranger);
+        assert!(with_strangers < aggregate && with_strangers > 0.6);
+    }
+
+    #[test]
+    fn test_empty_or_unknown_faction_is_trust_level() {
+        let rep = ReputationKnowledge::with_trust_level(0.3);
+        assert_eq!(faction_reputation(&rep, &[]), 0.3);
+        assert_eq!(faction_reputation(&rep, &[AgentId(1), AgentId(2)]), 0.3);
+        assert_eq!(stranger_trust(&rep, AgentId(3), &[]), 0.3);
+    }
+
+    #[test]
+    fn test_faction_reputation_shapes_stranger_trust() {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Faction>();
+        for (id, faction) in [(1, 7), (2, 7), (3, 7), (4, 8), (5, 8)] {
+            world
+                .create_entity()
+                .with(Agent { id: AgentId(id) })
+                .with(Faction(faction))
+                .build();
+        }
+
+        let rep = trusting(&[(1, 1.0), (2, 1.0), (4, -1.0)]);
+        let friends = faction_members(&world, Faction(7));
+        let rivals = faction_members(&world, Faction(8));
+        assert_eq!(friends, vec![AgentId(1), AgentId(2), AgentId(3)]);
+
+        // Agent 3 is unknown, but its faction is trusted
+        let friendly_stranger = stranger_trust(&rep, AgentId(3), &friends);
+        assert!(friendly_stranger > rep.trust_level);
+        assert!(friendly_stranger < faction_reputation(&rep, &friends));
+
+        // Agent 5 is unknown, and its faction is distrusted
+        assert!(stranger_trust(&rep, AgentId(5), &rivals) < rep.trust_level);
+
+        // First-hand experience wins over the faction
+        assert_eq!(stranger_trust(&rep, AgentId(4), &friends), rep.get_score(AgentId(4)));
+    }
+
+    #[test]
+    fn test_trusted_faction_lets_a_stranger_trade() {
+        use crate::TradeEligibility;
+
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Faction>();
+        world.register::<ReputationKnowledge>();
+        for (id, faction) in [(1, 7), (2, 7), (3, 7)] {
+            world
+                .create_entity()
+                .with(Agent { id: AgentId(id) })
+                .with(Faction(faction))
+                .build();
+        }
+        world.create_entity().with(Agent { id: AgentId(4) }).build();
+        let seller = world.create_entity().with(trusting(&[(1, 1.0), (2, 1.0)])).build();
+
+        // Both strangers sit at trust_level on their own; only 3's faction vouches for it
+        let floor = TradeEligibility::new(0.6);
+        assert_eq!(floor.refusal(&world, AgentId(3), seller), None);
+        assert_eq!(floor.refusal(&world, AgentId(4), seller), Some(0.5));
+    }
+
+    #[test]
+    fn test_spawned_member_trusts_its_faction() {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Faction>();
+        world.register::<ReputationKnowledge>();
+        for (id, faction) in [(1, 7), (2, 7), (3, 8)] {
+            world
+                .create_entity()
+                .with(Agent { id: AgentId(id) })
+                .with(Faction(faction))
+                .build();
+        }
+        let mut grudge = ReputationKnowledge::new();
+        for tick in 0..5 {
+            grudge.update_reputation(AgentId(2), -1.0, tick);
+        }
+        let child = world
+            .create_entity()
+            .with(Agent { id: AgentId(4) })
+            .with(Faction(7))
+            .with(grudge)
+            .build();
+
+        let members = faction_members(&world, Faction(7));
+        assert_eq!(seed_reputation_from_faction(&world, child, &members, 1.0), 1);
+
+        let storage = world.read_storage::<ReputationKnowledge>();
+        let rep = storage.get(child).unwrap();
+        assert!(rep.trust_score(AgentId(1)) > rep.trust_level);
+        assert!(rep.is_trusted(AgentId(1), 0.6));
+        // Existing views, the agent itself and outsiders are untouched
+        assert!(rep.get_score(AgentId(2)) < rep.trust_level);
+        assert!(!rep.first_hand.contains_key(&AgentId(4)));
+        assert_eq!(rep.trust_score(AgentId(3)), rep.trust_level);
+    }
+
+    #[test]
+    fn test_empty_faction_seeds_nothing() {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<ReputationKnowledge>();
+        let loner = world.create_entity().with(Agent { id: AgentId(1) }).build();
+
+        assert_eq!(seed_reputation_from_faction(&world, loner, &[], 1.0), 0);
+        assert_eq!(seed_reputation_from_faction(&world, loner, &[AgentId(2)], 0.0), 0);
+        assert!(world.read_storage::<ReputationKnowledge>().get(loner).is_none());
+    }
+}
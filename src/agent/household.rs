@@ -0,0 +1,337 @@
+//! Households: agents pooling currency in a shared wallet
+//! Members spend from the household wallet up to a per-member allowance
+
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+use std::collections::HashMap;
+
+use super::components::{Agent, Wallet};
+use super::identity::AgentId;
+use crate::systems::{transfer_currency, CurrencyError};
+
+/// Reasons a household operation was refused
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HouseholdError {
+    /// The entity has no `Household` component
+    NotHousehold,
+    /// The entity is not an agent, or not a member of this household
+    NotMember,
+    /// The agent already belongs to a household
+    AlreadyMember(Entity),
+    /// The member's remaining allowance does not cover the amount
+    AllowanceExceeded {
+        /// What the member may still spend this period
+        remaining: f32,
+    },
+    /// The currency movement itself failed
+    Currency(CurrencyError),
+}
+
+impl core::fmt::Display for HouseholdError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            HouseholdError::NotHousehold => write!(f, "entity is not a household"),
+            HouseholdError::NotMember => write!(f, "agent is not a member of the household"),
+            HouseholdError::AlreadyMember(household) => {
+                write!(f, "agent already belongs to household {}", household.id())
+            }
+            HouseholdError::AllowanceExceeded { remaining } => {
+                write!(f, "amount exceeds remaining allowance {}", remaining)
+            }
+            HouseholdError::Currency(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for HouseholdError {}
+
+impl From<CurrencyError> for HouseholdError {
+    fn from(err: CurrencyError) -> Self {
+        HouseholdError::Currency(err)
+    }
+}
+
+/// A group of agents (family, firm) sharing one wallet
+///
+/// Lives on its own entity next to the shared `Wallet`; an `Inventory` on the
+/// same entity serves as a shared stockpile. Each member may take up to
+/// `allowance` from the wallet per period; call
+/// [`reset_spending`](Self::reset_spending) when a period ends.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Household {
+    /// Member agents, in the order they joined
+    pub members: Vec<AgentId>,
+    /// Most each member may take from the shared wallet per period
+    pub allowance: f32,
+    /// Amount each member has taken this period
+    #[serde(serialize_with = "crate::snapshot::sorted_map")]
+    spent: HashMap<AgentId, f32>,
+}
+
+impl Component for Household {
+    type Storage = VecStorage<Self>;
+}
+
+impl Household {
+    /// An empty household with a per-member allowance
+    pub fn new(allowance: f32) -> Self {
+        Self {
+            allowance,
+            ..Default::default()
+        }
+    }
+
+    /// Check if `agent` is a member
+    pub fn is_member(&self, agent: AgentId) -> bool {
+        self.members.contains(&agent)
+    }
+
+    /// What `agent` may still take this period (0 for non-members)
+    pub fn remaining_allowance(&self, agent: AgentId) -> f32 {
+        if !self.is_member(agent) {
+            return 0.0;
+        }
+        let spent = self.spent.get(&agent).copied().unwrap_or(0.0);
+        (self.allowance - spent).max(0.0)
+    }
+
+    /// Start a new period: every member's full allowance is available again
+    pub fn reset_spending(&mut self) {
+        self.spent.clear();
+    }
+}
+
+/// Create a household entity with an empty shared wallet
+///
+/// Registers `Household` and `Wallet` if needed.
+pub fn create_household(world: &mut World, allowance: f32) -> Entity {
+    if !world.has_value::<MaskedStorage<Household>>() {
+        world.register::<Household>();
+    }
+    if !world.has_value::<MaskedStorage<Wallet>>() {
+        world.register::<Wallet>();
+    }
+    world
+        .create_entity()
+        .with(Household::new(allowance))
+        .with(Wallet::new(0.0))
+        .build()
+}
+
+/// Household `agent` belongs to, if any
+pub fn household_of(world: &World, agent: AgentId) -> Option<Entity> {
+    if !world.has_value::<MaskedStorage<Household>>() {
+        return None;
+    }
+    let entities = world.entities();
+    let households = world.read_storage::<Household>();
+    (&entities, &households)
+        .join()
+        .find(|(_, household)| household.is_member(agent))
+        .map(|(entity, _)| entity)
+}
+
+/// Add `agent` to `household`
+///
+/// An agent belongs to at most one household, so no one can draw on two
+/// shared wallets. Re-adding a member is a no-op.
+///
+/// # Errors
+/// [`HouseholdError::AlreadyMember`] if the agent is in another household,
+/// [`HouseholdError::NotHousehold`] if `household` isn't one
+pub fn add_member(world: &mut World, household: Entity, agent: AgentId) -> Result<(), HouseholdError> {
+    match household_of(world, agent) {
+        Some(current) if current == household => return Ok(()),
+        Some(other) => return Err(HouseholdError::AlreadyMember(other)),
+        None => {}
+    }
+    if !world.has_value::<MaskedStorage<Household>>() {
+        return Err(HouseholdError::NotHousehold);
+    }
+    let mut households = world.write_storage::<Household>();
+    let target = households.get_mut(household).ok_or(HouseholdError::NotHousehold)?;
+    target.members.push(agent);
+    Ok(())
+}
+
+/// Remove `agent` from `household`, forgetting what it spent this period
+///
+/// Returns whether the agent was a member.
+pub fn remove_member(world: &mut World, household: Entity, agent: AgentId) -> bool {
+    if !world.has_value::<MaskedStorage<Household>>() {
+        return false;
+    }
+    let mut households = world.write_storage::<Household>();
+    match households.get_mut(household) {
+        Some(target) if target.is_member(agent) => {
+            target.members.retain(|member| *member != agent);
+            target.spent.remove(&agent);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Pay `amount` from `household`'s wallet to `to`, charged to `member`
+///
+/// All or nothing: the member's allowance and the wallet must both cover the
+/// amount. The currency leaves the shared wallet, so it can't be spent again
+/// from either side.
+///
+/// # Errors
+/// [`HouseholdError::NotMember`], [`HouseholdError::NotHousehold`],
+/// [`HouseholdError::AllowanceExceeded`] or [`HouseholdError::Currency`]
+pub fn pay_from_household(
+    world: &mut World,
+    household: Entity,
+    member: Entity,
+    to: Entity,
+    amount: f32,
+) -> Result<(), HouseholdError> {
+    let agent = world
+        .read_storage::<Agent>()
+        .get(member)
+        .map(|a| a.id)
+        .ok_or(HouseholdError::NotMember)?;
+    if !world.has_value::<MaskedStorage<Household>>() {
+        return Err(HouseholdError::NotHousehold);
+    }
+
+    let remaining = {
+        let households = world.read_storage::<Household>();
+        let target = households.get(household).ok_or(HouseholdError::NotHousehold)?;
+        if !target.is_member(agent) {
+            return Err(HouseholdError::NotMember);
+        }
+        target.remaining_allowance(agent)
+    };
+    if amount > remaining {
+        return Err(HouseholdError::AllowanceExceeded { remaining });
+    }
+
+    transfer_currency(world, household, to, amount)?;
+    if let Some(target) = world.write_storage::<Household>().get_mut(household) {
+        *target.spent.entry(agent).or_insert(0.0) += amount;
+    }
+    Ok(())
+}
+
+/// Move `amount` from `household`'s wallet into `member`'s own wallet
+///
+/// Same checks as [`pay_from_household`], with the member as payee.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// register_core_components(&mut world);
+/// let agent = create_agent(&mut world);
+/// let home = create_household(&mut world, 30.0);
+/// world.write_storage::<Wallet>().get_mut(home).unwrap().deposit(100.0);
+///
+/// add_member(&mut world, home, AgentId(1)).unwrap();
+/// draw_from_household(&mut world, home, agent, 20.0).unwrap();
+/// assert!(draw_from_household(&mut world, home, agent, 20.0).is_err());
+/// ```
+///
+/// # Errors
+/// See [`pay_from_household`]
+pub fn draw_from_household(
+    world: &mut World,
+    household: Entity,
+    member: Entity,
+    amount: f32,
+) -> Result<(), HouseholdError> {
+    pay_from_household(world, household, member, member, amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::total_currency;
+    use pretty_assertions::assert_eq;
+
+    fn world_with_household(funds: f32, allowance: f32) -> (World, Entity, Entity, Entity) {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Wallet>();
+        let alice = world
+            .create_entity()
+            .with(Agent { id: AgentId(1) })
+            .with(Wallet::new(5.0))
+            .build();
+        let bob = world
+            .create_entity()
+            .with(Agent { id: AgentId(2) })
+            .with(Wallet::new(0.0))
+            .build();
+        let home = create_household(&mut world, allowance);
+        world.write_storage::<Wallet>().get_mut(home).unwrap().deposit(funds);
+        (world, home, alice, bob)
+    }
+
+    fn wallet(world: &World, entity: Entity) -> f32 {
+        world.read_storage::<Wallet>().get(entity).unwrap().currency
+    }
+
+    #[test]
+    fn test_member_spends_up_to_allowance_and_no_further() {
+        let (mut world, home, alice, bob) = world_with_household(100.0, 40.0);
+        add_member(&mut world, home, AgentId(1)).unwrap();
+        let total = total_currency(&world);
+
+        draw_from_household(&mut world, home, alice, 25.0).unwrap();
+        pay_from_household(&mut world, home, alice, bob, 15.0).unwrap();
+        assert_eq!((wallet(&world, alice), wallet(&world, bob)), (30.0, 15.0));
+        assert_eq!(wallet(&world, home), 60.0);
+
+        assert_eq!(
+            draw_from_household(&mut world, home, alice, 0.5),
+            Err(HouseholdError::AllowanceExceeded { remaining: 0.0 })
+        );
+        assert_eq!(wallet(&world, home), 60.0);
+        assert_eq!(total_currency(&world), total);
+
+        // A new period restores the allowance
+        world.write_storage::<Household>().get_mut(home).unwrap().reset_spending();
+        draw_from_household(&mut world, home, alice, 40.0).unwrap();
+        assert_eq!(wallet(&world, home), 20.0);
+    }
+
+    #[test]
+    fn test_allowance_cannot_exceed_shared_wallet() {
+        let (mut world, home, alice, _) = world_with_household(10.0, 50.0);
+        add_member(&mut world, home, AgentId(1)).unwrap();
+
+        assert_eq!(
+            draw_from_household(&mut world, home, alice, 20.0),
+            Err(HouseholdError::Currency(CurrencyError::InsufficientFunds))
+        );
+        // A failed draw doesn't use up allowance
+        let households = world.read_storage::<Household>();
+        assert_eq!(households.get(home).unwrap().remaining_allowance(AgentId(1)), 50.0);
+    }
+
+    #[test]
+    fn test_membership_is_exclusive() {
+        let (mut world, home, _, bob) = world_with_household(100.0, 10.0);
+        let other = create_household(&mut world, 10.0);
+        add_member(&mut world, home, AgentId(2)).unwrap();
+        add_member(&mut world, home, AgentId(2)).unwrap();
+
+        assert_eq!(add_member(&mut world, other, AgentId(2)), Err(HouseholdError::AlreadyMember(home)));
+        assert_eq!(household_of(&world, AgentId(2)), Some(home));
+        assert_eq!(world.read_storage::<Household>().get(home).unwrap().members.len(), 1);
+
+        // Former members lose access
+        assert!(remove_member(&mut world, home, AgentId(2)));
+        assert_eq!(
+            draw_from_household(&mut world, home, bob, 1.0),
+            Err(HouseholdError::NotMember)
+        );
+        add_member(&mut world, other, AgentId(2)).unwrap();
+    }
+}
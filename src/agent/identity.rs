@@ -2,15 +2,19 @@
 //! Data-only module for AgentId and its allocator resource
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 /// Strongly-typed identifier for agents
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct AgentId(pub u64);
 
 /// Errors that can occur when allocating a new AgentId
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AgentIdError {
     /// Exhausted all available AgentId values
+    ///
+    /// The counter has reached `u64::MAX`, which is never handed out, so ids
+    /// cannot wrap around and collide with live agents.
     Overflow,
 }
 
@@ -27,29 +31,74 @@ impl std::error::Error for AgentIdError {}
 /// Resource responsible for generating unique AgentId values
 ///
 /// Insert this into the ECS world as a resource when you need to
-/// allocate IDs during entity creation.
+/// allocate IDs during entity creation. Ids given back with
+/// [`release`](Self::release) are reused, lowest first, before new ones.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AgentIdAllocator {
     next: u64,
+    #[serde(default)]
+    released: BTreeSet<u64>,
 }
 
 impl AgentIdAllocator {
     /// Create a new allocator starting at 1
     pub fn new() -> Self {
-        Self { next: 1 }
+        Self { next: 1, released: BTreeSet::new() }
+    }
+
+    /// Create an allocator whose next id is `next` (e.g. when loading a save)
+    pub fn starting_at(next: AgentId) -> Self {
+        Self { next: next.0, released: BTreeSet::new() }
     }
 
     /// Allocate the next unique AgentId
+    ///
+    /// Returns [`AgentIdError::Overflow`] instead of wrapping once every id
+    /// below `u64::MAX` has been issued; the allocator is left unchanged, so
+    /// later calls keep failing the same way.
     pub fn allocate(&mut self) -> Result<AgentId, AgentIdError> {
+        if let Some(id) = self.released.pop_first() {
+            return Ok(AgentId(id));
+        }
         let id = self.next;
         // checked_add to prevent overflow in release builds as well
-        self.next = self.next.checked_add(1).ok_or(AgentIdError::Overflow)?;
+        let Some(next) = self.next.checked_add(1) else {
+            log_warn!("agent ids exhausted at {}", id);
+            return Err(AgentIdError::Overflow);
+        };
+        self.next = next;
         Ok(AgentId(id))
     }
 
+    /// Allocate `count` consecutive AgentIds at once
+    ///
+    /// All or nothing: on overflow no ids are consumed. Released ids are not
+    /// used, so a batch is always one unbroken run.
+    pub fn allocate_many(&mut self, count: usize) -> Result<Vec<AgentId>, AgentIdError> {
+        let start = self.next;
+        let end = u64::try_from(count)
+            .ok()
+            .and_then(|n| start.checked_add(n))
+            .ok_or_else(|| {
+                log_warn!("cannot allocate {} agent ids starting at {}", count, start);
+                AgentIdError::Overflow
+            })?;
+        self.next = end;
+        Ok((start..end).map(AgentId).collect())
+    }
+
     /// Peek the next value without consuming it (useful for testing)
     pub fn peek(&self) -> AgentId {
-        AgentId(self.next)
+        AgentId(self.released.first().copied().unwrap_or(self.next))
+    }
+
+    /// Hand `id` back for reuse once its agent is gone
+    ///
+    /// Ids this allocator never issued, or already holds, are ignored.
+    pub fn release(&mut self, id: AgentId) {
+        if id.0 < self.next {
+            self.released.insert(id.0);
+        }
     }
 }
 
@@ -58,3 +107,46 @@ impl Default for AgentIdAllocator {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_allocator_at_boundary_reports_overflow() {
+        let mut allocator = AgentIdAllocator::starting_at(AgentId(u64::MAX - 1));
+        assert_eq!(allocator.allocate(), Ok(AgentId(u64::MAX - 1)));
+        assert_eq!(allocator.allocate(), Err(AgentIdError::Overflow));
+        // Exhaustion is sticky rather than wrapping back to 0
+        assert_eq!(allocator.allocate(), Err(AgentIdError::Overflow));
+        assert_eq!(allocator.peek(), AgentId(u64::MAX));
+
+        let mut batch = AgentIdAllocator::starting_at(AgentId(u64::MAX - 2));
+        assert_eq!(batch.allocate_many(3), Err(AgentIdError::Overflow));
+        assert_eq!(batch.allocate_many(2), Ok(vec![AgentId(u64::MAX - 2), AgentId(u64::MAX - 1)]));
+    }
+
+    #[test]
+    fn test_released_ids_are_reused_lowest_first() {
+        let mut allocator = AgentIdAllocator::new();
+        let ids = allocator.allocate_many(4).unwrap();
+        allocator.release(ids[2]);
+        allocator.release(ids[0]);
+        allocator.release(AgentId(99));
+
+        assert_eq!(allocator.peek(), AgentId(1));
+        assert_eq!(allocator.allocate(), Ok(AgentId(1)));
+        assert_eq!(allocator.allocate(), Ok(AgentId(3)));
+        assert_eq!(allocator.allocate(), Ok(AgentId(5)));
+        // Batches stay consecutive
+        allocator.release(AgentId(2));
+        assert_eq!(allocator.allocate_many(2), Ok(vec![AgentId(6), AgentId(7)]));
+    }
+
+    #[test]
+    fn test_overflow_error_displays_reason() {
+        let err: Box<dyn std::error::Error> = Box::new(AgentIdError::Overflow);
+        assert_eq!(err.to_string(), "AgentId overflow: no more IDs available");
+    }
+}
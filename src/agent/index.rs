@@ -0,0 +1,84 @@
+//! AgentId to Entity lookup
+//! Data-only index resolving stable agent ids back to ECS entities
+
+use specs::prelude::*;
+use std::collections::HashMap;
+use super::components::Agent;
+use super::identity::AgentId;
+
+/// Lookup table from [`AgentId`] to the [`Entity`] carrying it
+///
+/// Spatial queries, reputation and employment all speak in `AgentId`s while
+/// component storages are keyed by `Entity`. Build an index once per tick
+/// (or keep it updated as agents are created/removed) to avoid a full join
+/// for every lookup.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Agent>();
+/// world.register::<Needs>();
+/// world.register::<Inventory>();
+/// world.register::<Wallet>();
+/// world.insert(AgentIdAllocator::new());
+///
+/// let entity = create_agent(&mut world);
+/// let index = AgentIndex::from_world(&world);
+/// assert_eq!(index.get(AgentId(1)), Some(entity));
+/// assert_eq!(index.get(AgentId(99)), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AgentIndex {
+    entities: HashMap<AgentId, Entity>,
+}
+
+impl AgentIndex {
+    /// Create an empty index
+    pub fn new() -> Self {
+        Self {
+            entities: HashMap::new(),
+        }
+    }
+
+    /// Build an index from every live entity with an [`Agent`] component
+    ///
+    /// # Panics
+    /// Panics if the `Agent` component is not registered in the world
+    pub fn from_world(world: &World) -> Self {
+        let entities = world.entities();
+        let agents = world.read_storage::<Agent>();
+        let mut index = Self::new();
+        for (entity, agent) in (&entities, &agents).join() {
+            index.insert(agent.id, entity);
+        }
+        index
+    }
+
+    /// Record (or replace) the entity for an agent id
+    pub fn insert(&mut self, id: AgentId, entity: Entity) {
+        self.entities.insert(id, entity);
+    }
+
+    /// Forget an agent id, returning the entity it pointed to
+    pub fn remove(&mut self, id: AgentId) -> Option<Entity> {
+        self.entities.remove(&id)
+    }
+
+    /// Resolve an agent id to its entity
+    pub fn get(&self, id: AgentId) -> Option<Entity> {
+        self.entities.get(&id).copied()
+    }
+
+    /// Number of indexed agents
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Check if the index is empty
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
@@ -0,0 +1,218 @@
+//! Agent inspection for debugging
+//! Gathers an agent's full economic state into one serializable report
+
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+use super::components::{
+    Agent, Employment, Inventory, Needs, ReputationKnowledge, Skills, SpeciesComponent, Wallet,
+};
+use super::identity::AgentId;
+use crate::items::ItemRegistry;
+
+/// Number of trusted partners included in an [`AgentReport`]
+pub const REPORT_TRUSTED_PARTNERS: usize = 5;
+
+/// One inventory entry valued with the item registry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InventoryLine {
+    pub item: String,
+    pub quantity: u32,
+    /// Registry value of one unit
+    pub unit_value: f32,
+    /// `quantity * unit_value`
+    pub total_value: f32,
+}
+
+/// A partner the agent trusts, with its reputation score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrustedPartner {
+    pub id: AgentId,
+    pub score: f32,
+}
+
+/// Snapshot of an agent's economic state
+///
+/// Components the entity doesn't have are `None` (null in JSON) or empty.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentReport {
+    pub id: Option<AgentId>,
+    pub needs: Option<Needs>,
+    /// Wallet balance
+    pub currency: Option<f32>,
+    /// Inventory entries sorted by item id
+    pub inventory: Vec<InventoryLine>,
+    /// Sum of all inventory `total_value`s
+    pub inventory_value: f32,
+    pub skills: Option<Skills>,
+    pub employment: Option<Employment>,
+    pub species: Option<SpeciesComponent>,
+    /// Most trusted partners, highest score first
+    pub trusted_partners: Vec<TrustedPartner>,
+}
+
+/// Clone the entity's `T` if the storage is registered and the entity has one
+fn component<T: Component + Clone>(world: &World, entity: Entity) -> Option<T> {
+    if !world.has_value::<MaskedStorage<T>>() {
+        return None;
+    }
+    world.read_storage::<T>().get(entity).cloned()
+}
+
+/// Build a debug report of an agent's full economic state
+///
+/// Inventory items are valued with `registry` (unregistered items use the
+/// default item value). Up to [`REPORT_TRUSTED_PARTNERS`] trusted partners are
+/// listed from the agent's own `ReputationKnowledge`.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Agent>();
+/// world.register::<Needs>();
+/// world.register::<Inventory>();
+/// world.register::<Wallet>();
+/// world.insert(AgentIdAllocator::new());
+///
+/// let agent = create_agent(&mut world);
+/// let report = inspect_agent(&world, agent, &ItemRegistry::with_defaults());
+///
+/// assert_eq!(report.currency, Some(100.0));
+/// assert!(report.skills.is_none());
+/// println!("{}", serde_json::to_string_pretty(&report).unwrap());
+/// ```
+pub fn inspect_agent(world: &World, entity: Entity, registry: &ItemRegistry) -> AgentReport {
+    let mut inventory: Vec<InventoryLine> = component::<Inventory>(world, entity)
+        .map(|inv| {
+            inv.items
+                .into_iter()
+                .map(|(item, quantity)| {
+                    let unit_value = registry.value_of(&item);
+                    InventoryLine {
+                        item,
+                        quantity,
+                        unit_value,
+                        total_value: unit_value * quantity as f32,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    inventory.sort_by(|a, b| a.item.cmp(&b.item));
+    let inventory_value = inventory.iter().map(|line| line.total_value).sum();
+
+    let trusted_partners = component::<ReputationKnowledge>(world, entity)
+        .map(|knowledge| {
+            knowledge
+                .get_most_trusted(REPORT_TRUSTED_PARTNERS)
+                .into_iter()
+                .map(|(id, score)| TrustedPartner { id, score })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    AgentReport {
+        id: component::<Agent>(world, entity).map(|agent| agent.id),
+        needs: component::<Needs>(world, entity),
+        currency: component::<Wallet>(world, entity).map(|wallet| wallet.currency),
+        inventory,
+        inventory_value,
+        skills: component::<Skills>(world, entity),
+        employment: component::<Employment>(world, entity),
+        species: component::<SpeciesComponent>(world, entity),
+        trusted_partners,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::ItemType;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    fn setup_world() -> World {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Needs>();
+        world.register::<Inventory>();
+        world.register::<Wallet>();
+        world.register::<Skills>();
+        world.register::<Employment>();
+        world.register::<SpeciesComponent>();
+        world.register::<ReputationKnowledge>();
+        world
+    }
+
+    #[test]
+    fn test_inspect_fully_configured_agent() {
+        let mut world = setup_world();
+        let mut registry = ItemRegistry::new();
+        registry.register(ItemType::new("gold".into(), HashMap::new(), false).with_value(10.0));
+
+        let mut inventory = Inventory::default();
+        inventory.add("gold", 3);
+        inventory.add("bread", 2);
+        let mut skills = Skills::default();
+        skills.skills.insert("farming".into(), 4);
+        let employment = Employment {
+            job_status: Some("employed".into()),
+            ..Default::default()
+        };
+        let mut knowledge = ReputationKnowledge::new();
+        knowledge.update_reputation(AgentId(2), 5.0, 0);
+
+        let entity = world
+            .create_entity()
+            .with(Agent { id: AgentId(1) })
+            .with(Needs::new(10.0, 20.0, 30.0))
+            .with(inventory)
+            .with(Wallet::new(42.0))
+            .with(skills.clone())
+            .with(employment.clone())
+            .with(SpeciesComponent::human())
+            .with(knowledge)
+            .build();
+
+        let report = inspect_agent(&world, entity, &registry);
+
+        assert_eq!(report.id, Some(AgentId(1)));
+        assert_eq!(report.needs, Some(Needs::new(10.0, 20.0, 30.0)));
+        assert_eq!(report.currency, Some(42.0));
+        assert_eq!(report.inventory.len(), 2);
+        assert_eq!(report.inventory[0].item, "bread");
+        assert_eq!(report.inventory[1].total_value, 30.0);
+        // bread is unregistered: default value 1.0
+        assert_eq!(report.inventory_value, 32.0);
+        assert_eq!(report.skills, Some(skills));
+        assert_eq!(report.employment, Some(employment));
+        assert_eq!(report.species, Some(SpeciesComponent::human()));
+        assert_eq!(report.trusted_partners.len(), 1);
+        assert_eq!(report.trusted_partners[0].id, AgentId(2));
+    }
+
+    #[test]
+    fn test_inspect_bare_agent() {
+        let mut world = setup_world();
+        let entity = world.create_entity().with(Agent { id: AgentId(7) }).build();
+
+        let report = inspect_agent(&world, entity, &ItemRegistry::with_defaults());
+
+        assert_eq!(report.id, Some(AgentId(7)));
+        assert!(report.needs.is_none());
+        assert!(report.currency.is_none());
+        assert!(report.inventory.is_empty());
+        assert!(report.skills.is_none());
+        assert!(report.employment.is_none());
+        assert!(report.species.is_none());
+        assert!(report.trusted_partners.is_empty());
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert!(json["needs"].is_null());
+        assert!(json["species"].is_null());
+    }
+}
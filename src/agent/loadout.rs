@@ -0,0 +1,330 @@
+//! Per-species starting loadouts
+//! Data-driven starting inventory and wallet for agents of a given species
+
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use std::collections::HashMap;
+
+use super::components::{Inventory, Needs, Species, SpeciesComponent, Wallet};
+use specs::storage::MaskedStorage;
+
+use super::creation::{
+    try_create_agent_custom, CreationError, DEFAULT_CURRENCY, DEFAULT_HUNGER, DEFAULT_THIRST, DEFAULT_TIREDNESS,
+};
+
+/// Starting items and currency for a new agent
+///
+/// # Example
+/// ```rust
+/// use libreconomy::Loadout;
+///
+/// let loadout = Loadout::new(25.0).with_item("food", 3).with_item("basket", 1);
+/// assert_eq!(loadout.inventory().quantity("food"), 3);
+/// assert_eq!(loadout.wallet().currency, 25.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Loadout {
+    /// Starting item quantities
+    pub items: HashMap<String, u32>,
+    /// Starting currency
+    pub currency: f32,
+}
+
+impl Loadout {
+    /// Loadout with no items and the given currency
+    pub fn new(currency: f32) -> Self {
+        Self {
+            items: HashMap::new(),
+            currency,
+        }
+    }
+
+    /// Add a starting item (quantities for the same item accumulate)
+    pub fn with_item(mut self, item_id: &str, quantity: u32) -> Self {
+        let entry = self.items.entry(item_id.to_string()).or_insert(0);
+        *entry = entry.saturating_add(quantity);
+        self
+    }
+
+    /// Build the starting inventory
+    pub fn inventory(&self) -> Inventory {
+        let mut inventory = Inventory::default();
+        for (item_id, quantity) in &self.items {
+            inventory.add(item_id, *quantity);
+        }
+        inventory
+    }
+
+    /// Build the starting wallet (negative currency is clamped to 0)
+    pub fn wallet(&self) -> Wallet {
+        Wallet::new(self.currency)
+    }
+}
+
+impl Default for Loadout {
+    fn default() -> Self {
+        Self::new(DEFAULT_CURRENCY)
+    }
+}
+
+/// Registry of starting loadouts by species
+///
+/// Insert into the world as a resource to have [`create_agent_of_species`]
+/// equip new agents. Species without an entry get the default loadout
+/// (empty inventory, default currency).
+///
+/// Serializes as a list of `{"species": .., "loadout": ..}` entries, since
+/// `Species::Custom` cannot be a JSON object key.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let loadouts: SpeciesLoadout = serde_json::from_str(
+///     r#"[{"species": "Human", "loadout": {"items": {"food": 2}, "currency": 40.0}}]"#,
+/// )
+/// .unwrap();
+/// assert_eq!(loadouts.get(Species::Human), Some(&Loadout::new(40.0).with_item("food", 2)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Vec<SpeciesLoadoutEntry>", into = "Vec<SpeciesLoadoutEntry>")]
+pub struct SpeciesLoadout {
+    loadouts: HashMap<Species, Loadout>,
+}
+
+/// Serialized form of one [`SpeciesLoadout`] entry
+#[derive(Serialize, Deserialize)]
+struct SpeciesLoadoutEntry {
+    species: Species,
+    loadout: Loadout,
+}
+
+impl From<Vec<SpeciesLoadoutEntry>> for SpeciesLoadout {
+    fn from(entries: Vec<SpeciesLoadoutEntry>) -> Self {
+        Self {
+            loadouts: entries.into_iter().map(|e| (e.species, e.loadout)).collect(),
+        }
+    }
+}
+
+impl From<SpeciesLoadout> for Vec<SpeciesLoadoutEntry> {
+    fn from(registry: SpeciesLoadout) -> Self {
+        registry
+            .loadouts
+            .into_iter()
+            .map(|(species, loadout)| SpeciesLoadoutEntry { species, loadout })
+            .collect()
+    }
+}
+
+impl SpeciesLoadout {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            loadouts: HashMap::new(),
+        }
+    }
+
+    /// Set (or replace) the loadout for a species
+    pub fn set(&mut self, species: Species, loadout: Loadout) {
+        self.loadouts.insert(species, loadout);
+    }
+
+    /// Builder-style [`set`](Self::set)
+    pub fn with(mut self, species: Species, loadout: Loadout) -> Self {
+        self.set(species, loadout);
+        self
+    }
+
+    /// Loadout for a species, if one is configured
+    pub fn get(&self, species: Species) -> Option<&Loadout> {
+        self.loadouts.get(&species)
+    }
+}
+
+/// Create an agent of the given species, equipped from the world's loadouts
+///
+/// Uses default needs. If a [`SpeciesLoadout`] resource is present and has an
+/// entry for the species, the agent starts with that inventory and wallet;
+/// otherwise it gets the default loadout.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Agent>();
+/// world.register::<Needs>();
+/// world.register::<Inventory>();
+/// world.register::<Wallet>();
+/// world.register::<SpeciesComponent>();
+/// world.insert(AgentIdAllocator::new());
+/// world.insert(SpeciesLoadout::new().with(Species::Human, Loadout::new(40.0).with_item("food", 2)));
+///
+/// let human = create_agent_of_species(&mut world, SpeciesComponent::human());
+///
+/// let inventories = world.read_storage::<Inventory>();
+/// assert_eq!(inventories.get(human).unwrap().quantity("food"), 2);
+/// ```
+///
+/// # Panics
+/// Panics if AgentIdAllocator resource is not registered in the world
+/// Panics if required component types (including `SpeciesComponent`) are not
+/// registered; nothing is created when a check fails. See
+/// [`try_create_agent_of_species`] for a non-panicking variant
+pub fn create_agent_of_species(world: &mut World, species: SpeciesComponent) -> Entity {
+    try_create_agent_of_species(world, species).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Create an agent of the given species, without panicking
+///
+/// Checks that `SpeciesComponent` (as well as everything
+/// [`try_create_agent_custom`] needs) is registered before allocating an id,
+/// so a failed call leaves the world unchanged.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Agent>();
+/// world.register::<Needs>();
+/// world.register::<Inventory>();
+/// world.register::<Wallet>();
+/// world.insert(AgentIdAllocator::new());
+///
+/// let result = try_create_agent_of_species(&mut world, SpeciesComponent::human());
+/// assert_eq!(result, Err(CreationError::MissingComponentRegistration("SpeciesComponent")));
+/// ```
+pub fn try_create_agent_of_species(
+    world: &mut World,
+    species: SpeciesComponent,
+) -> Result<Entity, CreationError> {
+    if !world.has_value::<MaskedStorage<SpeciesComponent>>() {
+        return Err(CreationError::MissingComponentRegistration("SpeciesComponent"));
+    }
+
+    let loadout = world
+        .try_fetch::<SpeciesLoadout>()
+        .and_then(|loadouts| loadouts.get(species.species).cloned())
+        .unwrap_or_default();
+
+    let entity = try_create_agent_custom(
+        world,
+        Needs::new(DEFAULT_THIRST, DEFAULT_HUNGER, DEFAULT_TIREDNESS),
+        loadout.inventory(),
+        loadout.wallet(),
+    )?;
+
+    world
+        .write_storage::<SpeciesComponent>()
+        .insert(entity, species)
+        .expect("Failed to insert SpeciesComponent");
+
+    Ok(entity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::components::Agent;
+    use crate::agent::identity::{AgentId, AgentIdAllocator};
+    use pretty_assertions::assert_eq;
+
+    fn setup_world() -> World {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Needs>();
+        world.register::<Inventory>();
+        world.register::<Wallet>();
+        world.register::<SpeciesComponent>();
+        world.insert(AgentIdAllocator::new());
+        world
+    }
+
+    #[test]
+    fn test_configured_species_gets_loadout() {
+        let mut world = setup_world();
+        world.insert(
+            SpeciesLoadout::new()
+                .with(Species::Human, Loadout::new(40.0).with_item("food", 3))
+                .with(Species::Custom(7), Loadout::new(0.0).with_item("basket", 1)),
+        );
+
+        let human = create_agent_of_species(&mut world, SpeciesComponent::human());
+        let forager = create_agent_of_species(
+            &mut world,
+            SpeciesComponent::custom(7, SpeciesComponent::human().diet),
+        );
+
+        let inventories = world.read_storage::<Inventory>();
+        let wallets = world.read_storage::<Wallet>();
+        let species = world.read_storage::<SpeciesComponent>();
+
+        assert_eq!(inventories.get(human).unwrap().quantity("food"), 3);
+        assert_eq!(wallets.get(human).unwrap().currency, 40.0);
+        assert_eq!(species.get(human).unwrap().species, Species::Human);
+
+        assert_eq!(inventories.get(forager).unwrap().quantity("basket"), 1);
+        assert_eq!(wallets.get(forager).unwrap().currency, 0.0);
+    }
+
+    #[test]
+    fn test_unconfigured_species_gets_default_loadout() {
+        let mut world = setup_world();
+        world.insert(SpeciesLoadout::new().with(Species::Human, Loadout::new(40.0)));
+
+        let rabbit = create_agent_of_species(&mut world, SpeciesComponent::rabbit());
+
+        let inventories = world.read_storage::<Inventory>();
+        let wallets = world.read_storage::<Wallet>();
+        assert!(inventories.get(rabbit).unwrap().items.is_empty());
+        assert_eq!(wallets.get(rabbit).unwrap().currency, DEFAULT_CURRENCY);
+    }
+
+    #[test]
+    fn test_no_registry_uses_default_loadout() {
+        let mut world = setup_world();
+        let human = create_agent_of_species(&mut world, SpeciesComponent::human());
+
+        let wallets = world.read_storage::<Wallet>();
+        assert_eq!(wallets.get(human).unwrap().currency, DEFAULT_CURRENCY);
+    }
+
+    #[test]
+    fn test_loadout_from_json() {
+        let loadout: Loadout =
+            serde_json::from_str(r#"{"items": {"food": 2, "basket": 1}, "currency": 15.0}"#)
+                .unwrap();
+        assert_eq!(loadout, Loadout::new(15.0).with_item("food", 2).with_item("basket", 1));
+    }
+
+    #[test]
+    fn test_species_loadout_json_round_trip() {
+        let loadouts = SpeciesLoadout::new()
+            .with(Species::Human, Loadout::new(40.0).with_item("food", 3))
+            .with(Species::Custom(7), Loadout::new(0.0).with_item("basket", 1));
+
+        let json = serde_json::to_string(&loadouts).unwrap();
+        let restored: SpeciesLoadout = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, loadouts);
+    }
+
+    #[test]
+    fn test_missing_species_registration_creates_nothing() {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Needs>();
+        world.register::<Inventory>();
+        world.register::<Wallet>();
+        world.insert(AgentIdAllocator::new());
+
+        let result = try_create_agent_of_species(&mut world, SpeciesComponent::human());
+
+        assert_eq!(result, Err(CreationError::MissingComponentRegistration("SpeciesComponent")));
+        assert_eq!(world.read_storage::<Agent>().join().count(), 0);
+        assert_eq!(world.read_resource::<AgentIdAllocator>().peek(), AgentId(1));
+    }
+}
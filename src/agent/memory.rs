@@ -0,0 +1,265 @@
+//! Spatial memory of recently visited resources
+//! Lets agents head back to known resources when none are currently visible
+
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+
+use super::identity::AgentId;
+
+/// Default number of remembered resource locations
+pub const DEFAULT_MEMORY_CAPACITY: usize = 16;
+
+/// Default number of ticks a memory stays valid
+pub const DEFAULT_MEMORY_TTL: u64 = 1000;
+
+/// A resource location the agent has seen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RememberedResource {
+    /// Resource or item type (e.g., "water", "grass")
+    pub resource_type: String,
+    /// X coordinate in world space
+    pub x: f32,
+    /// Y coordinate in world space
+    pub y: f32,
+    /// Tick at which the resource was last seen
+    pub tick: u64,
+    /// Agent that told us about it; `None` for first-hand sightings
+    #[serde(default)]
+    pub tipped_by: Option<AgentId>,
+}
+
+/// Agent memory of recently seen resource locations
+///
+/// Holds at most `capacity` entries; when full, the oldest entry is evicted.
+/// Entries older than `ttl` ticks are ignored by [`recall`](Self::recall) and
+/// dropped by [`forget_expired`](Self::forget_expired).
+///
+/// # Example
+/// ```rust
+/// use libreconomy::SpatialMemory;
+///
+/// let mut memory = SpatialMemory::new(4, 100);
+/// memory.remember("water", 10.0, 20.0, 5);
+///
+/// assert_eq!(memory.recall("water", 50).map(|m| (m.x, m.y)), Some((10.0, 20.0)));
+/// assert!(memory.recall("water", 200).is_none()); // expired
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpatialMemory {
+    /// Remembered locations, oldest first
+    pub entries: Vec<RememberedResource>,
+    /// Maximum number of entries kept
+    pub capacity: usize,
+    /// Ticks after which an entry is considered stale
+    pub ttl: u64,
+}
+
+impl SpatialMemory {
+    /// Create an empty memory with the given capacity and TTL
+    pub fn new(capacity: usize, ttl: u64) -> Self {
+        Self {
+            entries: Vec::new(),
+            capacity,
+            ttl,
+        }
+    }
+
+    /// Record a sighting of a resource
+    ///
+    /// A sighting at an already-remembered location of the same type refreshes
+    /// that entry instead of adding a duplicate.
+    pub fn remember(&mut self, resource_type: &str, x: f32, y: f32, tick: u64) {
+        self.insert(resource_type, x, y, tick, None);
+    }
+
+    /// Record a location another agent told us about
+    ///
+    /// `tick` is when the teller saw the resource, so a tip never looks
+    /// fresher than the sighting behind it. Ignored if we already know a
+    /// location of this type seen at `tick` or later.
+    ///
+    /// Returns whether the tip was stored.
+    pub fn remember_tip(&mut self, resource_type: &str, x: f32, y: f32, tick: u64, from: AgentId) -> bool {
+        let known_fresher = self
+            .entries
+            .iter()
+            .any(|e| e.resource_type == resource_type && e.tick >= tick);
+        if known_fresher || self.capacity == 0 {
+            return false;
+        }
+        self.insert(resource_type, x, y, tick, Some(from));
+        true
+    }
+
+    fn insert(&mut self, resource_type: &str, x: f32, y: f32, tick: u64, tipped_by: Option<AgentId>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.entries
+            .retain(|e| !(e.resource_type == resource_type && e.x == x && e.y == y));
+        self.entries.push(RememberedResource {
+            resource_type: resource_type.to_string(),
+            x,
+            y,
+            tick,
+            tipped_by,
+        });
+
+        // Keep oldest first so eviction drops the stalest entry
+        self.entries.sort_by_key(|e| e.tick);
+        while self.entries.len() > self.capacity {
+            self.entries.remove(0);
+        }
+    }
+
+    /// Most recently seen, non-expired location of a resource type
+    pub fn recall(&self, resource_type: &str, current_tick: u64) -> Option<&RememberedResource> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|e| e.resource_type == resource_type)
+            .find(|e| !self.is_expired(e, current_tick))
+    }
+
+    /// Drop every entry older than the TTL
+    pub fn forget_expired(&mut self, current_tick: u64) {
+        let ttl = self.ttl;
+        self.entries
+            .retain(|e| current_tick.saturating_sub(e.tick) <= ttl);
+    }
+
+    fn is_expired(&self, entry: &RememberedResource, current_tick: u64) -> bool {
+        current_tick.saturating_sub(entry.tick) > self.ttl
+    }
+}
+
+impl Default for SpatialMemory {
+    fn default() -> Self {
+        Self::new(DEFAULT_MEMORY_CAPACITY, DEFAULT_MEMORY_TTL)
+    }
+}
+
+impl Component for SpatialMemory {
+    type Storage = VecStorage<Self>;
+}
+
+/// Record that an agent visited (or saw) a resource
+///
+/// Adds a default [`SpatialMemory`] to the agent if it has none.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<SpatialMemory>();
+/// let agent = world.create_entity().build();
+///
+/// remember_resource(&mut world, agent, "water", 3.0, 4.0, 10);
+///
+/// let memories = world.read_storage::<SpatialMemory>();
+/// assert!(memories.get(agent).unwrap().recall("water", 10).is_some());
+/// ```
+///
+/// # Panics
+/// Panics if `SpatialMemory` is not registered in the world
+pub fn remember_resource(
+    world: &mut World,
+    agent: Entity,
+    resource_type: &str,
+    x: f32,
+    y: f32,
+    tick: u64,
+) {
+    let mut memories = world.write_storage::<SpatialMemory>();
+    if let Ok(entry) = memories.entry(agent) {
+        entry
+            .or_insert_with(SpatialMemory::default)
+            .remember(resource_type, x, y, tick);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_recall_most_recent() {
+        let mut memory = SpatialMemory::new(4, 100);
+        memory.remember("water", 1.0, 1.0, 10);
+        memory.remember("water", 2.0, 2.0, 20);
+        memory.remember("grass", 3.0, 3.0, 30);
+
+        let water = memory.recall("water", 25).unwrap();
+        assert_eq!((water.x, water.y), (2.0, 2.0));
+        assert!(memory.recall("food", 25).is_none());
+    }
+
+    #[test]
+    fn test_ttl_eviction() {
+        let mut memory = SpatialMemory::new(4, 100);
+        memory.remember("water", 1.0, 1.0, 0);
+        memory.remember("water", 2.0, 2.0, 50);
+
+        // First entry stale, second still valid
+        let water = memory.recall("water", 120).unwrap();
+        assert_eq!(water.tick, 50);
+
+        memory.forget_expired(120);
+        assert_eq!(memory.entries.len(), 1);
+
+        memory.forget_expired(500);
+        assert!(memory.entries.is_empty());
+    }
+
+    #[test]
+    fn test_capacity_evicts_oldest() {
+        let mut memory = SpatialMemory::new(2, 1000);
+        memory.remember("water", 1.0, 1.0, 1);
+        memory.remember("grass", 2.0, 2.0, 2);
+        memory.remember("food", 3.0, 3.0, 3);
+
+        assert_eq!(memory.entries.len(), 2);
+        assert!(memory.recall("water", 3).is_none());
+        assert!(memory.recall("food", 3).is_some());
+    }
+
+    #[test]
+    fn test_revisit_refreshes_entry() {
+        let mut memory = SpatialMemory::new(2, 1000);
+        memory.remember("water", 1.0, 1.0, 1);
+        memory.remember("grass", 2.0, 2.0, 2);
+        memory.remember("water", 1.0, 1.0, 3);
+        memory.remember("food", 3.0, 3.0, 4);
+
+        // Refreshed water outlives grass
+        assert_eq!(memory.entries.len(), 2);
+        assert!(memory.recall("water", 4).is_some());
+        assert!(memory.recall("grass", 4).is_none());
+    }
+
+    #[test]
+    fn test_tip_never_overrides_fresher_knowledge() {
+        let mut memory = SpatialMemory::new(4, 1000);
+        memory.remember("water", 1.0, 1.0, 50);
+
+        // An older tip about another spring is ignored
+        assert!(!memory.remember_tip("water", 9.0, 9.0, 40, AgentId(2)));
+        assert_eq!(memory.recall("water", 60).unwrap().tipped_by, None);
+
+        // A newer one is kept and attributed
+        assert!(memory.remember_tip("water", 9.0, 9.0, 55, AgentId(2)));
+        let water = memory.recall("water", 60).unwrap();
+        assert_eq!((water.x, water.tipped_by), (9.0, Some(AgentId(2))));
+    }
+
+    #[test]
+    fn test_zero_capacity_remembers_nothing() {
+        let mut memory = SpatialMemory::new(0, 1000);
+        memory.remember("water", 1.0, 1.0, 1);
+        assert!(memory.entries.is_empty());
+    }
+}
@@ -1,10 +1,67 @@
 pub mod components;
 
+pub mod currency;
+
 pub mod identity;
 
 pub mod creation;
 
+pub mod index;
+
+pub mod memory;
+
+pub mod profile;
+
+pub mod loadout;
+
+pub mod wellbeing;
+
+pub mod productivity;
+
+pub mod inspect;
+
+pub mod registration;
+
+pub mod bulk;
+
+pub mod trajectory;
+
+pub mod faction;
+
+pub mod household;
+
+pub mod triage;
+
+pub mod rng;
+
+pub mod overflow;
+
+pub mod employment;
+
+pub mod starvation;
+
+pub use currency::{Currency, CurrencyAmount, MINOR_UNITS_PER_MAJOR};
 pub use identity::{AgentId, AgentIdAllocator, AgentIdError};
-pub use creation::{create_agent, create_agent_with_needs, create_agent_with_wallet, create_agent_custom, remove_agent};
+pub use creation::{create_agent, create_agent_with_needs, create_agent_with_wallet, create_agent_custom, try_create_agent_custom, remove_agent, try_remove_agent, CreationError, RemoveError};
+pub use index::AgentIndex;
+pub use memory::{SpatialMemory, RememberedResource, remember_resource};
+pub use profile::{NeedsProfile, create_agent_with_profile};
+pub use loadout::{Loadout, SpeciesLoadout, create_agent_of_species, try_create_agent_of_species};
+pub use wellbeing::{WellbeingWeights, agent_wellbeing};
+pub use productivity::{ProductivityConfig, agent_productivity, productivity, productivity_with};
+pub use triage::{most_in_need, Dormant};
+pub use rng::{agent_rng, AgentRng};
+pub use overflow::{OverflowBuffer, OverflowConfig};
+pub use starvation::{starvation_effectiveness, StarvationConfig, StarvationState, StarvationSystem};
+pub use employment::{employees_of, employer_of, LegacyEmployment};
+pub use inspect::{AgentReport, InventoryLine, TrustedPartner, inspect_agent};
+pub use registration::{ensure_components_registered, register_core_components};
+pub use bulk::{AgentTemplate, create_agents, try_create_agents};
+pub use trajectory::predict_ticks_to_threshold;
+pub use faction::{Faction, FACTION_SPILLOVER, faction_members, faction_reputation, seed_reputation_from_faction, stranger_trust, trust_in};
+pub use household::{
+    Household, HouseholdError, add_member, create_household, draw_from_household, household_of,
+    pay_from_household, remove_member,
+};
 
 // TODO: Add agent systems and logic
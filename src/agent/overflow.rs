@@ -0,0 +1,118 @@
+//! Carrying excess satisfaction over into later ticks
+//!
+//! Needs are clamped at [`MIN_NEEDS`], so drinking 30 points of water at
+//! thirst 20 wastes 10. Agents with an [`OverflowBuffer`] keep that excess:
+//! consumption fills the buffer and [`NeedDecaySystem`] drains it before the
+//! need starts growing again, so a well-fed agent stays satisfied longer.
+//! Agents without the component behave exactly as before.
+//!
+//! [`NeedDecaySystem`]: super::components::NeedDecaySystem
+
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+
+use super::components::{Needs, MIN_NEEDS};
+use crate::items::NeedType;
+
+/// World resource tuning [`OverflowBuffer`]s
+///
+/// Worlds without the resource use the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverflowConfig {
+    /// Whether buffers fill and drain at all
+    pub enabled: bool,
+    /// Most excess satisfaction stored per need
+    pub capacity: f32,
+}
+
+impl Default for OverflowConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            capacity: 50.0,
+        }
+    }
+}
+
+/// Excess satisfaction an agent has banked for each need
+///
+/// Opt-in: add it to agents whose surplus eating and drinking should delay
+/// their next need increase.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let mut buffer = OverflowBuffer::default();
+/// buffer.fill(NeedType::Thirst, 10.0, 50.0);
+///
+/// // The first 10 points of thirst growth come out of the buffer
+/// assert_eq!(buffer.absorb(NeedType::Thirst, 4.0), 0.0);
+/// assert_eq!(buffer.absorb(NeedType::Thirst, 8.0), 2.0);
+/// assert_eq!(buffer.get(NeedType::Thirst), 0.0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OverflowBuffer {
+    pub thirst: f32,
+    pub hunger: f32,
+    pub tiredness: f32,
+}
+
+impl OverflowBuffer {
+    /// Banked satisfaction for one need
+    pub fn get(&self, need: NeedType) -> f32 {
+        match need {
+            NeedType::Thirst => self.thirst,
+            NeedType::Hunger => self.hunger,
+            NeedType::Tiredness => self.tiredness,
+        }
+    }
+
+    fn get_mut(&mut self, need: NeedType) -> &mut f32 {
+        match need {
+            NeedType::Thirst => &mut self.thirst,
+            NeedType::Hunger => &mut self.hunger,
+            NeedType::Tiredness => &mut self.tiredness,
+        }
+    }
+
+    /// Bank `excess` satisfaction for `need`, up to `capacity`
+    ///
+    /// Returns the amount actually banked; non-positive or NaN amounts bank
+    /// nothing.
+    pub fn fill(&mut self, need: NeedType, excess: f32, capacity: f32) -> f32 {
+        let slot = self.get_mut(need);
+        let room = (capacity - *slot).max(0.0);
+        let banked = if excess > 0.0 { excess.min(room) } else { 0.0 };
+        *slot += banked;
+        banked
+    }
+
+    /// Pay `growth` of `need` out of the buffer first
+    ///
+    /// Returns the growth left over once the buffer is empty.
+    pub fn absorb(&mut self, need: NeedType, growth: f32) -> f32 {
+        if growth <= 0.0 {
+            return growth;
+        }
+        let slot = self.get_mut(need);
+        let used = growth.min(*slot);
+        *slot -= used;
+        growth - used
+    }
+
+    /// Bank whatever `needs` has been pushed below [`MIN_NEEDS`]
+    ///
+    /// Call on unclamped needs, right after applying an item's effects and
+    /// before clamping.
+    pub fn capture(&mut self, needs: &Needs, capacity: f32) {
+        for need in [NeedType::Thirst, NeedType::Hunger, NeedType::Tiredness] {
+            self.fill(need, MIN_NEEDS - needs.get(need), capacity);
+        }
+    }
+}
+
+impl Component for OverflowBuffer {
+    type Storage = VecStorage<Self>;
+}
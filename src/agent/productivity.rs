@@ -0,0 +1,146 @@
+//! Work productivity from tiredness and energy
+//! Tired or drained agents harvest less and teach less well
+
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+use super::components::{EnergyComponent, Needs, MAX_NEEDS, MIN_NEEDS};
+use super::starvation::starvation_effectiveness;
+
+/// Shape of the penalty applied by [`productivity_with`]
+///
+/// Insert into the world as a resource to override the defaults. Each
+/// factor is raised to its exponent: 0 ignores the factor, 1 scales
+/// linearly, values below 1 forgive mild tiredness and punish only
+/// exhaustion.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProductivityConfig {
+    /// Exponent on restedness (`1 - tiredness / 100`)
+    pub tiredness_exponent: f32,
+    /// Exponent on the energy fraction (`current / max`)
+    pub energy_exponent: f32,
+}
+
+impl Default for ProductivityConfig {
+    fn default() -> Self {
+        Self {
+            tiredness_exponent: 0.5,
+            energy_exponent: 0.5,
+        }
+    }
+}
+
+/// 0-1 work multiplier with the default [`ProductivityConfig`]
+///
+/// # Example
+/// ```rust
+/// use libreconomy::{productivity, EnergyComponent, Needs};
+///
+/// let rested = Needs::new(50.0, 50.0, 0.0);
+/// let tired = Needs::new(50.0, 50.0, 75.0);
+/// assert_eq!(productivity(&rested, None), 1.0);
+/// assert_eq!(productivity(&tired, None), 0.5);
+///
+/// let drained = EnergyComponent::new(25.0, 100.0);
+/// assert_eq!(productivity(&rested, Some(&drained)), 0.5);
+/// ```
+pub fn productivity(needs: &Needs, energy: Option<&EnergyComponent>) -> f32 {
+    productivity_with(needs, energy, &ProductivityConfig::default())
+}
+
+/// 0-1 work multiplier falling as tiredness rises and energy drains
+///
+/// `restedness^tiredness_exponent * energy_fraction^energy_exponent`, so it
+/// changes smoothly and only reaches 0 at full exhaustion (tiredness at the
+/// maximum or no energy left). A missing `EnergyComponent` counts as full.
+pub fn productivity_with(
+    needs: &Needs,
+    energy: Option<&EnergyComponent>,
+    config: &ProductivityConfig,
+) -> f32 {
+    let rested = (MAX_NEEDS - needs.tiredness) / (MAX_NEEDS - MIN_NEEDS);
+    let charged = energy.map_or(1.0, |e| e.current / e.max.max(f32::EPSILON));
+
+    let factor = |value: f32, exponent: f32| {
+        let value = if value.is_nan() { 0.0 } else { value.clamp(0.0, 1.0) };
+        let exponent = if exponent.is_finite() { exponent.max(0.0) } else { 1.0 };
+        value.powf(exponent)
+    };
+    factor(rested, config.tiredness_exponent) * factor(charged, config.energy_exponent)
+}
+
+/// Productivity of an entity in the world
+///
+/// Uses the [`ProductivityConfig`] resource if present, and is further
+/// scaled by a [`StarvationState`](super::starvation::StarvationState)'s
+/// effectiveness. Entities without `Needs` work at full productivity.
+pub fn agent_productivity(world: &World, entity: Entity) -> f32 {
+    if !world.has_value::<MaskedStorage<Needs>>() {
+        return 1.0;
+    }
+    let needs_storage = world.read_storage::<Needs>();
+    let needs = match needs_storage.get(entity) {
+        Some(needs) => needs,
+        None => return 1.0,
+    };
+
+    let energy_storage = world
+        .has_value::<MaskedStorage<EnergyComponent>>()
+        .then(|| world.read_storage::<EnergyComponent>());
+    let energy = energy_storage.as_ref().and_then(|s| s.get(entity));
+
+    let base = match world.try_fetch::<ProductivityConfig>() {
+        Some(config) => productivity_with(needs, energy, &config),
+        None => productivity(needs, energy),
+    };
+    base * starvation_effectiveness(world, entity)
+}
+
+/// Scale a whole-unit yield by `productivity`
+///
+/// Rounds to the nearest unit, but anyone still able to work gets at least
+/// one unit out of a non-zero yield.
+pub(crate) fn scale_yield(amount: u32, productivity: f32) -> u32 {
+    if amount == 0 || productivity.is_nan() || productivity <= 0.0 {
+        return 0;
+    }
+    let scaled = (amount as f32 * productivity.min(1.0)).round() as u32;
+    scaled.clamp(1, amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_productivity_is_continuous_and_positive_until_exhausted() {
+        let full = EnergyComponent::new(100.0, 100.0);
+        let mut last = 1.0;
+        for tiredness in 1..=99 {
+            let needs = Needs::new(0.0, 0.0, tiredness as f32);
+            let p = productivity(&needs, Some(&full));
+            assert!(p > 0.0 && p < last);
+            assert!(last - p < 0.1);
+            last = p;
+        }
+
+        assert_eq!(productivity(&Needs::new(0.0, 0.0, 100.0), None), 0.0);
+        assert_eq!(productivity(&Needs::new(0.0, 0.0, 0.0), Some(&EnergyComponent::new(0.0, 100.0))), 0.0);
+
+        // A zero exponent switches a factor off
+        let config = ProductivityConfig { tiredness_exponent: 0.0, ..Default::default() };
+        assert_eq!(productivity_with(&Needs::new(0.0, 0.0, 100.0), None, &config), 1.0);
+    }
+
+    #[test]
+    fn test_scale_yield_keeps_working_agents_productive() {
+        assert_eq!(scale_yield(10, 1.0), 10);
+        assert_eq!(scale_yield(10, 0.44), 4);
+        assert_eq!(scale_yield(10, 0.01), 1);
+        assert_eq!(scale_yield(10, 0.0), 0);
+        assert_eq!(scale_yield(0, 1.0), 0);
+    }
+}
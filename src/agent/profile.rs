@@ -0,0 +1,232 @@
+//! Needs profiles for common agent archetypes
+//! Bundles starting needs with need decay multipliers for quick scenario setup
+
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+use super::components::{Inventory, NeedDecayRates, Needs, Wallet};
+use super::creation::{
+    create_agent_custom, CreationError, DEFAULT_CURRENCY, DEFAULT_HUNGER, DEFAULT_THIRST, DEFAULT_TIREDNESS,
+};
+
+/// Starting needs and decay multipliers for an agent archetype
+///
+/// Profiles are plain data: hosts can use the presets, tweak them with the
+/// builder methods, or define their own (including via serde).
+///
+/// # Example
+/// ```rust
+/// use libreconomy::NeedsProfile;
+///
+/// let profile = NeedsProfile::new("miner")
+///     .with_needs(40.0, 50.0, 20.0)
+///     .with_decay(1.0, 1.2, 2.0);
+///
+/// assert_eq!(profile.needs.tiredness, 20.0);
+/// assert_eq!(profile.decay.tiredness, 2.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NeedsProfile {
+    /// Archetype name (informational)
+    pub name: String,
+    /// Starting needs
+    pub needs: Needs,
+    /// Need decay multipliers
+    pub decay: NeedDecayRates,
+}
+
+impl NeedsProfile {
+    /// Profile with default starting needs and base decay rates
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            needs: Needs::new(DEFAULT_THIRST, DEFAULT_HUNGER, DEFAULT_TIREDNESS),
+            decay: NeedDecayRates::default(),
+        }
+    }
+
+    /// Set starting needs (clamped to the valid range)
+    pub fn with_needs(mut self, thirst: f32, hunger: f32, tiredness: f32) -> Self {
+        self.needs = Needs::new(thirst, hunger, tiredness);
+        self
+    }
+
+    /// Set need decay multipliers
+    pub fn with_decay(mut self, thirst: f32, hunger: f32, tiredness: f32) -> Self {
+        self.decay = NeedDecayRates::new(thirst, hunger, tiredness);
+        self
+    }
+
+    /// Physical worker: starts rested but tires quickly
+    pub fn laborer() -> Self {
+        Self::new("laborer")
+            .with_needs(45.0, 45.0, 20.0)
+            .with_decay(1.2, 1.2, 2.0)
+    }
+
+    /// Trader: sedentary, needs grow slowly
+    pub fn merchant() -> Self {
+        Self::new("merchant")
+            .with_needs(40.0, 35.0, 40.0)
+            .with_decay(0.9, 0.8, 0.8)
+    }
+
+    /// Gatherer: always on the move and hungers faster
+    pub fn forager() -> Self {
+        Self::new("forager")
+            .with_needs(50.0, 60.0, 35.0)
+            .with_decay(1.1, 1.8, 1.2)
+    }
+}
+
+impl Default for NeedsProfile {
+    fn default() -> Self {
+        Self::new("default")
+    }
+}
+
+/// Create an agent from a needs profile
+///
+/// Stamps the profile's `Needs` and `NeedDecayRates` onto a new agent with
+/// an empty inventory and default wallet.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Agent>();
+/// world.register::<Needs>();
+/// world.register::<Inventory>();
+/// world.register::<Wallet>();
+/// world.register::<NeedDecayRates>();
+/// world.insert(AgentIdAllocator::new());
+///
+/// let agent = create_agent_with_profile(&mut world, &NeedsProfile::laborer());
+///
+/// let rates = world.read_storage::<NeedDecayRates>();
+/// assert_eq!(rates.get(agent), Some(&NeedsProfile::laborer().decay));
+/// ```
+///
+/// # Panics
+/// Panics if AgentIdAllocator resource is not registered in the world
+/// Panics if required component types (including `NeedDecayRates`) are not
+/// registered; a missing `NeedDecayRates` is caught before the agent is created
+pub fn create_agent_with_profile(world: &mut World, profile: &NeedsProfile) -> Entity {
+    if !world.has_value::<MaskedStorage<NeedDecayRates>>() {
+        panic!("{}", CreationError::MissingComponentRegistration("NeedDecayRates"));
+    }
+    let entity = create_agent_custom(
+        world,
+        profile.needs.clone(),
+        Inventory::default(),
+        Wallet::new(DEFAULT_CURRENCY),
+    );
+
+    world
+        .write_storage::<NeedDecayRates>()
+        .insert(entity, profile.decay)
+        .expect("Failed to insert NeedDecayRates component");
+
+    entity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::components::{Agent, NeedDecaySystem};
+    use crate::agent::identity::{AgentId, AgentIdAllocator};
+    use pretty_assertions::assert_eq;
+
+    fn setup_world() -> World {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Needs>();
+        world.register::<Inventory>();
+        world.register::<Wallet>();
+        world.register::<NeedDecayRates>();
+        world.insert(AgentIdAllocator::new());
+        world
+    }
+
+    #[test]
+    fn test_presets_are_distinct() {
+        let presets = [
+            NeedsProfile::laborer(),
+            NeedsProfile::merchant(),
+            NeedsProfile::forager(),
+        ];
+
+        for (i, a) in presets.iter().enumerate() {
+            for b in presets.iter().skip(i + 1) {
+                assert_ne!(a.needs, b.needs, "{} vs {}", a.name, b.name);
+                assert_ne!(a.decay, b.decay, "{} vs {}", a.name, b.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_preset_decay_traits() {
+        let laborer = NeedsProfile::laborer();
+        let forager = NeedsProfile::forager();
+
+        // Laborers tire faster, foragers hunger faster
+        assert!(laborer.decay.tiredness > forager.decay.tiredness);
+        assert!(forager.decay.hunger > laborer.decay.hunger);
+    }
+
+    #[test]
+    fn test_create_agent_with_profile() {
+        let mut world = setup_world();
+        let profile = NeedsProfile::forager();
+        let agent = create_agent_with_profile(&mut world, &profile);
+
+        let needs = world.read_storage::<Needs>();
+        let rates = world.read_storage::<NeedDecayRates>();
+        assert_eq!(needs.get(agent), Some(&profile.needs));
+        assert_eq!(rates.get(agent), Some(&profile.decay));
+    }
+
+    #[test]
+    fn test_missing_decay_rates_panics_before_creating_agent() {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Needs>();
+        world.register::<Inventory>();
+        world.register::<Wallet>();
+        world.insert(AgentIdAllocator::new());
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            create_agent_with_profile(&mut world, &NeedsProfile::laborer())
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(world.read_storage::<Agent>().join().count(), 0);
+        assert_eq!(world.read_resource::<AgentIdAllocator>().peek(), AgentId(1));
+    }
+
+    #[test]
+    fn test_profile_decay_applies() {
+        let laborer = NeedsProfile::laborer();
+        let merchant = NeedsProfile::merchant();
+
+        let mut laborer_needs = Needs::new(0.0, 0.0, 0.0);
+        let mut merchant_needs = Needs::new(0.0, 0.0, 0.0);
+        for _ in 0..100 {
+            NeedDecaySystem::tick_with_rates(&mut laborer_needs, &laborer.decay);
+            NeedDecaySystem::tick_with_rates(&mut merchant_needs, &merchant.decay);
+        }
+
+        assert!(laborer_needs.tiredness > merchant_needs.tiredness);
+    }
+
+    #[test]
+    fn test_profile_serde_roundtrip() {
+        let profile = NeedsProfile::new("custom").with_decay(0.5, 0.5, 3.0);
+        let json = serde_json::to_string(&profile).unwrap();
+        let back: NeedsProfile = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, profile);
+    }
+}
@@ -0,0 +1,103 @@
+//! Component registration checks
+//! Verifies a world is ready for agent creation before specs panics on a missing storage
+
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+use super::components::{Agent, Inventory, Needs, Wallet};
+use super::identity::AgentIdAllocator;
+
+fn missing<T: Component>(world: &World, name: &'static str, out: &mut Vec<&'static str>) {
+    if !world.has_value::<MaskedStorage<T>>() {
+        out.push(name);
+    }
+}
+
+/// Check that every component `create_agent` needs is registered
+///
+/// # Errors
+/// The names of the unregistered components (`Agent`, `Needs`, `Inventory`,
+/// `Wallet`), in that order
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Agent>();
+/// assert_eq!(
+///     ensure_components_registered(&world),
+///     Err(vec!["Needs", "Inventory", "Wallet"])
+/// );
+///
+/// register_core_components(&mut world);
+/// assert_eq!(ensure_components_registered(&world), Ok(()));
+/// ```
+pub fn ensure_components_registered(world: &World) -> Result<(), Vec<&'static str>> {
+    let mut names = Vec::new();
+    missing::<Agent>(world, "Agent", &mut names);
+    missing::<Needs>(world, "Needs", &mut names);
+    missing::<Inventory>(world, "Inventory", &mut names);
+    missing::<Wallet>(world, "Wallet", &mut names);
+    if names.is_empty() {
+        Ok(())
+    } else {
+        Err(names)
+    }
+}
+
+/// Register the core agent components and insert an `AgentIdAllocator`
+///
+/// Safe to call more than once: registration is idempotent and an existing
+/// allocator is kept.
+pub fn register_core_components(world: &mut World) {
+    world.register::<Agent>();
+    world.register::<Needs>();
+    world.register::<Inventory>();
+    world.register::<Wallet>();
+    world
+        .entry::<AgentIdAllocator>()
+        .or_insert_with(AgentIdAllocator::new);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::creation::create_agent;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_guard_reports_missing_components() {
+        let mut world = World::new();
+        assert_eq!(
+            ensure_components_registered(&world),
+            Err(vec!["Agent", "Needs", "Inventory", "Wallet"])
+        );
+
+        world.register::<Needs>();
+        world.register::<Wallet>();
+        assert_eq!(ensure_components_registered(&world), Err(vec!["Agent", "Inventory"]));
+    }
+
+    #[test]
+    fn test_register_core_components_satisfies_guard() {
+        let mut world = World::new();
+        register_core_components(&mut world);
+        register_core_components(&mut world);
+        assert_eq!(ensure_components_registered(&world), Ok(()));
+
+        let agent = create_agent(&mut world);
+        assert!(world.entities().is_alive(agent));
+    }
+
+    #[test]
+    #[should_panic(expected = "Needs, Inventory, Wallet")]
+    #[cfg(debug_assertions)]
+    fn test_create_agent_names_all_missing_components() {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.insert(AgentIdAllocator::new());
+        create_agent(&mut world);
+    }
+}
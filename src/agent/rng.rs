@@ -0,0 +1,128 @@
+//! Per-agent random number streams
+//!
+//! Systems that share one generator hand out draws in processing order, so
+//! adding an agent or visiting agents in a different order changes everyone
+//! else's luck. Seeding a separate stream from `(world_seed, agent_id)` makes
+//! each agent's draws depend only on the seed and its own id.
+
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use specs::prelude::*;
+
+use super::identity::AgentId;
+
+/// Deterministic generator for one agent
+///
+/// The same `world_seed` and `agent_id` always produce the same sequence,
+/// whatever other agents exist. Different ids get unrelated streams.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use rand::Rng;
+///
+/// let a: f32 = agent_rng(42, AgentId(7)).gen();
+/// let b: f32 = agent_rng(42, AgentId(7)).gen();
+/// assert_eq!(a, b);
+/// ```
+pub fn agent_rng(world_seed: u64, agent_id: AgentId) -> StdRng {
+    StdRng::seed_from_u64(mix(world_seed ^ mix(agent_id.0)))
+}
+
+/// SplitMix64 finalizer, so neighbouring ids don't get neighbouring seeds
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// An agent's own random stream, kept across ticks
+///
+/// Attach to agents whose random choices must not depend on processing
+/// order. Not included in snapshots; recreate it from the world seed after
+/// loading.
+#[derive(Debug, Clone)]
+pub struct AgentRng(pub StdRng);
+
+impl AgentRng {
+    /// Stream for `agent_id` under `world_seed` (see [`agent_rng`])
+    pub fn new(world_seed: u64, agent_id: AgentId) -> Self {
+        AgentRng(agent_rng(world_seed, agent_id))
+    }
+}
+
+impl RngCore for AgentRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+impl Component for AgentRng {
+    type Storage = VecStorage<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::components::Agent;
+    use pretty_assertions::assert_eq;
+    use rand::Rng;
+
+    fn draws(rng: &mut AgentRng) -> Vec<f32> {
+        (0..5).map(|_| rng.gen()).collect()
+    }
+
+    /// Draws for agent 7 after creating `others` in the given order
+    fn draws_for_seven(others: &[u64]) -> Vec<f32> {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<AgentRng>();
+        for &id in others.iter().chain([7].iter()) {
+            world
+                .create_entity()
+                .with(Agent { id: AgentId(id) })
+                .with(AgentRng::new(99, AgentId(id)))
+                .build();
+        }
+
+        let agents = world.read_storage::<Agent>();
+        let mut rngs = world.write_storage::<AgentRng>();
+        let mut result = Vec::new();
+        for (agent, rng) in (&agents, &mut rngs).join() {
+            let own = draws(rng);
+            if agent.id == AgentId(7) {
+                result = own;
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_draws_ignore_other_agents_and_order() {
+        let alone = draws_for_seven(&[]);
+        assert_eq!(alone.len(), 5);
+        assert_eq!(draws_for_seven(&[1, 2, 3]), alone);
+        assert_eq!(draws_for_seven(&[30, 2, 11, 5, 8]), alone);
+    }
+
+    #[test]
+    fn test_streams_differ_by_agent_and_seed() {
+        let base = draws(&mut AgentRng::new(1, AgentId(1)));
+        assert_ne!(draws(&mut AgentRng::new(1, AgentId(2))), base);
+        assert_ne!(draws(&mut AgentRng::new(2, AgentId(1))), base);
+        assert_eq!(draws(&mut AgentRng::new(1, AgentId(1))), base);
+    }
+}
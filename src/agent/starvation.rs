@@ -0,0 +1,241 @@
+//! Lingering effects of starvation
+//!
+//! An agent that spends a long time with a need at or above
+//! [`StarvationConfig::critical_level`] builds up starvation
+//! [`severity`](StarvationState::severity). Severity makes eating and
+//! drinking less effective and lowers productivity, and it only wears off
+//! gradually once the need drops: the further below critical, the faster
+//! the recovery. Both directions move a little each tick, so the penalty
+//! never snaps on or off. Agents without a [`StarvationState`] are
+//! unaffected.
+
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+use super::components::Needs;
+use crate::items::NeedType;
+
+/// World resource shaping starvation and recovery
+///
+/// Worlds without the resource use the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StarvationConfig {
+    /// Need being tracked
+    pub need: NeedType,
+    /// Need level (0-100) at which starvation sets in
+    pub critical_level: f32,
+    /// Severity gained per tick spent at or above `critical_level`
+    pub onset_rate: f32,
+    /// Severity lost per tick with the need at 0; scales down linearly to
+    /// nothing just below `critical_level`
+    pub recovery_rate: f32,
+    /// Fraction of effectiveness lost at full severity (0-1)
+    pub max_penalty: f32,
+}
+
+impl Default for StarvationConfig {
+    fn default() -> Self {
+        Self {
+            need: NeedType::Hunger,
+            critical_level: 90.0,
+            onset_rate: 0.01,
+            recovery_rate: 0.004,
+            max_penalty: 0.6,
+        }
+    }
+}
+
+/// How starved an agent is
+///
+/// Opt-in: add it to agents that should suffer after prolonged starvation.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let config = StarvationConfig::default();
+/// let mut state = StarvationState::default();
+/// for _ in 0..50 {
+///     state.update(100.0, &config);
+/// }
+/// assert_eq!(state.critical_ticks, 50);
+/// assert!((state.effectiveness(&config) - 0.7).abs() < 1e-4);
+///
+/// // Eating brings the need down, but severity only eases off over time
+/// state.update(10.0, &config);
+/// assert_eq!(state.critical_ticks, 0);
+/// assert!(state.effectiveness(&config) < 0.71);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StarvationState {
+    /// Consecutive ticks the need has been critical (0 once it drops)
+    pub critical_ticks: u64,
+    /// Accumulated starvation, 0 (none) to 1 (full penalty)
+    pub severity: f32,
+}
+
+impl StarvationState {
+    /// Advance one tick with the tracked need at `level`
+    pub fn update(&mut self, level: f32, config: &StarvationConfig) {
+        let severity = if self.severity.is_nan() { 0.0 } else { self.severity };
+        if level >= config.critical_level {
+            self.critical_ticks = self.critical_ticks.saturating_add(1);
+            self.severity = (severity + config.onset_rate.max(0.0)).min(1.0);
+        } else {
+            self.critical_ticks = 0;
+            let depth = if config.critical_level > 0.0 {
+                (1.0 - level / config.critical_level).clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+            self.severity = (severity - config.recovery_rate.max(0.0) * depth).max(0.0);
+        }
+    }
+
+    /// Multiplier (0-1) on consumption and productivity at the current
+    /// severity
+    pub fn effectiveness(&self, config: &StarvationConfig) -> f32 {
+        let severity = if self.severity.is_nan() { 0.0 } else { self.severity.clamp(0.0, 1.0) };
+        1.0 - config.max_penalty.clamp(0.0, 1.0) * severity
+    }
+}
+
+impl Component for StarvationState {
+    type Storage = VecStorage<Self>;
+}
+
+/// Starvation multiplier for an entity in the world
+///
+/// 1.0 for entities without a [`StarvationState`]. Uses the
+/// [`StarvationConfig`] resource if present.
+pub fn starvation_effectiveness(world: &World, entity: Entity) -> f32 {
+    if !world.has_value::<MaskedStorage<StarvationState>>() {
+        return 1.0;
+    }
+    let config = world.try_fetch::<StarvationConfig>().map(|c| (*c).clone()).unwrap_or_default();
+    world
+        .read_storage::<StarvationState>()
+        .get(entity)
+        .map_or(1.0, |state| state.effectiveness(&config))
+}
+
+/// Updates every [`StarvationState`] from the agent's current needs
+///
+/// Run it after need decay and consumption so it sees the tick's final need
+/// levels. Reads the optional [`StarvationConfig`] resource.
+pub struct StarvationSystem;
+
+impl StarvationSystem {
+    /// Stable dispatcher name
+    pub const NAME: &'static str = "starvation";
+}
+
+impl<'a> System<'a> for StarvationSystem {
+    type SystemData = (
+        ReadStorage<'a, Needs>,
+        WriteStorage<'a, StarvationState>,
+        Option<Read<'a, StarvationConfig>>,
+    );
+
+    fn run(&mut self, (needs, mut states, config): Self::SystemData) {
+        let config = config.map(|c| (*c).clone()).unwrap_or_default();
+        for (needs, state) in (&needs, &mut states).join() {
+            state.update(needs.get(config.need), &config);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::components::Inventory;
+    use crate::agent::productivity::agent_productivity;
+    use crate::systems::consumption::{execute_consume, ConsumptionConfig, ConsumptionSystem};
+    use crate::decision::ActionType;
+    use crate::items::ItemRegistry;
+    use pretty_assertions::assert_eq;
+
+    /// An agent at hunger 95 holding food
+    fn hungry_agent(world: &mut World) -> Entity {
+        let mut inventory = Inventory::default();
+        inventory.add("food", 5);
+        world
+            .create_entity()
+            .with(Needs::new(0.0, 95.0, 0.0))
+            .with(inventory)
+            .with(StarvationState::default())
+            .build()
+    }
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.register::<Needs>();
+        world.register::<Inventory>();
+        world.register::<StarvationState>();
+        world.insert(ItemRegistry::with_defaults());
+        world
+    }
+
+    #[test]
+    fn test_long_starved_agent_recovers_more_slowly() {
+        let mut world = world();
+        let peckish = hungry_agent(&mut world);
+        let starving = hungry_agent(&mut world);
+        // The starving agent has been critical for 80 ticks; the peckish one
+        // only just got hungry
+        for _ in 0..80 {
+            StarvationSystem.run_now(&world);
+        }
+        *world.write_storage::<StarvationState>().get_mut(peckish).unwrap() = StarvationState::default();
+
+        let eat = ActionType::ConsumeItem { item: "food".into(), quantity: 1 };
+        let fed = execute_consume(&world, peckish, &eat).unwrap();
+        let weak = execute_consume(&world, starving, &eat).unwrap();
+        assert!(weak.need_reduction < fed.need_reduction);
+        assert!((weak.need_reduction - fed.need_reduction * (1.0 - 0.6 * 0.8)).abs() < 1e-3);
+        assert!(agent_productivity(&world, starving) < agent_productivity(&world, peckish));
+
+        // The system path applies the same penalty
+        for agent in [peckish, starving] {
+            world.write_storage::<Needs>().get_mut(agent).unwrap().hunger = 95.0;
+        }
+        world.insert(ConsumptionConfig::default());
+        ConsumptionSystem.run_now(&world);
+        let needs = world.read_storage::<Needs>();
+        assert!(needs.get(starving).unwrap().hunger > needs.get(peckish).unwrap().hunger);
+    }
+
+    #[test]
+    fn test_severity_builds_and_fades_gradually() {
+        let config = StarvationConfig::default();
+        let mut state = StarvationState::default();
+        let mut last = state.effectiveness(&config);
+        for _ in 0..100 {
+            state.update(100.0, &config);
+            let now = state.effectiveness(&config);
+            assert!(now <= last && last - now < 0.01);
+            last = now;
+        }
+        assert!(state.severity > 0.999);
+        assert_eq!(state.critical_ticks, 100);
+
+        // Recovery is faster at hunger 0 than just under critical
+        let mut shallow = state.clone();
+        let mut deep = state.clone();
+        shallow.update(80.0, &config);
+        deep.update(0.0, &config);
+        assert!(deep.severity < shallow.severity);
+
+        let mut ticks = 0;
+        while deep.severity > 0.0 {
+            let before = deep.effectiveness(&config);
+            deep.update(0.0, &config);
+            assert!(deep.effectiveness(&config) - before < 0.01);
+            ticks += 1;
+        }
+        assert!(ticks > 100);
+        assert_eq!(deep.effectiveness(&config), 1.0);
+    }
+}
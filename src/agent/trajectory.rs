@@ -0,0 +1,107 @@
+//! Needs trajectory prediction
+//! Projects need decay forward for UI warnings like "critically thirsty in ~20 ticks"
+
+use super::components::{NeedDecayRates, NeedDecaySystem, Needs, MAX_NEEDS};
+use crate::items::NeedType;
+
+/// Fraction of a tick ignored when rounding up, to absorb f32 rounding
+const TICK_TOLERANCE: f32 = 1e-3;
+
+/// Growth of one need per tick under `rates`
+fn growth_per_tick(rates: &NeedDecayRates, need: NeedType) -> f32 {
+    let rate = match need {
+        NeedType::Thirst => rates.thirst,
+        NeedType::Hunger => rates.hunger,
+        NeedType::Tiredness => rates.tiredness,
+    };
+    NeedDecaySystem::BASE_RATE * rate
+}
+
+/// Ticks of [`NeedDecaySystem`] until `need` reaches `threshold`
+///
+/// Returns `Some(0)` if the need is already at or above the threshold, and
+/// `None` if it never gets there: the need doesn't grow (zero or negative
+/// rate), or the threshold is above [`MAX_NEEDS`], where needs are clamped.
+/// Only decay is projected; eating, drinking and rest are not.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// // Thirst grows 0.01 per tick at the base rate
+/// let needs = Needs::new(79.8, 0.0, 0.0);
+/// let rates = NeedDecayRates::default();
+/// assert_eq!(predict_ticks_to_threshold(&needs, &rates, NeedType::Thirst, 80.0), Some(20));
+/// ```
+pub fn predict_ticks_to_threshold(
+    needs: &Needs,
+    decay: &NeedDecayRates,
+    need: NeedType,
+    threshold: f32,
+) -> Option<u64> {
+    let current = needs.get(need);
+    if current >= threshold {
+        return Some(0);
+    }
+    let growth = growth_per_tick(decay, need);
+    if !growth.is_finite() || growth <= 0.0 || !threshold.is_finite() || threshold > MAX_NEEDS {
+        return None;
+    }
+    // Rates like 0.01 aren't exact in f32; allow a sliver of a tick so a
+    // gap of exactly N ticks isn't rounded up to N + 1
+    let ticks = ((threshold - current) / growth - TICK_TOLERANCE).ceil().max(0.0);
+    Some(ticks as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_need_reaches_threshold_in_known_ticks() {
+        let needs = Needs::new(50.0, 60.0, 10.0);
+        let rates = NeedDecayRates::new(1.0, 2.0, 0.5);
+
+        // 0.01/tick: 30 points in 3000 ticks
+        assert_eq!(predict_ticks_to_threshold(&needs, &rates, NeedType::Thirst, 80.0), Some(3000));
+        // 0.02/tick: 20 points in 1000 ticks
+        assert_eq!(predict_ticks_to_threshold(&needs, &rates, NeedType::Hunger, 80.0), Some(1000));
+        // 0.005/tick: partial ticks round up
+        assert_eq!(predict_ticks_to_threshold(&needs, &rates, NeedType::Tiredness, 10.012), Some(3));
+    }
+
+    #[test]
+    fn test_prediction_matches_simulation() {
+        let start = Needs::new(0.0, 90.0, 0.0);
+        let rates = NeedDecayRates::new(1.0, 5.0, 1.0);
+        let predicted = predict_ticks_to_threshold(&start, &rates, NeedType::Hunger, 99.0).unwrap();
+        assert_eq!(predicted, 180);
+
+        let mut needs = start;
+        for _ in 1..predicted {
+            NeedDecaySystem::tick_with_rates(&mut needs, &rates);
+        }
+        assert!(needs.hunger < 99.0);
+        NeedDecaySystem::tick_with_rates(&mut needs, &rates);
+        assert!((needs.hunger - 99.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_already_above_or_never_reached() {
+        let needs = Needs::new(90.0, 20.0, 20.0);
+
+        assert_eq!(
+            predict_ticks_to_threshold(&needs, &NeedDecayRates::default(), NeedType::Thirst, 80.0),
+            Some(0)
+        );
+        // Zero decay never gets there
+        let still = NeedDecayRates::new(0.0, 0.0, 0.0);
+        assert_eq!(predict_ticks_to_threshold(&needs, &still, NeedType::Hunger, 80.0), None);
+        // Needs are clamped at MAX_NEEDS
+        assert_eq!(
+            predict_ticks_to_threshold(&needs, &NeedDecayRates::default(), NeedType::Hunger, 150.0),
+            None
+        );
+    }
+}
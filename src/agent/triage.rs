@@ -0,0 +1,117 @@
+//! Triage: which agents need attention first
+//! Lets hosts with a per-frame action budget service the neediest agents
+
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+use super::components::{Agent, Needs};
+use super::identity::AgentId;
+
+/// Marker for agents the host has paused (off-screen, hibernating)
+///
+/// Dormant agents are skipped by [`most_in_need`]. Set and cleared by the
+/// host; not included in snapshots.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Dormant;
+
+impl Component for Dormant {
+    type Storage = NullStorage<Self>;
+}
+
+/// Up to `max_count` agents with the highest [`Needs::pressure`], neediest first
+///
+/// Ties are broken by ascending `AgentId`, so the order is the same on every
+/// run. Agents without `Needs` and [`Dormant`] agents are left out.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// register_core_components(&mut world);
+/// let content = create_agent_with_needs(&mut world, Needs::new(10.0, 10.0, 10.0));
+/// let starving = create_agent_with_needs(&mut world, Needs::new(90.0, 95.0, 60.0));
+///
+/// let triage = most_in_need(&world, 1);
+/// assert_eq!(triage.len(), 1);
+/// assert_eq!(triage[0].0, starving);
+/// ```
+pub fn most_in_need(world: &World, max_count: usize) -> Vec<(Entity, AgentId, f32)> {
+    if max_count == 0
+        || !world.has_value::<MaskedStorage<Agent>>()
+        || !world.has_value::<MaskedStorage<Needs>>()
+    {
+        return Vec::new();
+    }
+
+    let entities = world.entities();
+    let agents = world.read_storage::<Agent>();
+    let needs = world.read_storage::<Needs>();
+    let dormant = world
+        .has_value::<MaskedStorage<Dormant>>()
+        .then(|| world.read_storage::<Dormant>());
+
+    let mut needy: Vec<(Entity, AgentId, f32)> = (&entities, &agents, &needs)
+        .join()
+        .filter(|(entity, _, _)| !dormant.as_ref().is_some_and(|d| d.contains(*entity)))
+        .map(|(entity, agent, needs)| (entity, agent.id, needs.pressure()))
+        .collect();
+
+    needy.sort_by(|a, b| {
+        b.2.partial_cmp(&a.2)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.1.cmp(&b.1))
+    });
+    needy.truncate(max_count);
+    needy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn world_with(needs: &[(u64, f32)]) -> (World, Vec<Entity>) {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Needs>();
+        world.register::<Dormant>();
+        let entities = needs
+            .iter()
+            .map(|(id, level)| {
+                world
+                    .create_entity()
+                    .with(Agent { id: AgentId(*id) })
+                    .with(Needs::new(*level, *level, *level))
+                    .build()
+            })
+            .collect();
+        (world, entities)
+    }
+
+    fn ids(triage: &[(Entity, AgentId, f32)]) -> Vec<u64> {
+        triage.iter().map(|(_, id, _)| id.0).collect()
+    }
+
+    #[test]
+    fn test_order_follows_pressure_with_ties_by_id() {
+        let (world, _) = world_with(&[(4, 50.0), (1, 20.0), (3, 80.0), (2, 50.0)]);
+
+        let triage = most_in_need(&world, 10);
+        assert_eq!(ids(&triage), vec![3, 2, 4, 1]);
+        assert!(triage.windows(2).all(|w| w[0].2 >= w[1].2));
+
+        assert_eq!(ids(&most_in_need(&world, 2)), vec![3, 2]);
+        assert!(most_in_need(&world, 0).is_empty());
+    }
+
+    #[test]
+    fn test_dormant_and_removed_agents_are_skipped() {
+        let (mut world, entities) = world_with(&[(1, 90.0), (2, 80.0), (3, 10.0)]);
+        world.write_storage::<Dormant>().insert(entities[0], Dormant).unwrap();
+        world.delete_entity(entities[1]).unwrap();
+
+        assert_eq!(ids(&most_in_need(&world, 5)), vec![3]);
+    }
+}
@@ -0,0 +1,275 @@
+//! Agent wellbeing score for analytics
+//! Collapses needs, wealth and reputation into a single 0-1 scalar
+
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+use super::components::{Agent, Needs, ReputationKnowledge, Wallet};
+use crate::world_query::WorldQuery;
+
+/// Contribution used for any factor whose component is missing
+pub const NEUTRAL_WELLBEING: f32 = 0.5;
+
+/// Most neighbors whose opinions make up the reputation factor
+const MAX_NEIGHBORS: usize = 16;
+
+/// Weights and normalization for [`agent_wellbeing`]
+///
+/// Insert into the world as a resource to override the defaults. Weights are
+/// relative; they do not need to sum to 1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WellbeingWeights {
+    /// Weight of satisfied needs (low thirst/hunger/tiredness)
+    pub needs: f32,
+    /// Weight of wealth
+    pub wealth: f32,
+    /// Weight of the agent's reputation among its neighbors
+    pub reputation: f32,
+    /// Currency at which the wealth factor reaches 0.5
+    ///
+    /// Wealth is normalized as `currency / (currency + wealth_reference)`.
+    pub wealth_reference: f32,
+}
+
+impl Default for WellbeingWeights {
+    fn default() -> Self {
+        Self {
+            needs: 0.5,
+            wealth: 0.3,
+            reputation: 0.2,
+            wealth_reference: 100.0,
+        }
+    }
+}
+
+/// Compute a 0-1 wellbeing score for an agent
+///
+/// Combines three factors, each in 0-1:
+/// - **needs**: `1 - Needs::pressure()`
+/// - **wealth**: `currency / (currency + wealth_reference)`
+/// - **reputation**: mean score this agent's neighbors hold of it
+///
+/// Neighbors are the nearest agents `world_query` reports (at most 16);
+/// opinions held by agents farther away don't count. Factors whose data is
+/// missing (no `Needs`, no `Wallet`, no neighbor has an opinion) contribute
+/// [`NEUTRAL_WELLBEING`]. Weights come from the
+/// [`WellbeingWeights`] resource if present, otherwise the defaults.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Agent>();
+/// world.register::<Needs>();
+/// world.register::<Wallet>();
+///
+/// let content = world.create_entity()
+///     .with(Needs::new(5.0, 5.0, 5.0))
+///     .with(Wallet::new(500.0))
+///     .build();
+/// let starving = world.create_entity()
+///     .with(Needs::new(95.0, 95.0, 95.0))
+///     .with(Wallet::new(0.0))
+///     .build();
+///
+/// let nobody = GridWorldQuery::new(10.0);
+/// assert!(agent_wellbeing(&world, content, &nobody) > agent_wellbeing(&world, starving, &nobody));
+/// ```
+pub fn agent_wellbeing(world: &World, entity: Entity, world_query: &dyn WorldQuery) -> f32 {
+    let weights = world
+        .try_fetch::<WellbeingWeights>()
+        .map(|w| (*w).clone())
+        .unwrap_or_default();
+
+    let needs_factor = read_optional::<Needs, _>(world, entity, |needs| 1.0 - needs.pressure());
+
+    let wealth_factor = read_optional::<Wallet, _>(world, entity, |wallet| {
+        let reference = weights.wealth_reference.max(f32::EPSILON);
+        wallet.currency / (wallet.currency + reference)
+    });
+
+    let reputation_factor = reputation_held(world, entity, world_query);
+
+    let total = weights.needs + weights.wealth + weights.reputation;
+    // Also catches NaN weights
+    if total.is_nan() || total <= 0.0 {
+        return NEUTRAL_WELLBEING;
+    }
+
+    let score = (needs_factor * weights.needs
+        + wealth_factor * weights.wealth
+        + reputation_factor * weights.reputation)
+        / total;
+
+    if score.is_finite() {
+        score.clamp(0.0, 1.0)
+    } else {
+        NEUTRAL_WELLBEING
+    }
+}
+
+/// Apply `f` to the entity's component if registered and present
+fn read_optional<T: Component, F: Fn(&T) -> f32>(world: &World, entity: Entity, f: F) -> f32 {
+    if !world.has_value::<MaskedStorage<T>>() {
+        return NEUTRAL_WELLBEING;
+    }
+    world
+        .read_storage::<T>()
+        .get(entity)
+        .map(f)
+        .unwrap_or(NEUTRAL_WELLBEING)
+}
+
+/// Mean first-hand score `entity`'s neighbors hold of it
+fn reputation_held(world: &World, entity: Entity, world_query: &dyn WorldQuery) -> f32 {
+    if !world.has_value::<MaskedStorage<Agent>>()
+        || !world.has_value::<MaskedStorage<ReputationKnowledge>>()
+    {
+        return NEUTRAL_WELLBEING;
+    }
+
+    let agents = world.read_storage::<Agent>();
+    let id = match agents.get(entity) {
+        Some(agent) => agent.id,
+        None => return NEUTRAL_WELLBEING,
+    };
+
+    let neighbors = world_query.get_nearby_agents(id, MAX_NEIGHBORS);
+    let knowledge = world.read_storage::<ReputationKnowledge>();
+    let (sum, count) = (&agents, &knowledge)
+        .join()
+        .filter(|(agent, _)| agent.id != id && neighbors.contains(&agent.id))
+        .filter_map(|(_, k)| k.first_hand.get(&id).map(|view| view.score()))
+        .fold((0.0f32, 0u32), |(sum, count), score| (sum + score, count + 1));
+
+    if count == 0 {
+        NEUTRAL_WELLBEING
+    } else {
+        sum / count as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::identity::AgentId;
+    use crate::world_query::GridWorldQuery;
+    use pretty_assertions::assert_eq;
+
+    /// Query in which every agent is everyone's neighbor
+    fn everyone() -> GridWorldQuery {
+        let mut grid = GridWorldQuery::new(10.0);
+        for id in 1..=5 {
+            grid.set_agent_position(AgentId(id), 0.0, 0.0);
+        }
+        grid
+    }
+
+    fn setup_world() -> World {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Needs>();
+        world.register::<Wallet>();
+        world.register::<ReputationKnowledge>();
+        world
+    }
+
+    fn spawn(world: &mut World, id: u64, need: f32, currency: f32) -> Entity {
+        world
+            .create_entity()
+            .with(Agent { id: AgentId(id) })
+            .with(Needs::new(need, need, need))
+            .with(Wallet::new(currency))
+            .build()
+    }
+
+    #[test]
+    fn test_well_off_scores_higher_than_starving() {
+        let mut world = setup_world();
+        let rich = spawn(&mut world, 1, 5.0, 1000.0);
+        let poor = spawn(&mut world, 2, 95.0, 0.0);
+
+        let rich_score = agent_wellbeing(&world, rich, &everyone());
+        let poor_score = agent_wellbeing(&world, poor, &everyone());
+
+        assert!(rich_score > poor_score);
+        assert!((0.0..=1.0).contains(&rich_score));
+        assert!((0.0..=1.0).contains(&poor_score));
+    }
+
+    #[test]
+    fn test_missing_components_are_neutral() {
+        let mut world = World::new();
+        world.register::<Needs>();
+        let bare = world.create_entity().build();
+
+        assert_eq!(agent_wellbeing(&world, bare, &everyone()), NEUTRAL_WELLBEING);
+    }
+
+    #[test]
+    fn test_reputation_contributes() {
+        let mut world = setup_world();
+        let liked = spawn(&mut world, 1, 50.0, 100.0);
+        let disliked = spawn(&mut world, 2, 50.0, 100.0);
+
+        let mut opinion = ReputationKnowledge::new();
+        for tick in 0..5 {
+            opinion.update_reputation(AgentId(1), 1.0, tick);
+            opinion.update_reputation(AgentId(2), -1.0, tick);
+        }
+        world.create_entity().with(Agent { id: AgentId(3) }).with(opinion).build();
+
+        let query = everyone();
+        assert!(agent_wellbeing(&world, liked, &query) > agent_wellbeing(&world, disliked, &query));
+    }
+
+    #[test]
+    fn test_only_neighbors_opinions_count() {
+        let mut world = setup_world();
+        let agent = spawn(&mut world, 1, 50.0, 100.0);
+        for (observer, outcome) in [(2, 1.0), (3, -1.0)] {
+            let mut opinion = ReputationKnowledge::new();
+            for tick in 0..5 {
+                opinion.update_reputation(AgentId(1), outcome, tick);
+            }
+            world.create_entity().with(Agent { id: AgentId(observer) }).with(opinion).build();
+        }
+        world.insert(WellbeingWeights {
+            needs: 0.0,
+            wealth: 0.0,
+            reputation: 1.0,
+            wealth_reference: 100.0,
+        });
+
+        // The fan lives next door, the critic far away
+        let mut query = GridWorldQuery::new(10.0);
+        query.set_agent_position(AgentId(1), 0.0, 0.0);
+        query.set_agent_position(AgentId(2), 1.0, 0.0);
+        let fan_score = agent_wellbeing(&world, agent, &query);
+        assert!(fan_score > 0.8);
+
+        query.set_agent_position(AgentId(3), 2.0, 0.0);
+        assert!(agent_wellbeing(&world, agent, &query) < fan_score);
+
+        // Nobody around: no opinion counts
+        assert_eq!(agent_wellbeing(&world, agent, &GridWorldQuery::new(10.0)), NEUTRAL_WELLBEING);
+    }
+
+    #[test]
+    fn test_custom_weights() {
+        let mut world = setup_world();
+        world.insert(WellbeingWeights {
+            needs: 0.0,
+            wealth: 1.0,
+            reputation: 0.0,
+            wealth_reference: 100.0,
+        });
+        let agent = spawn(&mut world, 1, 100.0, 100.0);
+
+        // Only wealth counts: 100 / (100 + 100)
+        assert_eq!(agent_wellbeing(&world, agent, &everyone()), 0.5);
+    }
+}
@@ -0,0 +1,1263 @@
+// Execution of agent-to-agent actions
+//
+// Decision-making picks an `Action`; these functions carry it out against the
+// ECS world and record the resulting `TransactionEvent` so reputation systems
+// can react to it.
+//
+// Interaction checks go through `WorldQuery::can_interact`, except when the
+// `TestMode` resource is enabled, in which case every pair may interact.
+//
+// Trade fairness policy: when the world's `ItemRegistry` gives the item a
+// reference price, a trade's outcome weight scales with how close the unit
+// price is to it (see `trade_fairness` and `fairness_outcome`); items
+// without one trade at a plain `Positive(1.0)`. Only
+// overpricing counts against the deal: a seller who undercuts the reference
+// price hurts only themselves, so the buyer has no reason to trust them less.
+// The same outcome updates both parties' views, so a lopsided deal earns the
+// buyer less trust from the seller too.
+
+use crate::decision::Transaction;
+use crate::events::{EventBus, Outcome, RefusalReason, SimEvent, TransactionEvent, TransactionLog};
+use crate::world_query::{TestMode, WorldQuery};
+use crate::items::ItemRegistry;
+use crate::agent::productivity::{agent_productivity, scale_yield};
+use crate::systems::{CurrencyJournal, TradeEligibility, TradePolicy};
+use crate::{Agent, AgentId, CurrentTick, EnergyComponent, Inventory, Skills, Wallet};
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+use std::collections::HashMap;
+
+/// Energy a teacher spends per lesson (if it has an `EnergyComponent`)
+pub const TEACH_ENERGY_COST: f32 = 5.0;
+
+/// Reputation weight of a successful lesson for both parties
+pub const TEACH_OUTCOME_WEIGHT: f32 = 1.0;
+
+/// Fairness at which a trade's outcome is neutral (no reputation change)
+pub const FAIRNESS_NEUTRAL_POINT: f32 = 0.5;
+
+/// How fair a price is relative to a reference price (0.0 - 1.0)
+///
+/// The ratio of the smaller to the larger of the two: 1.0 at the reference,
+/// 0.5 at double or half of it, approaching 0.0 as they diverge. Symmetric in
+/// over- and underpricing. Without a usable reference (zero, negative or not
+/// finite) every price counts as fair.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::decision::actions::trade_fairness;
+///
+/// assert_eq!(trade_fairness(10.0, 10.0), 1.0);
+/// assert_eq!(trade_fairness(20.0, 10.0), 0.5);
+/// assert_eq!(trade_fairness(5.0, 10.0), 0.5);
+/// ```
+pub fn trade_fairness(price: f32, reference: f32) -> f32 {
+    if !reference.is_finite() || reference <= 0.0 {
+        return 1.0;
+    }
+    if !price.is_finite() || price <= 0.0 {
+        return 0.0;
+    }
+    (price.min(reference) / price.max(reference)).clamp(0.0, 1.0)
+}
+
+/// Reputation outcome of a completed trade with the given fairness
+///
+/// Fully fair trades are `Positive(1.0)`; the weight falls linearly to
+/// `Neutral` at [`FAIRNESS_NEUTRAL_POINT`] and turns negative below it, so a
+/// badly overpriced deal completes but lowers trust.
+pub fn fairness_outcome(fairness: f32) -> Outcome {
+    let weight = (fairness.clamp(0.0, 1.0) - FAIRNESS_NEUTRAL_POINT) / (1.0 - FAIRNESS_NEUTRAL_POINT);
+    if weight > 0.0 {
+        Outcome::Positive(weight)
+    } else if weight < 0.0 {
+        Outcome::Negative(-weight)
+    } else {
+        Outcome::Neutral
+    }
+}
+
+/// Need reduction per unit of currency at which a purchase feels neutral
+pub const VALUE_NEUTRAL_POINT: f32 = 1.0;
+
+/// World resource setting what counts as value for money in [`rate_purchase`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PurchaseValueConfig {
+    /// Need reduction per unit of currency at which the outcome is neutral
+    pub neutral_value: f32,
+}
+
+impl Default for PurchaseValueConfig {
+    fn default() -> Self {
+        Self {
+            neutral_value: VALUE_NEUTRAL_POINT,
+        }
+    }
+}
+
+/// How a purchase felt, judged by the need reduction it bought
+///
+/// Uses [`VALUE_NEUTRAL_POINT`]; see [`outcome_from_need_delta_with`].
+///
+/// # Example
+/// ```rust
+/// use libreconomy::decision::actions::outcome_from_need_delta;
+///
+/// // 30 points of thirst for 5 currency: a bargain
+/// assert!(outcome_from_need_delta(30.0, 5.0).is_positive());
+/// // 2 points for 40 currency: a rip-off
+/// assert!(outcome_from_need_delta(2.0, 40.0).is_negative());
+/// ```
+pub fn outcome_from_need_delta(reduction: f32, price: f32) -> Outcome {
+    outcome_from_need_delta_with(reduction, price, VALUE_NEUTRAL_POINT)
+}
+
+/// How a purchase felt, judged by need reduction per unit of currency
+///
+/// The weight is the log of `reduction / price` relative to
+/// `neutral_value`, clamped to 1: buying twice the neutral value is about
+/// as good as buying half of it is bad. Free help is `Positive(1.0)`, paying
+/// for no help at all is `Negative(1.0)`, and a free item that did nothing
+/// (or non-finite input) is `Neutral`.
+pub fn outcome_from_need_delta_with(reduction: f32, price: f32, neutral_value: f32) -> Outcome {
+    if !reduction.is_finite() || !price.is_finite() {
+        return Outcome::Neutral;
+    }
+    let reduction = reduction.max(0.0);
+    let price = price.max(0.0);
+    let weight = match (reduction > 0.0, price > 0.0) {
+        (false, false) => 0.0,
+        (true, false) => 1.0,
+        (false, true) => -1.0,
+        (true, true) if neutral_value > 0.0 && neutral_value.is_finite() => {
+            ((reduction / price) / neutral_value).ln().clamp(-1.0, 1.0)
+        }
+        (true, true) => 0.0,
+    };
+    if weight > 0.0 {
+        Outcome::Positive(weight)
+    } else if weight < 0.0 {
+        Outcome::Negative(-weight)
+    } else {
+        Outcome::Neutral
+    }
+}
+
+/// Reasons an action could not be executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionError {
+    /// One of the entities has no `Agent` component
+    NotAnAgent,
+    /// The application reports the agents cannot interact
+    CannotInteract,
+    /// A required component is missing (component name)
+    MissingComponent(&'static str),
+    /// The seller does not hold enough of the item
+    InsufficientStock,
+    /// The buyer cannot afford the trade
+    InsufficientFunds,
+    /// Price is negative or not finite
+    InvalidPrice,
+    /// The [`TradeEligibility`] floor bars this agent from trading
+    TradeRefused(AgentId),
+    /// This agent lacks items or currency for its side of a bundle trade
+    CannotCover(AgentId),
+}
+
+impl core::fmt::Display for ActionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ActionError::NotAnAgent => write!(f, "entity is not an agent"),
+            ActionError::CannotInteract => write!(f, "agents cannot interact"),
+            ActionError::MissingComponent(name) => write!(f, "missing component: {}", name),
+            ActionError::InsufficientStock => write!(f, "seller does not have enough stock"),
+            ActionError::InsufficientFunds => write!(f, "buyer cannot afford the trade"),
+            ActionError::InvalidPrice => write!(f, "price must be finite and non-negative"),
+            ActionError::TradeRefused(agent) => {
+                write!(f, "agent {} is not eligible to trade", agent.0)
+            }
+            ActionError::CannotCover(agent) => {
+                write!(f, "agent {} cannot cover its side of the trade", agent.0)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ActionError {}
+
+/// Agent ids of two entities, or `NotAnAgent`
+fn agent_ids(world: &World, a: Entity, b: Entity) -> Result<(AgentId, AgentId), ActionError> {
+    let agents = world.read_storage::<Agent>();
+    match (agents.get(a), agents.get(b)) {
+        (Some(a), Some(b)) => Ok((a.id, b.id)),
+        _ => Err(ActionError::NotAnAgent),
+    }
+}
+
+/// "agent N" for agents, "entity N" otherwise (for log messages)
+fn describe(world: &World, entity: Entity) -> String {
+    match world.read_storage::<Agent>().get(entity) {
+        Some(agent) => format!("agent {}", agent.id.0),
+        None => format!("entity {}", entity.id()),
+    }
+}
+
+/// Whether two agents may interact, honoring [`TestMode`]
+fn can_interact(world: &World, a: AgentId, b: AgentId, world_query: &dyn WorldQuery) -> bool {
+    TestMode::is_enabled(world) || world_query.can_interact(a, b)
+}
+
+fn current_tick(world: &World) -> u64 {
+    world.try_fetch::<CurrentTick>().map(|t| t.0).unwrap_or(0)
+}
+
+/// Log an event, creating the `TransactionLog` if needed, and publish it to
+/// the `EventBus` if the host has one
+fn record(world: &mut World, event: TransactionEvent) {
+    if let Some(mut bus) = world.try_fetch_mut::<EventBus>() {
+        bus.publish(SimEvent::Transaction(event.clone()));
+    }
+    world
+        .entry::<TransactionLog>()
+        .or_insert_with(TransactionLog::new)
+        .add(event);
+}
+
+/// Refuse the trade if either party fails the world's [`TradeEligibility`]
+fn check_eligibility(
+    world: &World,
+    (buyer_id, buyer): (AgentId, Entity),
+    (seller_id, seller): (AgentId, Entity),
+) -> Result<(), ActionError> {
+    let Some(eligibility) = world.try_fetch::<TradeEligibility>().map(|e| *e) else {
+        return Ok(());
+    };
+    for (agent, counterparty) in [(buyer_id, seller), (seller_id, buyer)] {
+        if let Some(score) = eligibility.refusal(world, agent, counterparty) {
+            if let Some(mut bus) = world.try_fetch_mut::<EventBus>() {
+                bus.publish(SimEvent::TradeRefused {
+                    agent,
+                    reason: RefusalReason::LowReputation {
+                        score,
+                        required: eligibility.min_reputation,
+                    },
+                });
+            }
+            return Err(ActionError::TradeRefused(agent));
+        }
+    }
+    Ok(())
+}
+
+/// Buyer purchases `quantity` of `item` from seller at `price` per unit
+///
+/// Moves the items and currency and logs a successful-trade
+/// [`TransactionEvent`] (price = total paid) to the `TransactionLog`, creating
+/// the log if needed. The event is also published to the `EventBus`, if
+/// present. Nothing changes on error.
+///
+/// With a [`TradePolicy`] resource the buyer also pays its tax, which goes to
+/// the treasury or is burned; the seller and the logged price are unaffected.
+///
+/// With a [`TradeEligibility`] resource, a party whose reputation is below the
+/// floor (buyer first) refuses the trade, publishing
+/// [`SimEvent::TradeRefused`] to the `EventBus` if present.
+///
+/// If the world has an `ItemRegistry` resource, the event carries the item's
+/// category, and for items with a reference price the outcome comes from
+/// [`fairness_outcome`] of the unit price against it (prices at or below the
+/// reference count as fully fair). Otherwise the outcome is `Positive(1.0)`.
+///
+/// # Errors
+/// - [`ActionError::NotAnAgent`] if either entity lacks `Agent`
+/// - [`ActionError::CannotInteract`] if `world_query.can_interact` is false
+///   (ignored in [`TestMode`])
+/// - [`ActionError::InvalidPrice`] for a negative or non-finite price
+/// - [`ActionError::TradeRefused`] if a party fails the `TradeEligibility` floor
+/// - [`ActionError::MissingComponent`] if either party lacks `Inventory` or `Wallet`
+/// - [`ActionError::InsufficientStock`] / [`ActionError::InsufficientFunds`]
+///   (the buyer must cover price plus tax)
+pub fn execute_trade(
+    world: &mut World,
+    buyer: Entity,
+    seller: Entity,
+    item: &str,
+    quantity: u32,
+    price: f32,
+    world_query: &dyn WorldQuery,
+) -> Result<Transaction, ActionError> {
+    let result = try_trade(world, buyer, seller, item, quantity, price, world_query);
+    if let Err(err) = &result {
+        log_debug!(
+            "trade of {} {} from {} to {} at {} failed: {}",
+            quantity,
+            item,
+            describe(world, seller),
+            describe(world, buyer),
+            price,
+            err
+        );
+    }
+    result
+}
+
+fn try_trade(
+    world: &mut World,
+    buyer: Entity,
+    seller: Entity,
+    item: &str,
+    quantity: u32,
+    price: f32,
+    world_query: &dyn WorldQuery,
+) -> Result<Transaction, ActionError> {
+    let (buyer_id, seller_id) = agent_ids(world, buyer, seller)?;
+
+    if !can_interact(world, buyer_id, seller_id, world_query) {
+        return Err(ActionError::CannotInteract);
+    }
+
+    if !price.is_finite() || price < 0.0 {
+        return Err(ActionError::InvalidPrice);
+    }
+    check_eligibility(world, (buyer_id, buyer), (seller_id, seller))?;
+    let total = price * quantity as f32;
+    let policy = world.try_fetch::<TradePolicy>().map(|p| *p).unwrap_or_default();
+    let tax = policy.tax_on(total);
+    let mut burned = 0.0;
+
+    {
+        let mut inventories = world.write_storage::<Inventory>();
+        let mut wallets = world.write_storage::<Wallet>();
+
+        let stock = inventories
+            .get(seller)
+            .ok_or(ActionError::MissingComponent("Inventory"))?
+            .quantity(item);
+        if !inventories.contains(buyer) {
+            return Err(ActionError::MissingComponent("Inventory"));
+        }
+        let funds = wallets
+            .get(buyer)
+            .ok_or(ActionError::MissingComponent("Wallet"))?
+            .currency;
+        if !wallets.contains(seller) {
+            return Err(ActionError::MissingComponent("Wallet"));
+        }
+
+        if stock < quantity {
+            return Err(ActionError::InsufficientStock);
+        }
+        if funds < total + tax {
+            return Err(ActionError::InsufficientFunds);
+        }
+
+        if let Some(inv) = inventories.get_mut(seller) {
+            inv.remove(item, quantity);
+        }
+        if let Some(inv) = inventories.get_mut(buyer) {
+            inv.add(item, quantity);
+        }
+        let paid = wallets.get_mut(buyer).map_or(0.0, |w| w.withdraw(total));
+        if let Some(w) = wallets.get_mut(seller) {
+            w.deposit(paid);
+        }
+        if tax > 0.0 {
+            let collected = wallets.get_mut(buyer).map_or(0.0, |w| w.withdraw(tax));
+            match policy.treasury.and_then(|t| wallets.get_mut(t)) {
+                Some(treasury) => treasury.deposit(collected),
+                None => burned = collected,
+            }
+        }
+    }
+    if burned > 0.0 {
+        world.entry::<CurrencyJournal>().or_insert_with(CurrencyJournal::default).burned += burned;
+    }
+
+    let tick = current_tick(world);
+    let mut event =
+        TransactionEvent::successful_trade(buyer_id, seller_id, item.to_string(), total, tick);
+    if let Some(registry) = world.try_fetch::<ItemRegistry>() {
+        if let Some(reference) = registry.reference_price(item) {
+            event.outcome = fairness_outcome(trade_fairness(price.max(reference), reference));
+        }
+        event = event.with_category(registry.category(item));
+    }
+    record(world, event);
+
+    Ok(Transaction {
+        buyer: buyer_id,
+        seller: seller_id,
+        item: item.to_string(),
+        quantity,
+        price,
+        success: true,
+    })
+}
+
+/// Swap two bundles of items and currency atomically
+///
+/// Carries out an [`ActionType::BundleTrade`](crate::decision::ActionType):
+/// `initiator` hands over `give` (items, currency) and receives `receive`
+/// from `counterparty`. Only the net currency changes hands, so "10 coins for
+/// 4 coins and an axe" moves 6 coins; the payer also covers any
+/// [`TradePolicy`] tax on that net amount. Either both sides are covered and
+/// everything moves, or nothing does.
+///
+/// Logs a positive [`TransactionEvent`] (no item, price = net currency moved)
+/// like [`execute_trade`], with `initiator` as the first agent, and honors
+/// [`TradeEligibility`] the same way.
+///
+/// # Errors
+/// - [`ActionError::NotAnAgent`], [`ActionError::CannotInteract`],
+///   [`ActionError::TradeRefused`] as for [`execute_trade`]
+/// - [`ActionError::InvalidPrice`] for a negative or non-finite currency amount
+/// - [`ActionError::MissingComponent`] if either party lacks `Inventory` or
+///   `Wallet`
+/// - [`ActionError::CannotCover`] naming the first party (initiator first)
+///   short of items or of the net currency it owes
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use libreconomy::decision::execute_bundle_trade;
+/// # use libreconomy::world_query::ResourceLocation;
+/// use specs::prelude::*;
+/// use std::collections::HashMap;
+///
+/// # struct Adjacent;
+/// # impl WorldQuery for Adjacent {
+/// #     fn get_nearby_agents(&self, _: AgentId, _: usize) -> Vec<AgentId> { vec![] }
+/// #     fn get_nearby_resources(&self, _: AgentId, _: &str, _: f32) -> Vec<ResourceLocation> { vec![] }
+/// #     fn can_interact(&self, _: AgentId, _: AgentId) -> bool { true }
+/// # }
+/// let mut world = World::new();
+/// register_core_components(&mut world);
+/// let farmer = create_agent_with_wallet(&mut world, Wallet::new(20.0));
+/// let smith = create_agent_with_wallet(&mut world, Wallet::new(0.0));
+/// world.write_storage::<Inventory>().get_mut(farmer).unwrap().add("wheat", 5);
+/// world.write_storage::<Inventory>().get_mut(smith).unwrap().add("axe", 1);
+///
+/// // 3 wheat + 10 coins for 1 axe
+/// let give = HashMap::from([("wheat".to_string(), 3)]);
+/// let receive = HashMap::from([("axe".to_string(), 1)]);
+/// execute_bundle_trade(&mut world, farmer, smith, (&give, 10.0), (&receive, 0.0), &Adjacent).unwrap();
+///
+/// assert_eq!(world.read_storage::<Inventory>().get(farmer).unwrap().quantity("axe"), 1);
+/// assert_eq!(world.read_storage::<Wallet>().get(smith).unwrap().currency, 10.0);
+/// ```
+pub fn execute_bundle_trade(
+    world: &mut World,
+    initiator: Entity,
+    counterparty: Entity,
+    give: (&HashMap<String, u32>, f32),
+    receive: (&HashMap<String, u32>, f32),
+    world_query: &dyn WorldQuery,
+) -> Result<TransactionEvent, ActionError> {
+    let result = try_bundle_trade(world, initiator, counterparty, give, receive, world_query);
+    if let Err(err) = &result {
+        log_debug!(
+            "bundle trade between {} and {} failed: {}",
+            describe(world, initiator),
+            describe(world, counterparty),
+            err
+        );
+    }
+    result
+}
+
+fn try_bundle_trade(
+    world: &mut World,
+    initiator: Entity,
+    counterparty: Entity,
+    (give, give_currency): (&HashMap<String, u32>, f32),
+    (receive, receive_currency): (&HashMap<String, u32>, f32),
+    world_query: &dyn WorldQuery,
+) -> Result<TransactionEvent, ActionError> {
+    let (initiator_id, counterparty_id) = agent_ids(world, initiator, counterparty)?;
+
+    if !can_interact(world, initiator_id, counterparty_id, world_query) {
+        return Err(ActionError::CannotInteract);
+    }
+
+    for amount in [give_currency, receive_currency] {
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(ActionError::InvalidPrice);
+        }
+    }
+    check_eligibility(world, (initiator_id, initiator), (counterparty_id, counterparty))?;
+
+    // Only the difference changes hands, from whoever owes it
+    let net = give_currency - receive_currency;
+    let (payer, payee, payer_id, owed) = if net >= 0.0 {
+        (initiator, counterparty, initiator_id, net)
+    } else {
+        (counterparty, initiator, counterparty_id, -net)
+    };
+    let policy = world.try_fetch::<TradePolicy>().map(|p| *p).unwrap_or_default();
+    let tax = policy.tax_on(owed);
+    let mut burned = 0.0;
+
+    {
+        let mut inventories = world.write_storage::<Inventory>();
+        let mut wallets = world.write_storage::<Wallet>();
+
+        for entity in [initiator, counterparty] {
+            if !inventories.contains(entity) {
+                return Err(ActionError::MissingComponent("Inventory"));
+            }
+            if !wallets.contains(entity) {
+                return Err(ActionError::MissingComponent("Wallet"));
+            }
+        }
+
+        let covers = |entity: Entity, items: &HashMap<String, u32>| {
+            inventories.get(entity).is_some_and(|inv| inv.has_all(items))
+        };
+        if !covers(initiator, give) {
+            return Err(ActionError::CannotCover(initiator_id));
+        }
+        if !covers(counterparty, receive) {
+            return Err(ActionError::CannotCover(counterparty_id));
+        }
+        if wallets.get(payer).map_or(0.0, |w| w.currency) < owed + tax {
+            return Err(ActionError::CannotCover(payer_id));
+        }
+
+        // Take both bundles out before handing either over
+        for (from, items) in [(initiator, give), (counterparty, receive)] {
+            if let Some(inv) = inventories.get_mut(from) {
+                for (item, quantity) in items {
+                    inv.remove(item, *quantity);
+                }
+            }
+        }
+        for (to, items) in [(counterparty, give), (initiator, receive)] {
+            if let Some(inv) = inventories.get_mut(to) {
+                for (item, quantity) in items {
+                    inv.add(item, *quantity);
+                }
+            }
+        }
+
+        let paid = wallets.get_mut(payer).map_or(0.0, |w| w.withdraw(owed));
+        if let Some(w) = wallets.get_mut(payee) {
+            w.deposit(paid);
+        }
+        if tax > 0.0 {
+            let collected = wallets.get_mut(payer).map_or(0.0, |w| w.withdraw(tax));
+            match policy.treasury.and_then(|t| wallets.get_mut(t)) {
+                Some(treasury) => treasury.deposit(collected),
+                None => burned = collected,
+            }
+        }
+    }
+    if burned > 0.0 {
+        world.entry::<CurrencyJournal>().or_insert_with(CurrencyJournal::default).burned += burned;
+    }
+
+    let event = TransactionEvent::new(
+        initiator_id,
+        counterparty_id,
+        None,
+        Some(owed),
+        Outcome::Positive(1.0),
+        current_tick(world),
+    );
+    record(world, event.clone());
+    Ok(event)
+}
+
+/// Buyer rates a seller once a purchased item has been used
+///
+/// `reduction` is what the item did for the buyer's needs (e.g.
+/// `ConsumeAttempt::need_reduction`) and `price` what the buyer paid for it.
+/// Logs a [`TransactionEvent`] with the [`outcome_from_need_delta_with`]
+/// outcome, using the [`PurchaseValueConfig`] resource if present, to the
+/// `TransactionLog` and `EventBus` like [`execute_trade`]. Reputation systems
+/// then teach buyers which sellers give good value.
+///
+/// # Errors
+/// [`ActionError::NotAnAgent`] if either entity lacks `Agent`
+pub fn rate_purchase(
+    world: &mut World,
+    buyer: Entity,
+    seller: Entity,
+    item: &str,
+    reduction: f32,
+    price: f32,
+) -> Result<Outcome, ActionError> {
+    let (buyer_id, seller_id) = agent_ids(world, buyer, seller)?;
+    let neutral = world
+        .try_fetch::<PurchaseValueConfig>()
+        .map_or(VALUE_NEUTRAL_POINT, |c| c.neutral_value);
+    let outcome = outcome_from_need_delta_with(reduction, price, neutral);
+
+    let category = world
+        .try_fetch::<ItemRegistry>()
+        .and_then(|registry| registry.category(item).map(str::to_string));
+    let event = TransactionEvent::new(
+        buyer_id,
+        seller_id,
+        Some(item.to_string()),
+        Some(price),
+        outcome.clone(),
+        current_tick(world),
+    )
+    .with_category(category.as_deref());
+    record(world, event);
+    Ok(outcome)
+}
+
+/// Teacher transfers part of a skill to a student
+///
+/// The student gains half the level gap (at least 1) but always stays below
+/// the teacher. Tired or drained teachers pass on less, scaled by their
+/// [`agent_productivity`](crate::agent_productivity). A lesson costs the teacher [`TEACH_ENERGY_COST`] energy if it
+/// has an `EnergyComponent`, and logs a positive [`TransactionEvent`] (item =
+/// the skill) to the `TransactionLog`, creating the log if needed, and to the
+/// `EventBus` if present.
+///
+/// If the student is already within one level of the teacher, or the teacher
+/// is too exhausted to get anything across, there is nothing to teach: no
+/// skill, energy or event changes, and `Ok(0)` is returned.
+///
+/// # Returns
+/// Levels gained by the student
+///
+/// # Errors
+/// - [`ActionError::NotAnAgent`] if either entity lacks `Agent`
+/// - [`ActionError::CannotInteract`] if `world_query.can_interact` is false
+///   (ignored in [`TestMode`])
+/// - [`ActionError::MissingComponent`] if `Skills` is not registered or the
+///   teacher has no `Skills` (students without one get a fresh component)
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use libreconomy::decision::execute_teach;
+/// # use libreconomy::world_query::ResourceLocation;
+/// use specs::prelude::*;
+///
+/// # struct Adjacent;
+/// # impl WorldQuery for Adjacent {
+/// #     fn get_nearby_agents(&self, _: AgentId, _: usize) -> Vec<AgentId> { vec![] }
+/// #     fn get_nearby_resources(&self, _: AgentId, _: &str, _: f32) -> Vec<ResourceLocation> { vec![] }
+/// #     fn can_interact(&self, _: AgentId, _: AgentId) -> bool { true }
+/// # }
+/// let mut world = World::new();
+/// world.register::<Agent>();
+/// world.register::<Skills>();
+///
+/// let mut expert = Skills::default();
+/// expert.skills.insert("farming".into(), 10);
+/// let teacher = world.create_entity().with(Agent { id: AgentId(1) }).with(expert).build();
+/// let student = world.create_entity().with(Agent { id: AgentId(2) }).with(Skills::default()).build();
+///
+/// assert_eq!(execute_teach(&mut world, teacher, student, "farming", &Adjacent), Ok(5));
+/// ```
+pub fn execute_teach(
+    world: &mut World,
+    teacher: Entity,
+    student: Entity,
+    skill: &str,
+    world_query: &dyn WorldQuery,
+) -> Result<u32, ActionError> {
+    let result = try_teach(world, teacher, student, skill, world_query);
+    if let Err(err) = &result {
+        log_debug!(
+            "lesson in {} from {} to {} failed: {}",
+            skill,
+            describe(world, teacher),
+            describe(world, student),
+            err
+        );
+    }
+    result
+}
+
+fn try_teach(
+    world: &mut World,
+    teacher: Entity,
+    student: Entity,
+    skill: &str,
+    world_query: &dyn WorldQuery,
+) -> Result<u32, ActionError> {
+    let (teacher_id, student_id) = agent_ids(world, teacher, student)?;
+
+    if !can_interact(world, teacher_id, student_id, world_query) {
+        return Err(ActionError::CannotInteract);
+    }
+
+    if !world.has_value::<MaskedStorage<Skills>>() {
+        return Err(ActionError::MissingComponent("Skills"));
+    }
+
+    // A tired teacher gets less across
+    let productivity = agent_productivity(world, teacher);
+    let gained = {
+        let mut skills = world.write_storage::<Skills>();
+        let teacher_level = match skills.get(teacher) {
+            Some(s) => s.skills.get(skill).copied().unwrap_or(0),
+            None => return Err(ActionError::MissingComponent("Skills")),
+        };
+
+        let student_level = skills
+            .get(student)
+            .and_then(|s| s.skills.get(skill).copied())
+            .unwrap_or(0);
+
+        // Cap strictly below the teacher
+        let cap = teacher_level.saturating_sub(1);
+        if student_level >= cap {
+            return Ok(0);
+        }
+
+        let gap = teacher_level - student_level;
+        let step = scale_yield((gap / 2).max(1), productivity);
+        if step == 0 {
+            return Ok(0);
+        }
+        let new_level = (student_level + step).min(cap);
+        skills
+            .entry(student)
+            .map_err(|_| ActionError::NotAnAgent)?
+            .or_insert_with(Skills::default)
+            .skills
+            .insert(skill.to_string(), new_level);
+        new_level - student_level
+    };
+
+    if world.has_value::<MaskedStorage<EnergyComponent>>() {
+        if let Some(energy) = world.write_storage::<EnergyComponent>().get_mut(teacher) {
+            energy.current -= TEACH_ENERGY_COST;
+            energy.clamp();
+        }
+    }
+
+    let tick = current_tick(world);
+    record(
+        world,
+        TransactionEvent::new(
+            teacher_id,
+            student_id,
+            Some(skill.to_string()),
+            None,
+            Outcome::Positive(TEACH_OUTCOME_WEIGHT),
+            tick,
+        ),
+    );
+
+    Ok(gained)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world_query::ResourceLocation;
+    use crate::{ReputationKnowledge, ReputationUpdateSystem};
+    use pretty_assertions::assert_eq;
+
+    struct FixedWorldQuery {
+        interact: bool,
+    }
+
+    impl WorldQuery for FixedWorldQuery {
+        fn get_nearby_agents(&self, _agent: AgentId, _max_count: usize) -> Vec<AgentId> {
+            Vec::new()
+        }
+
+        fn get_nearby_resources(
+            &self,
+            _agent: AgentId,
+            _resource_type: &str,
+            _max_radius: f32,
+        ) -> Vec<ResourceLocation> {
+            Vec::new()
+        }
+
+        fn can_interact(&self, _agent1: AgentId, _agent2: AgentId) -> bool {
+            self.interact
+        }
+    }
+
+    const NEAR: FixedWorldQuery = FixedWorldQuery { interact: true };
+
+    fn skilled(level: u32) -> Skills {
+        let mut skills = Skills::default();
+        skills.skills.insert("farming".to_string(), level);
+        skills
+    }
+
+    fn setup_world(teacher_level: u32, student_level: u32) -> (World, Entity, Entity) {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Skills>();
+        world.register::<EnergyComponent>();
+        world.register::<ReputationKnowledge>();
+
+        let teacher = world
+            .create_entity()
+            .with(Agent { id: AgentId(1) })
+            .with(skilled(teacher_level))
+            .with(EnergyComponent::new(50.0, 100.0))
+            .with(ReputationKnowledge::default())
+            .build();
+        let student = world
+            .create_entity()
+            .with(Agent { id: AgentId(2) })
+            .with(skilled(student_level))
+            .with(ReputationKnowledge::default())
+            .build();
+
+        (world, teacher, student)
+    }
+
+    fn level(world: &World, entity: Entity) -> u32 {
+        world.read_storage::<Skills>().get(entity).unwrap().skills["farming"]
+    }
+
+    #[test]
+    fn test_teach_raises_student_below_teacher() {
+        let (mut world, teacher, student) = setup_world(10, 2);
+
+        assert_eq!(execute_teach(&mut world, teacher, student, "farming", &NEAR), Ok(4));
+        assert_eq!(level(&world, student), 6);
+
+        // Repeated lessons converge but never reach the teacher
+        for _ in 0..10 {
+            execute_teach(&mut world, teacher, student, "farming", &NEAR).unwrap();
+        }
+        assert_eq!(level(&world, student), 9);
+        assert_eq!(level(&world, teacher), 10);
+    }
+
+    #[test]
+    fn test_tired_teacher_teaches_less() {
+        let (mut world, teacher, student) = setup_world(20, 0);
+        world.register::<crate::Needs>();
+        world.write_storage().insert(teacher, crate::Needs::new(0.0, 0.0, 75.0)).unwrap();
+
+        // Tired and half drained, the teacher gets 4 of the usual 10 levels across
+        assert_eq!(execute_teach(&mut world, teacher, student, "farming", &NEAR), Ok(4));
+    }
+
+    #[test]
+    fn test_exhausted_teacher_teaches_nothing() {
+        let (mut world, teacher, student) = setup_world(10, 2);
+        world.register::<crate::Needs>();
+        world.write_storage().insert(teacher, crate::Needs::new(0.0, 0.0, 100.0)).unwrap();
+
+        assert_eq!(execute_teach(&mut world, teacher, student, "farming", &NEAR), Ok(0));
+        assert_eq!(level(&world, student), 2);
+        assert!(world.try_fetch::<TransactionLog>().is_none());
+        assert_eq!(world.read_storage::<EnergyComponent>().get(teacher).unwrap().current, 50.0);
+    }
+
+    #[test]
+    fn test_teach_costs_teacher_energy() {
+        let (mut world, teacher, student) = setup_world(10, 2);
+        execute_teach(&mut world, teacher, student, "farming", &NEAR).unwrap();
+
+        let energy = world.read_storage::<EnergyComponent>();
+        assert_eq!(energy.get(teacher).unwrap().current, 50.0 - TEACH_ENERGY_COST);
+    }
+
+    #[test]
+    fn test_teach_improves_both_reputations() {
+        let (mut world, teacher, student) = setup_world(10, 2);
+        execute_teach(&mut world, teacher, student, "farming", &NEAR).unwrap();
+
+        ReputationUpdateSystem::default().run_now(&world);
+
+        let knowledge = world.read_storage::<ReputationKnowledge>();
+        assert!(knowledge.get(teacher).unwrap().get_score(AgentId(2)) > 0.5);
+        assert!(knowledge.get(student).unwrap().get_score(AgentId(1)) > 0.5);
+    }
+
+    #[test]
+    fn test_teach_noop_when_student_matches_teacher() {
+        let (mut world, teacher, student) = setup_world(5, 5);
+
+        assert_eq!(execute_teach(&mut world, teacher, student, "farming", &NEAR), Ok(0));
+        assert_eq!(level(&world, student), 5);
+        assert!(world.try_fetch::<TransactionLog>().is_none());
+
+        let energy = world.read_storage::<EnergyComponent>();
+        assert_eq!(energy.get(teacher).unwrap().current, 50.0);
+    }
+
+    #[test]
+    fn test_noop_lesson_does_not_give_student_skills() {
+        let (mut world, teacher, student) = setup_world(1, 0);
+        world.write_storage::<Skills>().remove(student);
+
+        assert_eq!(execute_teach(&mut world, teacher, student, "farming", &NEAR), Ok(0));
+        assert!(world.read_storage::<Skills>().get(student).is_none());
+    }
+
+    #[test]
+    fn test_teach_requires_interaction() {
+        let (mut world, teacher, student) = setup_world(10, 2);
+        let far = FixedWorldQuery { interact: false };
+
+        assert_eq!(
+            execute_teach(&mut world, teacher, student, "farming", &far),
+            Err(ActionError::CannotInteract)
+        );
+        assert_eq!(level(&world, student), 2);
+    }
+
+    fn setup_market() -> (World, Entity, Entity) {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Inventory>();
+        world.register::<Wallet>();
+
+        let buyer = world
+            .create_entity()
+            .with(Agent { id: AgentId(1) })
+            .with(Inventory::default())
+            .with(Wallet::new(100.0))
+            .build();
+        let mut stock = Inventory::default();
+        stock.add("water", 5);
+        let seller = world
+            .create_entity()
+            .with(Agent { id: AgentId(2) })
+            .with(stock)
+            .with(Wallet::new(0.0))
+            .build();
+
+        (world, buyer, seller)
+    }
+
+    #[test]
+    fn test_trade_moves_items_and_currency() {
+        let (mut world, buyer, seller) = setup_market();
+
+        let tx = execute_trade(&mut world, buyer, seller, "water", 3, 10.0, &NEAR).unwrap();
+        assert!(tx.is_successful());
+        assert_eq!(tx.total_value(), 30.0);
+
+        let inventories = world.read_storage::<Inventory>();
+        let wallets = world.read_storage::<Wallet>();
+        assert_eq!(inventories.get(buyer).unwrap().quantity("water"), 3);
+        assert_eq!(inventories.get(seller).unwrap().quantity("water"), 2);
+        assert_eq!(wallets.get(buyer).unwrap().currency, 70.0);
+        assert_eq!(wallets.get(seller).unwrap().currency, 30.0);
+        assert_eq!(world.read_resource::<TransactionLog>().len(), 1);
+    }
+
+    #[test]
+    fn test_taxed_trade_routes_fee_to_treasury() {
+        let (mut world, buyer, seller) = setup_market();
+        let treasury = world.create_entity().with(Wallet::new(0.0)).build();
+        world.insert(TradePolicy { tax_rate: 0.1, treasury: Some(treasury) });
+
+        execute_trade(&mut world, buyer, seller, "water", 3, 10.0, &NEAR).unwrap();
+        {
+            let wallets = world.read_storage::<Wallet>();
+            assert_eq!(wallets.get(buyer).unwrap().currency, 67.0);
+            assert_eq!(wallets.get(seller).unwrap().currency, 30.0);
+            assert_eq!(wallets.get(treasury).unwrap().currency, 3.0);
+        }
+        assert_eq!(crate::systems::total_currency(&world), 100.0);
+
+        // Price alone is affordable, price plus tax is not
+        assert_eq!(
+            execute_trade(&mut world, buyer, seller, "water", 2, 32.0, &NEAR),
+            Err(ActionError::InsufficientFunds)
+        );
+    }
+
+    #[test]
+    fn test_untreasuried_tax_is_burned_and_journaled() {
+        let (mut world, buyer, seller) = setup_market();
+        world.insert(TradePolicy { tax_rate: 0.25, treasury: None });
+
+        execute_trade(&mut world, buyer, seller, "water", 2, 10.0, &NEAR).unwrap();
+        assert_eq!(crate::systems::total_currency(&world), 95.0);
+        assert_eq!(world.read_resource::<CurrencyJournal>().burned, 5.0);
+    }
+
+    #[test]
+    fn test_low_reputation_buyer_is_refused() {
+        let (mut world, buyer, seller) = setup_market();
+        let reputable = world
+            .create_entity()
+            .with(Agent { id: AgentId(3) })
+            .with(Inventory::default())
+            .with(Wallet::new(100.0))
+            .build();
+        world.register::<ReputationKnowledge>();
+        let mut seller_view = ReputationKnowledge::new();
+        for tick in 0..5 {
+            seller_view.update_reputation(AgentId(1), -1.0, tick);
+            seller_view.update_reputation(AgentId(3), 1.0, tick);
+        }
+        world.write_storage::<ReputationKnowledge>().insert(seller, seller_view).unwrap();
+        world.insert(TradeEligibility::new(0.4));
+        world.insert(EventBus::new());
+
+        assert_eq!(
+            execute_trade(&mut world, buyer, seller, "water", 1, 10.0, &NEAR),
+            Err(ActionError::TradeRefused(AgentId(1)))
+        );
+        assert_eq!(world.read_storage::<Wallet>().get(buyer).unwrap().currency, 100.0);
+        let events = world.write_resource::<EventBus>().poll();
+        assert!(matches!(
+            events.as_slice(),
+            [SimEvent::TradeRefused { agent: AgentId(1), reason: RefusalReason::LowReputation { required, .. } }]
+                if *required == 0.4
+        ));
+
+        assert!(execute_trade(&mut world, reputable, seller, "water", 1, 10.0, &NEAR).is_ok());
+    }
+
+    #[test]
+    fn test_market_reputation_judges_both_parties() {
+        let (mut world, buyer, seller) = setup_market();
+        world.register::<ReputationKnowledge>();
+        let mut ledger = ReputationKnowledge::new();
+        for tick in 0..5 {
+            ledger.update_reputation(AgentId(2), -1.0, tick);
+        }
+        let market = world.create_entity().with(ledger).build();
+        world.insert(TradeEligibility::new(0.4).judged_by(market));
+
+        // The seller's own blank view is ignored; the market blacklists it
+        assert_eq!(
+            execute_trade(&mut world, buyer, seller, "water", 1, 10.0, &NEAR),
+            Err(ActionError::TradeRefused(AgentId(2)))
+        );
+
+        world.insert(TradeEligibility::new(0.4));
+        assert!(execute_trade(&mut world, buyer, seller, "water", 1, 10.0, &NEAR).is_ok());
+    }
+
+    #[test]
+    fn test_trade_rejects_unaffordable_or_unstocked() {
+        let (mut world, buyer, seller) = setup_market();
+
+        assert_eq!(
+            execute_trade(&mut world, buyer, seller, "water", 6, 1.0, &NEAR),
+            Err(ActionError::InsufficientStock)
+        );
+        assert_eq!(
+            execute_trade(&mut world, buyer, seller, "water", 5, 50.0, &NEAR),
+            Err(ActionError::InsufficientFunds)
+        );
+        assert_eq!(
+            execute_trade(&mut world, buyer, seller, "water", 1, f32::NAN, &NEAR),
+            Err(ActionError::InvalidPrice)
+        );
+        assert_eq!(world.read_storage::<Wallet>().get(buyer).unwrap().currency, 100.0);
+    }
+
+    fn bundle(items: &[(&str, u32)]) -> HashMap<String, u32> {
+        items.iter().map(|(item, n)| (item.to_string(), *n)).collect()
+    }
+
+    #[test]
+    fn test_bundle_trade_swaps_items_and_nets_currency() {
+        let (mut world, buyer, seller) = setup_market();
+        world.write_storage::<Inventory>().get_mut(buyer).unwrap().add("wheat", 4);
+        world.write_storage::<Wallet>().get_mut(seller).unwrap().currency = 5.0;
+
+        // 3 wheat + 10 coins for 2 water + 4 coins: 6 coins move to the seller
+        let give = bundle(&[("wheat", 3)]);
+        let receive = bundle(&[("water", 2)]);
+        let event =
+            execute_bundle_trade(&mut world, buyer, seller, (&give, 10.0), (&receive, 4.0), &NEAR).unwrap();
+        assert_eq!((event.agent1, event.agent2, event.price), (AgentId(1), AgentId(2), Some(6.0)));
+
+        {
+            let inventories = world.read_storage::<Inventory>();
+            let (mine, theirs) = (inventories.get(buyer).unwrap(), inventories.get(seller).unwrap());
+            assert_eq!((mine.quantity("wheat"), mine.quantity("water")), (1, 2));
+            assert_eq!((theirs.quantity("wheat"), theirs.quantity("water")), (3, 3));
+            let wallets = world.read_storage::<Wallet>();
+            assert_eq!(wallets.get(buyer).unwrap().currency, 94.0);
+            assert_eq!(wallets.get(seller).unwrap().currency, 11.0);
+        }
+        assert_eq!(world.read_resource::<TransactionLog>().len(), 1);
+
+        // The seller owes the difference when it offers more coin: buys wheat back
+        let back = bundle(&[("wheat", 1)]);
+        execute_bundle_trade(&mut world, buyer, seller, (&back, 0.0), (&HashMap::new(), 8.0), &NEAR).unwrap();
+        let wallets = world.read_storage::<Wallet>();
+        assert_eq!(wallets.get(buyer).unwrap().currency, 102.0);
+        assert_eq!(wallets.get(seller).unwrap().currency, 3.0);
+    }
+
+    #[test]
+    fn test_bundle_trade_is_all_or_nothing() {
+        let (mut world, buyer, seller) = setup_market();
+        world.write_storage::<Inventory>().get_mut(buyer).unwrap().add("wheat", 2);
+        let snapshot = |world: &World| {
+            let inventories = world.read_storage::<Inventory>();
+            let wallets = world.read_storage::<Wallet>();
+            (
+                inventories.get(buyer).unwrap().clone(),
+                inventories.get(seller).unwrap().clone(),
+                wallets.get(buyer).unwrap().currency,
+                wallets.get(seller).unwrap().currency,
+            )
+        };
+        let before = snapshot(&world);
+
+        // The seller has the water but not the axe it also promised
+        let give = bundle(&[("wheat", 2)]);
+        let receive = bundle(&[("water", 2), ("axe", 1)]);
+        assert_eq!(
+            execute_bundle_trade(&mut world, buyer, seller, (&give, 0.0), (&receive, 0.0), &NEAR),
+            Err(ActionError::CannotCover(AgentId(2)))
+        );
+        // The buyer is one wheat short
+        let greedy = bundle(&[("wheat", 3)]);
+        assert_eq!(
+            execute_bundle_trade(&mut world, buyer, seller, (&greedy, 0.0), (&bundle(&[("water", 1)]), 0.0), &NEAR),
+            Err(ActionError::CannotCover(AgentId(1)))
+        );
+        // The seller cannot pay the net 10 coins it owes
+        assert_eq!(
+            execute_bundle_trade(&mut world, buyer, seller, (&give, 0.0), (&HashMap::new(), 10.0), &NEAR),
+            Err(ActionError::CannotCover(AgentId(2)))
+        );
+        assert_eq!(
+            execute_bundle_trade(&mut world, buyer, seller, (&give, -1.0), (&HashMap::new(), 0.0), &NEAR),
+            Err(ActionError::InvalidPrice)
+        );
+
+        assert_eq!(snapshot(&world), before);
+        assert!(world.try_fetch::<TransactionLog>().is_none());
+    }
+
+    #[test]
+    fn test_trade_fairness_scores() {
+        assert_eq!(trade_fairness(4.0, 1.0), 0.25);
+        assert_eq!(trade_fairness(1.0, 0.0), 1.0);
+        assert_eq!(trade_fairness(0.0, 1.0), 0.0);
+        assert_eq!(fairness_outcome(1.0), Outcome::Positive(1.0));
+        assert_eq!(fairness_outcome(0.5), Outcome::Neutral);
+        assert_eq!(fairness_outcome(0.25), Outcome::Negative(0.5));
+    }
+
+    #[test]
+    fn test_purchase_outcome_follows_value_for_money() {
+        assert!(outcome_from_need_delta(30.0, 5.0).is_positive());
+        assert!(outcome_from_need_delta(2.0, 40.0).is_negative());
+        assert_eq!(outcome_from_need_delta(10.0, 10.0), Outcome::Neutral);
+        assert_eq!(outcome_from_need_delta(5.0, 0.0), Outcome::Positive(1.0));
+        assert_eq!(outcome_from_need_delta(0.0, 5.0), Outcome::Negative(1.0));
+        assert_eq!(outcome_from_need_delta(0.0, 0.0), Outcome::Neutral);
+        assert_eq!(outcome_from_need_delta(f32::NAN, 5.0), Outcome::Neutral);
+
+        // A stricter buyer is unimpressed by a deal the default calls good
+        assert!(outcome_from_need_delta(15.0, 10.0).is_positive());
+        assert!(outcome_from_need_delta_with(15.0, 10.0, 2.0).is_negative());
+    }
+
+    #[test]
+    fn test_rate_purchase_logs_outcome() {
+        let (mut world, buyer, seller) = setup_market();
+        world.insert(PurchaseValueConfig { neutral_value: 2.0 });
+
+        let outcome = rate_purchase(&mut world, buyer, seller, "water", 15.0, 10.0).unwrap();
+        assert!(outcome.is_negative());
+
+        let log = world.read_resource::<TransactionLog>();
+        let event = &log.events()[0];
+        assert_eq!((event.agent1, event.agent2), (AgentId(1), AgentId(2)));
+        assert_eq!(event.outcome, outcome);
+    }
+
+    /// Buyer's score of the seller after one trade at `price` (water reference 10)
+    fn buyer_trust_after_trade(price: f32) -> f32 {
+        let (mut world, buyer, seller) = setup_market();
+        world.register::<ReputationKnowledge>();
+        for entity in [buyer, seller] {
+            world
+                .write_storage::<ReputationKnowledge>()
+                .insert(entity, ReputationKnowledge::default())
+                .unwrap();
+        }
+        let mut registry = ItemRegistry::with_defaults();
+        registry.get_mut("water").unwrap().reference_price = Some(10.0);
+        world.insert(registry);
+
+        execute_trade(&mut world, buyer, seller, "water", 1, price, &NEAR).unwrap();
+        ReputationUpdateSystem::default().run_now(&world);
+
+        let knowledge = world.read_storage::<ReputationKnowledge>();
+        knowledge.get(buyer).unwrap().get_score(AgentId(2))
+    }
+
+    #[test]
+    fn test_trade_event_carries_item_category() {
+        let (mut world, buyer, seller) = setup_market();
+        world.insert(ItemRegistry::with_defaults());
+        execute_trade(&mut world, buyer, seller, "water", 1, 1.0, &NEAR).unwrap();
+
+        let log = world.read_resource::<TransactionLog>();
+        assert_eq!(log.events()[0].category.as_deref(), Some("drink"));
+    }
+
+    #[test]
+    fn test_unpriced_item_trades_without_fairness_penalty() {
+        let (mut world, buyer, seller) = setup_market();
+        world.insert(ItemRegistry::with_defaults());
+        execute_trade(&mut world, buyer, seller, "water", 1, 10.0, &NEAR).unwrap();
+
+        // The defaults carry no reference price, so an ordinary price is fine
+        let log = world.read_resource::<TransactionLog>();
+        assert_eq!(log.events()[0].outcome, Outcome::Positive(1.0));
+    }
+
+    #[test]
+    fn test_trade_published_to_host_event_bus() {
+        let (mut world, buyer, seller) = setup_market();
+        execute_trade(&mut world, buyer, seller, "water", 1, 1.0, &NEAR).unwrap();
+        // No bus unless the host inserts one
+        assert!(world.try_fetch::<EventBus>().is_none());
+
+        world.insert(EventBus::new());
+        execute_trade(&mut world, buyer, seller, "water", 1, 1.0, &NEAR).unwrap();
+
+        let log = world.read_resource::<TransactionLog>();
+        let bus = world.read_resource::<EventBus>();
+        assert_eq!(bus.events(), &[SimEvent::Transaction(log.events()[1].clone())]);
+    }
+
+    #[test]
+    fn test_unfair_trade_earns_less_trust() {
+        let fair = buyer_trust_after_trade(10.0);
+        let pricey = buyer_trust_after_trade(15.0);
+        let gouged = buyer_trust_after_trade(50.0);
+
+        assert!(fair > pricey);
+        assert!(pricey > 0.5);
+        // Completed, but trust drops
+        assert!(gouged < 0.5);
+        // Underpricing only hurts the seller
+        assert_eq!(buyer_trust_after_trade(2.0), fair);
+    }
+
+    #[test]
+    fn test_trade_executes_in_test_mode_despite_distance() {
+        let (mut world, buyer, seller) = setup_market();
+        let far = FixedWorldQuery { interact: false };
+
+        assert_eq!(
+            execute_trade(&mut world, buyer, seller, "water", 1, 10.0, &far),
+            Err(ActionError::CannotInteract)
+        );
+
+        world.insert(TestMode::enabled());
+        let tx = execute_trade(&mut world, buyer, seller, "water", 1, 10.0, &far).unwrap();
+        assert!(tx.is_successful());
+        assert_eq!(world.read_storage::<Inventory>().get(buyer).unwrap().quantity("water"), 1);
+    }
+}
@@ -0,0 +1,109 @@
+// Intent commitment (hysteresis in utility space)
+//
+// With two options of near-equal utility, plain argmax flips between them
+// as needs drift by fractions of a point. Agents carrying a
+// `CommittedIntent` keep the goal they chose last time unless another one
+// beats it by more than `UtilityMaximizer::commit_margin`.
+
+use super::types::Intent;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+
+/// The goal an agent committed to at its last decision
+///
+/// Add it to agents that should resist flip-flopping; agents without it
+/// always take the best-scoring intent. `UtilityMaximizer::decide` keeps it
+/// up to date.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommittedIntent {
+    /// Intent chosen last time; `None` before the first decision
+    pub intent: Option<Intent>,
+}
+
+impl Component for CommittedIntent {
+    type Storage = VecStorage<Self>;
+}
+
+/// Whether two intents pursue the same goal
+///
+/// Parameters that drift from tick to tick (urgency, invest amount, wander
+/// heading, move target coordinates) are ignored; the item or region sought
+/// must match.
+pub fn same_goal(a: &Intent, b: &Intent) -> bool {
+    match (a, b) {
+        (Intent::SeekItem { item_type: x, .. }, Intent::SeekItem { item_type: y, .. }) => x == y,
+        (Intent::MoveTo { item_type: x, .. }, Intent::MoveTo { item_type: y, .. }) => x == y,
+        (
+            Intent::SeekTrade { buying: bx, item_type: x },
+            Intent::SeekTrade { buying: by, item_type: y },
+        ) => bx == by && x == y,
+        (Intent::Migrate { toward_region: x }, Intent::Migrate { toward_region: y }) => x == y,
+        (Intent::FindWork { skill_types: x }, Intent::FindWork { skill_types: y }) => x == y,
+        (Intent::VisitAgent { agent: x }, Intent::VisitAgent { agent: y }) => x == y,
+        (Intent::Invest { .. }, Intent::Invest { .. }) | (Intent::Rest, Intent::Rest) => true,
+        (
+            Intent::Wander | Intent::WanderHeading { .. },
+            Intent::Wander | Intent::WanderHeading { .. },
+        ) => true,
+        _ => false,
+    }
+}
+
+/// Index of the intent to pick from a ranking sorted best first
+///
+/// The previous goal's entry wins unless the best entry beats it by more
+/// than `margin`. A margin of 0 (or no previous goal, or one that is no
+/// longer on offer) picks the best entry.
+pub fn committed_choice(
+    ranked: &[(Intent, f32, String)],
+    previous: Option<&Intent>,
+    margin: f32,
+) -> usize {
+    let (Some(previous), Some((_, best, _))) = (previous, ranked.first()) else {
+        return 0;
+    };
+    if margin.is_nan() || margin <= 0.0 {
+        return 0;
+    }
+    ranked
+        .iter()
+        .position(|(intent, utility, _)| same_goal(intent, previous) && *best - *utility <= margin)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn seek(item: &str, urgency: f32) -> Intent {
+        Intent::SeekItem {
+            item_type: item.to_string(),
+            urgency,
+        }
+    }
+
+    #[test]
+    fn test_committed_choice_needs_a_clear_winner_to_switch() {
+        let ranked = vec![
+            (seek("food", 0.6), 0.62, String::new()),
+            (seek("water", 0.55), 0.60, String::new()),
+            (Intent::Wander, 0.1, String::new()),
+        ];
+        let previous = seek("water", 0.5);
+
+        assert_eq!(committed_choice(&ranked, Some(&previous), 0.05), 1);
+        assert_eq!(committed_choice(&ranked, Some(&previous), 0.01), 0);
+        assert_eq!(committed_choice(&ranked, Some(&previous), 0.0), 0);
+        assert_eq!(committed_choice(&ranked, None, 0.05), 0);
+        assert_eq!(committed_choice(&ranked, Some(&Intent::Rest), 0.05), 0);
+    }
+
+    #[test]
+    fn test_same_goal_ignores_drifting_parameters() {
+        assert!(same_goal(&seek("water", 0.2), &seek("water", 0.9)));
+        assert!(!same_goal(&seek("water", 0.2), &seek("food", 0.2)));
+        assert!(same_goal(&Intent::Wander, &Intent::WanderHeading { heading: 1.0, persistence: 3 }));
+        assert!(!same_goal(&Intent::Rest, &Intent::Wander));
+    }
+}
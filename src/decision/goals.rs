@@ -0,0 +1,319 @@
+// Long-term goals layered over survival
+//
+// `UtilityMaximizer` only reacts to needs, so a content agent just wanders.
+// Agents carrying `Goals` spend that idle time on an ordered to-do list
+// instead: `GoalDrivenDecisionMaker` works on the first unmet goal, retires
+// goals once they are met, and hands control back to the maximizer the
+// moment a survival need becomes pressing.
+
+use super::types::{DecisionOutput, Intent};
+use super::utility_maximizer::UtilityMaximizer;
+use super::DecisionMaker;
+use crate::world_query::WorldQuery;
+use crate::{Agent, AgentId, Inventory, Needs, Skills, Wallet};
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+/// Urgency of the `SeekItem` intents emitted for goals
+///
+/// Below the 0.7 at which `Intent::is_critical` kicks in: goals are never
+/// survival-critical.
+pub const GOAL_URGENCY: f32 = 0.5;
+
+/// Something an agent wants beyond staying alive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Goal {
+    /// Hold at least `quantity` of `item` in the inventory
+    AcquireItem { item: String, quantity: u32 },
+    /// Hold at least `amount` currency in the wallet
+    ReachWealth { amount: f32 },
+    /// Get within interaction range of another agent
+    VisitAgent { agent: AgentId },
+}
+
+impl Goal {
+    /// Whether `agent` has met the goal
+    ///
+    /// Goals whose component is missing or unregistered are unmet.
+    pub fn is_met(&self, agent: Entity, world: &World, world_query: &dyn WorldQuery) -> bool {
+        match self {
+            Goal::AcquireItem { item, quantity } => {
+                world.has_value::<MaskedStorage<Inventory>>()
+                    && world
+                        .read_storage::<Inventory>()
+                        .get(agent)
+                        .is_some_and(|inv| inv.quantity(item) >= *quantity)
+            }
+            Goal::ReachWealth { amount } => {
+                world.has_value::<MaskedStorage<Wallet>>()
+                    && world
+                        .read_storage::<Wallet>()
+                        .get(agent)
+                        .is_some_and(|wallet| wallet.currency >= *amount)
+            }
+            Goal::VisitAgent { agent: other } => world
+                .read_storage::<Agent>()
+                .get(agent)
+                .is_some_and(|me| world_query.can_interact(me.id, *other)),
+        }
+    }
+
+    /// Intent that moves `agent` toward the goal
+    pub fn intent(&self, agent: Entity, world: &World) -> Intent {
+        match self {
+            Goal::AcquireItem { item, .. } => Intent::SeekItem {
+                item_type: item.clone(),
+                urgency: GOAL_URGENCY,
+            },
+            Goal::ReachWealth { .. } => {
+                let mut skill_types: Vec<String> = if world.has_value::<MaskedStorage<Skills>>() {
+                    world
+                        .read_storage::<Skills>()
+                        .get(agent)
+                        .map(|s| s.skills.keys().cloned().collect())
+                        .unwrap_or_default()
+                } else {
+                    Vec::new()
+                };
+                skill_types.sort();
+                Intent::FindWork { skill_types }
+            }
+            Goal::VisitAgent { agent: other } => Intent::VisitAgent { agent: *other },
+        }
+    }
+}
+
+/// An agent's goals, most important first
+///
+/// # Example
+/// ```rust
+/// use libreconomy::decision::{Goal, Goals};
+///
+/// let mut goals = Goals::new(vec![Goal::ReachWealth { amount: 500.0 }]);
+/// goals.push(Goal::AcquireItem { item: "axe".into(), quantity: 1 });
+/// assert_eq!(goals.current(), Some(&Goal::ReachWealth { amount: 500.0 }));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Goals {
+    /// Goals still being worked on, in priority order
+    pub active: Vec<Goal>,
+    /// Goals met so far, in the order they were met
+    pub completed: Vec<Goal>,
+}
+
+impl Goals {
+    /// Goals to pursue in the given order
+    pub fn new(active: Vec<Goal>) -> Self {
+        Self {
+            active,
+            completed: Vec::new(),
+        }
+    }
+
+    /// Add a goal at the lowest priority
+    pub fn push(&mut self, goal: Goal) {
+        self.active.push(goal);
+    }
+
+    /// The goal currently being pursued
+    pub fn current(&self) -> Option<&Goal> {
+        self.active.first()
+    }
+
+    /// Move every met goal from `active` to `completed`
+    ///
+    /// Returns how many goals were completed.
+    pub fn retire_met(&mut self, mut is_met: impl FnMut(&Goal) -> bool) -> usize {
+        let before = self.completed.len();
+        let (met, unmet): (Vec<Goal>, Vec<Goal>) =
+            std::mem::take(&mut self.active).into_iter().partition(|g| is_met(g));
+        self.active = unmet;
+        self.completed.extend(met);
+        self.completed.len() - before
+    }
+}
+
+impl Component for Goals {
+    type Storage = VecStorage<Self>;
+}
+
+/// Decision maker that pursues an agent's [`Goals`] once it is comfortable
+///
+/// Each decision first retires any goals the agent has met. If a need is
+/// above its `high_*` threshold in the wrapped maximizer's
+/// [`DecisionThresholds`](super::DecisionThresholds), or the agent has no
+/// unmet goals, the maximizer decides as usual; otherwise the agent works
+/// on its first active goal.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use libreconomy::decision::{DecisionMaker, Goal, GoalDrivenDecisionMaker, Goals};
+/// use libreconomy::world_query::ResourceLocation;
+/// use specs::prelude::*;
+///
+/// struct Empty;
+/// impl WorldQuery for Empty {
+///     fn get_nearby_agents(&self, _: AgentId, _: usize) -> Vec<AgentId> { Vec::new() }
+///     fn get_nearby_resources(&self, _: AgentId, _: &str, _: f32) -> Vec<ResourceLocation> {
+///         Vec::new()
+///     }
+///     fn can_interact(&self, _: AgentId, _: AgentId) -> bool { false }
+/// }
+///
+/// let mut world = World::new();
+/// register_core_components(&mut world);
+/// world.register::<Goals>();
+/// let agent = create_agent(&mut world);
+/// world
+///     .write_storage::<Goals>()
+///     .insert(agent, Goals::new(vec![Goal::ReachWealth { amount: 1000.0 }]))
+///     .unwrap();
+///
+/// let decision = GoalDrivenDecisionMaker::default().decide(agent, &world, &Empty);
+/// assert!(matches!(decision, DecisionOutput::Intent(Intent::FindWork { .. })));
+/// ```
+pub struct GoalDrivenDecisionMaker {
+    /// Handles survival, and everything else when no goal is pending
+    pub survival: UtilityMaximizer,
+}
+
+impl GoalDrivenDecisionMaker {
+    /// Pursue goals on top of the given survival behavior
+    pub fn new(survival: UtilityMaximizer) -> Self {
+        Self { survival }
+    }
+
+    /// Whether any of the agent's needs is high enough to preempt its goals
+    pub fn survival_pressing(&self, agent: Entity, world: &World) -> bool {
+        if !world.has_value::<MaskedStorage<Needs>>() {
+            return false;
+        }
+        let thresholds = &self.survival.thresholds;
+        world.read_storage::<Needs>().get(agent).is_some_and(|needs| {
+            needs.thirst > thresholds.high_thirst
+                || needs.hunger > thresholds.high_hunger
+                || needs.tiredness > thresholds.high_tiredness
+        })
+    }
+
+    /// Decide for `agent`: survival first, then its top unmet goal
+    pub fn decide(&self, agent: Entity, world: &World, world_query: &dyn WorldQuery) -> DecisionOutput {
+        let goal = if world.has_value::<MaskedStorage<Goals>>() {
+            let mut storage = world.write_storage::<Goals>();
+            storage.get_mut(agent).and_then(|goals| {
+                goals.retire_met(|g| g.is_met(agent, world, world_query));
+                goals.current().cloned()
+            })
+        } else {
+            None
+        };
+
+        match goal {
+            Some(goal) if !self.survival_pressing(agent, world) => {
+                DecisionOutput::Intent(goal.intent(agent, world))
+            }
+            _ => self.survival.decide(agent, world, world_query),
+        }
+    }
+}
+
+impl Default for GoalDrivenDecisionMaker {
+    fn default() -> Self {
+        Self::new(UtilityMaximizer::default())
+    }
+}
+
+impl DecisionMaker for GoalDrivenDecisionMaker {
+    fn decide(&self, agent: Entity, world: &World, world_query: &dyn WorldQuery) -> DecisionOutput {
+        GoalDrivenDecisionMaker::decide(self, agent, world, world_query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world_query::ResourceLocation;
+    use crate::{create_agent, register_core_components, EnergyComponent, SpeciesComponent};
+    use pretty_assertions::assert_eq;
+
+    struct WaterNearby;
+
+    impl WorldQuery for WaterNearby {
+        fn get_nearby_agents(&self, _agent: AgentId, _max_count: usize) -> Vec<AgentId> {
+            Vec::new()
+        }
+
+        fn get_nearby_resources(
+            &self,
+            _agent: AgentId,
+            resource_type: &str,
+            _max_radius: f32,
+        ) -> Vec<ResourceLocation> {
+            match resource_type {
+                "water" => vec![ResourceLocation::new(10.0, 0.0, 10.0)],
+                _ => Vec::new(),
+            }
+        }
+
+        fn can_interact(&self, _agent1: AgentId, _agent2: AgentId) -> bool {
+            false
+        }
+    }
+
+    fn world_with_goal(goal: Goal) -> (World, Entity) {
+        let mut world = World::new();
+        register_core_components(&mut world);
+        world.register::<Goals>();
+        world.register::<EnergyComponent>();
+        world.register::<SpeciesComponent>();
+        let agent = create_agent(&mut world);
+        world.write_storage::<Goals>().insert(agent, Goals::new(vec![goal])).unwrap();
+        (world, agent)
+    }
+
+    fn set_thirst(world: &World, agent: Entity, thirst: f32) {
+        world.write_storage::<Needs>().get_mut(agent).unwrap().thirst = thirst;
+    }
+
+    #[test]
+    fn test_content_agent_pursues_goal_until_met() {
+        let axe = Goal::AcquireItem { item: "axe".into(), quantity: 1 };
+        let (world, agent) = world_with_goal(axe.clone());
+        set_thirst(&world, agent, 10.0);
+        let dm = GoalDrivenDecisionMaker::default();
+
+        assert_eq!(
+            dm.decide(agent, &world, &WaterNearby),
+            DecisionOutput::Intent(Intent::SeekItem { item_type: "axe".into(), urgency: GOAL_URGENCY })
+        );
+
+        world.write_storage::<Inventory>().get_mut(agent).unwrap().add("axe", 1);
+        let decision = dm.decide(agent, &world, &WaterNearby);
+        assert!(!matches!(decision, DecisionOutput::Intent(Intent::SeekItem { ref item_type, .. }) if item_type == "axe"));
+        let goals = world.read_storage::<Goals>();
+        assert_eq!(goals.get(agent).unwrap().active, Vec::new());
+        assert_eq!(goals.get(agent).unwrap().completed, vec![axe]);
+    }
+
+    #[test]
+    fn test_thirst_preempts_goal() {
+        let (world, agent) = world_with_goal(Goal::AcquireItem { item: "axe".into(), quantity: 1 });
+        set_thirst(&world, agent, 85.0);
+        let dm = GoalDrivenDecisionMaker::default();
+
+        match dm.decide(agent, &world, &WaterNearby) {
+            DecisionOutput::Intent(Intent::SeekItem { item_type, .. }) => assert_eq!(item_type, "water"),
+            other => panic!("expected to seek water, got {:?}", other),
+        }
+        // The goal is shelved, not dropped
+        assert_eq!(world.read_storage::<Goals>().get(agent).unwrap().active.len(), 1);
+
+        set_thirst(&world, agent, 10.0);
+        assert!(matches!(
+            dm.decide(agent, &world, &WaterNearby),
+            DecisionOutput::Intent(Intent::SeekItem { ref item_type, .. }) if item_type == "axe"
+        ));
+    }
+}
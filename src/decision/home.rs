@@ -0,0 +1,62 @@
+// Home bases and daily routines
+//
+// An agent with a `Home` goes back to it when it has nothing better to do:
+// `UtilityMaximizer` offers a `MoveTo` toward home in place of its fallback
+// intent. While the host sets the `Night` flag, heading home also outranks
+// investing, so agents settle in for the night. Any need past its threshold
+// still comes first. Agents without a `Home` keep wandering.
+
+use super::types::Intent;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+
+/// `item_type` of the `Intent::MoveTo` that sends an agent home
+pub const HOME_ITEM_TYPE: &str = "home";
+
+/// Where an agent returns when idle
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let home = Home::new(4.0, -2.0);
+/// assert_eq!(
+///     home.intent(),
+///     Intent::MoveTo { x: 4.0, y: -2.0, item_type: "home".into() }
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Home {
+    /// X coordinate in world space
+    pub x: f32,
+    /// Y coordinate in world space
+    pub y: f32,
+}
+
+impl Home {
+    /// Home at (`x`, `y`)
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    /// Intent to move back home
+    pub fn intent(&self) -> Intent {
+        Intent::MoveTo {
+            x: self.x,
+            y: self.y,
+            item_type: HOME_ITEM_TYPE.to_string(),
+        }
+    }
+}
+
+impl Component for Home {
+    type Storage = VecStorage<Self>;
+}
+
+/// Host-set world resource: whether it is night
+///
+/// The library has no clock of its own; hosts insert `Night(true)` at dusk
+/// and `Night(false)` at dawn. Worlds without the resource are always in
+/// daytime.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Night(pub bool);
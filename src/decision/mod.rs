@@ -1,10 +1,27 @@
 //! Decision-making trait and implementations
 
+pub mod actions;
+pub mod commitment;
+pub mod goals;
+pub mod home;
+pub mod resource_cache;
+pub mod scarcity;
+pub mod temperament;
 pub mod types;
 pub mod utility_maximizer;
+pub mod wander;
 
+pub use actions::{execute_bundle_trade, execute_teach, execute_trade, trade_fairness, fairness_outcome, ActionError, FAIRNESS_NEUTRAL_POINT};
+pub use actions::{outcome_from_need_delta, outcome_from_need_delta_with, rate_purchase, PurchaseValueConfig, VALUE_NEUTRAL_POINT};
+pub use commitment::{committed_choice, same_goal, CommittedIntent};
+pub use goals::{Goal, GoalDrivenDecisionMaker, Goals, GOAL_URGENCY};
+pub use home::{Home, Night, HOME_ITEM_TYPE};
+pub use resource_cache::ResourceQueryCache;
+pub use wander::WanderState;
+pub use scarcity::{RegionalScarcity, ScarcitySignal};
+pub use temperament::{rank_social_actions, SocialAction, Temperament};
 pub use types::{Intent, Action, ActionType, Transaction, DecisionOutput};
-pub use utility_maximizer::{UtilityMaximizer, DecisionThresholds, UtilityWeights};
+pub use utility_maximizer::{UtilityMaximizer, UtilityMaximizerConfig, DecisionThresholds, UtilityWeights, FALLBACK_UTILITY, HOME_AT_NIGHT_UTILITY};
 
 use crate::world_query::WorldQuery;
 use specs::prelude::*;
@@ -0,0 +1,193 @@
+// Approximate resource-query cache for decision making
+//
+// In continuous worlds an agent's nearby resources barely change from one
+// tick to the next, yet the decision maker queries them every tick. A
+// `ResourceQueryCache` remembers each agent's last result per resource type
+// and replays it for a few ticks, trading accuracy for throughput. The cache
+// has no view of positions: hosts invalidate an agent when it moves.
+
+use crate::agent::AgentId;
+use crate::world_query::{RelocationHook, ResourceDetail, ResourceLocation, WorldQuery};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// One remembered query
+#[derive(Debug, Clone)]
+struct CachedResult {
+    tick: u64,
+    radius: f32,
+    sources: Vec<ResourceDetail>,
+}
+
+/// Per-agent memo of resource queries, reused for `max_age` ticks
+///
+/// A result queried at tick `t` is replayed for ticks `t` to
+/// `t + max_age - 1`, then re-queried. Results are also re-queried if the
+/// search radius changed or the tick went backwards. Call
+/// [`invalidate`](Self::invalidate) when an agent moves (or pass the cache
+/// to [`relocate_agent`](crate::relocate_agent)) or its surroundings change,
+/// and [`clear`](Self::clear) after large world edits.
+///
+/// Uses a mutex internally so decisions can share it through `&self`.
+#[derive(Debug, Default)]
+pub struct ResourceQueryCache {
+    max_age: u64,
+    entries: Mutex<HashMap<(AgentId, String), CachedResult>>,
+}
+
+impl ResourceQueryCache {
+    /// Create an empty cache reusing results for `max_age` ticks
+    pub fn new(max_age: u64) -> Self {
+        Self {
+            max_age,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Ticks a result is reused for
+    pub fn max_age(&self) -> u64 {
+        self.max_age
+    }
+
+    /// Forget every result cached for `agent`
+    pub fn invalidate(&self, agent: AgentId) {
+        self.lock().retain(|(owner, _), _| *owner != agent);
+    }
+
+    /// Forget every cached result
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Number of cached (agent, resource type) results, fresh or stale
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check if nothing is cached
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<(AgentId, String), CachedResult>> {
+        // A panic mid-insert leaves the map consistent, so poisoning is harmless
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Cached result for the query, or the fresh one from `query` (then
+    /// cached, if `store`)
+    fn detailed(
+        &self,
+        query: &dyn WorldQuery,
+        tick: u64,
+        agent: AgentId,
+        resource_type: &str,
+        radius: f32,
+        store: bool,
+    ) -> Vec<ResourceDetail> {
+        let key = (agent, resource_type.to_string());
+        if let Some(cached) = self.lock().get(&key) {
+            let fresh = tick >= cached.tick && tick - cached.tick < self.max_age;
+            if fresh && cached.radius == radius {
+                return cached.sources.clone();
+            }
+        }
+
+        let sources = query.get_nearby_resources_detailed(agent, resource_type, radius);
+        if !store {
+            return sources;
+        }
+        self.lock().insert(
+            key,
+            CachedResult {
+                tick,
+                radius,
+                sources: sources.clone(),
+            },
+        );
+        sources
+    }
+}
+
+impl RelocationHook for ResourceQueryCache {
+    fn agent_relocated(&self, agent: AgentId, _from: Option<(f32, f32)>, _to: (f32, f32)) {
+        self.invalidate(agent);
+    }
+}
+
+/// `WorldQuery` adapter answering resource queries through a cache
+///
+/// Agent queries pass straight through.
+pub(crate) struct CachedQuery<'a> {
+    inner: &'a dyn WorldQuery,
+    cache: &'a ResourceQueryCache,
+    tick: u64,
+    store: bool,
+}
+
+impl<'a> CachedQuery<'a> {
+    /// Wrap `inner`, treating `tick` as the current tick
+    pub(crate) fn new(inner: &'a dyn WorldQuery, cache: &'a ResourceQueryCache, tick: u64) -> Self {
+        Self { inner, cache, tick, store: true }
+    }
+
+    /// Like [`new`](Self::new), but misses are answered by `inner` without
+    /// being cached, so the cache is never changed
+    pub(crate) fn read_only(inner: &'a dyn WorldQuery, cache: &'a ResourceQueryCache, tick: u64) -> Self {
+        Self { inner, cache, tick, store: false }
+    }
+}
+
+impl WorldQuery for CachedQuery<'_> {
+    fn get_nearby_agents(&self, agent: AgentId, max_count: usize) -> Vec<AgentId> {
+        self.inner.get_nearby_agents(agent, max_count)
+    }
+
+    fn get_nearby_agents_within(
+        &self,
+        agent: AgentId,
+        max_count: usize,
+        max_radius: f32,
+    ) -> Vec<AgentId> {
+        self.inner.get_nearby_agents_within(agent, max_count, max_radius)
+    }
+
+    fn get_nearby_agents_filtered(
+        &self,
+        agent: AgentId,
+        max_count: usize,
+        predicate: &dyn Fn(AgentId) -> bool,
+    ) -> Vec<AgentId> {
+        self.inner.get_nearby_agents_filtered(agent, max_count, predicate)
+    }
+
+    fn get_nearby_resources(
+        &self,
+        agent: AgentId,
+        resource_type: &str,
+        max_radius: f32,
+    ) -> Vec<ResourceLocation> {
+        self.get_nearby_resources_detailed(agent, resource_type, max_radius)
+            .into_iter()
+            .map(|detail| detail.location)
+            .collect()
+    }
+
+    fn get_nearby_resources_detailed(
+        &self,
+        agent: AgentId,
+        resource_type: &str,
+        max_radius: f32,
+    ) -> Vec<ResourceDetail> {
+        self.cache
+            .detailed(self.inner, self.tick, agent, resource_type, max_radius, self.store)
+    }
+
+    fn travel_cost(&self, agent: AgentId, target_x: f32, target_y: f32) -> Option<f32> {
+        self.inner.travel_cost(agent, target_x, target_y)
+    }
+
+    fn can_interact(&self, agent1: AgentId, agent2: AgentId) -> bool {
+        self.inner.can_interact(agent1, agent2)
+    }
+}
@@ -0,0 +1,231 @@
+// Resource scarcity signal for decision-making
+//
+// Hosts (or the library, from ResourceSource stock) publish how scarce each
+// item currently is. The UtilityMaximizer reads this as a world resource and
+// boosts the matching need, so agents react earlier when supplies run low.
+
+use crate::agent::components::{ResourceSource, MAX_NEEDS};
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use std::collections::HashMap;
+
+/// Per-item scarcity levels (0.0 = plentiful, 1.0 = critically scarce)
+///
+/// Insert into the world as a resource; the [`UtilityMaximizer`] multiplies
+/// the matching need by `1.0 + level` before comparing it to thresholds and
+/// computing urgency. Items without a level are unaffected.
+///
+/// [`UtilityMaximizer`]: crate::decision::UtilityMaximizer
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// let mut scarcity = ScarcitySignal::new();
+/// scarcity.set("water", 0.5);
+/// world.insert(scarcity);
+///
+/// let signal = world.read_resource::<ScarcitySignal>();
+/// assert_eq!(signal.boost("water", 40.0), 60.0);
+/// assert_eq!(signal.boost("food", 40.0), 40.0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScarcitySignal {
+    levels: HashMap<String, f32>,
+}
+
+impl ScarcitySignal {
+    /// Create an empty signal (no scarcity anywhere)
+    pub fn new() -> Self {
+        Self {
+            levels: HashMap::new(),
+        }
+    }
+
+    /// Derive scarcity from the total stock of each produced item
+    ///
+    /// An item whose sources hold `reference_stock` or more in total has
+    /// scarcity 0.0; an item whose sources are all empty has scarcity 1.0.
+    ///
+    /// # Panics
+    /// Panics if `ResourceSource` is not registered in the world
+    pub fn from_stock(world: &World, reference_stock: u32) -> Self {
+        let sources = world.read_storage::<ResourceSource>();
+        let mut totals: HashMap<String, u64> = HashMap::new();
+        for source in sources.join() {
+            *totals.entry(source.item_produced.clone()).or_insert(0) +=
+                u64::from(source.current_stock);
+        }
+
+        let mut signal = Self::new();
+        let reference = u64::from(reference_stock.max(1));
+        for (item, total) in totals {
+            let available = total.min(reference) as f32 / reference as f32;
+            signal.set(&item, 1.0 - available);
+        }
+        signal
+    }
+
+    /// Set the scarcity level for an item, clamped to [0.0, 1.0]
+    ///
+    /// Non-finite levels are treated as 0.0.
+    pub fn set(&mut self, item: &str, level: f32) {
+        let level = if level.is_finite() { level.clamp(0.0, 1.0) } else { 0.0 };
+        self.levels.insert(item.to_string(), level);
+    }
+
+    /// Scarcity level for an item (0.0 if unknown)
+    pub fn get(&self, item: &str) -> f32 {
+        self.levels.get(item).copied().unwrap_or(0.0)
+    }
+
+    /// Scale a need value (0-100) by the item's scarcity, capped at MAX_NEEDS
+    pub fn boost(&self, item: &str, need: f32) -> f32 {
+        (need * (1.0 + self.get(item))).min(MAX_NEEDS)
+    }
+}
+
+/// Host-supplied scarcity per map region
+///
+/// Each known region carries its own [`ScarcitySignal`]. Together with an
+/// agent's `Region` component this lets the [`UtilityMaximizer`] emit
+/// `Intent::Migrate` when the agent's region can't meet a critical need and
+/// another known region is better supplied. Regions without an entry are
+/// unknown and never chosen as a destination.
+///
+/// [`UtilityMaximizer`]: crate::decision::UtilityMaximizer
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let mut regions = RegionalScarcity::new();
+/// regions.set(1, "water", 0.9);
+/// regions.set(2, "water", 0.1);
+/// regions.set(3, "water", 0.4);
+///
+/// assert_eq!(regions.best_region(&["water"], 1), Some((2, 0.1)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RegionalScarcity {
+    regions: HashMap<u32, ScarcitySignal>,
+}
+
+impl RegionalScarcity {
+    /// Create an empty map (no known regions)
+    pub fn new() -> Self {
+        Self {
+            regions: HashMap::new(),
+        }
+    }
+
+    /// Set an item's scarcity in a region, clamped to [0.0, 1.0]
+    pub fn set(&mut self, region: u32, item: &str, level: f32) {
+        self.regions.entry(region).or_default().set(item, level);
+    }
+
+    /// Scarcity signal of a region, if known
+    pub fn region(&self, region: u32) -> Option<&ScarcitySignal> {
+        self.regions.get(&region)
+    }
+
+    /// Scarcity of an item in a region (0.0 if unknown)
+    pub fn get(&self, region: u32, item: &str) -> f32 {
+        self.region(region).map_or(0.0, |signal| signal.get(item))
+    }
+
+    /// Lowest scarcity among `items` in a region (0.0 if unknown)
+    ///
+    /// An agent that can use any of the items is only as badly off as its
+    /// best-supplied option.
+    pub fn best_level(&self, region: u32, items: &[&str]) -> f32 {
+        items
+            .iter()
+            .map(|item| self.get(region, item))
+            .reduce(f32::min)
+            .unwrap_or(0.0)
+    }
+
+    /// Known region other than `from` with the lowest scarcity for `items`
+    ///
+    /// Returns the region and its level; ties go to the lower region id.
+    pub fn best_region(&self, items: &[&str], from: u32) -> Option<(u32, f32)> {
+        self.regions
+            .keys()
+            .filter(|&&region| region != from)
+            .map(|&region| (region, self.best_level(region, items)))
+            .min_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_default_has_no_effect() {
+        let signal = ScarcitySignal::default();
+        assert_eq!(signal.get("water"), 0.0);
+        assert_eq!(signal.boost("water", 70.0), 70.0);
+    }
+
+    #[test]
+    fn test_set_clamps_level() {
+        let mut signal = ScarcitySignal::new();
+        signal.set("water", 3.0);
+        signal.set("food", -1.0);
+        signal.set("wood", f32::NAN);
+
+        assert_eq!(signal.get("water"), 1.0);
+        assert_eq!(signal.get("food"), 0.0);
+        assert_eq!(signal.get("wood"), 0.0);
+    }
+
+    #[test]
+    fn test_boost_capped_at_max_needs() {
+        let mut signal = ScarcitySignal::new();
+        signal.set("water", 1.0);
+        assert_eq!(signal.boost("water", 80.0), MAX_NEEDS);
+    }
+
+    #[test]
+    fn test_from_stock() {
+        let mut world = World::new();
+        world.register::<ResourceSource>();
+        world
+            .create_entity()
+            .with(ResourceSource::new("water".into(), "water".into(), 0.0, 25))
+            .build();
+        world
+            .create_entity()
+            .with(ResourceSource::new("water".into(), "water".into(), 0.0, 25))
+            .build();
+        world
+            .create_entity()
+            .with(ResourceSource::new("plant".into(), "grass".into(), 0.0, 500))
+            .build();
+
+        let signal = ScarcitySignal::from_stock(&world, 100);
+        assert_eq!(signal.get("water"), 0.5);
+        assert_eq!(signal.get("grass"), 0.0);
+    }
+
+    #[test]
+    fn test_regional_best_region() {
+        let mut regions = RegionalScarcity::new();
+        regions.set(1, "grass", 0.9);
+        regions.set(1, "food", 0.8);
+        regions.set(2, "grass", 0.5);
+        regions.set(2, "food", 0.6);
+        regions.set(3, "grass", 0.7);
+        regions.set(3, "food", 0.1);
+
+        assert_eq!(regions.best_level(1, &["grass", "food"]), 0.8);
+        assert_eq!(regions.best_region(&["grass", "food"], 1), Some((3, 0.1)));
+        assert_eq!(regions.best_region(&["grass"], 1), Some((2, 0.5)));
+        assert_eq!(RegionalScarcity::new().best_region(&["water"], 1), None);
+    }
+}
@@ -0,0 +1,161 @@
+// Temperament: personality biases among social actions
+//
+// Two agents with identical needs may still react differently to the same
+// neighbor: one trades, one steals, one gives a gift. A `Temperament` scales
+// the utility of each `SocialAction` by the matching trait. Temperament only
+// reorders social actions among themselves; the best social option keeps the
+// utility it had, so an aggressive agent is no more likely to skip drinking
+// to go stealing than a placid one.
+
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+
+/// Trait value with no effect on utility
+pub const NEUTRAL_TRAIT: f32 = 0.5;
+
+/// Social actions a temperament biases
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SocialAction {
+    /// Buy or sell with a neighbor
+    Trade,
+    /// Take from a neighbor without paying
+    Steal,
+    /// Attack a neighbor (for food)
+    Hunt,
+    /// Give to a neighbor for nothing in return
+    GiveGift,
+    /// Spend time with a neighbor
+    Socialize,
+}
+
+/// Personality traits, each from 0.0 to 1.0 with 0.5 neutral
+///
+/// Agents without a `Temperament` behave as [`Temperament::default`], which
+/// is neutral in every trait.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::decision::{SocialAction, Temperament};
+///
+/// let bully = Temperament { aggression: 1.0, ..Default::default() };
+/// assert_eq!(bully.bias(SocialAction::Steal), 1.5);
+/// assert_eq!(bully.bias(SocialAction::Trade), 1.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Temperament {
+    /// Raises stealing and hunting
+    pub aggression: f32,
+    /// Raises gift giving
+    pub generosity: f32,
+    /// Raises socializing
+    pub sociability: f32,
+}
+
+impl Default for Temperament {
+    fn default() -> Self {
+        Self {
+            aggression: NEUTRAL_TRAIT,
+            generosity: NEUTRAL_TRAIT,
+            sociability: NEUTRAL_TRAIT,
+        }
+    }
+}
+
+impl Component for Temperament {
+    type Storage = VecStorage<Self>;
+}
+
+impl Temperament {
+    /// Utility multiplier for `action`, from 0.5 (trait 0) to 1.5 (trait 1)
+    ///
+    /// Trading is never biased; out-of-range traits are clamped.
+    pub fn bias(&self, action: SocialAction) -> f32 {
+        let value = match action {
+            SocialAction::Trade => NEUTRAL_TRAIT,
+            SocialAction::Steal | SocialAction::Hunt => self.aggression,
+            SocialAction::GiveGift => self.generosity,
+            SocialAction::Socialize => self.sociability,
+        };
+        let value = if value.is_nan() { NEUTRAL_TRAIT } else { value.clamp(0.0, 1.0) };
+        0.5 + value
+    }
+}
+
+/// Rank available social actions for an agent, highest utility first
+///
+/// Each utility is scaled by the temperament's [`bias`](Temperament::bias),
+/// then all are rescaled so the best one keeps the best unbiased utility.
+/// Compare the first entry against survival intents as usual: temperament
+/// picks which social action an agent prefers, not whether it prefers one
+/// over meeting a need. `None` behaves neutrally.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::decision::{rank_social_actions, SocialAction, Temperament};
+///
+/// let generous = Temperament { generosity: 1.0, ..Default::default() };
+/// let options = [(SocialAction::Trade, 0.5), (SocialAction::GiveGift, 0.4)];
+///
+/// assert_eq!(rank_social_actions(None, &options)[0].0, SocialAction::Trade);
+/// let ranked = rank_social_actions(Some(&generous), &options);
+/// assert_eq!(ranked[0], (SocialAction::GiveGift, 0.5));
+/// ```
+pub fn rank_social_actions(
+    temperament: Option<&Temperament>,
+    candidates: &[(SocialAction, f32)],
+) -> Vec<(SocialAction, f32)> {
+    let neutral = Temperament::default();
+    let temperament = temperament.unwrap_or(&neutral);
+
+    let best_base = candidates.iter().map(|(_, u)| *u).fold(0.0, f32::max);
+    let mut ranked: Vec<(SocialAction, f32)> = candidates
+        .iter()
+        .map(|(action, utility)| (*action, utility * temperament.bias(*action)))
+        .collect();
+    let best_biased = ranked.iter().map(|(_, u)| *u).fold(0.0, f32::max);
+    if best_biased > 0.0 {
+        let scale = best_base / best_biased;
+        for (_, utility) in &mut ranked {
+            *utility *= scale;
+        }
+    }
+
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const OPTIONS: [(SocialAction, f32); 4] = [
+        (SocialAction::Trade, 0.5),
+        (SocialAction::Steal, 0.4),
+        (SocialAction::GiveGift, 0.4),
+        (SocialAction::Socialize, 0.3),
+    ];
+
+    #[test]
+    fn test_aggressive_agent_steals_and_generous_agent_gifts() {
+        assert_eq!(rank_social_actions(None, &OPTIONS)[0].0, SocialAction::Trade);
+
+        let aggressive = Temperament { aggression: 1.0, ..Default::default() };
+        assert_eq!(rank_social_actions(Some(&aggressive), &OPTIONS)[0].0, SocialAction::Steal);
+
+        let generous = Temperament { generosity: 0.9, ..Default::default() };
+        assert_eq!(rank_social_actions(Some(&generous), &OPTIONS)[0].0, SocialAction::GiveGift);
+    }
+
+    #[test]
+    fn test_temperament_does_not_raise_best_social_utility() {
+        // However aggressive, stealing is worth no more than the best
+        // unbiased option, so survival intents above it still win
+        let brute = Temperament { aggression: 50.0, generosity: 0.0, sociability: 0.0 };
+        let ranked = rank_social_actions(Some(&brute), &OPTIONS);
+        assert_eq!(ranked[0], (SocialAction::Steal, 0.5));
+        assert!(ranked.iter().all(|(_, utility)| *utility <= 0.5));
+        assert!(rank_social_actions(Some(&brute), &[]).is_empty());
+    }
+}
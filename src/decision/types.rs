@@ -4,8 +4,9 @@
 // They form a hierarchy from high-level intentions to specific actions
 // to completed transactions.
 
-use crate::agent::AgentId;
+use crate::agent::{AgentId, CurrencyAmount};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// High-level intent representing what an agent wants to achieve
 ///
@@ -51,11 +52,52 @@ pub enum Intent {
         item_type: String,
     },
 
+    /// Move toward a known location (e.g., a remembered resource)
+    MoveTo {
+        /// Target X coordinate in world space
+        x: f32,
+        /// Target Y coordinate in world space
+        y: f32,
+        /// Item expected at the target
+        item_type: String,
+    },
+
+    /// Leave the current region for one where a critical need can be met
+    ///
+    /// Higher-level than `MoveTo`: the application plans the region-to-region
+    /// travel.
+    Migrate {
+        /// Host region id to travel toward
+        toward_region: u32,
+    },
+
+    /// Move surplus wallet currency into savings (see `deposit_savings`)
+    Invest {
+        /// Currency to deposit
+        amount: f32,
+    },
+
+    /// Go and meet another agent
+    ///
+    /// The application knows where agents are and plans the route.
+    VisitAgent {
+        /// Agent to meet
+        agent: AgentId,
+    },
+
     /// Rest/sleep to recover tiredness
     Rest,
 
     /// Wander aimlessly (exploration or no pressing needs)
     Wander,
+
+    /// Wander along a heading kept across decisions (see `WanderState`)
+    WanderHeading {
+        /// Direction in radians (0 = +x, counter-clockwise)
+        heading: f32,
+        /// Ticks left before a new heading is chosen
+        persistence: u8,
+    },
 }
 
 impl Intent {
@@ -66,7 +108,12 @@ impl Intent {
             Intent::Rest => false,
             Intent::FindWork { .. } => false,
             Intent::SeekTrade { .. } => false,
+            Intent::MoveTo { .. } => false,
+            Intent::Migrate { .. } => true,
+            Intent::Invest { .. } => false,
+            Intent::VisitAgent { .. } => false,
             Intent::Wander => false,
+            Intent::WanderHeading { .. } => false,
         }
     }
 
@@ -76,8 +123,13 @@ impl Intent {
             Intent::SeekItem { .. } => "SeekItem",
             Intent::FindWork { .. } => "FindWork",
             Intent::SeekTrade { .. } => "SeekTrade",
+            Intent::MoveTo { .. } => "MoveTo",
+            Intent::Migrate { .. } => "Migrate",
+            Intent::Invest { .. } => "Invest",
+            Intent::VisitAgent { .. } => "VisitAgent",
             Intent::Rest => "Rest",
             Intent::Wander => "Wander",
+            Intent::WanderHeading { .. } => "WanderHeading",
         }
     }
 }
@@ -121,18 +173,48 @@ pub enum ActionType {
         offer_price: f32,
     },
 
+    /// Swap several items and currency with the target in one go
+    ///
+    /// "3 wheat + 10 coins for 1 axe". Executed by `execute_bundle_trade`,
+    /// which moves everything or nothing.
+    BundleTrade {
+        /// Items the acting agent hands over
+        give: HashMap<String, u32>,
+        /// Currency the acting agent hands over
+        give_currency: f32,
+        /// Items the target hands over
+        receive: HashMap<String, u32>,
+        /// Currency the target hands over
+        receive_currency: f32,
+    },
+
     /// Hunt/attack another agent (for food)
     Hunt {
         /// Target agent to hunt
         target: AgentId,
     },
 
-    /// Consume a resource at a location
+    /// Consume a resource where it lies in the world (drink from a river,
+    /// graze a field)
+    ///
+    /// The host owns world resources, so the host executes this one. Items
+    /// the agent carries use [`ActionType::ConsumeItem`] instead.
     Consume {
         /// World coordinates of the resource
         resource_location: (f32, f32),
     },
 
+    /// Consume items from the agent's own inventory
+    ///
+    /// Satisfaction comes from the `ItemRegistry`. Executed by
+    /// `execute_consume`.
+    ConsumeItem {
+        /// Item id in the `ItemRegistry`
+        item: String,
+        /// Most units to consume
+        quantity: u32,
+    },
+
     /// Accept employment offer
     AcceptEmployment {
         /// Proposed wage
@@ -144,6 +226,12 @@ pub enum ActionType {
         /// What kind of information is being requested
         query_type: String,
     },
+
+    /// Teach a skill to the target agent
+    Teach {
+        /// Skill being taught
+        skill: String,
+    },
 }
 
 /// Completed transaction between two agents
@@ -167,8 +255,11 @@ pub enum ActionType {
 ///     success: true,
 /// };
 /// ```
+///
+/// Prices are `f32` by default; `Transaction<Currency>` carries exact
+/// fixed-point prices (see [`Currency`](crate::Currency)).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct Transaction {
+pub struct Transaction<C = f32> {
     /// Agent buying the item
     pub buyer: AgentId,
     /// Agent selling the item
@@ -178,15 +269,15 @@ pub struct Transaction {
     /// Quantity of items
     pub quantity: u32,
     /// Price per item
-    pub price: f32,
+    pub price: C,
     /// Whether the transaction completed successfully
     pub success: bool,
 }
 
-impl Transaction {
+impl<C: CurrencyAmount> Transaction<C> {
     /// Calculate total transaction value
-    pub fn total_value(&self) -> f32 {
-        self.quantity as f32 * self.price
+    pub fn total_value(&self) -> C {
+        self.price.times(self.quantity)
     }
 
     /// Check if this transaction represents a successful trade
@@ -292,6 +383,28 @@ mod tests {
         assert!(!intent.is_critical());
     }
 
+    #[test]
+    fn test_intent_move_to() {
+        let intent = Intent::MoveTo {
+            x: 1.0,
+            y: 2.0,
+            item_type: "water".to_string(),
+        };
+        assert_eq!(intent.intent_type(), "MoveTo");
+        assert!(!intent.is_critical());
+    }
+
+    #[test]
+    fn test_intent_migrate() {
+        let intent = Intent::Migrate { toward_region: 3 };
+        assert_eq!(intent.intent_type(), "Migrate");
+        assert!(intent.is_critical());
+
+        let json = serde_json::to_string(&intent).unwrap();
+        assert_eq!(json, r#"{"Migrate":{"toward_region":3}}"#);
+        assert_eq!(serde_json::from_str::<Intent>(&json).unwrap(), intent);
+    }
+
     #[test]
     fn test_intent_wander() {
         let intent = Intent::Wander;
@@ -4,17 +4,114 @@
 // actions and selects the one with the highest utility score. It's based on
 // the JavaScript stub from libreterra but implemented in pure Rust.
 
-use crate::{Agent, AgentId, Needs, SpeciesComponent, DietType, EnergyComponent};
-use crate::decision::{DecisionOutput, Intent};
-use crate::world_query::WorldQuery;
+use crate::{Agent, AgentId, Needs, SpeciesComponent, DietType, EnergyComponent, Wallet};
+use crate::{AgentIndex, Inventory, Skills};
+use crate::{agent_rng, AgentRng, CurrentTick, Region, SavingsAccount, SpatialMemory};
+use crate::world_query::{ResourceDetail, ResourceLocation};
+use crate::decision::{DecisionOutput, Intent, RegionalScarcity, ScarcitySignal};
+use crate::decision::resource_cache::{CachedQuery, ResourceQueryCache};
+use crate::decision::commitment::{committed_choice, CommittedIntent};
+use crate::decision::home::{Home, Night};
+use crate::decision::wander::WanderState;
+use crate::world_query::{
+    normalize_distance, InRangeQuery, InteractionMatrix, MatrixQuery, TestMode, WorldQuery,
+};
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
 use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+/// Maximum number of nearby agents considered as trade partners
+const TRADE_PARTNER_SCAN: usize = 5;
+
+/// Efficiency bonus for having a trade partner at hand
+///
+/// Small enough that a reachable resource usually wins, large enough that
+/// buying from a neighbor beats searching blindly.
+const TRADE_PARTNER_BONUS: f32 = 0.2;
+
+/// Lowest multiplier applied to a contested resource's utility
+const MIN_CONTENTION_FACTOR: f32 = 0.1;
+
+/// Utility multiplier for a resource other agents are also targeting
+///
+/// Each agent's expected share is `stock / (contention + 1)`; shares below
+/// one unit scale utility down proportionally. Uncontested sources and
+/// sources with unknown stock are not discounted.
+fn contention_factor(detail: &ResourceDetail) -> f32 {
+    match detail.stock {
+        Some(stock) if detail.contention > 0 => {
+            let share = stock as f32 / (detail.contention as f32 + 1.0);
+            share.clamp(MIN_CONTENTION_FACTOR, 1.0)
+        }
+        _ => 1.0,
+    }
+}
+
+/// The deciding agent and what it can harvest with
+struct Gatherer<'a> {
+    id: AgentId,
+    inventory: Option<&'a Inventory>,
+    skills: Option<&'a Skills>,
+}
+
+impl Gatherer<'_> {
+    /// Agent with no tools or skills
+    #[cfg(test)]
+    fn bare(id: AgentId) -> Self {
+        Self {
+            id,
+            inventory: None,
+            skills: None,
+        }
+    }
+
+    /// Sources this agent may harvest, plus a tool that would unlock the
+    /// closest source it can't
+    ///
+    /// Only tools are reported: a missing skill can't be picked up, so a
+    /// source whose skill gate is also unmet yields no tool.
+    fn usable_sources(&self, sources: Vec<ResourceDetail>) -> (Vec<ResourceDetail>, Option<String>) {
+        let mut missing_tool = None;
+        let usable = sources
+            .into_iter()
+            .filter(|source| {
+                let usable = source.can_harvest(self.inventory, self.skills);
+                if !usable && missing_tool.is_none() {
+                    let skilled = ResourceDetail {
+                        requires_item: None,
+                        ..source.clone()
+                    }
+                    .can_harvest(None, self.skills);
+                    if skilled {
+                        missing_tool = source.requires_item.clone();
+                    }
+                }
+                usable
+            })
+            .collect();
+        (usable, missing_tool)
+    }
+}
+
+/// How an agent intends to get an item it needs
+#[derive(Debug, PartialEq)]
+enum Approach {
+    /// Seek the item itself (visible, or searching blindly)
+    Seek,
+    /// Head back to a remembered location
+    Remembered(ResourceLocation),
+    /// Every visible source needs a tool the agent lacks: seek the tool
+    AcquireTool(String),
+}
 
 /// Configuration thresholds for decision-making
 ///
 /// These thresholds determine when an agent considers a need "high" enough
 /// to warrant seeking satisfaction. Lower thresholds make agents more
 /// proactive, higher thresholds make them wait until needs are more urgent.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DecisionThresholds {
     /// Thirst level at which agent seeks water urgently (0-100)
     pub critical_thirst: f32,
@@ -28,6 +125,11 @@ pub struct DecisionThresholds {
     pub critical_tiredness: f32,
     /// Tiredness level at which agent should rest (0-100)
     pub high_tiredness: f32,
+    /// Regional scarcity (0-1) at which an agent with an unmet need migrates
+    pub migrate_scarcity: f32,
+    /// Wallet balance above which an agent with a `SavingsAccount` invests
+    /// the surplus
+    pub invest_above: f32,
 }
 
 impl Default for DecisionThresholds {
@@ -39,6 +141,8 @@ impl Default for DecisionThresholds {
             high_hunger: 50.0,
             critical_tiredness: 85.0,
             high_tiredness: 70.0,
+            migrate_scarcity: 0.7,
+            invest_above: 100.0,
         }
     }
 }
@@ -48,7 +152,8 @@ impl Default for DecisionThresholds {
 /// These weights determine how much each factor contributes to the final
 /// utility score. Higher weights make that factor more important in
 /// decision-making.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct UtilityWeights {
     /// Multiplier for survival-critical needs (hunger, thirst)
     pub survival: f32,
@@ -68,6 +173,56 @@ impl Default for UtilityWeights {
     }
 }
 
+/// Serializable tuning for a [`UtilityMaximizer`]
+///
+/// Lets designers load agent behavior from JSON/TOML instead of recompiling.
+/// Missing fields take their default values.
+///
+/// # Example
+///
+/// ```rust
+/// use libreconomy::{UtilityMaximizer, UtilityMaximizerConfig};
+///
+/// let config: UtilityMaximizerConfig = serde_json::from_str(
+///     r#"{ "weights": { "survival": 3.0 }, "trade_search_radius": 50.0 }"#,
+/// ).unwrap();
+///
+/// let maximizer = UtilityMaximizer::from_config(config);
+/// assert_eq!(maximizer.weights.survival, 3.0);
+/// assert_eq!(maximizer.trade_search_radius, 50.0);
+/// assert_eq!(maximizer.resource_search_radius, 1000.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UtilityMaximizerConfig {
+    /// Thresholds for triggering decisions
+    pub thresholds: DecisionThresholds,
+    /// Weights for utility calculation
+    pub weights: UtilityWeights,
+    /// Maximum radius to search for resources (world units)
+    pub resource_search_radius: f32,
+    /// Maximum distance to a trade partner (world units)
+    pub trade_search_radius: f32,
+    /// Consider buying needed items from neighbors
+    pub seek_trade: bool,
+    /// Intent chosen when no need calls for action
+    pub fallback_intent: Intent,
+    /// Ticks to reuse resource-query results for (0 = no cache)
+    pub resource_cache_ticks: u64,
+    /// Utility a new goal must win by to replace a committed one
+    pub commit_margin: f32,
+    /// Stop evaluating once a need passes its `critical_*` threshold
+    pub early_exit: bool,
+    /// Seed for random draws of agents without an `AgentRng`
+    pub seed: u64,
+}
+
+impl Default for UtilityMaximizerConfig {
+    fn default() -> Self {
+        UtilityMaximizer::default().to_config()
+    }
+}
+
 /// Utility-based decision maker
 ///
 /// Evaluates all possible actions and selects the one with the highest
@@ -80,15 +235,50 @@ impl Default for UtilityWeights {
 /// 3. Calculate utility for each possible intent:
 ///    - SEEK_WATER: if thirsty, find nearest water
 ///    - SEEK_FOOD: if hungry, find nearest food (species-dependent)
+///    - Sources gated by a tool or skill (`ResourceSource::requires_item`,
+///      `requires_skill`) are skipped unless the agent's `Inventory` and
+///      `Skills` meet them; if only tool-gated sources are visible, the agent
+///      seeks the tool instead
+///    - MOVE_TO: if no water/food is visible but the agent's
+///      [`SpatialMemory`] recalls some, head toward it
+///    - SEEK_TRADE (opt-in, see [`with_seek_trade`](Self::with_seek_trade)):
+///      if needy and holding currency, buy from a neighbor within
+///      `trade_search_radius` that holds the item
+///    - MIGRATE: if thirsty or hungry, nothing is visible or remembered, and
+///      the agent's [`Region`] is scarce in the item while another known
+///      region is not (see [`RegionalScarcity`])
 ///    - REST: if tired, sleep in place
-///    - WANDER: default low-utility action
-/// 4. Return intent with highest utility
+///    - FALLBACK: `fallback_intent` (Wander by default) at a low floor utility;
+///      agents with a [`WanderState`] wander along a persistent heading
+///      (`Intent::WanderHeading`) instead
+///    - HOME: agents with a [`Home`] move toward it in place of the fallback;
+///      while the [`Night`] flag is set it scores [`HOME_AT_NIGHT_UTILITY`],
+///      ahead of investing
+/// 4. Return intent with highest utility; an agent with a [`CommittedIntent`]
+///    keeps its previous goal unless the best beats it by more than
+///    `commit_margin`
+///
+/// With [`early_exit`](Self::with_early_exit) on, a critical thirst or hunger
+/// ends step 3 early and the later branches are never evaluated.
+///
+/// If a [`ScarcitySignal`] resource is present in the world, each need is
+/// scaled by `1.0 + scarcity` of the item that satisfies it before thresholds
+/// and urgency are computed, so agents act earlier on scarce items.
+///
+/// If the [`TestMode`] resource is enabled, the `WorldQuery` is wrapped in an
+/// [`InRangeQuery`]: every resource is at distance 0 and every nearby agent
+/// can be traded with.
+///
+/// Resources come from [`WorldQuery::get_nearby_resources_detailed`]. A
+/// source other agents are also targeting has its utility scaled by each
+/// agent's expected share of its stock (`stock / (contention + 1)`, capped at
+/// 1.0), so agents spread out instead of all converging on a low-stock patch.
 ///
 /// # Utility Calculation
 ///
 /// ```text
 /// urgency = need_value / 100.0
-/// distance_factor = max(0, 1 - distance / max_radius)
+/// distance_factor = 1 - normalize_distance(distance, max_radius)
 /// utility = urgency * survival_weight + distance_factor * efficiency_weight
 /// ```
 ///
@@ -117,7 +307,59 @@ pub struct UtilityMaximizer {
     /// Weights for utility calculation
     pub weights: UtilityWeights,
     /// Maximum radius to search for resources (world units)
+    ///
+    /// Uses the same metric as the `WorldQuery`'s distances; see the
+    /// `world_query` module's notes on distance metrics.
     pub resource_search_radius: f32,
+    /// Maximum distance to a trade partner (world units)
+    ///
+    /// Independent of `resource_search_radius`, so agents can scan far for
+    /// water while only trading with close neighbors. Partners come from
+    /// [`WorldQuery::get_nearby_agents_within`], so the host's query must
+    /// override it for the radius to apply.
+    pub trade_search_radius: f32,
+    /// Consider buying needed items from neighbors (default off)
+    ///
+    /// Set with [`with_seek_trade`](Self::with_seek_trade).
+    pub seek_trade: bool,
+    /// Intent chosen when no need calls for action (default `Wander`)
+    ///
+    /// Always scored at [`FALLBACK_UTILITY`], below any need that passed
+    /// its threshold, so it only wins for a content agent. Set it to `Rest`,
+    /// or a `MoveTo` the agent's home, to change what idle agents do.
+    pub fallback_intent: Intent,
+    /// Optional memo of resource queries (default off: every decision
+    /// queries the `WorldQuery`)
+    ///
+    /// Set with [`with_resource_cache`](Self::with_resource_cache). Hosts
+    /// call [`ResourceQueryCache::invalidate`] when an agent moves.
+    pub resource_cache: Option<ResourceQueryCache>,
+    /// Utility another goal must beat the committed one by before an agent
+    /// with a [`CommittedIntent`] switches (default 0: always take the best)
+    pub commit_margin: f32,
+    /// Skip the remaining branches once a need is critical (default off)
+    ///
+    /// Set with [`with_early_exit`](Self::with_early_exit).
+    pub early_exit: bool,
+    /// Seed for random draws of agents without an [`AgentRng`] (default 0)
+    ///
+    /// Such agents draw from [`agent_rng`] seeded with this and the current
+    /// tick, so runs repeat exactly. Set with [`with_seed`](Self::with_seed).
+    pub seed: u64,
+}
+
+/// Utility of the fallback intent, the floor every real need beats
+pub const FALLBACK_UTILITY: f32 = 0.1;
+
+/// Utility of investing surplus currency: above the fallback, below any need
+const INVEST_UTILITY: f32 = 0.2;
+
+/// Utility of heading home at night: above investing, below any need
+pub const HOME_AT_NIGHT_UTILITY: f32 = 0.3;
+
+/// Sort evaluated intents by utility, highest first (stable for ties)
+fn sort_by_utility(utilities: &mut [(Intent, f32, String)]) {
+    utilities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 }
 
 impl Default for UtilityMaximizer {
@@ -126,12 +368,22 @@ impl Default for UtilityMaximizer {
             thresholds: DecisionThresholds::default(),
             weights: UtilityWeights::default(),
             resource_search_radius: 1000.0,
+            trade_search_radius: 1000.0,
+            seek_trade: false,
+            fallback_intent: Intent::Wander,
+            resource_cache: None,
+            commit_margin: 0.0,
+            early_exit: false,
+            seed: 0,
         }
     }
 }
 
 impl UtilityMaximizer {
     /// Create a new UtilityMaximizer with custom configuration
+    ///
+    /// `trade_search_radius` defaults to `resource_search_radius`; use
+    /// [`with_trade_search_radius`](Self::with_trade_search_radius) to narrow it.
     pub fn new(
         thresholds: DecisionThresholds,
         weights: UtilityWeights,
@@ -141,9 +393,111 @@ impl UtilityMaximizer {
             thresholds,
             weights,
             resource_search_radius,
+            trade_search_radius: resource_search_radius,
+            seek_trade: false,
+            fallback_intent: Intent::Wander,
+            resource_cache: None,
+            commit_margin: 0.0,
+            early_exit: false,
+            seed: 0,
+        }
+    }
+
+    /// Create a UtilityMaximizer from a serializable configuration
+    pub fn from_config(config: UtilityMaximizerConfig) -> Self {
+        Self {
+            thresholds: config.thresholds,
+            weights: config.weights,
+            resource_search_radius: config.resource_search_radius,
+            trade_search_radius: config.trade_search_radius,
+            seek_trade: config.seek_trade,
+            fallback_intent: config.fallback_intent,
+            resource_cache: (config.resource_cache_ticks > 0)
+                .then(|| ResourceQueryCache::new(config.resource_cache_ticks)),
+            commit_margin: config.commit_margin,
+            early_exit: config.early_exit,
+            seed: config.seed,
+        }
+    }
+
+    /// Extract this maximizer's configuration for serialization
+    pub fn to_config(&self) -> UtilityMaximizerConfig {
+        UtilityMaximizerConfig {
+            thresholds: self.thresholds.clone(),
+            weights: self.weights.clone(),
+            resource_search_radius: self.resource_search_radius,
+            trade_search_radius: self.trade_search_radius,
+            seek_trade: self.seek_trade,
+            fallback_intent: self.fallback_intent.clone(),
+            resource_cache_ticks: self.resource_cache.as_ref().map_or(0, |c| c.max_age()),
+            commit_margin: self.commit_margin,
+            early_exit: self.early_exit,
+            seed: self.seed,
         }
     }
 
+    /// Set the maximum distance to a trade partner
+    pub fn with_trade_search_radius(mut self, radius: f32) -> Self {
+        self.trade_search_radius = radius;
+        self
+    }
+
+    /// Let needy agents with currency seek a trade
+    ///
+    /// A neighbor within `trade_search_radius` that the query reports as
+    /// interactable and whose `Inventory` holds the item becomes a
+    /// `SeekTrade` candidate. Off by default, so agents only gather.
+    pub fn with_seek_trade(mut self, seek_trade: bool) -> Self {
+        self.seek_trade = seek_trade;
+        self
+    }
+
+    /// Set the intent chosen when no need calls for action
+    pub fn with_fallback_intent(mut self, intent: Intent) -> Self {
+        self.fallback_intent = intent;
+        self
+    }
+
+    /// Reuse each agent's resource-query results for `ticks` ticks
+    ///
+    /// Trades accuracy for throughput in worlds where resources move slowly:
+    /// within the window, decisions see the (possibly stale) result of the
+    /// last query. `ticks == 0` turns the cache off.
+    pub fn with_resource_cache(mut self, ticks: u64) -> Self {
+        self.resource_cache = (ticks > 0).then(|| ResourceQueryCache::new(ticks));
+        self
+    }
+
+    /// Keep agents with a [`CommittedIntent`] on their goal unless another
+    /// beats it by more than `margin` utility
+    ///
+    /// Stops agents flipping between near-equal options. `margin == 0.0`
+    /// turns commitment off.
+    pub fn with_commit_margin(mut self, margin: f32) -> Self {
+        self.commit_margin = margin;
+        self
+    }
+
+    /// Seed the random draws of agents without an [`AgentRng`]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Stop evaluating intents as soon as a need passes its `critical_*`
+    /// threshold
+    ///
+    /// Branches are evaluated water, food, then rest; once thirst (or hunger)
+    /// is critical and its branch produced an intent, the later branches and
+    /// the fallback are skipped and the best intent found so far is taken.
+    /// Whenever full evaluation would pick an intent from those branches
+    /// anyway, the result is the same, just cheaper. Agents below 30% energy
+    /// are always evaluated in full, since resting may outrank the need.
+    pub fn with_early_exit(mut self, early_exit: bool) -> Self {
+        self.early_exit = early_exit;
+        self
+    }
+
     /// Make a decision for the given agent
     ///
     /// # Arguments
@@ -165,6 +519,170 @@ impl UtilityMaximizer {
         world: &World,
         world_query: &dyn WorldQuery,
     ) -> DecisionOutput {
+        self.decide_explained(agent, world, world_query).0
+    }
+
+    /// Make a decision for the given agent, along with the chosen intent's
+    /// utility and the reason it was chosen
+    ///
+    /// Same as [`decide`](Self::decide), side effects included; use it to
+    /// log or display why an agent acts as it does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the agent entity doesn't have required components (Needs).
+    pub fn decide_explained(
+        &self,
+        agent: Entity,
+        world: &World,
+        world_query: &dyn WorldQuery,
+    ) -> (DecisionOutput, f32, String) {
+        let (agent_id, mut utilities) = self.rank_intents(agent, world, world_query, true);
+        let chosen = self.committed_index(agent, world, &utilities);
+
+        let (ref intent, utility, ref reason) = utilities[chosen];
+        log_trace!(
+            "agent {} decided: {} (utility: {:.2}, reason: {})",
+            agent_id.0,
+            intent.intent_type(),
+            utility,
+            reason
+        );
+
+        // Return highest utility intent, steering wanderers along their heading
+        let intent = match &utilities[chosen].0 {
+            Intent::Wander => self.advance_wander(agent, world).unwrap_or(Intent::Wander),
+            other => other.clone(),
+        };
+        if world.has_value::<MaskedStorage<CommittedIntent>>() {
+            if let Some(committed) = world.write_storage::<CommittedIntent>().get_mut(agent) {
+                committed.intent = Some(intent.clone());
+            }
+        }
+        let (_, utility, reason) = utilities.swap_remove(chosen);
+        (DecisionOutput::Intent(intent), utility, reason)
+    }
+
+    /// Index into `ranked` of the intent to take, honoring the agent's
+    /// [`CommittedIntent`] and `commit_margin`
+    fn committed_index(&self, agent: Entity, world: &World, ranked: &[(Intent, f32, String)]) -> usize {
+        if self.commit_margin <= 0.0 || !world.has_value::<MaskedStorage<CommittedIntent>>() {
+            return 0;
+        }
+        let storage = world.read_storage::<CommittedIntent>();
+        let previous = storage.get(agent).and_then(|c| c.intent.as_ref());
+        committed_choice(ranked, previous, self.commit_margin)
+    }
+
+    /// Step the agent's [`WanderState`], drawing a new heading if it ran out
+    ///
+    /// The heading comes from the agent's [`AgentRng`], or else from
+    /// [`agent_rng`] seeded with `seed` and the tick. `None` if the agent has
+    /// no `WanderState`.
+    fn advance_wander(&self, agent: Entity, world: &World) -> Option<Intent> {
+        if !world.has_value::<MaskedStorage<WanderState>>() {
+            return None;
+        }
+        let tick = world.try_fetch::<CurrentTick>().map_or(0, |t| t.0);
+        let mut states = world.write_storage::<WanderState>();
+        let state = states.get_mut(agent)?;
+        let mut rngs = world
+            .has_value::<MaskedStorage<AgentRng>>()
+            .then(|| world.write_storage::<AgentRng>());
+        let pick = |rng: &mut dyn RngCore| rng.gen::<f32>() * std::f32::consts::TAU;
+        let (heading, persistence) = match rngs.as_mut().and_then(|rngs| rngs.get_mut(agent)) {
+            Some(rng) => state.advance(tick, || pick(rng)),
+            None => {
+                let id = world.read_storage::<Agent>().get(agent)?.id;
+                state.advance(tick, || pick(&mut agent_rng(self.seed ^ tick, id)))
+            }
+        };
+        Some(Intent::WanderHeading { heading, persistence })
+    }
+
+    /// Preview what the agent would decide, without any side effects
+    ///
+    /// Runs the same evaluation as [`decide`](Self::decide) but never draws
+    /// from an RNG, never logs, never writes to the world and never fills the
+    /// resource cache (it still reads from it), so calling it repeatedly with
+    /// unchanged inputs always yields the same output. Use it for UI hints
+    /// such as "this agent wants water".
+    ///
+    /// # Panics
+    ///
+    /// Panics if the agent entity doesn't have required components (Needs).
+    pub fn preview_decision(
+        &self,
+        agent: Entity,
+        world: &World,
+        world_query: &dyn WorldQuery,
+    ) -> DecisionOutput {
+        let (_agent_id, utilities) = self.rank_intents(agent, world, world_query, false);
+        let chosen = self.committed_index(agent, world, &utilities);
+
+        // A wanderer's heading shows only while it lasts; choosing one is random
+        let tick = world.try_fetch::<CurrentTick>().map_or(0, |t| t.0);
+        let heading = world
+            .has_value::<MaskedStorage<WanderState>>()
+            .then(|| world.read_storage::<WanderState>().get(agent).and_then(|s| s.current(tick)))
+            .flatten();
+        let intent = match (&utilities[chosen].0, heading) {
+            (Intent::Wander, Some((heading, persistence))) => {
+                Intent::WanderHeading { heading, persistence }
+            }
+            (intent, _) => intent.clone(),
+        };
+        DecisionOutput::Intent(intent)
+    }
+
+    /// Evaluate all candidate intents, sorted by utility (highest first)
+    ///
+    /// Pure with respect to the world and RNG; the returned list always
+    /// contains at least the fallback intent. Fresh resource queries are
+    /// stored in the resource cache only if `fill_cache` is set.
+    fn rank_intents(
+        &self,
+        agent: Entity,
+        world: &World,
+        world_query: &dyn WorldQuery,
+        fill_cache: bool,
+    ) -> (AgentId, Vec<(Intent, f32, String)>) {
+        let current_tick = world.try_fetch::<CurrentTick>().map_or(0, |t| t.0);
+
+        // The optional cache answers repeated resource queries
+        let cached;
+        let world_query: &dyn WorldQuery = match &self.resource_cache {
+            Some(cache) => {
+                cached = if fill_cache {
+                    CachedQuery::new(world_query, cache, current_tick)
+                } else {
+                    CachedQuery::read_only(world_query, cache, current_tick)
+                };
+                &cached
+            }
+            None => world_query,
+        };
+
+        // A precomputed matrix answers interaction checks
+        let matrix = world.try_fetch::<InteractionMatrix>();
+        let matrix_query;
+        let world_query: &dyn WorldQuery = match matrix.as_deref() {
+            Some(matrix) => {
+                matrix_query = MatrixQuery::new(world_query, matrix);
+                &matrix_query
+            }
+            None => world_query,
+        };
+
+        // Test mode places every resource and agent in range
+        let in_range;
+        let world_query: &dyn WorldQuery = if TestMode::is_enabled(world) {
+            in_range = InRangeQuery::new(world_query);
+            &in_range
+        } else {
+            world_query
+        };
+
         // Read agent's needs
         let needs_storage = world.read_storage::<Needs>();
         let needs = needs_storage
@@ -187,50 +705,163 @@ impl UtilityMaximizer {
             .expect("Agent must have Agent component");
         let agent_id = agent_component.id;
 
+        // Inventory and skills are optional - they only gate resource sources
+        let inventory_storage = world
+            .has_value::<MaskedStorage<Inventory>>()
+            .then(|| world.read_storage::<Inventory>());
+        let skills_storage = world
+            .has_value::<MaskedStorage<Skills>>()
+            .then(|| world.read_storage::<Skills>());
+        let gatherer = Gatherer {
+            id: agent_id,
+            inventory: inventory_storage.as_ref().and_then(|s| s.get(agent)),
+            skills: skills_storage.as_ref().and_then(|s| s.get(agent)),
+        };
+
+        // Currency is optional - agents without a Wallet never trade
+        let has_currency = self.seek_trade
+            && world.has_value::<MaskedStorage<Wallet>>()
+            && world
+                .read_storage::<Wallet>()
+                .get(agent)
+                .is_some_and(|w| w.currency > 0.0);
+
+        // Spatial memory is optional - recalled against the CurrentTick resource
+        let memory_storage = world
+            .has_value::<MaskedStorage<SpatialMemory>>()
+            .then(|| world.read_storage::<SpatialMemory>());
+        let memory = memory_storage.as_ref().and_then(|m| m.get(agent));
+        let recall = |item: &str| {
+            memory
+                .and_then(|m| m.recall(item, current_tick))
+                .map(|r| ResourceLocation::new(r.x, r.y, 0.0))
+        };
+
+        // Scarcity is optional - no signal means no boost
+        let default_scarcity = ScarcitySignal::default();
+        let scarcity_resource = world.try_fetch::<ScarcitySignal>();
+        let scarcity = scarcity_resource.as_deref().unwrap_or(&default_scarcity);
+
+        // Regions are optional - migration needs the agent's Region and a regional map
+        let region = world
+            .has_value::<MaskedStorage<Region>>()
+            .then(|| world.read_storage::<Region>().get(agent).copied())
+            .flatten();
+        let regional_resource = world.try_fetch::<RegionalScarcity>();
+        let migration = region.zip(regional_resource.as_deref());
+
         // Evaluate all possible intents
         let mut utilities: Vec<(Intent, f32, String)> = Vec::new();
+        let early_exit = self.early_exit && energy_percent >= 30.0;
 
         // Evaluate SEEK_WATER
-        if needs.thirst > self.thresholds.high_thirst {
-            if let Some((utility, reason)) = self.evaluate_seek_water(
-                agent_id,
-                needs.thirst,
+        let thirst = scarcity.boost("water", needs.thirst);
+        if thirst > self.thresholds.high_thirst {
+            if let Some((utility, reason, approach)) = self.evaluate_seek_water(
+                &gatherer,
+                thirst,
+                recall("water"),
                 world_query,
             ) {
-                utilities.push((
-                    Intent::SeekItem {
-                        item_type: "water".to_string(),
-                        urgency: needs.thirst / 100.0,
-                    },
-                    utility,
-                    reason,
-                ));
+                let intent = Self::approach_intent(approach, "water".to_string(), thirst);
+                utilities.push((intent, utility, reason));
+            }
+
+            if let Some(migration) = migration {
+                if let Some((utility, reason, toward_region)) = self.evaluate_migrate(
+                    agent_id,
+                    thirst,
+                    &["water"],
+                    migration,
+                    &recall,
+                    world_query,
+                ) {
+                    utilities.push((Intent::Migrate { toward_region }, utility, reason));
+                }
+            }
+
+            if has_currency {
+                if let Some((utility, reason)) =
+                    self.evaluate_seek_trade(agent_id, "water", thirst, world, world_query)
+                {
+                    utilities.push((
+                        Intent::SeekTrade {
+                            buying: true,
+                            item_type: "water".to_string(),
+                        },
+                        utility,
+                        reason,
+                    ));
+                }
             }
         }
 
-        // Evaluate SEEK_FOOD (species-aware)
-        if needs.hunger > self.thresholds.high_hunger {
-            // Get species component (optional - defaults to omnivore if not present)
-            let species_storage = world.read_storage::<SpeciesComponent>();
-            let species = species_storage.get(agent);
+        if early_exit && thirst >= self.thresholds.critical_thirst && !utilities.is_empty() {
+            sort_by_utility(&mut utilities);
+            return (agent_id, utilities);
+        }
 
-            if let Some((utility, reason, item_type)) = self.evaluate_seek_food(
-                agent_id,
+        // Evaluate SEEK_FOOD (species-aware)
+        // Get species component (optional - defaults to omnivore if not present)
+        let species_storage = world
+            .has_value::<MaskedStorage<SpeciesComponent>>()
+            .then(|| world.read_storage::<SpeciesComponent>());
+        let species = species_storage.as_ref().and_then(|s| s.get(agent));
+        let food_items = Self::food_items(species);
+        let hunger = food_items
+            .iter()
+            .map(|item| scarcity.boost(item, needs.hunger))
+            .fold(needs.hunger, f32::max);
+
+        if hunger > self.thresholds.high_hunger {
+            if let Some((utility, reason, item_type, approach)) = self.evaluate_seek_food(
+                &gatherer,
                 needs.hunger,
-                species,
+                &food_items,
+                scarcity,
+                &recall,
                 world_query,
             ) {
-                utilities.push((
-                    Intent::SeekItem {
-                        item_type,
-                        urgency: needs.hunger / 100.0,
-                    },
-                    utility,
-                    reason,
-                ));
+                let item_hunger = scarcity.boost(&item_type, needs.hunger);
+
+                if has_currency {
+                    if let Some((trade_utility, trade_reason)) =
+                        self.evaluate_seek_trade(agent_id, &item_type, item_hunger, world, world_query)
+                    {
+                        utilities.push((
+                            Intent::SeekTrade {
+                                buying: true,
+                                item_type: item_type.clone(),
+                            },
+                            trade_utility,
+                            trade_reason,
+                        ));
+                    }
+                }
+
+                let intent = Self::approach_intent(approach, item_type, item_hunger);
+                utilities.push((intent, utility, reason));
+            }
+
+            if let Some(migration) = migration {
+                if let Some((utility, reason, toward_region)) = self.evaluate_migrate(
+                    agent_id,
+                    hunger,
+                    &food_items,
+                    migration,
+                    &recall,
+                    world_query,
+                ) {
+                    utilities.push((Intent::Migrate { toward_region }, utility, reason));
+                }
             }
         }
 
+        if early_exit && hunger >= self.thresholds.critical_hunger && !utilities.is_empty() {
+            sort_by_utility(&mut utilities);
+            return (agent_id, utilities);
+        }
+
         // Evaluate REST (triggered by tiredness OR low energy)
         if needs.tiredness > self.thresholds.high_tiredness || energy_percent < 30.0 {
             let rest_urgency = if energy_percent < 30.0 {
@@ -253,8 +884,43 @@ impl UtilityMaximizer {
             utilities.push((Intent::Rest, utility, reason));
         }
 
-        // Always include WANDER as fallback
-        utilities.push((Intent::Wander, 0.1, "Exploring".to_string()));
+        // Evaluate INVEST (only savers: agents with a SavingsAccount)
+        let is_saver = world.has_value::<MaskedStorage<SavingsAccount>>()
+            && world.has_value::<MaskedStorage<Wallet>>()
+            && world.read_storage::<SavingsAccount>().contains(agent);
+        if is_saver {
+            let currency = world.read_storage::<Wallet>().get(agent).map_or(0.0, |w| w.currency);
+            let surplus = currency - self.thresholds.invest_above;
+            if surplus > 0.0 {
+                utilities.push((
+                    Intent::Invest { amount: surplus },
+                    INVEST_UTILITY,
+                    format!("Saving surplus: {:.0}", surplus),
+                ));
+            }
+        }
+
+        // Always include the fallback (WANDER by default), or the way home
+        // for agents that have one
+        let home = world
+            .has_value::<MaskedStorage<Home>>()
+            .then(|| world.read_storage::<Home>().get(agent).copied())
+            .flatten();
+        if let Some(home) = home {
+            let night = world.try_fetch::<Night>().is_some_and(|n| n.0);
+            let (utility, reason) = if night {
+                (HOME_AT_NIGHT_UTILITY, "Heading home for the night")
+            } else {
+                (FALLBACK_UTILITY, "Returning home")
+            };
+            utilities.push((home.intent(), utility, reason.to_string()));
+        } else {
+            let reason = match self.fallback_intent {
+                Intent::Wander => "Exploring".to_string(),
+                _ => "No pressing needs".to_string(),
+            };
+            utilities.push((self.fallback_intent.clone(), FALLBACK_UTILITY, reason));
+        }
 
         // Apply energy penalty to non-REST actions when low energy
         if energy_percent < 30.0 {
@@ -267,76 +933,121 @@ impl UtilityMaximizer {
             }
         }
 
-        // Sort by utility (highest first)
-        utilities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        sort_by_utility(&mut utilities);
+        (agent_id, utilities)
+    }
 
-        // Log decision (1% of the time to avoid spam)
-        if rand::random::<f32>() < 0.01 {
-            let (ref intent, utility, ref reason) = utilities[0];
-            println!(
-                "Agent {:?} decided: {} (utility: {:.2}, reason: {})",
-                agent_id,
-                intent.intent_type(),
-                utility,
-                reason
-            );
+    /// Intent that carries out an [`Approach`] to getting `item_type`
+    fn approach_intent(approach: Approach, item_type: String, need: f32) -> Intent {
+        match approach {
+            Approach::Seek => Intent::SeekItem {
+                item_type,
+                urgency: need / 100.0,
+            },
+            Approach::Remembered(spot) => Intent::MoveTo {
+                x: spot.x,
+                y: spot.y,
+                item_type,
+            },
+            Approach::AcquireTool(tool) => Intent::SeekItem {
+                item_type: tool,
+                urgency: need / 100.0,
+            },
         }
-
-        // Return highest utility intent
-        DecisionOutput::Intent(utilities[0].0.clone())
     }
 
     /// Evaluate utility of seeking water
+    ///
+    /// If no usable water is visible, falls back to the `remembered` location,
+    /// then to a tool that unlocks a visible gated source.
     fn evaluate_seek_water(
         &self,
-        agent_id: AgentId,
+        agent: &Gatherer,
         thirst: f32,
+        remembered: Option<ResourceLocation>,
         world_query: &dyn WorldQuery,
-    ) -> Option<(f32, String)> {
+    ) -> Option<(f32, String, Approach)> {
         let urgency = thirst / 100.0;
 
-        // Query for nearby water sources
-        let water_sources = world_query.get_nearby_resources(
-            agent_id,
-            "water",
-            self.resource_search_radius,
+        // Query for nearby water sources the agent can use
+        let (water_sources, missing_tool) = agent.usable_sources(
+            world_query.get_nearby_resources_detailed(
+                agent.id,
+                "water",
+                self.resource_search_radius,
+            ),
         );
 
-        if let Some(closest) = water_sources.first() {
-            // Calculate distance factor (closer is better)
-            let distance_factor =
-                (1.0 - (closest.distance / self.resource_search_radius)).max(0.0);
-
-            // Combine urgency and efficiency
-            let utility = urgency * self.weights.survival
-                + distance_factor * self.weights.efficiency;
-
+        if let Some((utility, best)) =
+            self.best_source(agent.id, urgency, &water_sources, world_query)
+        {
             let reason = format!(
                 "Thirst: {:.0} (water at distance {:.0})",
-                thirst, closest.distance
+                thirst, best.location.distance
             );
 
-            Some((utility, reason))
+            Some((utility, reason, Approach::Seek))
+        } else if let Some(spot) = remembered {
+            // No water visible - head back to where water was last seen
+            let utility = urgency * self.weights.survival;
+            let reason = format!(
+                "Thirst: {:.0} (remembered water at ({:.0}, {:.0}))",
+                thirst, spot.x, spot.y
+            );
+            Some((utility, reason, Approach::Remembered(spot)))
+        } else if let Some(tool) = missing_tool {
+            // Water is there but needs a tool - go get the tool
+            let utility = urgency * self.weights.survival;
+            let reason = format!("Thirst: {:.0} (water needs {})", thirst, tool);
+            Some((utility, reason, Approach::AcquireTool(tool)))
         } else {
             // No water found, but still urgent - create intent to wander toward water
             let utility = urgency * self.weights.survival;
             let reason = format!("Thirst: {:.0} (searching for water)", thirst);
-            Some((utility, reason))
+            Some((utility, reason, Approach::Seek))
         }
     }
 
-    /// Evaluate utility of seeking food
-    fn evaluate_seek_food(
+    /// Highest-utility source among `sources`, with its utility
+    ///
+    /// Combines urgency with a distance factor (cheaper to reach is better),
+    /// discounted by contention. Distance is the query's
+    /// [`travel_cost`](WorldQuery::travel_cost) when it knows one, else the
+    /// straight-line distance; sources with an infinite travel cost are
+    /// skipped. Without contention this is always the nearest source.
+    fn best_source<'a>(
         &self,
-        agent_id: AgentId,
-        hunger: f32,
-        species: Option<&SpeciesComponent>,
+        agent: AgentId,
+        urgency: f32,
+        sources: &'a [ResourceDetail],
         world_query: &dyn WorldQuery,
-    ) -> Option<(f32, String, String)> {
-        let urgency = hunger / 100.0;
+    ) -> Option<(f32, &'a ResourceDetail)> {
+        let mut best: Option<(f32, &ResourceDetail)> = None;
+
+        for source in sources {
+            let location = &source.location;
+            let cost = world_query
+                .travel_cost(agent, location.x, location.y)
+                .unwrap_or(location.distance);
+            if cost == f32::INFINITY {
+                continue;
+            }
+            let distance_factor = 1.0 - normalize_distance(cost, self.resource_search_radius);
+            let utility = (urgency * self.weights.survival
+                + distance_factor * self.weights.efficiency)
+                * contention_factor(source);
+
+            if best.is_none_or(|(u, _)| utility > u) {
+                best = Some((utility, source));
+            }
+        }
+
+        best
+    }
 
-        // Determine what food sources this species can eat
-        let food_items: Vec<&str> = match species {
+    /// Food item types a species can eat
+    fn food_items(species: Option<&SpeciesComponent>) -> Vec<&str> {
+        match species {
             Some(s) => match &s.diet {
                 DietType::Herbivore { preferred_plants } => {
                     if preferred_plants.is_empty() {
@@ -364,77 +1075,215 @@ impl UtilityMaximizer {
                 // No species component - default to omnivore behavior
                 vec!["grass", "food"]
             }
-        };
+        }
+    }
 
+    /// Evaluate utility of seeking food
+    ///
+    /// `hunger` is the raw need; each food type's urgency is boosted by its
+    /// own scarcity level. If no usable food is visible, falls back to the
+    /// first food type `recall` remembers a location for, then to a tool that
+    /// unlocks a visible gated source.
+    fn evaluate_seek_food(
+        &self,
+        agent: &Gatherer,
+        hunger: f32,
+        food_items: &[&str],
+        scarcity: &ScarcitySignal,
+        recall: &dyn Fn(&str) -> Option<ResourceLocation>,
+        world_query: &dyn WorldQuery,
+    ) -> Option<(f32, String, String, Approach)> {
         // Try each food type and find the best option
-        let mut best_option: Option<(f32, String, String)> = None;
-
-        for food_type in &food_items {
-            let food_sources = world_query.get_nearby_resources(
-                agent_id,
-                food_type,
-                self.resource_search_radius,
+        let mut best_option: Option<(f32, String, String, Approach)> = None;
+        let mut missing_tool: Option<(String, String)> = None;
+
+        for food_type in food_items {
+            let hunger = scarcity.boost(food_type, hunger);
+            let urgency = hunger / 100.0;
+
+            let (food_sources, tool) = agent.usable_sources(
+                world_query.get_nearby_resources_detailed(
+                    agent.id,
+                    food_type,
+                    self.resource_search_radius,
+                ),
             );
+            if missing_tool.is_none() {
+                missing_tool = tool.map(|tool| (food_type.to_string(), tool));
+            }
 
-            if let Some(closest) = food_sources.first() {
-                let distance_factor =
-                    (1.0 - (closest.distance / self.resource_search_radius)).max(0.0);
-
-                let utility = urgency * self.weights.survival
-                    + distance_factor * self.weights.efficiency;
-
+            if let Some((utility, best)) =
+                self.best_source(agent.id, urgency, &food_sources, world_query)
+            {
                 let reason = format!(
                     "Hunger: {:.0} ({} at distance {:.0})",
-                    hunger, food_type, closest.distance
+                    hunger, food_type, best.location.distance
                 );
 
                 // Keep the best option
                 if best_option.is_none() || utility > best_option.as_ref().unwrap().0 {
-                    best_option = Some((utility, reason, food_type.to_string()));
+                    best_option = Some((utility, reason, food_type.to_string(), Approach::Seek));
                 }
             }
         }
 
         if let Some(option) = best_option {
-            Some(option)
+            return Some(option);
+        }
+
+        // No food visible - head back to remembered food
+        if let Some((food_type, spot)) = food_items
+            .iter()
+            .find_map(|item| recall(item).map(|spot| (item.to_string(), spot)))
+        {
+            let hunger = scarcity.boost(&food_type, hunger);
+            let utility = hunger / 100.0 * self.weights.survival;
+            let reason = format!(
+                "Hunger: {:.0} (remembered {} at ({:.0}, {:.0}))",
+                hunger, food_type, spot.x, spot.y
+            );
+            Some((utility, reason, food_type, Approach::Remembered(spot)))
+        } else if let Some((food_type, tool)) = missing_tool {
+            // Food is there but needs a tool - go get the tool
+            let hunger = scarcity.boost(&food_type, hunger);
+            let utility = hunger / 100.0 * self.weights.survival;
+            let reason = format!("Hunger: {:.0} ({} needs {})", hunger, food_type, tool);
+            Some((utility, reason, food_type, Approach::AcquireTool(tool)))
         } else {
             // No food found, but still urgent - wander to search
-            let utility = urgency * self.weights.survival;
             let food_type = food_items.first().unwrap_or(&"food").to_string();
+            let hunger = scarcity.boost(&food_type, hunger);
+            let utility = hunger / 100.0 * self.weights.survival;
             let reason = format!("Hunger: {:.0} (searching for {})", hunger, food_type);
-            Some((utility, reason, food_type))
+            Some((utility, reason, food_type, Approach::Seek))
         }
     }
 
-    /// Calculate utility for a given urgency and distance
-    ///
-    /// This is a helper function that can be used by custom decision makers.
-    ///
-    /// # Arguments
-    ///
-    /// * `urgency` - How urgent the need is (0.0 to 1.0)
-    /// * `distance` - Distance to the resource (world units)
-    /// * `max_radius` - Maximum search radius
+    /// Evaluate leaving the agent's region for a better-supplied one
     ///
-    /// # Returns
-    ///
-    /// Calculated utility score (higher is better)
-    pub fn calculate_utility(
+    /// Only fires when the need is genuinely unmet locally: every item in
+    /// `items` is at least `migrate_scarcity` scarce in the agent's region, none is
+    /// visible or remembered, and another known region is less scarce. The
+    /// utility beats blind searching by the scarcity gap between regions.
+    fn evaluate_migrate(
         &self,
-        urgency: f32,
-        distance: f32,
-        max_radius: f32,
-    ) -> f32 {
-        let distance_factor = (1.0 - (distance / max_radius)).max(0.0);
-        urgency * self.weights.survival + distance_factor * self.weights.efficiency
-    }
-}
+        agent_id: AgentId,
+        need: f32,
+        items: &[&str],
+        (region, regional): (Region, &RegionalScarcity),
+        recall: &dyn Fn(&str) -> Option<ResourceLocation>,
+        world_query: &dyn WorldQuery,
+    ) -> Option<(f32, String, u32)> {
+        let local = regional.best_level(region.0, items);
+        if local < self.thresholds.migrate_scarcity {
+            return None;
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{Agent, AgentIdAllocator};
-    use crate::world_query::ResourceLocation;
+        let reachable = items.iter().any(|item| {
+            recall(item).is_some()
+                || !world_query
+                    .get_nearby_resources(agent_id, item, self.resource_search_radius)
+                    .is_empty()
+        });
+        if reachable {
+            return None;
+        }
+
+        let (target, level) = regional.best_region(items, region.0)?;
+        if level >= local {
+            return None;
+        }
+
+        let utility = need / 100.0 * self.weights.survival + (local - level) * self.weights.efficiency;
+        let reason = format!(
+            "Need: {:.0} (region {} scarcity {:.2}, region {} scarcity {:.2})",
+            need, region.0, local, target, level
+        );
+        Some((utility, reason, target))
+    }
+
+    /// Evaluate utility of buying an item from a nearby agent
+    ///
+    /// Only agents within `trade_search_radius` that the application reports
+    /// as able to interact, and whose `Inventory` holds at least one
+    /// `item_type`, are considered. Partner ids resolve through the world's
+    /// [`AgentIndex`] resource, or one built on the spot. Returns None if
+    /// there is no such partner.
+    fn evaluate_seek_trade(
+        &self,
+        agent_id: AgentId,
+        item_type: &str,
+        need: f32,
+        world: &World,
+        world_query: &dyn WorldQuery,
+    ) -> Option<(f32, String)> {
+        let candidates = world_query.get_nearby_agents_within(
+            agent_id,
+            TRADE_PARTNER_SCAN,
+            self.trade_search_radius,
+        );
+        if candidates.is_empty() || !world.has_value::<MaskedStorage<Inventory>>() {
+            return None;
+        }
+        let fetched = world.try_fetch::<AgentIndex>();
+        let built;
+        let index = match fetched.as_deref() {
+            Some(index) => index,
+            None => {
+                built = AgentIndex::from_world(world);
+                &built
+            }
+        };
+        let inventories = world.read_storage::<Inventory>();
+        let partner = candidates.into_iter().find(|other| {
+            world_query.can_interact(agent_id, *other)
+                && index
+                    .get(*other)
+                    .and_then(|entity| inventories.get(entity))
+                    .is_some_and(|inventory| inventory.quantity(item_type) > 0)
+        })?;
+
+        let urgency = need / 100.0;
+        let utility = urgency * self.weights.survival
+            + TRADE_PARTNER_BONUS * self.weights.efficiency;
+
+        let reason = format!(
+            "Need: {:.0} (can buy {} from agent {})",
+            need, item_type, partner.0
+        );
+
+        Some((utility, reason))
+    }
+
+    /// Calculate utility for a given urgency and distance
+    ///
+    /// This is a helper function that can be used by custom decision makers.
+    ///
+    /// # Arguments
+    ///
+    /// * `urgency` - How urgent the need is (0.0 to 1.0)
+    /// * `distance` - Distance to the resource (world units)
+    /// * `max_radius` - Maximum search radius, in the same metric as `distance`
+    ///
+    /// # Returns
+    ///
+    /// Calculated utility score (higher is better)
+    pub fn calculate_utility(
+        &self,
+        urgency: f32,
+        distance: f32,
+        max_radius: f32,
+    ) -> f32 {
+        let distance_factor = 1.0 - normalize_distance(distance, max_radius);
+        urgency * self.weights.survival + distance_factor * self.weights.efficiency
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Agent, AgentIdAllocator};
+    use crate::world_query::ResourceLocation;
 
     // Mock WorldQuery for testing
     struct MockWorldQuery {
@@ -469,6 +1318,7 @@ mod tests {
         let mut world = World::new();
         world.register::<Agent>();
         world.register::<Needs>();
+        world.register::<EnergyComponent>();
         world.register::<SpeciesComponent>();
         world.insert(AgentIdAllocator::new());
 
@@ -489,6 +1339,80 @@ mod tests {
         (world, entity)
     }
 
+    /// Counts resource queries so tests can see which branches ran
+    struct CountingQuery {
+        inner: MockWorldQuery,
+        queries: std::cell::Cell<usize>,
+    }
+
+    impl WorldQuery for CountingQuery {
+        fn get_nearby_agents(&self, agent: AgentId, max_count: usize) -> Vec<AgentId> {
+            self.inner.get_nearby_agents(agent, max_count)
+        }
+
+        fn get_nearby_resources(
+            &self,
+            agent: AgentId,
+            resource_type: &str,
+            max_radius: f32,
+        ) -> Vec<ResourceLocation> {
+            self.queries.set(self.queries.get() + 1);
+            self.inner.get_nearby_resources(agent, resource_type, max_radius)
+        }
+
+        fn can_interact(&self, agent1: AgentId, agent2: AgentId) -> bool {
+            self.inner.can_interact(agent1, agent2)
+        }
+    }
+
+    #[test]
+    fn test_early_exit_agrees_with_full_evaluation_for_critical_needs() {
+        let full = UtilityMaximizer::default();
+        let early = UtilityMaximizer::default().with_early_exit(true);
+        let query = || CountingQuery {
+            inner: MockWorldQuery {
+                water_sources: vec![ResourceLocation::new(10.0, 0.0, 10.0)],
+                food_sources: vec![ResourceLocation::new(0.0, 10.0, 10.0)],
+            },
+            queries: std::cell::Cell::new(0),
+        };
+
+        // Critical thirst, critical hunger, and both at once with tiredness
+        for (thirst, hunger, tiredness) in [(90.0, 20.0, 10.0), (20.0, 85.0, 10.0), (95.0, 90.0, 80.0)] {
+            let (world, agent) = create_test_world_with_agent(thirst, hunger, tiredness);
+            let (full_query, early_query) = (query(), query());
+            let expected = full.decide(agent, &world, &full_query);
+            assert_eq!(early.decide(agent, &world, &early_query), expected);
+            assert!(early_query.queries.get() <= full_query.queries.get());
+        }
+
+        // Critical thirst never reaches the food branch
+        let (world, agent) = create_test_world_with_agent(90.0, 60.0, 10.0);
+        let (full_query, early_query) = (query(), query());
+        assert_eq!(
+            early.decide(agent, &world, &early_query),
+            full.decide(agent, &world, &full_query)
+        );
+        assert!(early_query.queries.get() < full_query.queries.get());
+    }
+
+    #[test]
+    fn test_early_exit_waits_for_critical_threshold() {
+        let early = UtilityMaximizer::default().with_early_exit(true);
+        let query = MockWorldQuery {
+            water_sources: Vec::new(),
+            food_sources: Vec::new(),
+        };
+        // High but not critical thirst: rest still gets a say
+        let (world, agent) = create_test_world_with_agent(65.0, 20.0, 99.0);
+        assert_eq!(
+            early.decide(agent, &world, &query),
+            UtilityMaximizer::default().decide(agent, &world, &query)
+        );
+        assert!(early.to_config().early_exit);
+        assert!(!UtilityMaximizer::from_config(UtilityMaximizerConfig::default()).early_exit);
+    }
+
     #[test]
     fn test_default_configuration() {
         let dm = UtilityMaximizer::default();
@@ -518,6 +1442,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_decide_explained_reports_the_chosen_utility_and_reason() {
+        let (world, agent) = create_test_world_with_agent(80.0, 20.0, 10.0);
+        let world_query = MockWorldQuery {
+            water_sources: vec![ResourceLocation::new(100.0, 100.0, 50.0)],
+            food_sources: Vec::new(),
+        };
+        let dm = UtilityMaximizer::default();
+
+        let (decision, utility, reason) = dm.decide_explained(agent, &world, &world_query);
+        let (_, ranked) = dm.rank_intents(agent, &world, &world_query, false);
+
+        assert_eq!(decision, dm.decide(agent, &world, &world_query));
+        assert_eq!(decision.as_intent(), Some(&ranked[0].0));
+        assert_eq!((utility, reason), (ranked[0].1, ranked[0].2.clone()));
+        assert!(ranked[0].2.starts_with("Thirst"));
+    }
+
     #[test]
     fn test_decide_high_hunger_seeks_food() {
         let (world, agent) = create_test_world_with_agent(20.0, 70.0, 10.0);
@@ -578,6 +1520,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rest_fallback_for_content_agent() {
+        let world_query = MockWorldQuery {
+            water_sources: vec![ResourceLocation::new(10.0, 10.0, 14.0)],
+            food_sources: Vec::new(),
+        };
+        let dm = UtilityMaximizer::default().with_fallback_intent(Intent::Rest);
+
+        let (world, content) = create_test_world_with_agent(30.0, 30.0, 40.0);
+        assert_eq!(
+            dm.preview_decision(content, &world, &world_query),
+            DecisionOutput::Intent(Intent::Rest)
+        );
+
+        // An urgent need still beats the fallback
+        let (world, thirsty) = create_test_world_with_agent(85.0, 30.0, 40.0);
+        assert!(matches!(
+            dm.preview_decision(thirsty, &world, &world_query),
+            DecisionOutput::Intent(Intent::SeekItem { .. })
+        ));
+
+        // Host-provided home location
+        let home = Intent::MoveTo {
+            x: 5.0,
+            y: 5.0,
+            item_type: "home".to_string(),
+        };
+        let dm = UtilityMaximizer::default().with_fallback_intent(home.clone());
+        let (world, content) = create_test_world_with_agent(30.0, 30.0, 40.0);
+        assert_eq!(
+            dm.preview_decision(content, &world, &world_query),
+            DecisionOutput::Intent(home)
+        );
+    }
+
     #[test]
     fn test_decide_multiple_needs_selects_highest_utility() {
         let (world, agent) = create_test_world_with_agent(85.0, 65.0, 50.0);
@@ -619,17 +1596,17 @@ mod tests {
     #[test]
     fn test_evaluate_seek_water_with_source() {
         let dm = UtilityMaximizer::default();
-        let agent_id = AgentId(1);
+        let agent = Gatherer::bare(AgentId(1));
 
         let world_query = MockWorldQuery {
             water_sources: vec![ResourceLocation::new(100.0, 100.0, 50.0)],
             food_sources: Vec::new(),
         };
 
-        let result = dm.evaluate_seek_water(agent_id, 80.0, &world_query);
+        let result = dm.evaluate_seek_water(&agent, 80.0, None, &world_query);
         assert!(result.is_some());
 
-        let (utility, reason) = result.unwrap();
+        let (utility, reason, _) = result.unwrap();
         assert!(utility > 1.0); // Should be > survival weight
         assert!(reason.contains("Thirst"));
         assert!(reason.contains("distance"));
@@ -638,17 +1615,17 @@ mod tests {
     #[test]
     fn test_evaluate_seek_water_no_source() {
         let dm = UtilityMaximizer::default();
-        let agent_id = AgentId(1);
+        let agent = Gatherer::bare(AgentId(1));
 
         let world_query = MockWorldQuery {
             water_sources: Vec::new(),
             food_sources: Vec::new(),
         };
 
-        let result = dm.evaluate_seek_water(agent_id, 80.0, &world_query);
+        let result = dm.evaluate_seek_water(&agent, 80.0, None, &world_query);
         assert!(result.is_some());
 
-        let (utility, reason) = result.unwrap();
+        let (utility, reason, _) = result.unwrap();
         // Still has utility (urgency * survival_weight), even without found source
         assert!(utility > 0.0);
         assert!(reason.contains("searching"));
@@ -685,4 +1662,1077 @@ mod tests {
             _ => panic!("Expected water seeking with custom low threshold"),
         }
     }
+
+    #[test]
+    fn test_preview_decision_is_idempotent() {
+        let (world, agent) = create_test_world_with_agent(85.0, 65.0, 50.0);
+
+        let world_query = MockWorldQuery {
+            water_sources: vec![ResourceLocation::new(100.0, 100.0, 50.0)],
+            food_sources: vec![ResourceLocation::new(200.0, 200.0, 100.0)],
+        };
+
+        let dm = UtilityMaximizer::default();
+        let first = dm.preview_decision(agent, &world, &world_query);
+        let second = dm.preview_decision(agent, &world, &world_query);
+
+        assert_eq!(first, second);
+        // Preview agrees with the real decision
+        assert_eq!(first, dm.decide(agent, &world, &world_query));
+    }
+
+    #[test]
+    fn test_preview_decision_leaves_world_untouched() {
+        let (world, agent) = create_test_world_with_agent(20.0, 20.0, 80.0);
+
+        let world_query = MockWorldQuery {
+            water_sources: Vec::new(),
+            food_sources: Vec::new(),
+        };
+
+        let needs_before = world.read_storage::<Needs>().get(agent).cloned();
+
+        let dm = UtilityMaximizer::default();
+        let decision = dm.preview_decision(agent, &world, &world_query);
+
+        assert_eq!(decision, DecisionOutput::Intent(Intent::Rest));
+        assert_eq!(world.read_storage::<Needs>().get(agent).cloned(), needs_before);
+    }
+
+    #[test]
+    fn test_preview_leaves_the_agent_rng_alone_while_decide_advances_it() {
+        let world_query = MockWorldQuery {
+            water_sources: Vec::new(),
+            food_sources: Vec::new(),
+        };
+        let dm = UtilityMaximizer::default();
+        let (mut world, agent) = create_test_world_with_agent(30.0, 30.0, 40.0);
+        world.register::<WanderState>();
+        world.register::<AgentRng>();
+        world.write_storage::<WanderState>().insert(agent, WanderState::new(1)).unwrap();
+        world.write_storage::<AgentRng>().insert(agent, AgentRng::new(3, AgentId(1))).unwrap();
+        let next_draw =
+            |world: &World| world.read_storage::<AgentRng>().get(agent).unwrap().clone().next_u64();
+
+        let fresh = next_draw(&world);
+        dm.preview_decision(agent, &world, &world_query);
+        dm.preview_decision(agent, &world, &world_query);
+        assert_eq!(next_draw(&world), fresh);
+
+        // Each new heading is drawn from the stream, moving it on
+        dm.decide(agent, &world, &world_query);
+        let after_one = next_draw(&world);
+        assert_ne!(after_one, fresh);
+        world.insert(CurrentTick(1));
+        dm.decide(agent, &world, &world_query);
+        assert_ne!(next_draw(&world), after_one);
+    }
+
+    // Mock with a single neighbor at a fixed distance
+    struct NeighborWorldQuery {
+        neighbor: AgentId,
+        neighbor_distance: f32,
+    }
+
+    impl WorldQuery for NeighborWorldQuery {
+        fn get_nearby_agents(&self, _agent: AgentId, _max_count: usize) -> Vec<AgentId> {
+            vec![self.neighbor]
+        }
+
+        fn get_nearby_agents_within(
+            &self,
+            _agent: AgentId,
+            _max_count: usize,
+            max_radius: f32,
+        ) -> Vec<AgentId> {
+            if self.neighbor_distance <= max_radius {
+                vec![self.neighbor]
+            } else {
+                Vec::new()
+            }
+        }
+
+        fn get_nearby_resources(
+            &self,
+            _agent: AgentId,
+            _resource_type: &str,
+            _max_radius: f32,
+        ) -> Vec<ResourceLocation> {
+            Vec::new()
+        }
+
+        fn can_interact(&self, _agent1: AgentId, agent2: AgentId) -> bool {
+            agent2 == self.neighbor
+        }
+    }
+
+    fn give_currency(world: &mut World, agent: Entity, currency: f32) {
+        world.register::<Wallet>();
+        world
+            .write_storage::<Wallet>()
+            .insert(agent, Wallet { currency })
+            .unwrap();
+    }
+
+    /// Agent `id` holding `quantity` of `item`
+    fn add_seller(world: &mut World, id: u64, item: &str, quantity: u32) {
+        world.register::<Inventory>();
+        let mut inventory = Inventory::default();
+        inventory.add(item, quantity);
+        world
+            .create_entity()
+            .with(Agent { id: AgentId(id) })
+            .with(Needs::new(0.0, 0.0, 0.0))
+            .with(inventory)
+            .build();
+    }
+
+    #[test]
+    fn test_wander_heading_persists_within_window() {
+        let world_query = MockWorldQuery {
+            water_sources: Vec::new(),
+            food_sources: Vec::new(),
+        };
+        let dm = UtilityMaximizer::default();
+        let (mut world, agent) = create_test_world_with_agent(30.0, 30.0, 40.0);
+        world.register::<WanderState>();
+        world.write_storage::<WanderState>().insert(agent, WanderState::new(5)).unwrap();
+
+        let heading_at = |world: &mut World, tick| {
+            world.insert(CurrentTick(tick));
+            match dm.decide(agent, world, &world_query) {
+                DecisionOutput::Intent(Intent::WanderHeading { heading, persistence }) => {
+                    (heading, persistence)
+                }
+                other => panic!("Expected WanderHeading, got {:?}", other),
+            }
+        };
+
+        let (first, left) = heading_at(&mut world, 10);
+        assert_eq!(left, 5);
+        assert!((0.0..std::f32::consts::TAU).contains(&first));
+        for tick in 11..15 {
+            assert_eq!(heading_at(&mut world, tick), (first, (15 - tick) as u8));
+        }
+        // Preview shows the same heading without advancing it
+        assert_eq!(
+            dm.preview_decision(agent, &world, &world_query),
+            DecisionOutput::Intent(Intent::WanderHeading { heading: first, persistence: 1 })
+        );
+
+        // Window over: a fresh heading with a full window
+        assert_eq!(heading_at(&mut world, 15).1, 5);
+        assert_eq!(world.read_storage::<WanderState>().get(agent).unwrap().chosen_at, 15);
+    }
+
+    #[test]
+    fn test_wander_heading_comes_from_the_agents_stream() {
+        let world_query = MockWorldQuery {
+            water_sources: Vec::new(),
+            food_sources: Vec::new(),
+        };
+        let heading_with = |dm: &UtilityMaximizer, rng: Option<AgentRng>| {
+            let (mut world, agent) = create_test_world_with_agent(30.0, 30.0, 40.0);
+            world.register::<WanderState>();
+            world.register::<AgentRng>();
+            world.write_storage::<WanderState>().insert(agent, WanderState::new(5)).unwrap();
+            if let Some(rng) = rng {
+                world.write_storage::<AgentRng>().insert(agent, rng).unwrap();
+            }
+            world.insert(CurrentTick(10));
+            match dm.decide(agent, &world, &world_query) {
+                DecisionOutput::Intent(Intent::WanderHeading { heading, .. }) => heading,
+                other => panic!("Expected WanderHeading, got {:?}", other),
+            }
+        };
+        let id = AgentId(1);
+
+        // Without an AgentRng the maximizer's seed decides, mixed with the tick
+        let seeded = UtilityMaximizer::default().with_seed(7);
+        let expected = agent_rng(7 ^ 10, id).gen::<f32>() * std::f32::consts::TAU;
+        assert_eq!(heading_with(&seeded, None), expected);
+        assert_eq!(heading_with(&seeded, None), expected);
+
+        // An AgentRng takes precedence
+        let expected = AgentRng::new(99, id).gen::<f32>() * std::f32::consts::TAU;
+        assert_eq!(heading_with(&seeded, Some(AgentRng::new(99, id))), expected);
+    }
+
+    #[test]
+    fn test_saver_invests_surplus_when_content() {
+        let world_query = MockWorldQuery {
+            water_sources: vec![ResourceLocation::new(10.0, 10.0, 14.0)],
+            food_sources: Vec::new(),
+        };
+        let dm = UtilityMaximizer::default();
+
+        // Without an account, surplus currency is ignored
+        let (mut world, agent) = create_test_world_with_agent(30.0, 30.0, 40.0);
+        give_currency(&mut world, agent, 150.0);
+        world.register::<SavingsAccount>();
+        assert_eq!(dm.preview_decision(agent, &world, &world_query), DecisionOutput::Intent(Intent::Wander));
+
+        world
+            .write_storage::<SavingsAccount>()
+            .insert(agent, SavingsAccount::default())
+            .unwrap();
+        assert_eq!(
+            dm.preview_decision(agent, &world, &world_query),
+            DecisionOutput::Intent(Intent::Invest { amount: 50.0 })
+        );
+
+        // Needs come first
+        world.write_storage::<Needs>().get_mut(agent).unwrap().thirst = 85.0;
+        assert!(matches!(
+            dm.preview_decision(agent, &world, &world_query),
+            DecisionOutput::Intent(Intent::SeekItem { .. })
+        ));
+    }
+
+    /// Give `agent` a home at (5, 5)
+    fn settle(world: &mut World, agent: Entity) -> Intent {
+        world.register::<Home>();
+        let home = Home::new(5.0, 5.0);
+        world.write_storage::<Home>().insert(agent, home).unwrap();
+        home.intent()
+    }
+
+    #[test]
+    fn test_content_agent_heads_home_while_thirsty_seeks_water() {
+        let world_query = MockWorldQuery {
+            water_sources: vec![ResourceLocation::new(10.0, 10.0, 14.0)],
+            food_sources: Vec::new(),
+        };
+        let dm = UtilityMaximizer::default();
+
+        let (mut world, content) = create_test_world_with_agent(30.0, 30.0, 40.0);
+        let go_home = settle(&mut world, content);
+        assert_eq!(dm.decide(content, &world, &world_query), DecisionOutput::Intent(go_home));
+
+        let (mut world, thirsty) = create_test_world_with_agent(85.0, 30.0, 40.0);
+        settle(&mut world, thirsty);
+        match dm.decide(thirsty, &world, &world_query) {
+            DecisionOutput::Intent(Intent::SeekItem { item_type, .. }) => assert_eq!(item_type, "water"),
+            other => panic!("Expected SeekItem water, got {:?}", other),
+        }
+
+        // Homeless agents keep wandering
+        let (world, drifter) = create_test_world_with_agent(30.0, 30.0, 40.0);
+        assert_eq!(dm.decide(drifter, &world, &world_query), DecisionOutput::Intent(Intent::Wander));
+    }
+
+    #[test]
+    fn test_night_sends_agents_home_ahead_of_investing() {
+        let world_query = MockWorldQuery {
+            water_sources: vec![ResourceLocation::new(10.0, 10.0, 14.0)],
+            food_sources: Vec::new(),
+        };
+        let dm = UtilityMaximizer::default();
+        let (mut world, saver) = create_test_world_with_agent(30.0, 30.0, 40.0);
+        give_currency(&mut world, saver, 150.0);
+        world.register::<SavingsAccount>();
+        world
+            .write_storage::<SavingsAccount>()
+            .insert(saver, SavingsAccount::default())
+            .unwrap();
+        let go_home = settle(&mut world, saver);
+
+        // By day the spare cash gets invested first
+        world.insert(Night(false));
+        assert_eq!(
+            dm.preview_decision(saver, &world, &world_query),
+            DecisionOutput::Intent(Intent::Invest { amount: 50.0 })
+        );
+
+        world.insert(Night(true));
+        assert_eq!(dm.preview_decision(saver, &world, &world_query), DecisionOutput::Intent(go_home));
+
+        // Survival needs still win at night
+        world.write_storage::<Needs>().get_mut(saver).unwrap().thirst = 85.0;
+        assert!(matches!(
+            dm.preview_decision(saver, &world, &world_query),
+            DecisionOutput::Intent(Intent::SeekItem { .. })
+        ));
+    }
+
+    #[test]
+    fn test_trade_radius_defaults_to_resource_radius() {
+        assert_eq!(UtilityMaximizer::default().trade_search_radius, 1000.0);
+
+        let dm = UtilityMaximizer::new(DecisionThresholds::default(), UtilityWeights::default(), 300.0);
+        assert_eq!(dm.trade_search_radius, 300.0);
+    }
+
+    #[test]
+    fn test_trade_partner_within_radius_is_used() {
+        let (mut world, agent) = create_test_world_with_agent(80.0, 20.0, 10.0);
+        give_currency(&mut world, agent, 50.0);
+
+        let world_query = NeighborWorldQuery {
+            neighbor: AgentId(2),
+            neighbor_distance: 40.0,
+        };
+        add_seller(&mut world, 2, "water", 3);
+
+        let dm = UtilityMaximizer::default().with_seek_trade(true).with_trade_search_radius(50.0);
+        let decision = dm.preview_decision(agent, &world, &world_query);
+
+        assert_eq!(
+            decision,
+            DecisionOutput::Intent(Intent::SeekTrade {
+                buying: true,
+                item_type: "water".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_trade_partner_outside_trade_radius_is_ignored() {
+        let (mut world, agent) = create_test_world_with_agent(80.0, 20.0, 10.0);
+        give_currency(&mut world, agent, 50.0);
+
+        // Within resource_search_radius (1000) but beyond trade_search_radius
+        let world_query = NeighborWorldQuery {
+            neighbor: AgentId(2),
+            neighbor_distance: 51.0,
+        };
+        add_seller(&mut world, 2, "water", 3);
+
+        let dm = UtilityMaximizer::default().with_seek_trade(true).with_trade_search_radius(50.0);
+        let decision = dm.preview_decision(agent, &world, &world_query);
+
+        match decision {
+            DecisionOutput::Intent(Intent::SeekItem { item_type, .. }) => {
+                assert_eq!(item_type, "water");
+            }
+            other => panic!("Expected SeekItem when partner is out of range, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_interaction_matrix_replaces_per_pair_checks() {
+        let (mut world, agent) = create_test_world_with_agent(80.0, 20.0, 10.0);
+        give_currency(&mut world, agent, 50.0);
+        let me = world.read_storage::<Agent>().get(agent).unwrap().id;
+        let world_query = NeighborWorldQuery {
+            neighbor: AgentId(2),
+            neighbor_distance: 40.0,
+        };
+        add_seller(&mut world, 2, "water", 3);
+        let dm = UtilityMaximizer::default().with_seek_trade(true).with_trade_search_radius(50.0);
+        let on_demand = dm.preview_decision(agent, &world, &world_query);
+
+        // A matrix built from the same query decides the same way
+        world.insert(InteractionMatrix::from_query(&world_query, &[me, AgentId(2)]));
+        assert_eq!(dm.preview_decision(agent, &world, &world_query), on_demand);
+
+        // An empty matrix means nobody is in range, whatever the query says
+        world.insert(InteractionMatrix::new());
+        assert!(!matches!(
+            dm.preview_decision(agent, &world, &world_query),
+            DecisionOutput::Intent(Intent::SeekTrade { .. })
+        ));
+    }
+
+    #[test]
+    fn test_no_trade_without_currency() {
+        let (mut world, agent) = create_test_world_with_agent(80.0, 20.0, 10.0);
+        add_seller(&mut world, 2, "water", 3);
+
+        let world_query = NeighborWorldQuery {
+            neighbor: AgentId(2),
+            neighbor_distance: 1.0,
+        };
+
+        let dm = UtilityMaximizer::default().with_seek_trade(true);
+        let decision = dm.preview_decision(agent, &world, &world_query);
+
+        assert!(!matches!(
+            decision,
+            DecisionOutput::Intent(Intent::SeekTrade { .. })
+        ));
+    }
+
+    #[test]
+    fn test_seek_trade_is_opt_in_and_needs_a_stocked_partner() {
+        let world_query = NeighborWorldQuery {
+            neighbor: AgentId(2),
+            neighbor_distance: 1.0,
+        };
+        let trading = UtilityMaximizer::default().with_seek_trade(true);
+        let is_trade = |output: DecisionOutput| matches!(output, DecisionOutput::Intent(Intent::SeekTrade { .. }));
+
+        // A neighbor with nothing to sell is no trade partner
+        let (mut world, agent) = create_test_world_with_agent(80.0, 20.0, 10.0);
+        give_currency(&mut world, agent, 50.0);
+        add_seller(&mut world, 2, "food", 3);
+        assert!(!is_trade(trading.preview_decision(agent, &world, &world_query)));
+
+        let (mut world, agent) = create_test_world_with_agent(80.0, 20.0, 10.0);
+        give_currency(&mut world, agent, 50.0);
+        add_seller(&mut world, 2, "water", 3);
+        assert!(is_trade(trading.preview_decision(agent, &world, &world_query)));
+
+        // By default agents never consider trading
+        let dm = UtilityMaximizer::default();
+        assert!(!is_trade(dm.preview_decision(agent, &world, &world_query)));
+    }
+
+    fn utility_of(output: &[(Intent, f32, String)], intent_type: &str) -> f32 {
+        output
+            .iter()
+            .find(|(intent, _, _)| intent.intent_type() == intent_type)
+            .map(|(_, utility, _)| *utility)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_scarcity_raises_water_utility() {
+        let world_query = MockWorldQuery {
+            water_sources: vec![ResourceLocation::new(100.0, 100.0, 50.0)],
+            food_sources: Vec::new(),
+        };
+        let dm = UtilityMaximizer::default();
+
+        let (world, agent) = create_test_world_with_agent(65.0, 20.0, 10.0);
+        let (_, calm) = dm.rank_intents(agent, &world, &world_query, false);
+
+        let (mut world, agent) = create_test_world_with_agent(65.0, 20.0, 10.0);
+        let mut scarcity = ScarcitySignal::new();
+        scarcity.set("water", 0.8);
+        world.insert(scarcity);
+        let (_, scarce) = dm.rank_intents(agent, &world, &world_query, false);
+
+        assert!(utility_of(&scarce, "SeekItem") > utility_of(&calm, "SeekItem"));
+
+        // Urgency stays clamped to 1.0
+        match &scarce[0].0 {
+            Intent::SeekItem { item_type, urgency } => {
+                assert_eq!(item_type, "water");
+                assert!(*urgency <= 1.0);
+            }
+            other => panic!("Expected SeekItem, got {:?}", other),
+        }
+    }
+
+    /// Reports one water source with fixed stock and contention
+    struct ContestedWorldQuery {
+        stock: u32,
+        contention: u32,
+    }
+
+    impl WorldQuery for ContestedWorldQuery {
+        fn get_nearby_agents(&self, _agent: AgentId, _max_count: usize) -> Vec<AgentId> {
+            Vec::new()
+        }
+
+        fn get_nearby_resources(
+            &self,
+            agent: AgentId,
+            resource_type: &str,
+            max_radius: f32,
+        ) -> Vec<ResourceLocation> {
+            self.get_nearby_resources_detailed(agent, resource_type, max_radius)
+                .into_iter()
+                .map(|detail| detail.location)
+                .collect()
+        }
+
+        fn get_nearby_resources_detailed(
+            &self,
+            _agent: AgentId,
+            resource_type: &str,
+            _max_radius: f32,
+        ) -> Vec<ResourceDetail> {
+            if resource_type != "water" {
+                return Vec::new();
+            }
+            vec![ResourceDetail::new(ResourceLocation::new(10.0, 0.0, 10.0))
+                .with_stock(self.stock)
+                .with_contention(self.contention)]
+        }
+
+        fn can_interact(&self, _agent1: AgentId, _agent2: AgentId) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_contested_low_stock_source_lowers_utility() {
+        let dm = UtilityMaximizer::default();
+        let (world, agent) = create_test_world_with_agent(65.0, 20.0, 10.0);
+
+        let uncontested = ContestedWorldQuery { stock: 1, contention: 0 };
+        let contested = ContestedWorldQuery { stock: 1, contention: 3 };
+
+        let (_, free) = dm.rank_intents(agent, &world, &uncontested, false);
+        let (_, crowded) = dm.rank_intents(agent, &world, &contested, false);
+
+        assert!(utility_of(&crowded, "SeekItem") < utility_of(&free, "SeekItem"));
+    }
+
+    #[test]
+    fn test_contention_ignored_for_plentiful_source() {
+        let dm = UtilityMaximizer::default();
+        let (world, agent) = create_test_world_with_agent(65.0, 20.0, 10.0);
+
+        let uncontested = ContestedWorldQuery { stock: 100, contention: 0 };
+        let contested = ContestedWorldQuery { stock: 100, contention: 3 };
+
+        let (_, free) = dm.rank_intents(agent, &world, &uncontested, false);
+        let (_, crowded) = dm.rank_intents(agent, &world, &contested, false);
+
+        assert_eq!(utility_of(&crowded, "SeekItem"), utility_of(&free, "SeekItem"));
+    }
+
+    #[test]
+    fn test_single_agent_utility_unchanged_by_detailed_query() {
+        let dm = UtilityMaximizer::default();
+        let (world, agent) = create_test_world_with_agent(65.0, 20.0, 10.0);
+
+        // Same source, once via the default detailed query and once with stock
+        let plain = MockWorldQuery {
+            water_sources: vec![ResourceLocation::new(10.0, 0.0, 10.0)],
+            food_sources: Vec::new(),
+        };
+        let detailed = ContestedWorldQuery { stock: 1, contention: 0 };
+
+        let (_, a) = dm.rank_intents(agent, &world, &plain, false);
+        let (_, b) = dm.rank_intents(agent, &world, &detailed, false);
+
+        assert_eq!(utility_of(&a, "SeekItem"), utility_of(&b, "SeekItem"));
+    }
+
+    /// Water only exists beyond any sensible radius; nobody can interact
+    struct OutOfReachWorldQuery;
+
+    impl WorldQuery for OutOfReachWorldQuery {
+        fn get_nearby_agents(&self, _agent: AgentId, _max_count: usize) -> Vec<AgentId> {
+            vec![AgentId(99)]
+        }
+
+        fn get_nearby_resources(
+            &self,
+            _agent: AgentId,
+            resource_type: &str,
+            max_radius: f32,
+        ) -> Vec<ResourceLocation> {
+            let far = ResourceLocation::new(5000.0, 0.0, 5000.0);
+            if resource_type == "water" && far.distance <= max_radius {
+                vec![far]
+            } else {
+                Vec::new()
+            }
+        }
+
+        fn can_interact(&self, _agent1: AgentId, _agent2: AgentId) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_test_mode_places_everything_in_range() {
+        let dm = UtilityMaximizer::default().with_seek_trade(true);
+
+        let (mut world, agent) = create_test_world_with_agent(70.0, 20.0, 10.0);
+        give_currency(&mut world, agent, 50.0);
+        add_seller(&mut world, 99, "water", 3);
+        let (_, normal) = dm.rank_intents(agent, &world, &OutOfReachWorldQuery, false);
+
+        let (mut world, agent) = create_test_world_with_agent(70.0, 20.0, 10.0);
+        give_currency(&mut world, agent, 50.0);
+        add_seller(&mut world, 99, "water", 3);
+        world.insert(crate::world_query::TestMode::enabled());
+        let (_, test_mode) = dm.rank_intents(agent, &world, &OutOfReachWorldQuery, false);
+
+        // Water is found at distance 0, earning the full efficiency bonus
+        assert!(utility_of(&test_mode, "SeekItem") > utility_of(&normal, "SeekItem"));
+        assert!(test_mode
+            .iter()
+            .any(|(intent, _, _)| intent.intent_type() == "SeekTrade"));
+        assert!(!normal
+            .iter()
+            .any(|(intent, _, _)| intent.intent_type() == "SeekTrade"));
+    }
+
+    /// Grass lies just across a wall; food is farther away but on open ground
+    struct WalledWorldQuery {
+        obstacle_aware: bool,
+    }
+
+    impl WorldQuery for WalledWorldQuery {
+        fn get_nearby_agents(&self, _agent: AgentId, _max_count: usize) -> Vec<AgentId> {
+            Vec::new()
+        }
+
+        fn get_nearby_resources(
+            &self,
+            _agent: AgentId,
+            resource_type: &str,
+            _max_radius: f32,
+        ) -> Vec<ResourceLocation> {
+            match resource_type {
+                "grass" => vec![ResourceLocation::new(5.0, 0.0, 5.0)],
+                "food" => vec![ResourceLocation::new(-30.0, 0.0, 30.0)],
+                _ => Vec::new(),
+            }
+        }
+
+        fn travel_cost(&self, _agent: AgentId, target_x: f32, _target_y: f32) -> Option<f32> {
+            // The way around the wall to x > 0 is 90 units long
+            match (self.obstacle_aware, target_x > 0.0) {
+                (false, _) => None,
+                (true, true) => Some(90.0),
+                (true, false) => Some(target_x.abs()),
+            }
+        }
+
+        fn can_interact(&self, _agent1: AgentId, _agent2: AgentId) -> bool {
+            false
+        }
+    }
+
+    fn sought_item(output: &[(Intent, f32, String)]) -> String {
+        match &output[0].0 {
+            Intent::SeekItem { item_type, .. } => item_type.clone(),
+            other => panic!("expected SeekItem, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_travel_cost_prefers_reachable_resource() {
+        let dm = UtilityMaximizer::default();
+        let (world, agent) = create_test_world_with_agent(10.0, 80.0, 10.0);
+
+        let (_, by_line) = dm.rank_intents(agent, &world, &WalledWorldQuery { obstacle_aware: false }, false);
+        assert_eq!(sought_item(&by_line), "grass");
+
+        let (_, by_path) = dm.rank_intents(agent, &world, &WalledWorldQuery { obstacle_aware: true }, false);
+        assert_eq!(sought_item(&by_path), "food");
+    }
+
+    #[test]
+    fn test_scarcity_makes_agent_seek_sooner() {
+        let world_query = MockWorldQuery {
+            water_sources: vec![ResourceLocation::new(100.0, 100.0, 50.0)],
+            food_sources: Vec::new(),
+        };
+        let dm = UtilityMaximizer::default();
+
+        // Thirst 40 is below high_thirst (60) without scarcity
+        let (world, agent) = create_test_world_with_agent(40.0, 20.0, 10.0);
+        assert_eq!(
+            dm.preview_decision(agent, &world, &world_query),
+            DecisionOutput::Intent(Intent::Wander)
+        );
+
+        let (mut world, agent) = create_test_world_with_agent(40.0, 20.0, 10.0);
+        let mut scarcity = ScarcitySignal::new();
+        scarcity.set("water", 1.0);
+        world.insert(scarcity);
+
+        match dm.preview_decision(agent, &world, &world_query) {
+            DecisionOutput::Intent(Intent::SeekItem { item_type, .. }) => {
+                assert_eq!(item_type, "water");
+            }
+            other => panic!("Expected water seeking under scarcity, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_remembered_water_drives_move_to() {
+        let (mut world, agent) = create_test_world_with_agent(80.0, 20.0, 10.0);
+        world.register::<SpatialMemory>();
+        world.insert(CurrentTick(100));
+        crate::remember_resource(&mut world, agent, "water", 40.0, -12.0, 90);
+
+        let world_query = MockWorldQuery {
+            water_sources: Vec::new(),
+            food_sources: Vec::new(),
+        };
+
+        let dm = UtilityMaximizer::default();
+        assert_eq!(
+            dm.preview_decision(agent, &world, &world_query),
+            DecisionOutput::Intent(Intent::MoveTo {
+                x: 40.0,
+                y: -12.0,
+                item_type: "water".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_visible_water_beats_memory() {
+        let (mut world, agent) = create_test_world_with_agent(80.0, 20.0, 10.0);
+        world.register::<SpatialMemory>();
+        crate::remember_resource(&mut world, agent, "water", 40.0, -12.0, 0);
+
+        let world_query = MockWorldQuery {
+            water_sources: vec![ResourceLocation::new(100.0, 100.0, 50.0)],
+            food_sources: Vec::new(),
+        };
+
+        let dm = UtilityMaximizer::default();
+        assert!(matches!(
+            dm.preview_decision(agent, &world, &world_query),
+            DecisionOutput::Intent(Intent::SeekItem { .. })
+        ));
+    }
+
+    #[test]
+    fn test_expired_memory_is_ignored() {
+        let (mut world, agent) = create_test_world_with_agent(80.0, 20.0, 10.0);
+        world.register::<SpatialMemory>();
+        world.insert(CurrentTick(5000));
+        crate::remember_resource(&mut world, agent, "water", 40.0, -12.0, 0);
+
+        let world_query = MockWorldQuery {
+            water_sources: Vec::new(),
+            food_sources: Vec::new(),
+        };
+
+        let dm = UtilityMaximizer::default();
+        assert!(matches!(
+            dm.preview_decision(agent, &world, &world_query),
+            DecisionOutput::Intent(Intent::SeekItem { .. })
+        ));
+    }
+
+    #[test]
+    fn test_remembered_food_drives_move_to() {
+        let (mut world, agent) = create_test_world_with_agent(20.0, 80.0, 10.0);
+        world.register::<SpatialMemory>();
+        crate::remember_resource(&mut world, agent, "food", 7.0, 8.0, 0);
+
+        let world_query = MockWorldQuery {
+            water_sources: Vec::new(),
+            food_sources: Vec::new(),
+        };
+
+        let dm = UtilityMaximizer::default();
+        assert_eq!(
+            dm.preview_decision(agent, &world, &world_query),
+            DecisionOutput::Intent(Intent::MoveTo {
+                x: 7.0,
+                y: 8.0,
+                item_type: "food".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_config_json_round_trip() {
+        let json = r#"{
+            "thresholds": { "critical_thirst": 90.0, "high_thirst": 40.0 },
+            "weights": { "survival": 3.0, "comfort": 0.5, "efficiency": 1.0 },
+            "resource_search_radius": 250.0,
+            "trade_search_radius": 25.0
+        }"#;
+
+        let config: UtilityMaximizerConfig = serde_json::from_str(json).unwrap();
+        let dm = UtilityMaximizer::from_config(config.clone());
+
+        assert_eq!(dm.thresholds.critical_thirst, 90.0);
+        assert_eq!(dm.thresholds.high_thirst, 40.0);
+        // Unspecified thresholds keep their defaults
+        assert_eq!(dm.thresholds.high_hunger, DecisionThresholds::default().high_hunger);
+        assert_eq!(dm.weights.survival, 3.0);
+        assert_eq!(dm.resource_search_radius, 250.0);
+        assert_eq!(dm.trade_search_radius, 25.0);
+
+        let reserialized = serde_json::to_string(&dm.to_config()).unwrap();
+        let round_trip: UtilityMaximizerConfig = serde_json::from_str(&reserialized).unwrap();
+        assert_eq!(round_trip, config);
+    }
+
+    /// Thirsty agent in region 1, with region 2 wetter
+    fn world_in_dry_region(local_scarcity: f32) -> (World, Entity) {
+        let (mut world, agent) = create_test_world_with_agent(80.0, 20.0, 10.0);
+        world.register::<Region>();
+        world.write_storage::<Region>().insert(agent, Region(1)).unwrap();
+        let mut regions = RegionalScarcity::new();
+        regions.set(1, "water", local_scarcity);
+        regions.set(2, "water", 0.1);
+        regions.set(3, "water", 0.5);
+        world.insert(regions);
+        (world, agent)
+    }
+
+    #[test]
+    fn test_water_scarce_region_migrates() {
+        let (world, agent) = world_in_dry_region(0.9);
+        let world_query = MockWorldQuery {
+            water_sources: Vec::new(),
+            food_sources: Vec::new(),
+        };
+
+        assert_eq!(
+            UtilityMaximizer::default().preview_decision(agent, &world, &world_query),
+            DecisionOutput::Intent(Intent::Migrate { toward_region: 2 })
+        );
+    }
+
+    #[test]
+    fn test_no_migration_when_need_can_be_met_locally() {
+        let dm = UtilityMaximizer::default();
+        let dry = MockWorldQuery {
+            water_sources: Vec::new(),
+            food_sources: Vec::new(),
+        };
+
+        // Water visible nearby despite regional scarcity
+        let (world, agent) = world_in_dry_region(0.9);
+        let wet = MockWorldQuery {
+            water_sources: vec![ResourceLocation::new(10.0, 10.0, 14.0)],
+            food_sources: Vec::new(),
+        };
+        assert!(matches!(
+            dm.preview_decision(agent, &world, &wet),
+            DecisionOutput::Intent(Intent::SeekItem { .. })
+        ));
+
+        // Local scarcity below the migration threshold
+        let (world, agent) = world_in_dry_region(0.5);
+        assert!(matches!(
+            dm.preview_decision(agent, &world, &dry),
+            DecisionOutput::Intent(Intent::SeekItem { .. })
+        ));
+
+        // Not thirsty
+        let (mut world, agent) = world_in_dry_region(0.9);
+        world.write_storage::<Needs>().get_mut(agent).unwrap().thirst = 10.0;
+        assert_eq!(
+            dm.preview_decision(agent, &world, &dry),
+            DecisionOutput::Intent(Intent::Wander)
+        );
+    }
+
+    /// Food nearby that can only be harvested with an axe
+    struct GatedWorldQuery;
+
+    impl WorldQuery for GatedWorldQuery {
+        fn get_nearby_agents(&self, _agent: AgentId, _max_count: usize) -> Vec<AgentId> {
+            Vec::new()
+        }
+
+        fn get_nearby_resources(
+            &self,
+            _agent: AgentId,
+            _resource_type: &str,
+            _max_radius: f32,
+        ) -> Vec<ResourceLocation> {
+            Vec::new()
+        }
+
+        fn get_nearby_resources_detailed(
+            &self,
+            _agent: AgentId,
+            resource_type: &str,
+            _max_radius: f32,
+        ) -> Vec<ResourceDetail> {
+            if resource_type != "food" {
+                return Vec::new();
+            }
+            vec![ResourceDetail::new(ResourceLocation::new(10.0, 0.0, 10.0))
+                .with_requirements(Some("axe".to_string()), None)]
+        }
+
+        fn can_interact(&self, _agent1: AgentId, _agent2: AgentId) -> bool {
+            false
+        }
+    }
+
+    fn hungry_agent_holding(items: &[&str]) -> (World, Entity) {
+        let (mut world, agent) = create_test_world_with_agent(10.0, 80.0, 10.0);
+        world.register::<Inventory>();
+        let mut inventory = Inventory::default();
+        for item in items {
+            inventory.add(item, 1);
+        }
+        world.write_storage::<Inventory>().insert(agent, inventory).unwrap();
+        (world, agent)
+    }
+
+    #[test]
+    fn test_agent_without_tool_seeks_tool_not_gated_source() {
+        let dm = UtilityMaximizer::default();
+        let (world, agent) = hungry_agent_holding(&[]);
+
+        assert_eq!(
+            dm.preview_decision(agent, &world, &GatedWorldQuery),
+            DecisionOutput::Intent(Intent::SeekItem {
+                item_type: "axe".to_string(),
+                urgency: 0.8,
+            })
+        );
+    }
+
+    #[test]
+    fn test_agent_with_tool_targets_gated_source() {
+        let dm = UtilityMaximizer::default();
+        let (world, agent) = hungry_agent_holding(&["axe"]);
+
+        let (_, with_axe) = dm.rank_intents(agent, &world, &GatedWorldQuery, false);
+        let (intent, _, reason) = &with_axe[0];
+        assert_eq!(
+            intent,
+            &Intent::SeekItem {
+                item_type: "food".to_string(),
+                urgency: 0.8,
+            }
+        );
+        assert!(reason.contains("distance"));
+    }
+
+    /// Water at a distance the test can move, counting water queries
+    struct MovingWaterQuery {
+        distance: std::cell::Cell<f32>,
+        queries: std::cell::Cell<u32>,
+    }
+
+    impl WorldQuery for MovingWaterQuery {
+        fn get_nearby_agents(&self, _agent: AgentId, _max_count: usize) -> Vec<AgentId> {
+            Vec::new()
+        }
+
+        fn get_nearby_resources(
+            &self,
+            _agent: AgentId,
+            resource_type: &str,
+            _max_radius: f32,
+        ) -> Vec<ResourceLocation> {
+            if resource_type != "water" {
+                return Vec::new();
+            }
+            self.queries.set(self.queries.get() + 1);
+            vec![ResourceLocation::new(0.0, 0.0, self.distance.get())]
+        }
+
+        fn can_interact(&self, _agent1: AgentId, _agent2: AgentId) -> bool {
+            false
+        }
+    }
+
+    /// Reason for the top intent of a thirsty agent at `tick`
+    fn water_reason(
+        dm: &UtilityMaximizer,
+        world: &mut World,
+        agent: Entity,
+        query: &MovingWaterQuery,
+        tick: u64,
+    ) -> String {
+        world.insert(CurrentTick(tick));
+        let (_, utilities) = dm.rank_intents(agent, world, query, true);
+        utilities[0].2.clone()
+    }
+
+    #[test]
+    fn test_commit_margin_keeps_prior_intent_until_clearly_beaten() {
+        let world_query = MockWorldQuery {
+            water_sources: vec![ResourceLocation::new(10.0, 10.0, 20.0)],
+            food_sources: vec![ResourceLocation::new(10.0, 10.0, 20.0)],
+        };
+        let (mut world, agent) = create_test_world_with_agent(70.0, 72.0, 10.0);
+        world.register::<CommittedIntent>();
+        world
+            .write_storage::<CommittedIntent>()
+            .insert(agent, CommittedIntent::default())
+            .unwrap();
+        let sought = |output: DecisionOutput| match output {
+            DecisionOutput::Intent(Intent::SeekItem { item_type, .. }) => item_type,
+            other => panic!("Expected SeekItem, got {:?}", other),
+        };
+        let committed = UtilityMaximizer::default().with_commit_margin(0.1);
+
+        // Food is marginally better, but the agent already set out for water
+        world.write_storage::<CommittedIntent>().get_mut(agent).unwrap().intent =
+            Some(Intent::SeekItem { item_type: "water".into(), urgency: 0.7 });
+        assert_eq!(sought(UtilityMaximizer::default().preview_decision(agent, &world, &world_query)), "food");
+        assert_eq!(sought(committed.preview_decision(agent, &world, &world_query)), "water");
+        assert_eq!(sought(committed.decide(agent, &world, &world_query)), "water");
+
+        // Starving: food now clearly wins and becomes the new commitment
+        world.write_storage::<Needs>().get_mut(agent).unwrap().hunger = 95.0;
+        assert_eq!(sought(committed.decide(agent, &world, &world_query)), "food");
+        assert!(matches!(
+            &world.read_storage::<CommittedIntent>().get(agent).unwrap().intent,
+            Some(Intent::SeekItem { item_type, .. }) if item_type == "food"
+        ));
+    }
+
+    #[test]
+    fn test_resource_cache_reuses_result_within_window() {
+        let (mut world, agent) = create_test_world_with_agent(80.0, 20.0, 10.0);
+        let query = MovingWaterQuery {
+            distance: std::cell::Cell::new(50.0),
+            queries: std::cell::Cell::new(0),
+        };
+        let dm = UtilityMaximizer::default().with_resource_cache(5);
+
+        assert!(water_reason(&dm, &mut world, agent, &query, 10).contains("distance 50"));
+
+        // The water moved, but the cached result is still used
+        query.distance.set(500.0);
+        assert!(water_reason(&dm, &mut world, agent, &query, 14).contains("distance 50"));
+        assert_eq!(query.queries.get(), 1);
+
+        // Window over: re-queried
+        assert!(water_reason(&dm, &mut world, agent, &query, 15).contains("distance 500"));
+        assert_eq!(query.queries.get(), 2);
+
+        // Hosts invalidate agents that moved
+        query.distance.set(5.0);
+        dm.resource_cache.as_ref().unwrap().invalidate(AgentId(1));
+        assert!(water_reason(&dm, &mut world, agent, &query, 16).contains("distance 5)"));
+    }
+
+    #[test]
+    fn test_preview_does_not_fill_resource_cache() {
+        let (mut world, agent) = create_test_world_with_agent(80.0, 20.0, 10.0);
+        world.insert(CurrentTick(10));
+        let query = MovingWaterQuery {
+            distance: std::cell::Cell::new(50.0),
+            queries: std::cell::Cell::new(0),
+        };
+        let dm = UtilityMaximizer::default().with_resource_cache(5);
+        let cache = dm.resource_cache.as_ref().unwrap();
+
+        dm.preview_decision(agent, &world, &query);
+        assert_eq!(cache.len(), 0);
+
+        // A later decision queries afresh instead of reusing the preview's result
+        query.distance.set(500.0);
+        assert!(water_reason(&dm, &mut world, agent, &query, 10).contains("distance 500"));
+        assert_eq!(cache.len(), 1);
+
+        // Previews still read what decisions cached
+        let queries = query.queries.get();
+        dm.preview_decision(agent, &world, &query);
+        assert_eq!(query.queries.get(), queries);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_resource_cache_off_by_default() {
+        let (mut world, agent) = create_test_world_with_agent(80.0, 20.0, 10.0);
+        let query = MovingWaterQuery {
+            distance: std::cell::Cell::new(50.0),
+            queries: std::cell::Cell::new(0),
+        };
+        let dm = UtilityMaximizer::default();
+        assert!(dm.resource_cache.is_none());
+        assert_eq!(dm.to_config().resource_cache_ticks, 0);
+
+        water_reason(&dm, &mut world, agent, &query, 1);
+        query.distance.set(500.0);
+        assert!(water_reason(&dm, &mut world, agent, &query, 1).contains("distance 500"));
+        assert_eq!(query.queries.get(), 2);
+
+        let cached = UtilityMaximizer::from_config(UtilityMaximizerConfig {
+            resource_cache_ticks: 3,
+            ..Default::default()
+        });
+        assert_eq!(cached.resource_cache.as_ref().map(|c| c.max_age()), Some(3));
+    }
 }
@@ -0,0 +1,93 @@
+// Persistent wander headings
+//
+// A bare `Intent::Wander` leaves the host to pick a direction every decision,
+// which makes idle agents jitter in place. Agents carrying a `WanderState`
+// instead wander along a heading that `UtilityMaximizer::decide` keeps for
+// `persistence` ticks before drawing a new one, giving smooth exploration.
+
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+
+/// Heading an idle agent is wandering along
+///
+/// Add it to agents that should wander in straight-ish lines; agents without
+/// it keep getting a plain `Intent::Wander`.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::decision::WanderState;
+///
+/// let mut state = WanderState::new(10);
+/// let (heading, left) = state.advance(100, || 1.5);
+/// assert_eq!((heading, left), (1.5, 10));
+///
+/// // Later ticks in the window keep the heading
+/// assert_eq!(state.advance(105, || 3.0), (1.5, 5));
+/// assert_eq!(state.advance(110, || 3.0), (3.0, 10));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WanderState {
+    /// Ticks a heading is kept before a new one is drawn
+    pub persistence: u8,
+    /// Current heading in radians (0 = +x, counter-clockwise); `None` until
+    /// the first wander
+    pub heading: Option<f32>,
+    /// Tick the current heading was chosen
+    pub chosen_at: u64,
+}
+
+impl Component for WanderState {
+    type Storage = VecStorage<Self>;
+}
+
+impl WanderState {
+    /// Keep each heading for `persistence` ticks
+    pub fn new(persistence: u8) -> Self {
+        Self {
+            persistence,
+            ..Default::default()
+        }
+    }
+
+    /// Heading still valid at `tick`, with the ticks it has left
+    pub fn current(&self, tick: u64) -> Option<(f32, u8)> {
+        let heading = self.heading?;
+        let age = tick.checked_sub(self.chosen_at)?;
+        let left = u64::from(self.persistence).checked_sub(age).filter(|left| *left > 0)?;
+        Some((heading, left as u8))
+    }
+
+    /// Heading to wander along at `tick`, drawing a new one from `pick` once
+    /// the current one has run out
+    ///
+    /// Returns the heading and the ticks it has left, counting `tick`.
+    pub fn advance(&mut self, tick: u64, pick: impl FnOnce() -> f32) -> (f32, u8) {
+        if let Some(current) = self.current(tick) {
+            return current;
+        }
+        let heading = pick();
+        self.heading = Some(heading);
+        self.chosen_at = tick;
+        (heading, self.persistence.max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_heading_expires_after_persistence() {
+        let mut state = WanderState::new(3);
+        assert_eq!(state.current(0), None);
+        assert_eq!(state.advance(0, || 0.5), (0.5, 3));
+        assert_eq!(state.current(2), Some((0.5, 1)));
+        assert_eq!(state.current(3), None);
+
+        // Zero persistence redraws every tick
+        let mut restless = WanderState::new(0);
+        assert_eq!(restless.advance(7, || 1.0), (1.0, 1));
+        assert_eq!(restless.advance(7, || 2.0), (2.0, 1));
+    }
+}
@@ -0,0 +1,166 @@
+//! Simulation event bus
+//!
+//! The [`EventBus`] world resource collects [`SimEvent`]s for the host to
+//! poll. Libreconomy only publishes to a bus the host has inserted, so
+//! worlds without one pay nothing. Unlike the `TransactionLog`, which the
+//! reputation system drains, the bus is owned by the host.
+
+use std::collections::HashSet;
+
+use crate::AgentId;
+use serde::{Deserialize, Serialize};
+
+use super::TransactionEvent;
+
+/// Something that happened in the simulation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SimEvent {
+    /// A trade, lesson or other interaction between two agents
+    Transaction(TransactionEvent),
+    /// `observer`'s view of `target` rose past the trust threshold
+    TrustGained { observer: AgentId, target: AgentId },
+    /// `observer`'s view of `target` fell back below the trust threshold
+    TrustLost { observer: AgentId, target: AgentId },
+    /// A trade involving `agent` was refused because of `agent`
+    TradeRefused { agent: AgentId, reason: RefusalReason },
+}
+
+/// Why a [`SimEvent::TradeRefused`] trade was refused
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RefusalReason {
+    /// The agent's reputation is below the market's floor
+    LowReputation { score: f32, required: f32 },
+}
+
+impl SimEvent {
+    /// Agents this event references
+    pub fn agents(&self) -> impl Iterator<Item = AgentId> {
+        let agents = match self {
+            SimEvent::Transaction(event) => [Some(event.agent1), Some(event.agent2)],
+            SimEvent::TrustGained { observer, target } | SimEvent::TrustLost { observer, target } => {
+                [Some(*observer), Some(*target)]
+            }
+            SimEvent::TradeRefused { agent, .. } => [Some(*agent), None],
+        };
+        agents.into_iter().flatten()
+    }
+
+    /// Whether any referenced agent is in `agents`
+    pub fn involves_any(&self, agents: &HashSet<AgentId>) -> bool {
+        self.agents().any(|agent| agents.contains(&agent))
+    }
+}
+
+/// Queue of [`SimEvent`]s published during the simulation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventBus {
+    events: Vec<SimEvent>,
+}
+
+impl EventBus {
+    /// Create an empty bus
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue an event
+    pub fn publish(&mut self, event: SimEvent) {
+        self.events.push(event);
+    }
+
+    /// Take every queued event, oldest first
+    pub fn poll(&mut self) -> Vec<SimEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Copies of the queued events that reference any of `agents`
+    ///
+    /// Nothing is removed: the returned events, and all others, stay queued
+    /// for [`poll`](Self::poll). An event involving two agents matches if
+    /// either is in the set.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libreconomy::*;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut bus = EventBus::new();
+    /// bus.publish(SimEvent::Transaction(TransactionEvent::successful_trade(
+    ///     AgentId(1), AgentId(2), "water".into(), 5.0, 0,
+    /// )));
+    /// bus.publish(SimEvent::Transaction(TransactionEvent::successful_trade(
+    ///     AgentId(3), AgentId(4), "food".into(), 8.0, 0,
+    /// )));
+    ///
+    /// let player = HashSet::from([AgentId(2)]);
+    /// assert_eq!(bus.poll_for(&player).len(), 1);
+    /// assert_eq!(bus.len(), 2);
+    /// ```
+    pub fn poll_for(&self, agents: &HashSet<AgentId>) -> Vec<SimEvent> {
+        self.events
+            .iter()
+            .filter(|event| event.involves_any(agents))
+            .cloned()
+            .collect()
+    }
+
+    /// Queued events, oldest first
+    pub fn events(&self) -> &[SimEvent] {
+        &self.events
+    }
+
+    /// Number of queued events
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Check if nothing is queued
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn trade(buyer: u64, seller: u64, item: &str) -> SimEvent {
+        SimEvent::Transaction(TransactionEvent::successful_trade(
+            AgentId(buyer),
+            AgentId(seller),
+            item.to_string(),
+            1.0,
+            0,
+        ))
+    }
+
+    #[test]
+    fn test_poll_for_returns_trades_involving_either_party() {
+        let mut bus = EventBus::new();
+        bus.publish(trade(1, 2, "water"));
+        bus.publish(trade(3, 4, "food"));
+        bus.publish(trade(5, 1, "wood"));
+
+        let watched = HashSet::from([AgentId(1)]);
+        assert_eq!(bus.poll_for(&watched), vec![trade(1, 2, "water"), trade(5, 1, "wood")]);
+
+        let watched = HashSet::from([AgentId(4), AgentId(99)]);
+        assert_eq!(bus.poll_for(&watched), vec![trade(3, 4, "food")]);
+
+        assert!(bus.poll_for(&HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_poll_for_leaves_events_pollable() {
+        let mut bus = EventBus::new();
+        bus.publish(trade(1, 2, "water"));
+        bus.publish(trade(3, 4, "food"));
+
+        bus.poll_for(&HashSet::from([AgentId(1)]));
+        assert_eq!(bus.len(), 2);
+
+        assert_eq!(bus.poll(), vec![trade(1, 2, "water"), trade(3, 4, "food")]);
+        assert!(bus.is_empty());
+    }
+}
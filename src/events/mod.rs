@@ -1,11 +1,15 @@
 //! Event types for libreconomy
 //!
 //! This module defines events that occur during simulation, such as transactions,
-//! interactions, and reputation updates.
+//! interactions, and reputation updates, plus the host-facing [`EventBus`].
 
 use crate::AgentId;
 use serde::{Deserialize, Serialize};
 
+mod bus;
+
+pub use bus::{EventBus, RefusalReason, SimEvent};
+
 /// Outcome of an interaction or transaction
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Outcome {
@@ -59,6 +63,9 @@ pub struct TransactionEvent {
     pub outcome: Outcome,
     /// Tick when transaction occurred
     pub tick: u64,
+    /// Category of the item exchanged (if known), for contextual reputation
+    #[serde(default)]
+    pub category: Option<String>,
 }
 
 impl TransactionEvent {
@@ -78,9 +85,16 @@ impl TransactionEvent {
             price,
             outcome,
             tick,
+            category: None,
         }
     }
 
+    /// Stamp the item's category on the event
+    pub fn with_category(mut self, category: Option<&str>) -> Self {
+        self.category = category.map(str::to_string);
+        self
+    }
+
     /// Create a successful trade event
     pub fn successful_trade(
         buyer: AgentId,
@@ -3,9 +3,12 @@
 //! Functions for reading and writing agent components from C/C++
 
 use specs::prelude::*;
-use crate::agent::components::{Needs, Inventory, Wallet};
+use crate::agent::components::{Needs, Inventory, Wallet, CarryCapacity};
+use crate::agent::inspect::inspect_agent;
+use crate::items::ItemRegistry;
+use specs::storage::MaskedStorage;
 use super::WorldHandle;
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 
 /// Get agent needs
@@ -148,8 +151,13 @@ pub unsafe extern "C" fn get_inventory_item(
 /// * `item_id` - Item ID (null-terminated C string)
 /// * `quantity` - Quantity to add
 ///
+/// Agents with a `CarryCapacity` only receive what fits by item weight (from
+/// the world's `ItemRegistry`, or the default one); use
+/// [`try_add_inventory_item`] to learn how much was stored.
+///
 /// # Returns
-/// 1 on success, 0 if entity doesn't exist or doesn't have Inventory component
+/// 1 if everything was added, [`FFI_OUT_OF_SPACE`] if capacity rejected some
+/// or all of it, 0 if entity doesn't exist or doesn't have Inventory component
 ///
 /// # Safety
 /// The world handle and item_id must be valid. item_id must be a null-terminated string.
@@ -160,9 +168,42 @@ pub unsafe extern "C" fn add_inventory_item(
     item_id: *const c_char,
     quantity: u32,
 ) -> i32 {
-    if world.is_null() || item_id.is_null() {
+    let mut added: u32 = 0;
+    try_add_inventory_item(world, entity_id, item_id, quantity, &mut added)
+}
+
+/// Return code for inventory adds partially or fully rejected for space
+pub const FFI_OUT_OF_SPACE: i32 = 2;
+
+/// Add item to inventory, reporting the amount actually stored
+///
+/// # Arguments
+/// * `world` - World handle
+/// * `entity_id` - Entity ID
+/// * `item_id` - Item ID (null-terminated C string)
+/// * `quantity` - Quantity to add
+/// * `out_added` - Output pointer for the quantity actually added
+///
+/// # Returns
+/// 1 if everything was added, [`FFI_OUT_OF_SPACE`] if capacity (or a full
+/// `u32` count) rejected some or all of it, 0 if entity doesn't exist or
+/// doesn't have Inventory component
+///
+/// # Safety
+/// The world handle, item_id and out_added must be valid. item_id must be a
+/// null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn try_add_inventory_item(
+    world: *mut WorldHandle,
+    entity_id: u64,
+    item_id: *const c_char,
+    quantity: u32,
+    out_added: *mut u32,
+) -> i32 {
+    if world.is_null() || item_id.is_null() || out_added.is_null() {
         return 0;
     }
+    *out_added = 0;
 
     let item_str = match CStr::from_ptr(item_id).to_str() {
         Ok(s) => s,
@@ -176,12 +217,39 @@ pub unsafe extern "C" fn add_inventory_item(
         return 0;
     }
 
+    let capacity = if world_ref.has_value::<MaskedStorage<CarryCapacity>>() {
+        world_ref.read_storage::<CarryCapacity>().get(entity).copied()
+    } else {
+        None
+    };
+
+    let registry = world_ref.try_fetch::<ItemRegistry>();
+    let defaults;
+    let registry = match &registry {
+        Some(registry) => &**registry,
+        None => {
+            defaults = ItemRegistry::with_defaults();
+            &defaults
+        }
+    };
     let mut inventory_storage = world_ref.write_storage::<Inventory>();
 
     match inventory_storage.get_mut(entity) {
         Some(inventory) => {
-            inventory.add(item_str, quantity);
-            1
+            let added = match capacity {
+                Some(capacity) => capacity.add_to(inventory, registry, item_str, quantity),
+                None => {
+                    let before = inventory.quantity(item_str);
+                    inventory.add(item_str, quantity);
+                    inventory.quantity(item_str) - before
+                }
+            };
+            *out_added = added;
+            if added == quantity {
+                1
+            } else {
+                FFI_OUT_OF_SPACE
+            }
         }
         None => 0,
     }
@@ -231,6 +299,91 @@ pub unsafe extern "C" fn remove_inventory_item(
     }
 }
 
+/// Consume items from an agent's inventory
+///
+/// Items are looked up in the world's `ItemRegistry` resource, or the default
+/// registry if there is none. Needs thresholds are ignored.
+///
+/// # Arguments
+/// * `world` - World handle
+/// * `entity_id` - Entity ID
+/// * `item_id` - Item ID (null-terminated C string)
+/// * `quantity` - Most units to consume
+/// * `out_need_reduction` - Output pointer for the total drop across all needs
+///
+/// # Returns
+/// Units consumed; 0 if the agent doesn't hold the item, the item isn't
+/// consumable, or the entity doesn't exist or lacks Needs/Inventory
+///
+/// # Safety
+/// The world handle, item_id and out_need_reduction must be valid. item_id
+/// must be a null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn consume_item(
+    world: *mut WorldHandle,
+    entity_id: u64,
+    item_id: *const c_char,
+    quantity: u32,
+    out_need_reduction: *mut f32,
+) -> i32 {
+    if world.is_null() || item_id.is_null() || out_need_reduction.is_null() {
+        return 0;
+    }
+    *out_need_reduction = 0.0;
+
+    let item_str = match CStr::from_ptr(item_id).to_str() {
+        Ok(s) => s,
+        Err(_) => return 0,
+    };
+
+    let world_ref = &*(world as *mut World);
+    let entity = world_ref.entities().entity(entity_id as u32);
+
+    match crate::systems::consumption::consume_held(world_ref, entity, item_str, quantity) {
+        Some(attempt) => {
+            *out_need_reduction = attempt.need_reduction;
+            attempt.consumed as i32
+        }
+        None => 0,
+    }
+}
+
+/// Set or replace an agent's carry capacity
+///
+/// # Arguments
+/// * `world` - World handle
+/// * `entity_id` - Entity ID
+/// * `max_weight` - Maximum total item weight (not a unit count) the agent can carry
+///
+/// # Returns
+/// 1 on success, 0 if entity doesn't exist
+///
+/// # Safety
+/// The world handle must be valid (created by `create_world`).
+#[no_mangle]
+pub unsafe extern "C" fn set_carry_capacity(
+    world: *mut WorldHandle,
+    entity_id: u64,
+    max_weight: u32,
+) -> i32 {
+    if world.is_null() {
+        return 0;
+    }
+
+    let world_ref = &mut *(world as *mut World);
+    let entity = world_ref.entities().entity(entity_id as u32);
+
+    if !world_ref.entities().is_alive(entity) {
+        return 0;
+    }
+
+    let mut capacity_storage = world_ref.write_storage::<CarryCapacity>();
+    match capacity_storage.insert(entity, CarryCapacity::new(max_weight)) {
+        Ok(_) => 1,
+        Err(_) => 0,
+    }
+}
+
 /// Get wallet currency amount
 ///
 /// # Arguments
@@ -353,6 +506,150 @@ pub unsafe extern "C" fn withdraw_wallet(
     }
 }
 
+/// Flattened `AgentReport` for C callers
+///
+/// `has_*` flags are 1 when the component is present, 0 otherwise; the
+/// matching values are zero when absent. `agent_id` is 0 if the entity has no
+/// `Agent` component.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AgentReportC {
+    pub agent_id: u64,
+    pub has_needs: i32,
+    pub thirst: f32,
+    pub hunger: f32,
+    pub tiredness: f32,
+    pub has_wallet: i32,
+    pub currency: f32,
+    /// Total item units held
+    pub inventory_units: u32,
+    /// Total inventory value according to the item registry
+    pub inventory_value: f32,
+    pub skill_count: u32,
+    pub has_employment: i32,
+    pub has_species: i32,
+    pub trusted_partner_count: u32,
+    /// Most trusted partner's agent id (0 if none)
+    pub top_partner_id: u64,
+    pub top_partner_score: f32,
+}
+
+/// Inspect an agent's economic state
+///
+/// Items are valued with the world's `ItemRegistry` resource, or the default
+/// registry if there is none.
+///
+/// # Arguments
+/// * `world` - World handle
+/// * `entity_id` - Entity ID
+/// * `out_report` - Output pointer for the flattened report
+///
+/// # Returns
+/// 1 on success, 0 if the entity doesn't exist
+///
+/// # Safety
+/// The world handle and out_report pointer must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn inspect_agent_report(
+    world: *mut WorldHandle,
+    entity_id: u64,
+    out_report: *mut AgentReportC,
+) -> i32 {
+    if world.is_null() || out_report.is_null() {
+        return 0;
+    }
+
+    let world_ref = &*(world as *const World);
+    let entity = world_ref.entities().entity(entity_id as u32);
+
+    if !world_ref.entities().is_alive(entity) {
+        return 0;
+    }
+
+    let report = match world_ref.try_fetch::<ItemRegistry>() {
+        Some(registry) => inspect_agent(world_ref, entity, &registry),
+        None => inspect_agent(world_ref, entity, &ItemRegistry::with_defaults()),
+    };
+
+    let needs = report.needs.as_ref();
+    let top = report.trusted_partners.first();
+    *out_report = AgentReportC {
+        agent_id: report.id.map_or(0, |id| id.0),
+        has_needs: needs.is_some() as i32,
+        thirst: needs.map_or(0.0, |n| n.thirst),
+        hunger: needs.map_or(0.0, |n| n.hunger),
+        tiredness: needs.map_or(0.0, |n| n.tiredness),
+        has_wallet: report.currency.is_some() as i32,
+        currency: report.currency.unwrap_or(0.0),
+        inventory_units: report
+            .inventory
+            .iter()
+            .fold(0u32, |acc, line| acc.saturating_add(line.quantity)),
+        inventory_value: report.inventory_value,
+        skill_count: report.skills.as_ref().map_or(0, |s| s.skills.len() as u32),
+        has_employment: report.employment.is_some() as i32,
+        has_species: report.species.is_some() as i32,
+        trusted_partner_count: report.trusted_partners.len() as u32,
+        top_partner_id: top.map_or(0, |p| p.id.0),
+        top_partner_score: top.map_or(0.0, |p| p.score),
+    };
+    1
+}
+
+/// Inspect an agent as a JSON string
+///
+/// The same report as [`inspect_agent_report`], with every field, serialized
+/// as JSON (see `AgentReport` for the layout).
+///
+/// # Arguments
+/// * `world` - World handle
+/// * `entity_id` - Entity ID
+///
+/// # Returns
+/// A NUL-terminated UTF-8 string owned by the caller, or null if the world is
+/// null or the entity doesn't exist. The caller must release it with
+/// [`free_libreconomy_string`], not with C `free`.
+///
+/// # Safety
+/// The world handle must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn inspect_agent_json(world: *mut WorldHandle, entity_id: u64) -> *mut c_char {
+    if world.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let world_ref = &*(world as *const World);
+    let entity = world_ref.entities().entity(entity_id as u32);
+
+    // A bare index carries a dead generation once its entity is deleted
+    if !entity.gen().is_alive() || !world_ref.entities().is_alive(entity) {
+        return std::ptr::null_mut();
+    }
+
+    let report = match world_ref.try_fetch::<ItemRegistry>() {
+        Some(registry) => inspect_agent(world_ref, entity, &registry),
+        None => inspect_agent(world_ref, entity, &ItemRegistry::with_defaults()),
+    };
+    serde_json::to_string(&report)
+        .ok()
+        .and_then(|json| CString::new(json).ok())
+        .map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// Release a string returned by libreconomy (e.g. [`inspect_agent_json`])
+///
+/// Null is ignored.
+///
+/// # Safety
+/// `ptr` must be null or a string returned by libreconomy that has not been
+/// freed yet. It must not be used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn free_libreconomy_string(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        drop(CString::from_raw(ptr));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -426,6 +723,94 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_add_inventory_past_capacity() {
+        unsafe {
+            let world = create_world();
+            let agent_id = create_agent_default(world);
+
+            let item_water = CString::new("water").unwrap();
+            let mut added: u32 = 0;
+
+            assert_eq!(set_carry_capacity(world, agent_id, 10), 1);
+
+            // Fits entirely
+            assert_eq!(
+                try_add_inventory_item(world, agent_id, item_water.as_ptr(), 6, &mut added),
+                1
+            );
+            assert_eq!(added, 6);
+
+            // Only 4 of 7 fit; shortfall of 3
+            assert_eq!(
+                try_add_inventory_item(world, agent_id, item_water.as_ptr(), 7, &mut added),
+                FFI_OUT_OF_SPACE
+            );
+            assert_eq!(added, 4);
+            assert_eq!(get_inventory_item(world, agent_id, item_water.as_ptr()), 10);
+
+            // Full: nothing fits
+            assert_eq!(
+                add_inventory_item(world, agent_id, item_water.as_ptr(), 1),
+                FFI_OUT_OF_SPACE
+            );
+            assert_eq!(get_inventory_item(world, agent_id, item_water.as_ptr()), 10);
+
+            crate::destroy_world(world);
+        }
+    }
+
+    #[test]
+    fn test_add_inventory_reports_saturation() {
+        unsafe {
+            let world = create_world();
+            let agent_id = create_agent_default(world);
+            let item_water = CString::new("water").unwrap();
+            let mut added: u32 = 0;
+
+            assert_eq!(
+                try_add_inventory_item(world, agent_id, item_water.as_ptr(), u32::MAX - 2, &mut added),
+                1
+            );
+            assert_eq!(added, u32::MAX - 2);
+
+            // Without a CarryCapacity only the count's overflow limits the add
+            assert_eq!(
+                try_add_inventory_item(world, agent_id, item_water.as_ptr(), 5, &mut added),
+                FFI_OUT_OF_SPACE
+            );
+            assert_eq!(added, 2);
+            assert_eq!(get_inventory_item(world, agent_id, item_water.as_ptr()), u32::MAX);
+
+            crate::destroy_world(world);
+        }
+    }
+
+    #[test]
+    fn test_consume_item() {
+        unsafe {
+            let world = create_world();
+            let agent_id = create_agent_default(world);
+            let item_water = CString::new("water").unwrap();
+            let mut reduction: f32 = -1.0;
+
+            // Nothing held: nothing consumed
+            assert_eq!(consume_item(world, agent_id, item_water.as_ptr(), 1, &mut reduction), 0);
+            assert_eq!(reduction, 0.0);
+
+            assert_eq!(add_inventory_item(world, agent_id, item_water.as_ptr(), 2), 1);
+            assert_eq!(consume_item(world, agent_id, item_water.as_ptr(), 1, &mut reduction), 1);
+            assert_eq!(reduction, 30.0);
+            assert_eq!(get_inventory_item(world, agent_id, item_water.as_ptr()), 1);
+
+            let (mut thirst, mut hunger, mut tiredness) = (0.0f32, 0.0f32, 0.0f32);
+            assert_eq!(get_needs(world, agent_id, &mut thirst, &mut hunger, &mut tiredness), 1);
+            assert_eq!(thirst, 20.0);
+
+            crate::destroy_world(world);
+        }
+    }
+
     #[test]
     fn test_wallet_operations() {
         unsafe {
@@ -459,4 +844,55 @@ mod tests {
             crate::destroy_world(world);
         }
     }
+
+    #[test]
+    fn test_inspect_agent_report() {
+        unsafe {
+            let world = create_world();
+            let agent_id = create_agent_default(world);
+            let item_water = CString::new("water").unwrap();
+            assert_eq!(add_inventory_item(world, agent_id, item_water.as_ptr(), 3), 1);
+
+            let mut report = AgentReportC::default();
+            assert_eq!(inspect_agent_report(world, agent_id, &mut report), 1);
+
+            assert_eq!(report.has_needs, 1);
+            assert_eq!(report.thirst, 50.0);
+            assert_eq!(report.has_wallet, 1);
+            assert_eq!(report.currency, 100.0);
+            assert_eq!(report.inventory_units, 3);
+            assert_eq!(report.has_employment, 0);
+            assert_eq!(report.trusted_partner_count, 0);
+            assert_eq!(report.top_partner_id, 0);
+
+            assert_eq!(inspect_agent_report(world, agent_id, std::ptr::null_mut()), 0);
+            crate::destroy_world(world);
+        }
+    }
+
+    #[test]
+    fn test_inspect_agent_json_round_trip() {
+        unsafe {
+            let world = create_world();
+            let agent_id = create_agent_default(world);
+            let item_water = CString::new("water").unwrap();
+            assert_eq!(add_inventory_item(world, agent_id, item_water.as_ptr(), 3), 1);
+
+            let ptr = inspect_agent_json(world, agent_id);
+            assert!(!ptr.is_null());
+            let json = CStr::from_ptr(ptr).to_str().unwrap().to_string();
+            free_libreconomy_string(ptr);
+
+            let report: crate::AgentReport = serde_json::from_str(&json).unwrap();
+            assert_eq!(report.currency, Some(100.0));
+            assert_eq!(report.inventory[0].item, "water");
+            assert_eq!(report.inventory[0].quantity, 3);
+
+            assert_eq!(crate::ffi_remove_agent(world, agent_id), 1);
+            assert!(inspect_agent_json(world, agent_id).is_null());
+            assert!(inspect_agent_json(std::ptr::null_mut(), agent_id).is_null());
+            free_libreconomy_string(std::ptr::null_mut());
+            crate::destroy_world(world);
+        }
+    }
 }
@@ -9,9 +9,13 @@ pub mod components;
 pub mod uniffi_impl;
 
 use specs::prelude::*;
-use crate::agent::components::{Agent, Needs, Inventory, Wallet};
+use crate::agent::components::{
+    Agent, Needs, Inventory, Wallet, CarryCapacity, NeedDecayRates, ReputationKnowledge,
+};
 use crate::agent::identity::AgentIdAllocator;
+use crate::agent::overflow::OverflowBuffer;
 use crate::agent::creation;
+use crate::systems::{CurrentTick, ReputationUpdateSystem, SimulationBuilder};
 
 // Re-export component FFI functions
 pub use components::*;
@@ -35,7 +39,12 @@ pub extern "C" fn create_world() -> *mut WorldHandle {
     world.register::<Needs>();
     world.register::<Inventory>();
     world.register::<Wallet>();
+    world.register::<CarryCapacity>();
+    world.register::<NeedDecayRates>();
+    world.register::<OverflowBuffer>();
+    world.register::<ReputationKnowledge>();
     world.insert(AgentIdAllocator::new());
+    world.insert(CurrentTick(0));
     
     Box::into_raw(Box::new(world)) as *mut WorldHandle
 }
@@ -51,20 +60,32 @@ pub unsafe extern "C" fn destroy_world(world: *mut WorldHandle) {
     }
 }
 
+/// Create an agent, mapping any creation error to the 0 failure code
+fn create_agent_or_zero(world: &mut World, needs: Needs, wallet: Wallet) -> u64 {
+    match creation::try_create_agent_custom(world, needs, Inventory::default(), wallet) {
+        Ok(entity) => entity.id() as u64,
+        Err(_) => 0,
+    }
+}
+
 /// Create an agent with default components
-/// Returns the entity ID as u64
+/// Returns the entity ID as u64, or 0 on failure
 #[no_mangle]
 pub unsafe extern "C" fn create_agent_default(world: *mut WorldHandle) -> u64 {
     if world.is_null() {
         return 0;
     }
     let world_ref = &mut *(world as *mut World);
-    let entity = creation::create_agent(world_ref);
-    entity.id() as u64
+    let needs = Needs::new(
+        creation::DEFAULT_THIRST,
+        creation::DEFAULT_HUNGER,
+        creation::DEFAULT_TIREDNESS,
+    );
+    create_agent_or_zero(world_ref, needs, Wallet::new(creation::DEFAULT_CURRENCY))
 }
 
 /// Create an agent with custom needs (thirst, hunger) and default inventory/wallet
-/// Returns the entity ID as u64
+/// Returns the entity ID as u64, or 0 on failure
 #[no_mangle]
 pub unsafe extern "C" fn create_agent_with_needs(
     world: *mut WorldHandle,
@@ -75,13 +96,12 @@ pub unsafe extern "C" fn create_agent_with_needs(
         return 0;
     }
     let world_ref = &mut *(world as *mut World);
-    let needs = Needs::new(thirst as f32, hunger as f32, 50.0); // default tiredness
-    let entity = creation::create_agent_with_needs(world_ref, needs);
-    entity.id() as u64
+    let needs = Needs::new(thirst as f32, hunger as f32, creation::DEFAULT_TIREDNESS);
+    create_agent_or_zero(world_ref, needs, Wallet::new(creation::DEFAULT_CURRENCY))
 }
 
 /// Create an agent with custom wallet and default needs/inventory
-/// Returns the entity ID as u64
+/// Returns the entity ID as u64, or 0 on failure
 #[no_mangle]
 pub unsafe extern "C" fn create_agent_with_wallet(
     world: *mut WorldHandle,
@@ -91,13 +111,16 @@ pub unsafe extern "C" fn create_agent_with_wallet(
         return 0;
     }
     let world_ref = &mut *(world as *mut World);
-    let wallet = Wallet::new(currency as f32);
-    let entity = creation::create_agent_with_wallet(world_ref, wallet);
-    entity.id() as u64
+    let needs = Needs::new(
+        creation::DEFAULT_THIRST,
+        creation::DEFAULT_HUNGER,
+        creation::DEFAULT_TIREDNESS,
+    );
+    create_agent_or_zero(world_ref, needs, Wallet::new(currency as f32))
 }
 
 /// Create an agent with fully custom components
-/// Returns the entity ID as u64
+/// Returns the entity ID as u64, or 0 on failure
 #[no_mangle]
 pub unsafe extern "C" fn create_agent_full(
     world: *mut WorldHandle,
@@ -109,15 +132,13 @@ pub unsafe extern "C" fn create_agent_full(
         return 0;
     }
     let world_ref = &mut *(world as *mut World);
-    let needs = Needs::new(thirst as f32, hunger as f32, 50.0); // default tiredness
-    let inventory = Inventory::default();
-    let wallet = Wallet::new(currency as f32);
-    let entity = creation::create_agent_custom(world_ref, needs, inventory, wallet);
-    entity.id() as u64
+    let needs = Needs::new(thirst as f32, hunger as f32, creation::DEFAULT_TIREDNESS);
+    create_agent_or_zero(world_ref, needs, Wallet::new(currency as f32))
 }
 
 /// Remove an agent from the world by entity ID
-/// Returns 1 on success, 0 on failure
+/// Returns 1 on success, 0 for a null world or dead entity, -1 if the entity
+/// is not an agent
 #[no_mangle]
 pub unsafe extern "C" fn remove_agent(world: *mut WorldHandle, entity_id: u64) -> i32 {
     if world.is_null() {
@@ -128,13 +149,12 @@ pub unsafe extern "C" fn remove_agent(world: *mut WorldHandle, entity_id: u64) -
     // Convert u64 back to Entity
     // Note: Entity contains generation info; this is simplified
     let entity = world_ref.entities().entity(entity_id as u32);
-    
-    if !world_ref.entities().is_alive(entity) {
-        return 0;
+
+    match creation::try_remove_agent(world_ref, entity) {
+        Ok(_) => 1,
+        Err(creation::RemoveError::AlreadyDead) => 0,
+        Err(creation::RemoveError::NotAnAgent) => -1,
     }
-    
-    creation::remove_agent(world_ref, entity);
-    1
 }
 
 /// Get the total number of agents in the world
@@ -147,3 +167,114 @@ pub unsafe extern "C" fn get_agent_count(world: *mut WorldHandle) -> u64 {
     let agents = world_ref.read_storage::<Agent>();
     agents.count() as u64
 }
+
+/// Get the current simulation tick
+/// Returns 0 for a null world
+///
+/// # Safety
+/// The world handle must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn get_current_tick(world: *mut WorldHandle) -> u64 {
+    if world.is_null() {
+        return 0;
+    }
+    let world_ref = &*(world as *mut World);
+    world_ref.try_fetch::<CurrentTick>().map_or(0, |tick| tick.0)
+}
+
+/// Set the current simulation tick
+/// Returns 1 on success, 0 on failure
+///
+/// # Safety
+/// The world handle must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn set_current_tick(world: *mut WorldHandle, tick: u64) -> i32 {
+    if world.is_null() {
+        return 0;
+    }
+    let world_ref = &mut *(world as *mut World);
+    world_ref.insert(CurrentTick(tick));
+    1
+}
+
+/// Advance the current simulation tick without running any systems
+/// Returns the new tick, or 0 for a null world
+///
+/// # Safety
+/// The world handle must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn advance_tick(world: *mut WorldHandle, by: u64) -> u64 {
+    if world.is_null() {
+        return 0;
+    }
+    let world_ref = &mut *(world as *mut World);
+    let mut tick = world_ref.entry::<CurrentTick>().or_insert_with(CurrentTick::default);
+    tick.0 = tick.0.saturating_add(by);
+    tick.0
+}
+
+/// Run the built-in systems (need decay, reputation) for a number of ticks
+///
+/// Each step advances `CurrentTick` before dispatching, so systems see the same
+/// tick that `get_current_tick` reports afterwards.
+/// Returns the new tick, or 0 for a null world
+///
+/// # Safety
+/// The world handle must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn simulate_ticks(world: *mut WorldHandle, ticks: u64) -> u64 {
+    if world.is_null() {
+        return 0;
+    }
+    let world_ref = &mut *(world as *mut World);
+    let mut dispatcher = SimulationBuilder::new()
+        .with_need_decay()
+        .with_reputation(ReputationUpdateSystem::default())
+        .build();
+    dispatcher.setup(world_ref);
+
+    for _ in 0..ticks {
+        crate::systems::step(world_ref, &mut dispatcher);
+    }
+    world_ref.read_resource::<CurrentTick>().0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_advance_and_simulate_ticks() {
+        unsafe {
+            let world = create_world();
+            assert_eq!(get_current_tick(world), 0);
+
+            assert_eq!(advance_tick(world, 5), 5);
+            assert_eq!(set_current_tick(world, 10), 1);
+            assert_eq!(get_current_tick(world), 10);
+
+            let agent = create_agent_default(world);
+            assert_eq!(simulate_ticks(world, 3), 13);
+            assert_eq!(get_current_tick(world), 13);
+
+            // Need decay ran once per simulated tick
+            let (mut thirst, mut hunger, mut tiredness) = (0.0f32, 0.0f32, 0.0f32);
+            assert_eq!(get_needs(world, agent, &mut thirst, &mut hunger, &mut tiredness), 1);
+            assert!((thirst - (50.0 + 3.0 * crate::NeedDecaySystem::BASE_RATE)).abs() < 1e-4);
+
+            destroy_world(world);
+        }
+    }
+
+    #[test]
+    fn test_tick_functions_reject_null_world() {
+        unsafe {
+            let null = std::ptr::null_mut();
+            assert_eq!(get_current_tick(null), 0);
+            assert_eq!(set_current_tick(null, 1), 0);
+            assert_eq!(advance_tick(null, 1), 0);
+            assert_eq!(simulate_ticks(null, 1), 0);
+        }
+    }
+}
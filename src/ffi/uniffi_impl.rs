@@ -74,15 +74,11 @@ impl World {
     }
 
     /// Remove an agent from the world
-    /// Returns true on success
+    /// Returns true on success, false if the entity is dead or not an agent
     pub fn remove_agent(&self, entity_id: u64) -> bool {
         let mut world = self.world.lock().unwrap();
         let entity = world.entities().entity(entity_id as u32);
-        if !world.entities().is_alive(entity) {
-            return false;
-        }
-        creation::remove_agent(&mut world, entity);
-        true
+        creation::try_remove_agent(&mut world, entity).is_ok()
     }
 
     /// Get the total number of agents
@@ -6,4 +6,4 @@
 
 pub mod registry;
 
-pub use registry::{ItemRegistry, ItemType, NeedType};
+pub use registry::{ItemRegistry, ItemType, NeedType, DEFAULT_ITEM_VALUE, DEFAULT_ITEM_WEIGHT};
@@ -5,6 +5,20 @@
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
+/// Weight of items that don't specify one (or aren't registered)
+pub const DEFAULT_ITEM_WEIGHT: f32 = 1.0;
+
+/// Value of items that don't specify one (or aren't registered)
+pub const DEFAULT_ITEM_VALUE: f32 = 1.0;
+
+fn default_item_weight() -> f32 {
+    DEFAULT_ITEM_WEIGHT
+}
+
+fn default_item_value() -> f32 {
+    DEFAULT_ITEM_VALUE
+}
+
 /// Types of needs that items can satisfy
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum NeedType {
@@ -27,6 +41,10 @@ pub enum NeedType {
 ///     id: "water".to_string(),
 ///     satisfies,
 ///     consumable: true,
+///     weight: 1.0,
+///     value: 2.0,
+///     category: Some("drink".to_string()),
+///     reference_price: None,
 /// };
 ///
 /// assert_eq!(water.satisfies.get(&NeedType::Thirst), Some(&-30.0));
@@ -38,6 +56,19 @@ pub struct ItemType {
     /// Maps need types to satisfaction values (negative = reduces need)
     pub satisfies: HashMap<NeedType, f32>,
     pub consumable: bool,
+    /// Weight of one unit, counted against `CarryCapacity`
+    #[serde(default = "default_item_weight")]
+    pub weight: f32,
+    /// Nominal value of one unit, used to prioritize what to carry
+    #[serde(default = "default_item_value")]
+    pub value: f32,
+    /// Broad category (e.g. "food", "drink") for category-aware reputation
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Fair market price of one unit, if known; trades are only judged for
+    /// fairness against a set reference
+    #[serde(default)]
+    pub reference_price: Option<f32>,
 }
 
 impl ItemType {
@@ -47,9 +78,37 @@ impl ItemType {
             id,
             satisfies,
             consumable,
+            weight: DEFAULT_ITEM_WEIGHT,
+            value: DEFAULT_ITEM_VALUE,
+            category: None,
+            reference_price: None,
         }
     }
 
+    /// Set the weight of one unit
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Set the nominal value of one unit
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Set the item's category
+    pub fn with_category(mut self, category: &str) -> Self {
+        self.category = Some(category.to_string());
+        self
+    }
+
+    /// Set the fair market price of one unit
+    pub fn with_reference_price(mut self, price: f32) -> Self {
+        self.reference_price = Some(price);
+        self
+    }
+
     /// Get how much this item satisfies a particular need
     /// Returns 0.0 if the item doesn't affect this need
     pub fn satisfaction_for(&self, need: NeedType) -> f32 {
@@ -93,10 +152,11 @@ impl ItemRegistry {
     /// Create a registry with default items pre-loaded
     ///
     /// Default items:
-    /// - `"water"` - Reduces Thirst by 30.0
-    /// - `"food"` - Reduces Hunger by 25.0
-    /// - `"grass"` - Reduces Hunger by 15.0 (for herbivores)
-    /// - `"rabbit_meat"` - Reduces Hunger by 40.0 (for carnivores/omnivores)
+    /// - `"water"` - Reduces Thirst by 30.0 (category `"drink"`)
+    /// - `"food"` - Reduces Hunger by 25.0 (category `"food"`)
+    /// - `"grass"` - Reduces Hunger by 15.0, for herbivores (category `"food"`)
+    /// - `"rabbit_meat"` - Reduces Hunger by 40.0, for carnivores/omnivores
+    ///   (category `"food"`)
     pub fn with_defaults() -> Self {
         let mut registry = Self::new();
         registry.register_defaults();
@@ -108,38 +168,30 @@ impl ItemRegistry {
         // Water - satisfies thirst
         let mut water_satisfies = HashMap::new();
         water_satisfies.insert(NeedType::Thirst, -30.0);
-        self.register(ItemType::new(
-            "water".to_string(),
-            water_satisfies,
-            true,
-        ));
+        self.register(
+            ItemType::new("water".to_string(), water_satisfies, true).with_category("drink"),
+        );
 
         // Food - generic food satisfies hunger moderately
         let mut food_satisfies = HashMap::new();
         food_satisfies.insert(NeedType::Hunger, -25.0);
-        self.register(ItemType::new(
-            "food".to_string(),
-            food_satisfies,
-            true,
-        ));
+        self.register(
+            ItemType::new("food".to_string(), food_satisfies, true).with_category("food"),
+        );
 
         // Grass - low nutrition plant food
         let mut grass_satisfies = HashMap::new();
         grass_satisfies.insert(NeedType::Hunger, -15.0);
-        self.register(ItemType::new(
-            "grass".to_string(),
-            grass_satisfies,
-            true,
-        ));
+        self.register(
+            ItemType::new("grass".to_string(), grass_satisfies, true).with_category("food"),
+        );
 
         // Rabbit meat - high nutrition meat
         let mut rabbit_meat_satisfies = HashMap::new();
         rabbit_meat_satisfies.insert(NeedType::Hunger, -40.0);
-        self.register(ItemType::new(
-            "rabbit_meat".to_string(),
-            rabbit_meat_satisfies,
-            true,
-        ));
+        self.register(
+            ItemType::new("rabbit_meat".to_string(), rabbit_meat_satisfies, true).with_category("food"),
+        );
     }
 
     /// Register a new item type
@@ -163,6 +215,43 @@ impl ItemRegistry {
         self.items.insert(item.id.clone(), item);
     }
 
+    /// Add every item from `other`, replacing items with the same id
+    ///
+    /// An overriding item replaces the existing definition entirely; its
+    /// fields are not merged. Items only in `self` are kept.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libreconomy::{ItemRegistry, ItemType, NeedType};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut pack = ItemRegistry::new();
+    /// let mut satisfies = HashMap::new();
+    /// satisfies.insert(NeedType::Thirst, -50.0);
+    /// pack.register(ItemType::new("water".to_string(), satisfies, true));
+    ///
+    /// let mut registry = ItemRegistry::with_defaults();
+    /// registry.extend(pack);
+    /// assert_eq!(registry.get("water").unwrap().satisfaction_for(NeedType::Thirst), -50.0);
+    /// assert!(registry.contains("food"));
+    /// ```
+    pub fn extend(&mut self, other: ItemRegistry) {
+        self.items.extend(other.items);
+    }
+
+    /// Build a registry by layering packs in order
+    ///
+    /// Equivalent to extending an empty registry with each pack in turn, so
+    /// later packs override earlier ones item by item (e.g. base pack, then
+    /// expansion packs, then user mods).
+    pub fn from_packs(packs: Vec<ItemRegistry>) -> Self {
+        let mut registry = Self::new();
+        for pack in packs {
+            registry.extend(pack);
+        }
+        registry
+    }
+
     /// Get an item type by ID
     pub fn get(&self, item_id: &str) -> Option<&ItemType> {
         self.items.get(item_id)
@@ -173,6 +262,16 @@ impl ItemRegistry {
         self.items.get_mut(item_id)
     }
 
+    /// Weight of one unit of an item, [`DEFAULT_ITEM_WEIGHT`] if unregistered
+    pub fn weight_of(&self, item_id: &str) -> f32 {
+        self.get(item_id).map_or(DEFAULT_ITEM_WEIGHT, |item| item.weight)
+    }
+
+    /// Value of one unit of an item, [`DEFAULT_ITEM_VALUE`] if unregistered
+    pub fn value_of(&self, item_id: &str) -> f32 {
+        self.get(item_id).map_or(DEFAULT_ITEM_VALUE, |item| item.value)
+    }
+
     /// Check if an item is registered
     pub fn contains(&self, item_id: &str) -> bool {
         self.items.contains_key(item_id)
@@ -193,6 +292,25 @@ impl ItemRegistry {
         self.items.len()
     }
 
+    /// Category of an item (None if unregistered or uncategorized)
+    ///
+    /// # Example
+    /// ```rust
+    /// use libreconomy::ItemRegistry;
+    ///
+    /// let registry = ItemRegistry::with_defaults();
+    /// assert_eq!(registry.category("water"), Some("drink"));
+    /// assert_eq!(registry.category("unobtainium"), None);
+    /// ```
+    pub fn category(&self, item_id: &str) -> Option<&str> {
+        self.get(item_id).and_then(|item| item.category.as_deref())
+    }
+
+    /// Fair market price of one unit, `None` if unregistered or never set
+    pub fn reference_price(&self, item_id: &str) -> Option<f32> {
+        self.get(item_id).and_then(|item| item.reference_price)
+    }
+
     /// Find all items that satisfy a particular need
     ///
     /// # Example
@@ -364,4 +482,68 @@ mod tests {
         assert_eq!(soup.satisfaction_for(NeedType::Thirst), -10.0);
         assert_eq!(soup.satisfaction_for(NeedType::Tiredness), 0.0);
     }
+
+    #[test]
+    fn test_default_item_categories() {
+        let registry = ItemRegistry::with_defaults();
+        assert_eq!(registry.category("water"), Some("drink"));
+        assert_eq!(registry.category("food"), Some("food"));
+        assert_eq!(registry.category("grass"), Some("food"));
+        assert_eq!(registry.category("rabbit_meat"), Some("food"));
+        assert_eq!(registry.category("stone"), None);
+
+        // Uncategorized item
+        let mut registry = ItemRegistry::new();
+        registry.register(ItemType::new("rock".to_string(), HashMap::new(), false));
+        assert_eq!(registry.category("rock"), None);
+    }
+
+    fn pack(items: &[(&str, NeedType, f32)]) -> ItemRegistry {
+        let mut registry = ItemRegistry::new();
+        for (id, need, delta) in items {
+            let mut satisfies = HashMap::new();
+            satisfies.insert(*need, *delta);
+            registry.register(ItemType::new(id.to_string(), satisfies, true));
+        }
+        registry
+    }
+
+    #[test]
+    fn test_extend_overrides_and_preserves_items() {
+        let mut registry = ItemRegistry::with_defaults();
+        registry.extend(pack(&[
+            ("food", NeedType::Hunger, -35.0),
+            ("bread", NeedType::Hunger, -20.0),
+        ]));
+
+        assert_eq!(registry.count(), 5);
+        let food = registry.get("food").unwrap();
+        assert_eq!(food.satisfaction_for(NeedType::Hunger), -35.0);
+        // The whole item is replaced, not merged
+        assert_eq!(food.category, None);
+        assert_eq!(registry.get("water").unwrap().satisfaction_for(NeedType::Thirst), -30.0);
+        assert_eq!(registry.category("grass"), Some("food"));
+        assert!(registry.contains("bread"));
+    }
+
+    #[test]
+    fn test_from_packs_later_packs_win() {
+        let registry = ItemRegistry::from_packs(vec![
+            ItemRegistry::with_defaults(),
+            pack(&[("water", NeedType::Thirst, -40.0)]),
+            pack(&[("water", NeedType::Thirst, -45.0), ("tea", NeedType::Thirst, -10.0)]),
+        ]);
+
+        assert_eq!(registry.count(), 5);
+        assert_eq!(registry.get("water").unwrap().satisfaction_for(NeedType::Thirst), -45.0);
+        assert_eq!(registry.get("rabbit_meat").unwrap().satisfaction_for(NeedType::Hunger), -40.0);
+        assert_eq!(ItemRegistry::from_packs(Vec::new()).count(), 0);
+    }
+
+    #[test]
+    fn test_category_defaults_when_missing_from_json() {
+        let json = r#"{ "id": "bread", "satisfies": {}, "consumable": true }"#;
+        let item: ItemType = serde_json::from_str(json).unwrap();
+        assert_eq!(item.category, None);
+    }
 }
@@ -38,6 +38,8 @@
 //! - **Systems**: Logic that operates on components (ReputationUpdateSystem, ReputationDecaySystem)
 //! - **Events**: Transaction events that trigger reputation updates
 //! - **Decision Making**: Utility-based AI with species-aware behavior
+//! - **Snapshots**: Versioned JSON and binary saves of a world's state
+//! - **Scenarios**: Starting worlds described as data and built in one call
 //!
 //! # FFI Support
 //!
@@ -47,25 +49,85 @@
 //!
 //! For detailed FFI documentation, see `docs/api/FFI.md`
 
+#[macro_use]
+mod logging;
+
 pub mod agent;
 pub mod decision;
 pub mod events;
 pub mod ffi;
 pub mod items;
+pub mod market;
+pub mod scenario;
+pub mod snapshot;
 pub mod systems;
 pub mod world_query;
 
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
 #[cfg(feature = "wasm")]
 pub mod wasm;
 
 pub use agent::components::*;
+pub use agent::currency::{Currency, CurrencyAmount, MINOR_UNITS_PER_MAJOR};
 pub use agent::identity::{AgentId, AgentIdAllocator, AgentIdError};
-pub use agent::creation::{create_agent, create_agent_with_needs, create_agent_with_wallet, create_agent_custom, remove_agent};
-pub use decision::{Intent, Action, ActionType, Transaction, DecisionOutput, DecisionMaker, UtilityMaximizer, DecisionThresholds, UtilityWeights};
-pub use events::{Outcome, TransactionEvent, TransactionLog};
+pub use agent::index::AgentIndex;
+pub use agent::memory::{SpatialMemory, RememberedResource, remember_resource};
+pub use agent::profile::{NeedsProfile, create_agent_with_profile};
+pub use agent::loadout::{Loadout, SpeciesLoadout, create_agent_of_species, try_create_agent_of_species};
+pub use agent::wellbeing::{WellbeingWeights, agent_wellbeing};
+pub use agent::productivity::{ProductivityConfig, agent_productivity, productivity, productivity_with};
+pub use agent::triage::{most_in_need, Dormant};
+pub use agent::rng::{agent_rng, AgentRng};
+pub use agent::overflow::{OverflowBuffer, OverflowConfig};
+pub use agent::starvation::{starvation_effectiveness, StarvationConfig, StarvationState, StarvationSystem};
+pub use agent::employment::{employees_of, employer_of, LegacyEmployment};
+pub use agent::inspect::{AgentReport, InventoryLine, TrustedPartner, inspect_agent};
+pub use agent::registration::{ensure_components_registered, register_core_components};
+pub use agent::bulk::{AgentTemplate, create_agents, try_create_agents};
+pub use agent::trajectory::predict_ticks_to_threshold;
+pub use agent::faction::{Faction, FACTION_SPILLOVER, faction_members, faction_reputation, seed_reputation_from_faction, stranger_trust, trust_in};
+pub use agent::household::{
+    Household, HouseholdError, add_member, create_household, draw_from_household, household_of,
+    pay_from_household, remove_member,
+};
+pub use agent::creation::{create_agent, create_agent_with_needs, create_agent_with_wallet, create_agent_custom, try_create_agent_custom, remove_agent, try_remove_agent, CreationError, RemoveError};
+pub use decision::{Intent, Action, ActionType, Transaction, DecisionOutput, DecisionMaker, UtilityMaximizer, UtilityMaximizerConfig, DecisionThresholds, UtilityWeights, ScarcitySignal, RegionalScarcity, ResourceQueryCache, WanderState, CommittedIntent, Home, Night, HOME_ITEM_TYPE, Goal, Goals, GoalDrivenDecisionMaker, Temperament, SocialAction, rank_social_actions};
+pub use events::{Outcome, TransactionEvent, TransactionLog, EventBus, RefusalReason, SimEvent};
 pub use items::{ItemRegistry, ItemType, NeedType};
-pub use systems::{ReputationUpdateSystem, ReputationDecaySystem, ReputationDecayConfig, CurrentTick};
-pub use world_query::{WorldQuery, ResourceLocation};
+pub use market::{BookLevel, BookSnapshot, Market, MarketError, Order, OrderId, Side};
+pub use systems::{ReputationUpdateSystem, ReputationUpdateConfig, ReputationDecaySystem, ReputationDecayConfig, CurrentTick, OutcomeFn, rebuild_reputation};
+pub use systems::{
+    total_currency, total_currency_exact, ConservationGuard, ConservationAction, CurrencyLeak,
+    ConservationSnapshotSystem, ConservationCheckSystem,
+};
+pub use systems::{check_invariants, violating_agent, InvariantViolation};
+pub use systems::{
+    transfer_currency, transfer_exact, deposit_currency, mint_currency, EconomyPolicy, CurrencyJournal,
+    MintRecord, CurrencyError, ReputationJudge, TradeEligibility, TradePolicy,
+};
+pub use systems::{consume_plan, consume_for_need, consume_item, execute_consume, ConsumeAttempt, ConsumptionConfig, ConsumptionSystem};
+pub use systems::{total_resource_stock, resource_source_count, resource_sources, resource_forecast, ResourceForecast};
+pub use systems::{EnvironmentConfig, EnvironmentEffect, EnvironmentEffectSystem};
+pub use systems::{harvest_action, harvest_from_location, harvest_into};
+pub use systems::{KnowledgeDecayConfig, KnowledgeDecaySystem};
+pub use systems::{current_season, ResourceRegenSystem, Season, SeasonConfig};
+pub use systems::{apply_rest, RestConfig, RestSystem, Resting};
+pub use systems::{
+    deposit_savings, withdraw_savings, total_savings, InterestConfig, InterestSystem,
+    SavingsAccount, SavingsBank,
+};
+pub use systems::{ResourceTipSystem, TipConfig};
+pub use systems::{ReputationThresholdSystem, TrustThresholds};
+pub use systems::{simulate_until, step, SimulationBuilder};
+pub use systems::{OptionalReadStorage, OptionalWriteStorage};
+pub use snapshot::{
+    WorldSnapshot, AgentSnapshot, HouseholdSnapshot, SavingsBankSnapshot, SnapshotError, components_equal,
+    world_to_bytes, world_from_bytes, world_to_json, world_from_json, SNAPSHOT_FORMAT_VERSION,
+};
+pub use world_query::{WorldQuery, ResourceLocation, ResourceDetail, EcsPositionQuery, Positioned, TestMode, GridWorldQuery, InteractionMatrix, RelocationHook, relocate_agent};
+pub use scenario::{Scenario, AgentGroup, ResourceSpec, ScenarioConfig, ScenarioError};
 
 // C FFI exports
 pub use ffi::{
@@ -74,10 +136,14 @@ pub use ffi::{
     create_agent_with_wallet as ffi_create_agent_with_wallet,
     create_agent_full, remove_agent as ffi_remove_agent,
     get_agent_count as ffi_get_agent_count,
+    get_current_tick, set_current_tick, advance_tick, simulate_ticks,
     // Component access
     get_needs, set_needs,
-    get_inventory_item, add_inventory_item, remove_inventory_item,
+    get_inventory_item, add_inventory_item, try_add_inventory_item, remove_inventory_item,
+    consume_item as ffi_consume_item,
+    set_carry_capacity, FFI_OUT_OF_SPACE,
     get_wallet, deposit_wallet, withdraw_wallet,
+    inspect_agent_report, AgentReportC, inspect_agent_json, free_libreconomy_string,
 };
 
 #[export_name = "libreconomy_version"]
@@ -0,0 +1,153 @@
+//! Optional logging through the `log` crate facade
+//!
+//! With the `logging` feature, the crate reports what it does through
+//! `log`'s macros and hosts route it with whatever logger they install:
+//! decisions at `trace`, failed transactions at `debug`, id exhaustion and
+//! conservation leaks at `warn`. Without the feature these macros expand to
+//! nothing that survives compilation.
+
+/// Emit at `trace` level when the `logging` feature is enabled
+macro_rules! log_trace {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "logging")]
+        ::log::trace!($($arg)+);
+        #[cfg(not(feature = "logging"))]
+        if false {
+            let _ = format_args!($($arg)+);
+        }
+    }};
+}
+
+/// Emit at `debug` level when the `logging` feature is enabled
+macro_rules! log_debug {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "logging")]
+        ::log::debug!($($arg)+);
+        #[cfg(not(feature = "logging"))]
+        if false {
+            let _ = format_args!($($arg)+);
+        }
+    }};
+}
+
+/// Emit at `warn` level when the `logging` feature is enabled
+macro_rules! log_warn {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "logging")]
+        ::log::warn!($($arg)+);
+        #[cfg(not(feature = "logging"))]
+        if false {
+            let _ = format_args!($($arg)+);
+        }
+    }};
+}
+
+#[cfg(all(test, feature = "logging"))]
+mod tests {
+    use crate::decision::{execute_trade, ActionError, UtilityMaximizer};
+    use crate::world_query::{ResourceLocation, WorldQuery};
+    use crate::{Agent, AgentId, EnergyComponent, Inventory, Needs, SpeciesComponent, Wallet};
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+    use specs::prelude::*;
+    use std::sync::{Mutex, Once};
+    use std::thread::{self, ThreadId};
+
+    /// Records every message with the thread that logged it
+    struct Capture(Mutex<Vec<(ThreadId, Level, String)>>);
+
+    impl Log for Capture {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            let entry = (thread::current().id(), record.level(), record.args().to_string());
+            self.0.lock().unwrap().push(entry);
+        }
+
+        fn flush(&self) {}
+    }
+
+    static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+    static INSTALL: Once = Once::new();
+
+    /// Messages logged so far by the calling test's thread
+    fn captured() -> Vec<(Level, String)> {
+        INSTALL.call_once(|| {
+            log::set_logger(&CAPTURE).unwrap();
+            log::set_max_level(LevelFilter::Trace);
+        });
+        let me = thread::current().id();
+        CAPTURE
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(thread, ..)| *thread == me)
+            .map(|(_, level, message)| (*level, message.clone()))
+            .collect()
+    }
+
+    struct Nearby;
+
+    impl WorldQuery for Nearby {
+        fn get_nearby_agents(&self, _agent: AgentId, _max_count: usize) -> Vec<AgentId> {
+            Vec::new()
+        }
+
+        fn get_nearby_resources(&self, _agent: AgentId, _kind: &str, _radius: f32) -> Vec<ResourceLocation> {
+            Vec::new()
+        }
+
+        fn can_interact(&self, _agent1: AgentId, _agent2: AgentId) -> bool {
+            true
+        }
+    }
+
+    fn agent(world: &mut World, id: u64, currency: f32) -> Entity {
+        world
+            .create_entity()
+            .with(Agent { id: AgentId(id) })
+            .with(Needs::new(10.0, 10.0, 10.0))
+            .with(Inventory::default())
+            .with(Wallet::new(currency))
+            .build()
+    }
+
+    #[test]
+    fn test_failed_trade_logs_at_debug() {
+        captured();
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Needs>();
+        world.register::<Inventory>();
+        world.register::<Wallet>();
+        let buyer = agent(&mut world, 71, 0.0);
+        let seller = agent(&mut world, 72, 0.0);
+        world.write_storage::<Inventory>().get_mut(seller).unwrap().add("water", 1);
+
+        let result = execute_trade(&mut world, buyer, seller, "water", 1, 5.0, &Nearby);
+        assert_eq!(result, Err(ActionError::InsufficientFunds));
+        assert!(captured()
+            .iter()
+            .any(|(level, message)| *level == Level::Debug && message.contains("71")));
+    }
+
+    #[test]
+    fn test_decision_logs_at_trace() {
+        captured();
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Needs>();
+        world.register::<Inventory>();
+        world.register::<Wallet>();
+        world.register::<EnergyComponent>();
+        world.register::<SpeciesComponent>();
+        let entity = agent(&mut world, 81, 0.0);
+
+        UtilityMaximizer::default().decide(entity, &world, &Nearby);
+        assert!(captured()
+            .iter()
+            .any(|(level, message)| *level == Level::Trace && message.contains("81")));
+    }
+}
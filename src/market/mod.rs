@@ -1,2 +1,286 @@
 //! Market systems (trading, prices, etc)
-// TODO: Implement market logic
+//!
+//! [`Market`] is a world resource holding resting limit orders per item.
+//! Orders are posted, partially or fully filled, and cancelled by id; a fill
+//! takes effect immediately, so a [`BookSnapshot`] taken afterwards already
+//! shows the reduced quantity. Matching bids against asks is left to the
+//! host for now.
+
+use crate::AgentId;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Identifier of an order, unique within its [`Market`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct OrderId(pub u64);
+
+/// Which side of the book an order rests on
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Side {
+    /// An offer to buy
+    Bid,
+    /// An offer to sell
+    Ask,
+}
+
+/// A resting limit order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Order {
+    pub id: OrderId,
+    pub agent: AgentId,
+    pub item: String,
+    pub side: Side,
+    /// Limit price per unit
+    pub price: f32,
+    /// Units still unfilled
+    pub quantity: u32,
+}
+
+/// Errors from posting, filling or cancelling orders
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MarketError {
+    /// The price is NaN, infinite or not positive
+    InvalidPrice(f32),
+    /// Orders and fills must be for at least one unit
+    ZeroQuantity,
+    /// No resting order has this id
+    UnknownOrder(OrderId),
+}
+
+impl core::fmt::Display for MarketError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MarketError::InvalidPrice(price) => write!(f, "invalid order price: {}", price),
+            MarketError::ZeroQuantity => write!(f, "order quantity must be at least 1"),
+            MarketError::UnknownOrder(id) => write!(f, "no resting order {}", id.0),
+        }
+    }
+}
+
+impl std::error::Error for MarketError {}
+
+/// Resting orders for every item
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let mut market = Market::new();
+/// let bid = market.post(AgentId(1), "water", Side::Bid, 2.0, 10).unwrap();
+/// market.post(AgentId(2), "water", Side::Ask, 3.0, 4).unwrap();
+/// market.fill(bid, 6).unwrap();
+///
+/// let book = market.book_snapshot("water");
+/// assert_eq!(book.bids[0].quantity, 4);
+/// assert_eq!(book.spread, Some(1.0));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Market {
+    orders: Vec<Order>,
+    next_id: u64,
+}
+
+impl Market {
+    /// An empty market
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Post a limit order and return its id
+    pub fn post(
+        &mut self,
+        agent: AgentId,
+        item: &str,
+        side: Side,
+        price: f32,
+        quantity: u32,
+    ) -> Result<OrderId, MarketError> {
+        if !(price.is_finite() && price > 0.0) {
+            return Err(MarketError::InvalidPrice(price));
+        }
+        if quantity == 0 {
+            return Err(MarketError::ZeroQuantity);
+        }
+        let id = OrderId(self.next_id);
+        self.next_id += 1;
+        self.orders.push(Order {
+            id,
+            agent,
+            item: item.to_string(),
+            side,
+            price,
+            quantity,
+        });
+        Ok(id)
+    }
+
+    /// Fill up to `quantity` units of an order
+    ///
+    /// Returns the units actually filled, capped at what remains. A fully
+    /// filled order leaves the book.
+    pub fn fill(&mut self, id: OrderId, quantity: u32) -> Result<u32, MarketError> {
+        if quantity == 0 {
+            return Err(MarketError::ZeroQuantity);
+        }
+        let pos = self.position(id)?;
+        let order = &mut self.orders[pos];
+        let filled = quantity.min(order.quantity);
+        order.quantity -= filled;
+        if order.quantity == 0 {
+            self.orders.remove(pos);
+        }
+        Ok(filled)
+    }
+
+    /// Withdraw an order, returning what was left of it
+    pub fn cancel(&mut self, id: OrderId) -> Result<Order, MarketError> {
+        let pos = self.position(id)?;
+        Ok(self.orders.remove(pos))
+    }
+
+    /// A resting order by id
+    pub fn order(&self, id: OrderId) -> Option<&Order> {
+        self.orders.iter().find(|o| o.id == id)
+    }
+
+    /// Resting orders for `item`, in the order they were posted
+    pub fn orders_for<'a>(&'a self, item: &'a str) -> impl Iterator<Item = &'a Order> + 'a {
+        self.orders.iter().filter(move |o| o.item == item)
+    }
+
+    /// Aggregated view of the book for `item`
+    ///
+    /// Reflects every fill and cancellation applied so far.
+    pub fn book_snapshot(&self, item: &str) -> BookSnapshot {
+        let mut bids: Vec<&Order> = self.orders_for(item).filter(|o| o.side == Side::Bid).collect();
+        let mut asks: Vec<&Order> = self.orders_for(item).filter(|o| o.side == Side::Ask).collect();
+        bids.sort_by(|a, b| b.price.total_cmp(&a.price));
+        asks.sort_by(|a, b| a.price.total_cmp(&b.price));
+        BookSnapshot::new(item, aggregate(&bids), aggregate(&asks))
+    }
+
+    fn position(&self, id: OrderId) -> Result<usize, MarketError> {
+        self.orders
+            .iter()
+            .position(|o| o.id == id)
+            .ok_or(MarketError::UnknownOrder(id))
+    }
+}
+
+/// Collapse price-sorted orders into one level per price
+fn aggregate(sorted: &[&Order]) -> Vec<BookLevel> {
+    let mut levels: Vec<BookLevel> = Vec::new();
+    for order in sorted {
+        match levels.last_mut() {
+            Some(level) if level.price.total_cmp(&order.price) == Ordering::Equal => {
+                level.quantity = level.quantity.saturating_add(order.quantity);
+                level.orders += 1;
+            }
+            _ => levels.push(BookLevel {
+                price: order.price,
+                quantity: order.quantity,
+                orders: 1,
+            }),
+        }
+    }
+    levels
+}
+
+/// All resting quantity at one price
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookLevel {
+    pub price: f32,
+    /// Unfilled units summed over the level's orders
+    pub quantity: u32,
+    /// Number of orders resting at this price
+    pub orders: usize,
+}
+
+/// Read-only view of one item's book, for dashboards
+///
+/// Bids run from the highest price down, asks from the lowest price up, so
+/// the first level on each side is the best.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookSnapshot {
+    pub item: String,
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+    /// Highest bid price
+    pub best_bid: Option<f32>,
+    /// Lowest ask price
+    pub best_ask: Option<f32>,
+    /// `best_ask - best_bid`, when both sides have orders
+    pub spread: Option<f32>,
+}
+
+impl BookSnapshot {
+    fn new(item: &str, bids: Vec<BookLevel>, asks: Vec<BookLevel>) -> Self {
+        let best_bid = bids.first().map(|l| l.price);
+        let best_ask = asks.first().map(|l| l.price);
+        let spread = best_bid.zip(best_ask).map(|(bid, ask)| ask - bid);
+        Self {
+            item: item.to_string(),
+            bids,
+            asks,
+            best_bid,
+            best_ask,
+            spread,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn level(price: f32, quantity: u32, orders: usize) -> BookLevel {
+        BookLevel { price, quantity, orders }
+    }
+
+    #[test]
+    fn test_book_aggregates_levels_and_best_prices() {
+        let mut market = Market::new();
+        market.post(AgentId(1), "grain", Side::Bid, 4.0, 5).unwrap();
+        market.post(AgentId(2), "grain", Side::Bid, 5.0, 2).unwrap();
+        market.post(AgentId(3), "grain", Side::Bid, 4.0, 3).unwrap();
+        market.post(AgentId(4), "grain", Side::Ask, 7.0, 1).unwrap();
+        market.post(AgentId(5), "grain", Side::Ask, 6.5, 8).unwrap();
+        market.post(AgentId(6), "grain", Side::Ask, 7.0, 4).unwrap();
+        market.post(AgentId(7), "water", Side::Ask, 1.0, 50).unwrap();
+
+        let book = market.book_snapshot("grain");
+        assert_eq!(book.bids, vec![level(5.0, 2, 1), level(4.0, 8, 2)]);
+        assert_eq!(book.asks, vec![level(6.5, 8, 1), level(7.0, 5, 2)]);
+        assert_eq!((book.best_bid, book.best_ask, book.spread), (Some(5.0), Some(6.5), Some(1.5)));
+
+        let empty = market.book_snapshot("salt");
+        assert!(empty.bids.is_empty() && empty.asks.is_empty());
+        assert_eq!((empty.best_bid, empty.spread), (None, None));
+    }
+
+    #[test]
+    fn test_book_reflects_partial_and_full_fills() {
+        let mut market = Market::new();
+        let top = market.post(AgentId(1), "grain", Side::Bid, 5.0, 6).unwrap();
+        let lower = market.post(AgentId(2), "grain", Side::Bid, 4.0, 3).unwrap();
+        market.post(AgentId(3), "grain", Side::Ask, 6.0, 2).unwrap();
+
+        assert_eq!(market.fill(top, 4), Ok(4));
+        assert_eq!(market.book_snapshot("grain").bids[0], level(5.0, 2, 1));
+
+        // Overfilling takes only what is left and clears the level
+        assert_eq!(market.fill(top, 10), Ok(2));
+        let book = market.book_snapshot("grain");
+        assert_eq!(book.bids, vec![level(4.0, 3, 1)]);
+        assert_eq!(book.spread, Some(2.0));
+        assert_eq!(market.fill(top, 1), Err(MarketError::UnknownOrder(top)));
+
+        assert_eq!(market.cancel(lower).unwrap().quantity, 3);
+        assert_eq!(market.book_snapshot("grain").best_bid, None);
+        assert_eq!(
+            market.post(AgentId(1), "grain", Side::Bid, f32::NAN, 1).unwrap_err().to_string(),
+            "invalid order price: NaN"
+        );
+    }
+}
@@ -0,0 +1,347 @@
+//! Declarative world setup
+//!
+//! A [`Scenario`] describes a starting world as data: groups of agents,
+//! resource sources with positions, custom item types and a few world
+//! settings. Hosts load one from JSON (or any serde format) and call
+//! [`Scenario::build`] to get a ready `World` plus a [`GridWorldQuery`]
+//! holding everyone's position, instead of hand-rolling the setup.
+//!
+//! Scenarios are validated before anything is built, so a bad file fails
+//! with a [`ScenarioError`] naming the offending entry.
+
+use crate::agent::bulk::{try_create_agents, AgentTemplate};
+use crate::agent::components::{Agent, Needs, ResourceSource, SpeciesComponent, Wallet};
+use crate::agent::registration::register_core_components;
+use crate::items::{ItemRegistry, ItemType};
+use crate::systems::{ConsumptionConfig, CurrentTick, RestConfig};
+use crate::world_query::GridWorldQuery;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use std::collections::HashSet;
+
+/// Reasons a scenario could not be loaded or built
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScenarioError {
+    /// The scenario text is not valid for this format
+    Parse(String),
+    /// Two custom items share an id
+    DuplicateItem(String),
+    /// A resource source starts with less than zero stock
+    NegativeStock { resource_type: String, stock: i64 },
+    /// A resource source has a negative or non-finite regeneration rate
+    InvalidRegeneration { resource_type: String, rate: f32 },
+    /// Agents could not be created
+    Creation(String),
+}
+
+impl core::fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ScenarioError::Parse(msg) => write!(f, "invalid scenario: {}", msg),
+            ScenarioError::DuplicateItem(id) => {
+                write!(f, "item '{}' is defined more than once", id)
+            }
+            ScenarioError::NegativeStock { resource_type, stock } => write!(
+                f,
+                "resource '{}' has negative stock {}",
+                resource_type, stock
+            ),
+            ScenarioError::InvalidRegeneration { resource_type, rate } => write!(
+                f,
+                "resource '{}' has invalid regeneration rate {}",
+                resource_type, rate
+            ),
+            ScenarioError::Creation(msg) => write!(f, "could not create agents: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+/// A group of identical agents
+///
+/// Unset fields take the `create_agent` defaults. Every agent in the group
+/// starts at `position`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentGroup {
+    /// Number of agents to create
+    pub count: u32,
+    #[serde(default)]
+    pub needs: Option<Needs>,
+    /// Starting currency
+    #[serde(default)]
+    pub wallet: Option<f32>,
+    #[serde(default)]
+    pub species: Option<SpeciesComponent>,
+    #[serde(default)]
+    pub position: (f32, f32),
+}
+
+/// A resource source to place
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceSpec {
+    pub resource_type: String,
+    /// Item harvested from the source; defaults to `resource_type`
+    #[serde(default)]
+    pub item_produced: Option<String>,
+    /// Starting stock; signed so negative values can be reported clearly
+    pub stock: i64,
+    #[serde(default)]
+    pub regeneration_rate: f32,
+    #[serde(default)]
+    pub position: (f32, f32),
+}
+
+/// World-wide settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScenarioConfig {
+    /// Maximum distance for `can_interact`
+    pub interaction_distance: f32,
+    /// Tick the world starts at
+    pub start_tick: u64,
+    /// Inserted as a resource if set
+    pub consumption: Option<ConsumptionConfig>,
+    /// Inserted as a resource if set
+    pub rest: Option<RestConfig>,
+}
+
+impl Default for ScenarioConfig {
+    fn default() -> Self {
+        Self {
+            interaction_distance: 10.0,
+            start_tick: 0,
+            consumption: None,
+            rest: None,
+        }
+    }
+}
+
+/// Description of a starting world
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let scenario = Scenario::from_json(r#"{
+///     "agents": [{ "count": 3, "wallet": 50.0 }],
+///     "resources": [{ "resource_type": "water", "stock": 20, "position": [5.0, 0.0] }]
+/// }"#).unwrap();
+///
+/// let (world, grid) = scenario.build().unwrap();
+/// assert_eq!(world.read_storage::<Agent>().count(), 3);
+/// assert_eq!(grid.get_nearby_resources(AgentId(1), "water", 10.0).len(), 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scenario {
+    pub agents: Vec<AgentGroup>,
+    pub resources: Vec<ResourceSpec>,
+    /// Added to the default item registry, replacing defaults with the same id
+    pub items: Vec<ItemType>,
+    pub config: ScenarioConfig,
+}
+
+impl Scenario {
+    /// Parse and validate a JSON scenario
+    ///
+    /// # Errors
+    /// [`ScenarioError::Parse`] for malformed JSON, otherwise any error from
+    /// [`validate`](Self::validate)
+    pub fn from_json(json: &str) -> Result<Self, ScenarioError> {
+        let scenario: Scenario =
+            serde_json::from_str(json).map_err(|err| ScenarioError::Parse(err.to_string()))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Check the scenario for mistakes serde can't catch
+    ///
+    /// # Errors
+    /// The first problem found, checking items, then resources, in order
+    pub fn validate(&self) -> Result<(), ScenarioError> {
+        let mut seen = HashSet::new();
+        for item in &self.items {
+            if !seen.insert(item.id.as_str()) {
+                return Err(ScenarioError::DuplicateItem(item.id.clone()));
+            }
+        }
+
+        for resource in &self.resources {
+            if resource.stock < 0 {
+                return Err(ScenarioError::NegativeStock {
+                    resource_type: resource.resource_type.clone(),
+                    stock: resource.stock,
+                });
+            }
+            let rate = resource.regeneration_rate;
+            if !rate.is_finite() || rate < 0.0 {
+                return Err(ScenarioError::InvalidRegeneration {
+                    resource_type: resource.resource_type.clone(),
+                    rate,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the world and a grid holding every agent and resource position
+    ///
+    /// The world has the core agent components, `SpeciesComponent` and
+    /// `ResourceSource` registered, an `ItemRegistry` with the scenario's
+    /// items, and `CurrentTick` set to `config.start_tick`. Agents get ids in
+    /// group order.
+    ///
+    /// # Errors
+    /// Any error from [`validate`](Self::validate)
+    pub fn build(&self) -> Result<(World, GridWorldQuery), ScenarioError> {
+        self.validate()?;
+
+        let mut world = World::new();
+        register_core_components(&mut world);
+        world.register::<SpeciesComponent>();
+        world.register::<ResourceSource>();
+        world.insert(CurrentTick(self.config.start_tick));
+
+        let mut registry = ItemRegistry::with_defaults();
+        for item in &self.items {
+            registry.register(item.clone());
+        }
+        world.insert(registry);
+        if let Some(consumption) = &self.config.consumption {
+            world.insert(consumption.clone());
+        }
+        if let Some(rest) = &self.config.rest {
+            world.insert(rest.clone());
+        }
+
+        let mut grid = GridWorldQuery::new(self.config.interaction_distance);
+        for group in &self.agents {
+            let mut template = AgentTemplate::default();
+            if let Some(needs) = &group.needs {
+                template = template.with_needs(needs.clone());
+            }
+            if let Some(currency) = group.wallet {
+                template = template.with_wallet(Wallet::new(currency));
+            }
+            if let Some(species) = &group.species {
+                template = template.with_species(species.clone());
+            }
+
+            let entities = try_create_agents(&mut world, group.count as usize, &template)
+                .map_err(|err| ScenarioError::Creation(err.to_string()))?;
+            let agents = world.read_storage::<Agent>();
+            for entity in entities {
+                if let Some(agent) = agents.get(entity) {
+                    grid.set_agent_position(agent.id, group.position.0, group.position.1);
+                }
+            }
+        }
+
+        for resource in &self.resources {
+            let item = resource
+                .item_produced
+                .clone()
+                .unwrap_or_else(|| resource.resource_type.clone());
+            let stock = u32::try_from(resource.stock).unwrap_or(u32::MAX);
+            grid.add_resource(&resource.resource_type, &item, resource.position.0, resource.position.1);
+            world
+                .create_entity()
+                .with(ResourceSource::new(
+                    resource.resource_type.clone(),
+                    item,
+                    resource.regeneration_rate,
+                    stock,
+                ))
+                .build();
+        }
+
+        world.maintain();
+        Ok((world, grid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::AgentId;
+    use crate::world_query::WorldQuery;
+    use crate::items::NeedType;
+    use pretty_assertions::assert_eq;
+
+    const SMALL: &str = r#"{
+        "agents": [
+            { "count": 4, "needs": { "thirst": 70.0, "hunger": 20.0, "tiredness": 10.0 } },
+            { "count": 2, "wallet": 5.0, "position": [20.0, 0.0] }
+        ],
+        "resources": [
+            { "resource_type": "water", "stock": 50, "regeneration_rate": 1.0, "position": [3.0, 4.0] },
+            { "resource_type": "plant", "item_produced": "berry", "stock": 10 }
+        ],
+        "items": [
+            { "id": "berry", "satisfies": { "Hunger": -10.0 }, "consumable": true }
+        ],
+        "config": { "interaction_distance": 5.0, "start_tick": 100 }
+    }"#;
+
+    #[test]
+    fn test_small_scenario_builds_expected_world() {
+        let (world, grid) = Scenario::from_json(SMALL).unwrap().build().unwrap();
+
+        assert_eq!(world.read_storage::<Agent>().count(), 6);
+        assert_eq!(world.read_storage::<ResourceSource>().count(), 2);
+        assert_eq!((grid.agent_count(), grid.resource_count()), (6, 2));
+        assert_eq!(world.read_resource::<CurrentTick>().0, 100);
+
+        let needs = world.read_storage::<Needs>();
+        assert_eq!(needs.join().filter(|n| n.thirst == 70.0).count(), 4);
+        let wallets = world.read_storage::<Wallet>();
+        assert_eq!(wallets.join().filter(|w| w.currency == 5.0).count(), 2);
+
+        let registry = world.read_resource::<ItemRegistry>();
+        assert_eq!(registry.get("berry").unwrap().satisfaction_for(NeedType::Hunger), -10.0);
+        assert!(registry.contains("water"));
+
+        // First group at the origin, second group out of reach
+        assert_eq!(grid.get_nearby_resources(AgentId(1), "water", 10.0)[0].distance, 5.0);
+        assert_eq!(grid.get_nearby_resources(AgentId(1), "berry", 10.0).len(), 1);
+        assert!(grid.can_interact(AgentId(1), AgentId(4)));
+        assert!(!grid.can_interact(AgentId(1), AgentId(5)));
+    }
+
+    #[test]
+    fn test_invalid_scenarios_report_the_problem() {
+        let duplicate = r#"{ "items": [
+            { "id": "berry", "satisfies": {}, "consumable": true },
+            { "id": "berry", "satisfies": {}, "consumable": false }
+        ] }"#;
+        assert_eq!(
+            Scenario::from_json(duplicate),
+            Err(ScenarioError::DuplicateItem("berry".to_string()))
+        );
+
+        let negative = r#"{ "resources": [{ "resource_type": "water", "stock": -5 }] }"#;
+        let err = Scenario::from_json(negative).unwrap_err();
+        assert_eq!(
+            err,
+            ScenarioError::NegativeStock { resource_type: "water".to_string(), stock: -5 }
+        );
+        assert_eq!(err.to_string(), "resource 'water' has negative stock -5");
+
+        assert!(matches!(Scenario::from_json("{ \"agents\": 3 }"), Err(ScenarioError::Parse(_))));
+
+        // Scenarios built in code are validated too
+        let scenario = Scenario {
+            resources: vec![ResourceSpec {
+                resource_type: "ore".to_string(),
+                item_produced: None,
+                stock: 1,
+                regeneration_rate: -1.0,
+                position: (0.0, 0.0),
+            }],
+            ..Default::default()
+        };
+        assert!(matches!(scenario.build(), Err(ScenarioError::InvalidRegeneration { .. })));
+    }
+}
@@ -0,0 +1,729 @@
+//! World snapshots for saving and loading
+//!
+//! A [`WorldSnapshot`] captures the state libreconomy owns: every agent's
+//! components, households with their shared wallets, resource sources,
+//! wallets held outside agents (such as the savings bank's), the current
+//! tick and the id allocator.
+//! Host components (positions, sprites) are not included; hosts save those
+//! themselves, keyed by `AgentId`.
+//!
+//! Two encodings of the same snapshot are provided:
+//! - JSON ([`world_to_json`] / [`world_from_json`]) for readable saves
+//! - a compact binary form ([`world_to_bytes`] / [`world_from_bytes`]) for
+//!   frequent autosaves of large worlds
+//!
+//! Both are deterministic: agents are ordered by id and maps by key, so the
+//! same world always encodes to the same output. The binary form starts with
+//! a magic tag and [`SNAPSHOT_FORMAT_VERSION`], so data written by an
+//! incompatible version is rejected instead of misread.
+
+use crate::agent::components::{
+    Agent, CarryCapacity, EnergyComponent, Employment, Inventory, Knowledge, NeedDecayRates,
+    Needs, Preferences, Region, ReputationKnowledge, ResourceSource, Skills, SpeciesComponent,
+    Wallet,
+};
+use crate::agent::household::Household;
+use crate::agent::identity::{AgentId, AgentIdAllocator};
+use crate::agent::memory::SpatialMemory;
+use crate::agent::overflow::OverflowBuffer;
+use crate::systems::{CurrentTick, Resting, SavingsAccount, SavingsBank};
+use serde::{Deserialize, Serialize, Serializer};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+use std::collections::{BTreeMap, HashMap};
+
+/// Version of the snapshot format; bumped on incompatible changes
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 9;
+
+/// Tag at the start of every binary snapshot
+const MAGIC: &[u8; 4] = b"LECO";
+
+/// Serialize a `HashMap` in key order, for deterministic output
+pub(crate) fn sorted_map<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Ord + Serialize,
+    V: Serialize,
+    S: Serializer,
+{
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+/// Reasons a snapshot could not be loaded
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotError {
+    /// The data does not start with the snapshot tag
+    NotASnapshot,
+    /// Written by an incompatible format version
+    UnsupportedVersion(u32),
+    /// The data is corrupt or truncated
+    Decode(String),
+}
+
+impl core::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SnapshotError::NotASnapshot => write!(f, "data is not a libreconomy snapshot"),
+            SnapshotError::UnsupportedVersion(v) => write!(
+                f,
+                "snapshot format version {} is not supported (expected {})",
+                v, SNAPSHOT_FORMAT_VERSION
+            ),
+            SnapshotError::Decode(msg) => write!(f, "invalid snapshot: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// One agent's components
+///
+/// Every component is optional, so agents built with only some of them
+/// restore exactly as they were.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentSnapshot {
+    pub id: AgentId,
+    pub needs: Option<Needs>,
+    pub inventory: Option<Inventory>,
+    pub wallet: Option<Wallet>,
+    pub energy: Option<EnergyComponent>,
+    pub carry_capacity: Option<CarryCapacity>,
+    pub skills: Option<Skills>,
+    pub knowledge: Option<Knowledge>,
+    pub employment: Option<Employment>,
+    pub preferences: Option<Preferences>,
+    pub species: Option<SpeciesComponent>,
+    pub region: Option<Region>,
+    pub reputation: Option<ReputationKnowledge>,
+    pub need_decay_rates: Option<NeedDecayRates>,
+    pub overflow_buffer: Option<OverflowBuffer>,
+    pub memory: Option<SpatialMemory>,
+    pub savings: Option<SavingsAccount>,
+    pub resting: bool,
+}
+
+/// A household entity: its membership, shared wallet and stockpile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HouseholdSnapshot {
+    pub household: Household,
+    pub wallet: Option<Wallet>,
+    pub inventory: Option<Inventory>,
+}
+
+/// Entity the [`SavingsBank`] resource names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SavingsBankSnapshot {
+    /// The bank is an agent
+    Agent(AgentId),
+    /// The bank is the household at `WorldSnapshot::households[index]`
+    Household(usize),
+    /// The bank is the entity holding `WorldSnapshot::wallets[index]`
+    Wallet(usize),
+}
+
+/// Everything libreconomy stores in a world
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// register_core_components(&mut world);
+/// create_agents(&mut world, 3, &AgentTemplate::default());
+///
+/// let snapshot = WorldSnapshot::capture(&world);
+/// let restored = snapshot.restore();
+/// assert_eq!(WorldSnapshot::capture(&restored), snapshot);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    /// Format version the snapshot was written with
+    pub version: u32,
+    /// `CurrentTick` (0 if absent)
+    pub tick: u64,
+    /// Next id the `AgentIdAllocator` hands out
+    pub next_agent_id: AgentId,
+    /// Agents, ordered by id
+    pub agents: Vec<AgentSnapshot>,
+    /// Households, in entity order
+    pub households: Vec<HouseholdSnapshot>,
+    /// Resource sources on non-agent entities, in entity order
+    pub resource_sources: Vec<ResourceSource>,
+    /// Wallets on entities that are neither agents nor households, in entity order
+    pub wallets: Vec<Wallet>,
+    /// Where the `SavingsBank` resource points, if it is present
+    pub savings_bank: Option<SavingsBankSnapshot>,
+}
+
+/// Storage of `T`, if registered
+fn storage<T: Component>(world: &World) -> Option<ReadStorage<'_, T>> {
+    world
+        .has_value::<MaskedStorage<T>>()
+        .then(|| world.read_storage::<T>())
+}
+
+fn component<T: Component + Clone>(storage: &Option<ReadStorage<'_, T>>, entity: Entity) -> Option<T> {
+    storage.as_ref().and_then(|s| s.get(entity)).cloned()
+}
+
+fn insert<T: Component>(world: &World, entity: Entity, value: Option<T>) {
+    if let Some(value) = value {
+        // The entity was just created, so insertion cannot fail
+        let _ = world.write_storage::<T>().insert(entity, value);
+    }
+}
+
+/// Read access to every per-agent component a snapshot holds
+struct AgentStorages<'a> {
+    needs: Option<ReadStorage<'a, Needs>>,
+    inventory: Option<ReadStorage<'a, Inventory>>,
+    wallet: Option<ReadStorage<'a, Wallet>>,
+    energy: Option<ReadStorage<'a, EnergyComponent>>,
+    carry_capacity: Option<ReadStorage<'a, CarryCapacity>>,
+    skills: Option<ReadStorage<'a, Skills>>,
+    knowledge: Option<ReadStorage<'a, Knowledge>>,
+    employment: Option<ReadStorage<'a, Employment>>,
+    preferences: Option<ReadStorage<'a, Preferences>>,
+    species: Option<ReadStorage<'a, SpeciesComponent>>,
+    region: Option<ReadStorage<'a, Region>>,
+    reputation: Option<ReadStorage<'a, ReputationKnowledge>>,
+    need_decay_rates: Option<ReadStorage<'a, NeedDecayRates>>,
+    overflow_buffer: Option<ReadStorage<'a, OverflowBuffer>>,
+    memory: Option<ReadStorage<'a, SpatialMemory>>,
+    savings: Option<ReadStorage<'a, SavingsAccount>>,
+    resting: Option<ReadStorage<'a, Resting>>,
+}
+
+impl<'a> AgentStorages<'a> {
+    fn fetch(world: &'a World) -> Self {
+        Self {
+            needs: storage(world),
+            inventory: storage(world),
+            wallet: storage(world),
+            energy: storage(world),
+            carry_capacity: storage(world),
+            skills: storage(world),
+            knowledge: storage(world),
+            employment: storage(world),
+            preferences: storage(world),
+            species: storage(world),
+            region: storage(world),
+            reputation: storage(world),
+            need_decay_rates: storage(world),
+            overflow_buffer: storage(world),
+            memory: storage(world),
+            savings: storage(world),
+            resting: storage(world),
+        }
+    }
+
+    fn snapshot(&self, id: AgentId, entity: Entity) -> AgentSnapshot {
+        AgentSnapshot {
+            id,
+            needs: component(&self.needs, entity),
+            inventory: component(&self.inventory, entity),
+            wallet: component(&self.wallet, entity),
+            energy: component(&self.energy, entity),
+            carry_capacity: component(&self.carry_capacity, entity),
+            skills: component(&self.skills, entity),
+            knowledge: component(&self.knowledge, entity),
+            employment: component(&self.employment, entity),
+            preferences: component(&self.preferences, entity),
+            species: component(&self.species, entity),
+            region: component(&self.region, entity),
+            reputation: component(&self.reputation, entity),
+            need_decay_rates: component(&self.need_decay_rates, entity),
+            overflow_buffer: component(&self.overflow_buffer, entity),
+            memory: component(&self.memory, entity),
+            savings: component(&self.savings, entity),
+            resting: component(&self.resting, entity).is_some(),
+        }
+    }
+}
+
+/// Whether two entities carry the same libreconomy components, ignoring ids
+///
+/// Compares every component a snapshot holds (`Needs`, `Inventory`,
+/// `Wallet` and the optional ones) but not the `Agent` id, so procedurally
+/// generated duplicates can be found. Inventories compare by contents, not
+/// insertion order. A missing component only equals another missing one:
+/// an agent without an `Inventory` differs from one with an empty
+/// `Inventory`, since systems treat the two differently.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// register_core_components(&mut world);
+/// let a = create_agent(&mut world);
+/// let b = create_agent(&mut world);
+/// assert!(components_equal(&world, a, b));
+///
+/// world.write_storage::<Wallet>().get_mut(b).unwrap().currency += 1.0;
+/// assert!(!components_equal(&world, a, b));
+/// ```
+pub fn components_equal(world: &World, a: Entity, b: Entity) -> bool {
+    let storages = AgentStorages::fetch(world);
+    // Same placeholder id on both sides, so only the components are compared
+    storages.snapshot(AgentId(0), a) == storages.snapshot(AgentId(0), b)
+}
+
+impl WorldSnapshot {
+    /// Capture the world's libreconomy state
+    ///
+    /// Unregistered components are treated as absent.
+    pub fn capture(world: &World) -> Self {
+        let entities = world.entities();
+        let storages = AgentStorages::fetch(world);
+
+        let mut agents = Vec::new();
+        if let Some(agent_storage) = storage::<Agent>(world) {
+            for (entity, agent) in (&entities, &agent_storage).join() {
+                agents.push(storages.snapshot(agent.id, entity));
+            }
+        }
+        agents.sort_by_key(|agent| agent.id.0);
+
+        let agent_storage = storage::<Agent>(world);
+        let resource_sources = storage::<ResourceSource>(world)
+            .map(|sources| {
+                (&entities, &sources)
+                    .join()
+                    .filter(|(entity, _)| component(&agent_storage, *entity).is_none())
+                    .map(|(_, source)| source.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let wallet_storage = storage::<Wallet>(world);
+        let inventory_storage = storage::<Inventory>(world);
+        let household_storage = storage::<Household>(world);
+        let (household_entities, households): (Vec<Entity>, Vec<HouseholdSnapshot>) = household_storage
+            .as_ref()
+            .map(|households| {
+                (&entities, households)
+                    .join()
+                    .map(|(entity, household)| {
+                        let snapshot = HouseholdSnapshot {
+                            household: household.clone(),
+                            wallet: component(&wallet_storage, entity),
+                            inventory: component(&inventory_storage, entity),
+                        };
+                        (entity, snapshot)
+                    })
+                    .unzip()
+            })
+            .unwrap_or_default();
+
+        let (wallet_entities, wallets): (Vec<Entity>, Vec<Wallet>) = wallet_storage
+            .as_ref()
+            .map(|wallets| {
+                (&entities, wallets)
+                    .join()
+                    .filter(|(entity, _)| component(&agent_storage, *entity).is_none())
+                    .filter(|(entity, _)| component(&household_storage, *entity).is_none())
+                    .map(|(entity, wallet)| (entity, wallet.clone()))
+                    .unzip()
+            })
+            .unwrap_or_default();
+
+        let savings_bank = world.try_fetch::<SavingsBank>().and_then(|bank| {
+            match component(&agent_storage, bank.0) {
+                Some(agent) => Some(SavingsBankSnapshot::Agent(agent.id)),
+                None => household_entities
+                    .iter()
+                    .position(|&entity| entity == bank.0)
+                    .map(SavingsBankSnapshot::Household)
+                    .or_else(|| {
+                        wallet_entities
+                            .iter()
+                            .position(|&entity| entity == bank.0)
+                            .map(SavingsBankSnapshot::Wallet)
+                    }),
+            }
+        });
+
+        Self {
+            version: SNAPSHOT_FORMAT_VERSION,
+            tick: world.try_fetch::<CurrentTick>().map_or(0, |t| t.0),
+            next_agent_id: world
+                .try_fetch::<AgentIdAllocator>()
+                .map_or(AgentId(1), |a| a.peek()),
+            agents,
+            households,
+            resource_sources,
+            wallets,
+            savings_bank,
+        }
+    }
+
+    /// Build a new world holding this state
+    ///
+    /// Every component type the snapshot can hold is registered, and the
+    /// `CurrentTick` and `AgentIdAllocator` resources are inserted, as is
+    /// `SavingsBank` when the snapshot names one.
+    pub fn restore(&self) -> World {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Needs>();
+        world.register::<Inventory>();
+        world.register::<Wallet>();
+        world.register::<EnergyComponent>();
+        world.register::<CarryCapacity>();
+        world.register::<Skills>();
+        world.register::<Knowledge>();
+        world.register::<Employment>();
+        world.register::<Preferences>();
+        world.register::<SpeciesComponent>();
+        world.register::<Region>();
+        world.register::<ReputationKnowledge>();
+        world.register::<NeedDecayRates>();
+        world.register::<OverflowBuffer>();
+        world.register::<SpatialMemory>();
+        world.register::<SavingsAccount>();
+        world.register::<Household>();
+        world.register::<Resting>();
+        world.register::<ResourceSource>();
+        world.insert(CurrentTick(self.tick));
+        world.insert(AgentIdAllocator::starting_at(self.next_agent_id));
+
+        let mut bank = None;
+        for agent in &self.agents {
+            let entity = world.create_entity().with(Agent { id: agent.id }).build();
+            if self.savings_bank == Some(SavingsBankSnapshot::Agent(agent.id)) {
+                bank = Some(entity);
+            }
+            insert(&world, entity, agent.needs.clone());
+            insert(&world, entity, agent.inventory.clone());
+            insert(&world, entity, agent.wallet.clone());
+            insert(&world, entity, agent.energy.clone());
+            insert(&world, entity, agent.carry_capacity);
+            insert(&world, entity, agent.skills.clone());
+            insert(&world, entity, agent.knowledge.clone());
+            insert(&world, entity, agent.employment.clone());
+            insert(&world, entity, agent.preferences.clone());
+            insert(&world, entity, agent.species.clone());
+            insert(&world, entity, agent.region);
+            insert(&world, entity, agent.reputation.clone());
+            insert(&world, entity, agent.need_decay_rates);
+            insert(&world, entity, agent.overflow_buffer.clone());
+            insert(&world, entity, agent.memory.clone());
+            insert(&world, entity, agent.savings.clone());
+            insert(&world, entity, agent.resting.then_some(Resting));
+        }
+        for (index, household) in self.households.iter().enumerate() {
+            let entity = world.create_entity().with(household.household.clone()).build();
+            insert(&world, entity, household.wallet.clone());
+            insert(&world, entity, household.inventory.clone());
+            if self.savings_bank == Some(SavingsBankSnapshot::Household(index)) {
+                bank = Some(entity);
+            }
+        }
+        for source in &self.resource_sources {
+            world.create_entity().with(source.clone()).build();
+        }
+        for (index, wallet) in self.wallets.iter().enumerate() {
+            let entity = world.create_entity().with(wallet.clone()).build();
+            if self.savings_bank == Some(SavingsBankSnapshot::Wallet(index)) {
+                bank = Some(entity);
+            }
+        }
+        if let Some(bank) = bank {
+            world.insert(SavingsBank(bank));
+        }
+
+        world.maintain();
+        world
+    }
+}
+
+/// Encode the world as a compact, versioned binary snapshot
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// register_core_components(&mut world);
+/// create_agents(&mut world, 10, &AgentTemplate::default());
+///
+/// let bytes = world_to_bytes(&world);
+/// assert!(bytes.len() < world_to_json(&world).len());
+///
+/// let restored = world_from_bytes(&bytes).unwrap();
+/// assert_eq!(restored.read_storage::<Agent>().join().count(), 10);
+/// ```
+pub fn world_to_bytes(world: &World) -> Vec<u8> {
+    let snapshot = WorldSnapshot::capture(world);
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&snapshot.version.to_le_bytes());
+    // Snapshots only contain plain data, which bincode always encodes
+    let body = bincode::serialize(&snapshot).expect("snapshot data is always encodable");
+    bytes.extend_from_slice(&body);
+    bytes
+}
+
+/// Rebuild a world from [`world_to_bytes`] output
+///
+/// # Errors
+/// - [`SnapshotError::NotASnapshot`] if the magic tag is missing
+/// - [`SnapshotError::UnsupportedVersion`] for another format version
+/// - [`SnapshotError::Decode`] if the body is corrupt or truncated
+pub fn world_from_bytes(bytes: &[u8]) -> Result<World, SnapshotError> {
+    let body = bytes.strip_prefix(MAGIC).ok_or(SnapshotError::NotASnapshot)?;
+    if body.len() < 4 {
+        return Err(SnapshotError::Decode("missing format version".to_string()));
+    }
+    let (version, body) = body.split_at(4);
+    let version = u32::from_le_bytes([version[0], version[1], version[2], version[3]]);
+    if version != SNAPSHOT_FORMAT_VERSION {
+        return Err(SnapshotError::UnsupportedVersion(version));
+    }
+    let snapshot: WorldSnapshot =
+        bincode::deserialize(body).map_err(|e| SnapshotError::Decode(e.to_string()))?;
+    Ok(snapshot.restore())
+}
+
+/// Encode the world as a JSON snapshot
+pub fn world_to_json(world: &World) -> String {
+    serde_json::to_string(&WorldSnapshot::capture(world)).expect("snapshot data is always encodable")
+}
+
+/// Rebuild a world from [`world_to_json`] output
+///
+/// # Errors
+/// [`SnapshotError::UnsupportedVersion`] for another format version, or
+/// [`SnapshotError::Decode`] for invalid JSON
+pub fn world_from_json(json: &str) -> Result<World, SnapshotError> {
+    let snapshot: WorldSnapshot =
+        serde_json::from_str(json).map_err(|e| SnapshotError::Decode(e.to_string()))?;
+    if snapshot.version != SNAPSHOT_FORMAT_VERSION {
+        return Err(SnapshotError::UnsupportedVersion(snapshot.version));
+    }
+    Ok(snapshot.restore())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::bulk::{create_agents, AgentTemplate};
+    use crate::agent::creation::create_agent;
+    use crate::agent::registration::register_core_components;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_components_equal_ignores_ids_and_item_order() {
+        let mut world = World::new();
+        register_core_components(&mut world);
+        world.register::<Skills>();
+        let a = create_agent(&mut world);
+        let b = create_agent(&mut world);
+        {
+            let mut inventories = world.write_storage::<Inventory>();
+            let first = inventories.get_mut(a).unwrap();
+            first.add("water", 2);
+            first.add("food", 1);
+            let second = inventories.get_mut(b).unwrap();
+            second.add("food", 1);
+            second.add("water", 2);
+        }
+        assert_ne!(world.read_storage::<Agent>().get(a), world.read_storage::<Agent>().get(b));
+        assert!(components_equal(&world, a, b));
+
+        world.write_storage::<Inventory>().get_mut(b).unwrap().add("water", 1);
+        assert!(!components_equal(&world, a, b));
+    }
+
+    #[test]
+    fn test_components_equal_distinguishes_absent_from_empty() {
+        let mut world = World::new();
+        register_core_components(&mut world);
+        world.register::<Skills>();
+        let a = create_agent(&mut world);
+        let b = create_agent(&mut world);
+
+        world.write_storage::<Skills>().insert(a, Skills::default()).unwrap();
+        assert!(!components_equal(&world, a, b));
+        world.write_storage::<Skills>().insert(b, Skills::default()).unwrap();
+        assert!(components_equal(&world, a, b));
+    }
+
+    /// World exercising every optional component
+    fn busy_world(agents: usize) -> World {
+        let mut world = World::new();
+        register_core_components(&mut world);
+        world.register::<Skills>();
+        world.register::<SpeciesComponent>();
+        world.register::<ReputationKnowledge>();
+        world.register::<EnergyComponent>();
+        world.register::<Region>();
+        world.register::<Resting>();
+        world.register::<ResourceSource>();
+        world.insert(CurrentTick(42));
+
+        let mut inventory = Inventory::default();
+        for (item, n) in [("water", 3), ("food", 2), ("wood", 7), ("axe", 1)] {
+            inventory.add(item, n);
+        }
+        let mut skills = Skills::default();
+        skills.skills.insert("farming".to_string(), 4);
+        skills.skills.insert("trading".to_string(), 2);
+        let template = AgentTemplate::default()
+            .with_inventory(inventory)
+            .with_skills(skills)
+            .with_species(SpeciesComponent::human());
+        let entities = create_agents(&mut world, agents, &template);
+
+        {
+            let mut reputation = world.write_storage::<ReputationKnowledge>();
+            let mut energy = world.write_storage::<EnergyComponent>();
+            let mut region = world.write_storage::<Region>();
+            for (i, &entity) in entities.iter().enumerate() {
+                let mut knowledge = ReputationKnowledge::new();
+                for other in 0..4 {
+                    knowledge.update_reputation(AgentId(other + 1), 1.0, i as u64);
+                }
+                reputation.insert(entity, knowledge).unwrap();
+                energy.insert(entity, EnergyComponent::new(50.0 + i as f32 % 50.0, 100.0)).unwrap();
+                region.insert(entity, Region(i as u32 % 3)).unwrap();
+            }
+            world.write_storage::<Resting>().insert(entities[0], Resting).unwrap();
+        }
+        world
+            .create_entity()
+            .with(ResourceSource::new("water".into(), "water".into(), 0.5, 80).with_required_item("bucket"))
+            .build();
+        world
+    }
+
+    #[test]
+    fn test_bytes_round_trip_is_lossless() {
+        let world = busy_world(20);
+        let bytes = world_to_bytes(&world);
+        let restored = world_from_bytes(&bytes).unwrap();
+
+        assert_eq!(WorldSnapshot::capture(&restored), WorldSnapshot::capture(&world));
+        // Deterministic: re-encoding gives identical bytes
+        assert_eq!(world_to_bytes(&restored), bytes);
+        assert_eq!(restored.read_resource::<CurrentTick>().0, 42);
+        assert_eq!(restored.read_resource::<AgentIdAllocator>().peek(), AgentId(21));
+        assert_eq!(restored.read_storage::<Resting>().join().count(), 1);
+    }
+
+    #[test]
+    fn test_json_round_trip_matches_bytes() {
+        let world = busy_world(5);
+        let from_json = world_from_json(&world_to_json(&world)).unwrap();
+        assert_eq!(WorldSnapshot::capture(&from_json), WorldSnapshot::capture(&world));
+    }
+
+    #[test]
+    fn test_bytes_smaller_than_json() {
+        let world = busy_world(200);
+        let bytes = world_to_bytes(&world).len();
+        let json = world_to_json(&world).len();
+        assert!(bytes * 2 < json, "binary {} bytes vs JSON {} bytes", bytes, json);
+    }
+
+    #[test]
+    fn test_rejects_foreign_or_future_data() {
+        let mut bytes = world_to_bytes(&busy_world(1));
+
+        assert_eq!(
+            world_from_bytes(b"{\"version\":1}").err(),
+            Some(SnapshotError::NotASnapshot)
+        );
+        assert!(matches!(
+            world_from_bytes(&bytes[..bytes.len() - 3]),
+            Err(SnapshotError::Decode(_))
+        ));
+
+        let future = SNAPSHOT_FORMAT_VERSION + 1;
+        bytes[4] = future as u8;
+        assert_eq!(
+            world_from_bytes(&bytes).err(),
+            Some(SnapshotError::UnsupportedVersion(future))
+        );
+    }
+
+    #[test]
+    fn test_agents_only_world() {
+        let mut world = World::new();
+        register_core_components(&mut world);
+        create_agents(&mut world, 2, &AgentTemplate::default());
+
+        let restored = world_from_bytes(&world_to_bytes(&world)).unwrap();
+        let snapshot = WorldSnapshot::capture(&restored);
+        assert_eq!(snapshot.tick, 0);
+        assert_eq!(snapshot.agents.len(), 2);
+        assert!(snapshot.resource_sources.is_empty());
+        assert!(snapshot.households.is_empty());
+        assert!(snapshot.wallets.is_empty());
+        assert_eq!(snapshot.savings_bank, None);
+        assert_eq!(snapshot.agents[0].skills, None);
+    }
+
+    #[test]
+    fn test_savings_survive_round_trip() {
+        use crate::systems::{deposit_savings, total_currency, total_savings, withdraw_savings};
+
+        let mut world = World::new();
+        register_core_components(&mut world);
+        let bank = world.create_entity().with(Wallet::new(10.0)).build();
+        world.insert(SavingsBank(bank));
+        let saver = create_agent(&mut world);
+        deposit_savings(&mut world, saver, 60.0).unwrap();
+
+        let mut restored = world_from_bytes(&world_to_bytes(&world)).unwrap();
+        assert_eq!(WorldSnapshot::capture(&restored), WorldSnapshot::capture(&world));
+        assert_eq!(total_currency(&restored), total_currency(&world));
+        assert_eq!(total_savings(&restored), 60.0);
+
+        let bank = restored.read_resource::<SavingsBank>().0;
+        assert_eq!(restored.read_storage::<Wallet>().get(bank), Some(&Wallet::new(70.0)));
+
+        let saver = (&restored.entities(), &restored.read_storage::<Agent>())
+            .join()
+            .next()
+            .map(|(entity, _)| entity)
+            .unwrap();
+        withdraw_savings(&mut restored, saver, 60.0).unwrap();
+        assert_eq!(total_savings(&restored), 0.0);
+    }
+
+    #[test]
+    fn test_households_survive_round_trip() {
+        use crate::agent::household::{add_member, create_household, draw_from_household, household_of};
+
+        let mut world = World::new();
+        register_core_components(&mut world);
+        let family = create_household(&mut world, 30.0);
+        world.write_storage::<Wallet>().get_mut(family).unwrap().deposit(100.0);
+        let parent = create_agent(&mut world);
+        let child = create_agent(&mut world);
+        let parent_id = world.read_storage::<Agent>().get(parent).unwrap().id;
+        let child_id = world.read_storage::<Agent>().get(child).unwrap().id;
+        add_member(&mut world, family, parent_id).unwrap();
+        add_member(&mut world, family, child_id).unwrap();
+        draw_from_household(&mut world, family, child, 20.0).unwrap();
+
+        let mut restored = world_from_bytes(&world_to_bytes(&world)).unwrap();
+        assert_eq!(WorldSnapshot::capture(&restored), WorldSnapshot::capture(&world));
+        assert_eq!(world_to_bytes(&restored), world_to_bytes(&world));
+
+        let family = household_of(&restored, child_id).unwrap();
+        assert_eq!(restored.read_storage::<Wallet>().get(family), Some(&Wallet::new(80.0)));
+
+        // The child's spending this period was kept too
+        let child = (&restored.entities(), &restored.read_storage::<Agent>())
+            .join()
+            .find(|(_, agent)| agent.id == child_id)
+            .map(|(entity, _)| entity)
+            .unwrap();
+        assert!(draw_from_household(&mut restored, family, child, 20.0).is_err());
+        draw_from_household(&mut restored, family, child, 10.0).unwrap();
+    }
+}
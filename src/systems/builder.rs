@@ -0,0 +1,252 @@
+//! Simulation dispatcher builder
+//!
+//! Assembles the crate's systems into a specs `Dispatcher` with the ordering
+//! they rely on:
+//!
+//! ```text
+//! need_decay ───────────────┐
+//!                           ├──> decision systems
+//! reputation_update ──┬─────┘
+//!                     └──> reputation_decay ──> reputation_threshold
+//! ```
+//!
+//! - Reputation updates run before decay, so a transaction logged this tick
+//!   is folded into reputation before decay/rebalancing sees it.
+//! - Need decay runs before decision systems, so decisions see this tick's
+//!   needs.
+//! - Rest runs after need decay, so a resting agent's recovery is applied on
+//!   top of this tick's tiredness growth.
+//! - Trust milestones are checked after decay, against this tick's final
+//!   scores.
+//!
+//! [`step`] runs one tick of a built dispatcher; [`simulate_until`] repeats
+//! it until a condition holds.
+
+use crate::agent::components::NeedDecaySystem;
+use crate::systems::{
+    CurrentTick, ReputationDecaySystem, ReputationThresholdSystem, ReputationUpdateSystem, ResourceRegenSystem,
+    RestSystem,
+};
+use specs::prelude::*;
+
+/// Builds a `Dispatcher` with correctly ordered crate systems
+///
+/// # Example
+///
+/// ```
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// let mut dispatcher = SimulationBuilder::new()
+///     .with_need_decay()
+///     .with_reputation(ReputationUpdateSystem::default())
+///     .build();
+///
+/// dispatcher.setup(&mut world);
+/// dispatcher.dispatch(&world);
+/// ```
+pub struct SimulationBuilder<'a, 'b> {
+    dispatcher: DispatcherBuilder<'a, 'b>,
+    has_need_decay: bool,
+    has_reputation: bool,
+}
+
+impl<'a, 'b> SimulationBuilder<'a, 'b> {
+    /// Start with no systems
+    pub fn new() -> Self {
+        Self {
+            dispatcher: DispatcherBuilder::new(),
+            has_need_decay: false,
+            has_reputation: false,
+        }
+    }
+
+    /// Add [`NeedDecaySystem`] (named [`NeedDecaySystem::NAME`])
+    pub fn with_need_decay(mut self) -> Self {
+        if !self.has_need_decay {
+            self.dispatcher.add(NeedDecaySystem, NeedDecaySystem::NAME, &[]);
+            self.has_need_decay = true;
+        }
+        self
+    }
+
+    /// Add the reputation update system followed by [`ReputationDecaySystem`]
+    ///
+    /// Decay depends on the update, so same-tick transactions are applied
+    /// before decay.
+    pub fn with_reputation(mut self, update: ReputationUpdateSystem) -> Self {
+        if !self.has_reputation {
+            self.dispatcher.add(update, ReputationUpdateSystem::NAME, &[]);
+            self.dispatcher.add(
+                ReputationDecaySystem,
+                ReputationDecaySystem::NAME,
+                &[ReputationUpdateSystem::NAME],
+            );
+            self.has_reputation = true;
+        }
+        self
+    }
+
+    /// Add [`RestSystem`] (named [`RestSystem::NAME`])
+    ///
+    /// Runs after need decay if it was added before this call.
+    pub fn with_rest(mut self) -> Self {
+        let deps: &[&str] = if self.has_need_decay {
+            &[NeedDecaySystem::NAME]
+        } else {
+            &[]
+        };
+        self.dispatcher.add(RestSystem, RestSystem::NAME, deps);
+        self
+    }
+
+    /// Add [`ResourceRegenSystem`] (named [`ResourceRegenSystem::NAME`])
+    pub fn with_resource_regen(mut self) -> Self {
+        self.dispatcher.add(ResourceRegenSystem, ResourceRegenSystem::NAME, &[]);
+        self
+    }
+
+    /// Add [`ReputationThresholdSystem`] (named [`ReputationThresholdSystem::NAME`])
+    ///
+    /// Runs after reputation decay if reputation was added before this call.
+    pub fn with_trust_events(mut self) -> Self {
+        let deps: &[&str] = if self.has_reputation {
+            &[ReputationDecaySystem::NAME]
+        } else {
+            &[]
+        };
+        self.dispatcher
+            .add(ReputationThresholdSystem::new(), ReputationThresholdSystem::NAME, deps);
+        self
+    }
+
+    /// Add a decision-making system
+    ///
+    /// Runs after need decay and reputation updates, whichever of them were
+    /// added before this call.
+    pub fn with_decision_system<S>(mut self, system: S, name: &str) -> Self
+    where
+        S: for<'c> System<'c> + Send + 'a,
+    {
+        let mut deps = Vec::new();
+        if self.has_need_decay {
+            deps.push(NeedDecaySystem::NAME);
+        }
+        if self.has_reputation {
+            deps.push(ReputationUpdateSystem::NAME);
+        }
+        self.dispatcher.add(system, name, &deps);
+        self
+    }
+
+    /// Add any system with explicit dependencies
+    ///
+    /// # Panics
+    /// Panics if a dependency name has not been added yet (specs behavior)
+    pub fn with_system<S>(mut self, system: S, name: &str, deps: &[&str]) -> Self
+    where
+        S: for<'c> System<'c> + Send + 'a,
+    {
+        self.dispatcher.add(system, name, deps);
+        self
+    }
+
+    /// Build the dispatcher
+    pub fn build(self) -> Dispatcher<'a, 'b> {
+        self.dispatcher.build()
+    }
+}
+
+impl Default for SimulationBuilder<'_, '_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run one tick: advance the `CurrentTick` resource (if the world has one),
+/// dispatch every system, then apply deferred entity changes
+///
+/// The tick advances first, so systems see the tick the host reads afterwards.
+pub fn step(world: &mut World, dispatcher: &mut Dispatcher<'_, '_>) {
+    if let Some(mut tick) = world.try_fetch_mut::<CurrentTick>() {
+        tick.0 = tick.0.saturating_add(1);
+    }
+    dispatcher.dispatch(world);
+    world.maintain();
+}
+
+/// [`step`] until `predicate` holds, at most `max_ticks` times
+///
+/// The predicate is checked after each step, never before the first.
+/// Returns the number of steps run: the first tick after which the predicate
+/// held, or `max_ticks` if it never did.
+///
+/// # Example
+///
+/// ```
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// let mut dispatcher = SimulationBuilder::new().with_need_decay().build();
+/// dispatcher.setup(&mut world);
+/// let agent = world.create_entity().with(Needs::new(10.0, 10.0, 10.0)).build();
+///
+/// let thirsty = |world: &World| world.read_storage::<Needs>().get(agent).unwrap().thirst >= 12.0;
+/// let ticks = simulate_until(&mut world, &mut dispatcher, 1000, thirsty);
+/// assert!(ticks > 0 && ticks < 1000);
+/// ```
+pub fn simulate_until(
+    world: &mut World,
+    dispatcher: &mut Dispatcher<'_, '_>,
+    max_ticks: u64,
+    predicate: impl Fn(&World) -> bool,
+) -> u64 {
+    for tick in 1..=max_ticks {
+        step(world, dispatcher);
+        if predicate(world) {
+            return tick;
+        }
+    }
+    max_ticks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::components::Needs;
+    use pretty_assertions::assert_eq;
+
+    fn decaying_world() -> (World, Dispatcher<'static, 'static>, Entity) {
+        let mut world = World::new();
+        let mut dispatcher = SimulationBuilder::new().with_need_decay().build();
+        dispatcher.setup(&mut world);
+        world.insert(CurrentTick(0));
+        let agent = world.create_entity().with(Needs::new(0.0, 0.0, 0.0)).build();
+        (world, dispatcher, agent)
+    }
+
+    #[test]
+    fn test_stops_on_first_tick_the_predicate_holds() {
+        let (mut world, mut dispatcher, agent) = decaying_world();
+        let hungry = |world: &World| world.read_storage::<Needs>().get(agent).unwrap().hunger > 0.0;
+
+        assert_eq!(simulate_until(&mut world, &mut dispatcher, 50, hungry), 1);
+        assert_eq!(world.read_resource::<CurrentTick>().0, 1);
+
+        // The predicate already holds, but it is only checked after a step
+        assert_eq!(simulate_until(&mut world, &mut dispatcher, 50, hungry), 1);
+        assert_eq!(world.read_resource::<CurrentTick>().0, 2);
+    }
+
+    #[test]
+    fn test_runs_the_full_count_when_never_satisfied() {
+        let (mut world, mut dispatcher, _) = decaying_world();
+
+        assert_eq!(simulate_until(&mut world, &mut dispatcher, 25, |_| false), 25);
+        assert_eq!(world.read_resource::<CurrentTick>().0, 25);
+        assert_eq!(simulate_until(&mut world, &mut dispatcher, 0, |_| true), 0);
+        assert_eq!(world.read_resource::<CurrentTick>().0, 25);
+    }
+}
@@ -0,0 +1,303 @@
+//! Currency conservation audit
+//!
+//! Helps catch accounting bugs in custom systems: snapshot total currency
+//! before systems that should only move money between agents, and check it
+//! afterwards. Currency created with [`mint_currency`](super::mint_currency)
+//! and currency burned by a [`TradePolicy`](super::TradePolicy) are recorded
+//! in the [`CurrencyJournal`] and not reported as leaks.
+
+use super::economy::CurrencyJournal;
+use crate::{Currency, Wallet};
+use specs::prelude::*;
+
+/// Sum of all wallet balances in the world
+///
+/// # Panics
+/// Panics if `Wallet` is not registered in the world
+pub fn total_currency(world: &World) -> f32 {
+    sum_wallets(&world.read_storage::<Wallet>())
+}
+
+/// Exact sum of all `Wallet<Currency>` balances in the world
+///
+/// # Panics
+/// Panics if `Wallet<Currency>` is not registered in the world
+pub fn total_currency_exact(world: &World) -> Currency {
+    world.read_storage::<Wallet<Currency>>().join().map(|w| w.currency).sum()
+}
+
+fn sum_wallets(wallets: &ReadStorage<Wallet>) -> f32 {
+    // Accumulate in f64 so large populations don't drift on their own
+    wallets.join().map(|w| f64::from(w.currency)).sum::<f64>() as f32
+}
+
+/// What to do when total currency changes across a conserving step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConservationAction {
+    /// Panic with the before/after totals
+    #[default]
+    Panic,
+    /// Log a warning (with the `logging` feature) and record the leak
+    Warn,
+}
+
+/// A detected change in total currency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurrencyLeak {
+    /// Total currency at the snapshot
+    pub before: f32,
+    /// Total currency at the check
+    pub after: f32,
+}
+
+impl CurrencyLeak {
+    /// Currency created (positive) or destroyed (negative)
+    pub fn delta(&self) -> f32 {
+        self.after - self.before
+    }
+}
+
+impl core::fmt::Display for CurrencyLeak {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "currency not conserved: {} -> {} (delta {})",
+            self.before,
+            self.after,
+            self.delta()
+        )
+    }
+}
+
+/// Resource that audits total currency across systems that should conserve it
+///
+/// Run [`ConservationSnapshotSystem`] before and [`ConservationCheckSystem`]
+/// after the systems under audit. Nothing happens unless `enabled` is set.
+///
+/// # Example
+///
+/// ```
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Wallet>();
+/// let a = world.create_entity().with(Wallet::new(100.0)).build();
+/// let b = world.create_entity().with(Wallet::new(50.0)).build();
+/// world.insert(ConservationGuard::enabled(ConservationAction::Warn));
+///
+/// ConservationSnapshotSystem.run_now(&world);
+/// {
+///     // Correct transfer: withdraw, then deposit what was actually withdrawn
+///     let mut wallets = world.write_storage::<Wallet>();
+///     let moved = wallets.get_mut(a).unwrap().withdraw(30.0);
+///     wallets.get_mut(b).unwrap().deposit(moved);
+/// }
+/// ConservationCheckSystem.run_now(&world);
+///
+/// assert!(world.read_resource::<ConservationGuard>().last_leak.is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConservationGuard {
+    /// Whether the audit runs at all
+    pub enabled: bool,
+    /// Largest change in total currency tolerated as rounding error
+    pub epsilon: f32,
+    /// What to do when a leak is detected
+    pub action: ConservationAction,
+    /// Most recent leak detected in `Warn` mode
+    pub last_leak: Option<CurrencyLeak>,
+    snapshot: Option<f32>,
+}
+
+impl ConservationGuard {
+    /// Default tolerance for rounding error
+    pub const DEFAULT_EPSILON: f32 = 0.01;
+
+    /// An enabled guard with the default epsilon
+    pub fn enabled(action: ConservationAction) -> Self {
+        Self {
+            enabled: true,
+            action,
+            ..Default::default()
+        }
+    }
+
+    /// Record the current total as the baseline
+    pub fn snapshot(&mut self, total: f32) {
+        self.snapshot = Some(total);
+    }
+
+    /// Compare `total` against the baseline
+    ///
+    /// Returns `Ok` if disabled, if no snapshot was taken, or if the change
+    /// is within epsilon.
+    pub fn check(&self, total: f32) -> Result<(), CurrencyLeak> {
+        match self.snapshot {
+            Some(before) if self.enabled && (total - before).abs() > self.epsilon => {
+                Err(CurrencyLeak { before, after: total })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for ConservationGuard {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            epsilon: Self::DEFAULT_EPSILON,
+            action: ConservationAction::default(),
+            last_leak: None,
+            snapshot: None,
+        }
+    }
+}
+
+/// Wallet total minus everything minted and plus everything burned so far
+fn unminted_total(wallets: &ReadStorage<Wallet>, journal: Option<Read<CurrencyJournal>>) -> f32 {
+    sum_wallets(wallets) - journal.map_or(0.0, |j| j.total_minted() - j.burned)
+}
+
+/// Snapshots total currency into the [`ConservationGuard`] (if present and enabled)
+pub struct ConservationSnapshotSystem;
+
+impl<'a> System<'a> for ConservationSnapshotSystem {
+    type SystemData = (
+        ReadStorage<'a, Wallet>,
+        Option<Read<'a, CurrencyJournal>>,
+        Option<Write<'a, ConservationGuard>>,
+    );
+
+    fn run(&mut self, (wallets, journal, guard): Self::SystemData) {
+        if let Some(mut guard) = guard {
+            if guard.enabled {
+                guard.snapshot(unminted_total(&wallets, journal));
+            }
+        }
+    }
+}
+
+/// Checks total currency against the [`ConservationGuard`] snapshot
+///
+/// # Panics
+/// Panics on a leak when the guard's action is [`ConservationAction::Panic`]
+pub struct ConservationCheckSystem;
+
+impl<'a> System<'a> for ConservationCheckSystem {
+    type SystemData = (
+        ReadStorage<'a, Wallet>,
+        Option<Read<'a, CurrencyJournal>>,
+        Option<Write<'a, ConservationGuard>>,
+    );
+
+    fn run(&mut self, (wallets, journal, guard): Self::SystemData) {
+        let mut guard = match guard {
+            Some(guard) if guard.enabled => guard,
+            _ => return,
+        };
+
+        if let Err(leak) = guard.check(unminted_total(&wallets, journal)) {
+            match guard.action {
+                ConservationAction::Panic => panic!("{}", leak),
+                ConservationAction::Warn => {
+                    log_warn!("{}", leak);
+                    guard.last_leak = Some(leak);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn setup_world(action: ConservationAction) -> (World, Entity, Entity) {
+        let mut world = World::new();
+        world.register::<Wallet>();
+        let a = world.create_entity().with(Wallet::new(100.0)).build();
+        let b = world.create_entity().with(Wallet::new(50.0)).build();
+        world.insert(ConservationGuard::enabled(action));
+        (world, a, b)
+    }
+
+    /// Deposits the requested amount even if the withdrawal was clamped
+    fn buggy_transfer(world: &World, from: Entity, to: Entity, amount: f32) {
+        let mut wallets = world.write_storage::<Wallet>();
+        wallets.get_mut(from).unwrap().withdraw(amount);
+        wallets.get_mut(to).unwrap().deposit(amount);
+    }
+
+    fn correct_transfer(world: &World, from: Entity, to: Entity, amount: f32) {
+        let mut wallets = world.write_storage::<Wallet>();
+        let moved = wallets.get_mut(from).unwrap().withdraw(amount);
+        wallets.get_mut(to).unwrap().deposit(moved);
+    }
+
+    #[test]
+    fn test_total_currency() {
+        let (world, _, _) = setup_world(ConservationAction::Warn);
+        assert_eq!(total_currency(&world), 150.0);
+    }
+
+    #[test]
+    fn test_correct_transfer_passes() {
+        let (world, a, b) = setup_world(ConservationAction::Panic);
+
+        ConservationSnapshotSystem.run_now(&world);
+        correct_transfer(&world, a, b, 500.0);
+        ConservationCheckSystem.run_now(&world);
+
+        assert_eq!(total_currency(&world), 150.0);
+    }
+
+    #[test]
+    fn test_buggy_transfer_detected() {
+        let (world, a, b) = setup_world(ConservationAction::Warn);
+
+        ConservationSnapshotSystem.run_now(&world);
+        buggy_transfer(&world, a, b, 500.0);
+        ConservationCheckSystem.run_now(&world);
+
+        let guard = world.read_resource::<ConservationGuard>();
+        let leak = guard.last_leak.expect("leak should be recorded");
+        assert_eq!(leak.before, 150.0);
+        assert_eq!(leak.after, 550.0);
+        assert_eq!(leak.delta(), 400.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "currency not conserved")]
+    fn test_buggy_transfer_panics() {
+        let (world, a, b) = setup_world(ConservationAction::Panic);
+
+        ConservationSnapshotSystem.run_now(&world);
+        buggy_transfer(&world, a, b, 500.0);
+        ConservationCheckSystem.run_now(&world);
+    }
+
+    #[test]
+    fn test_disabled_guard_ignores_leaks() {
+        let (mut world, a, b) = setup_world(ConservationAction::Panic);
+        world.insert(ConservationGuard::default());
+
+        ConservationSnapshotSystem.run_now(&world);
+        buggy_transfer(&world, a, b, 500.0);
+        ConservationCheckSystem.run_now(&world);
+
+        assert!(world.read_resource::<ConservationGuard>().last_leak.is_none());
+    }
+
+    #[test]
+    fn test_minting_is_not_a_leak() {
+        let (mut world, a, _) = setup_world(ConservationAction::Panic);
+
+        ConservationSnapshotSystem.run_now(&world);
+        crate::systems::mint_currency(&mut world, a, 25.0).unwrap();
+        ConservationCheckSystem.run_now(&world);
+
+        assert_eq!(total_currency(&world), 175.0);
+    }
+}
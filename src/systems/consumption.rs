@@ -0,0 +1,578 @@
+//! Eating and drinking from an agent's own inventory
+//!
+//! [`ConsumptionSystem`] lets agents consume held items once a need reaches
+//! [`ConsumptionConfig::trigger`]. By default one unit is consumed per need
+//! per tick; raise [`ConsumptionConfig::max_consume_per_tick`] to model eating
+//! several portions at once. Consumption always stops once the need is at or
+//! below [`ConsumptionConfig::target`], or when matching items run out.
+//! Agents with an [`OverflowBuffer`] bank whatever an item takes a need
+//! below the floor.
+
+use crate::agent::components::{Inventory, Needs};
+use crate::agent::overflow::{OverflowBuffer, OverflowConfig};
+use crate::agent::starvation::{starvation_effectiveness, StarvationConfig, StarvationState};
+use crate::systems::{OptionalReadStorage, OptionalWriteStorage};
+use crate::decision::ActionType;
+use crate::items::{ItemRegistry, ItemType, NeedType};
+use specs::storage::MaskedStorage;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+
+/// World resource tuning [`ConsumptionSystem`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsumptionConfig {
+    /// Most units consumed for one need in a single tick
+    pub max_consume_per_tick: u32,
+    /// Need level (0-100) at which an agent starts consuming
+    pub trigger: f32,
+    /// Need level (0-100) at which an agent stops consuming
+    pub target: f32,
+}
+
+impl Default for ConsumptionConfig {
+    fn default() -> Self {
+        Self {
+            max_consume_per_tick: 1,
+            trigger: 50.0,
+            target: 30.0,
+        }
+    }
+}
+
+impl ConsumptionConfig {
+    /// Default thresholds, consuming up to `max` units per need per tick
+    pub fn multi(max: u32) -> Self {
+        Self {
+            max_consume_per_tick: max,
+            ..Default::default()
+        }
+    }
+}
+
+/// Held consumable that reduces `need` the most (ties by item id)
+fn best_item<'r>(
+    need: NeedType,
+    held: &Inventory,
+    registry: &'r ItemRegistry,
+    used: &[(String, u32)],
+) -> Option<(&'r str, f32)> {
+    let already = |id: &str| used.iter().find(|(u, _)| u == id).map_or(0, |(_, n)| *n);
+    registry
+        .items_satisfying(need)
+        .into_iter()
+        .filter_map(|id| registry.get(id))
+        .filter(|item| item.consumable && item.satisfaction_for(need) < 0.0)
+        .filter(|item| held.quantity(&item.id) > already(&item.id))
+        .map(|item| (item.id.as_str(), item.satisfaction_for(need)))
+        .min_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(b.0)))
+}
+
+/// Items an agent would consume this tick for one need
+///
+/// Picks the strongest held item for `need` one unit at a time, stopping at
+/// `max_consume_per_tick` units, once the need reaches `target`, or when the
+/// inventory has nothing left that helps. Returns `(item, units)` in the order
+/// they would be eaten; empty if the need is below `trigger`.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let registry = ItemRegistry::with_defaults();
+/// let mut inventory = Inventory::default();
+/// inventory.add("food", 5);
+///
+/// // Hunger 90, each food is -25: three portions bring it to 15
+/// let plan = consume_plan(
+///     &Needs::new(0.0, 90.0, 0.0),
+///     NeedType::Hunger,
+///     &inventory,
+///     &registry,
+///     &ConsumptionConfig::multi(5),
+/// );
+/// assert_eq!(plan, vec![("food".to_string(), 3)]);
+/// ```
+pub fn consume_plan(
+    needs: &Needs,
+    need: NeedType,
+    inventory: &Inventory,
+    registry: &ItemRegistry,
+    config: &ConsumptionConfig,
+) -> Vec<(String, u32)> {
+    let mut level = needs.get(need);
+    let mut plan: Vec<(String, u32)> = Vec::new();
+    if level < config.trigger {
+        return plan;
+    }
+
+    let mut units = 0;
+    while units < config.max_consume_per_tick && level > config.target {
+        let Some((id, delta)) = best_item(need, inventory, registry, &plan) else {
+            break;
+        };
+        match plan.last_mut() {
+            Some((last, n)) if last == id => *n += 1,
+            _ => plan.push((id.to_string(), 1)),
+        }
+        level = (level + delta).max(crate::MIN_NEEDS);
+        units += 1;
+    }
+    plan
+}
+
+/// A buffer to bank excess satisfaction in, with its capacity
+type Overflow<'b> = Option<(&'b mut OverflowBuffer, f32)>;
+
+/// Apply every effect of `units` of `item`, banking overflow, then clamp
+///
+/// Satisfying (negative) effects are scaled by `effectiveness`, which is
+/// below 1 for starved agents; effects that raise a need are not.
+fn apply_item(needs: &mut Needs, item: &ItemType, units: u32, effectiveness: f32, overflow: &mut Overflow) {
+    for (effect, delta) in &item.satisfies {
+        let delta = if *delta < 0.0 { delta * effectiveness } else { *delta };
+        *needs.get_mut(*effect) += delta * units as f32;
+    }
+    if let Some((buffer, capacity)) = overflow {
+        buffer.capture(needs, *capacity);
+    }
+    needs.clamp();
+}
+
+/// Consume items for one need, updating needs and inventory
+///
+/// Follows [`consume_plan`]; every effect of each consumed item is applied and
+/// needs are clamped. Returns the number of units consumed.
+pub fn consume_for_need(
+    needs: &mut Needs,
+    need: NeedType,
+    inventory: &mut Inventory,
+    registry: &ItemRegistry,
+    config: &ConsumptionConfig,
+) -> u32 {
+    consume_for_need_buffered(needs, need, inventory, registry, config, 1.0, &mut None)
+}
+
+fn consume_for_need_buffered(
+    needs: &mut Needs,
+    need: NeedType,
+    inventory: &mut Inventory,
+    registry: &ItemRegistry,
+    config: &ConsumptionConfig,
+    effectiveness: f32,
+    overflow: &mut Overflow,
+) -> u32 {
+    let plan = consume_plan(needs, need, inventory, registry, config);
+    let mut consumed = 0;
+    for (id, units) in plan {
+        let removed = inventory.remove(&id, units);
+        if let Some(item) = registry.get(&id) {
+            apply_item(needs, item, removed, effectiveness, overflow);
+        }
+        consumed += removed;
+    }
+    consumed
+}
+
+/// Result of consuming a specific item on request
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConsumeAttempt {
+    /// Units actually consumed
+    pub consumed: u32,
+    /// Total drop across all needs, after clamping
+    pub need_reduction: f32,
+    /// Whether anything was consumed
+    pub success: bool,
+}
+
+/// Consume up to `quantity` units of `item_id` from an agent's inventory
+///
+/// Unlike [`consume_for_need`], this ignores thresholds: the host decides
+/// what to eat. Every effect of the item is applied per unit and needs are
+/// clamped. Nothing is consumed if the item is not held, unknown to the
+/// registry, or not consumable; the attempt then reports `success: false`.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let registry = ItemRegistry::with_defaults();
+/// let mut needs = Needs::new(80.0, 0.0, 0.0);
+/// let mut inventory = Inventory::default();
+/// inventory.add("water", 1);
+///
+/// let attempt = consume_item(&mut needs, &mut inventory, &registry, "water", 2);
+/// assert_eq!(attempt.consumed, 1);
+/// assert_eq!(attempt.need_reduction, 30.0);
+///
+/// let attempt = consume_item(&mut needs, &mut inventory, &registry, "water", 1);
+/// assert!(!attempt.success);
+/// ```
+pub fn consume_item(
+    needs: &mut Needs,
+    inventory: &mut Inventory,
+    registry: &ItemRegistry,
+    item_id: &str,
+    quantity: u32,
+) -> ConsumeAttempt {
+    consume_item_buffered(needs, inventory, registry, item_id, quantity, 1.0, &mut None)
+}
+
+fn consume_item_buffered(
+    needs: &mut Needs,
+    inventory: &mut Inventory,
+    registry: &ItemRegistry,
+    item_id: &str,
+    quantity: u32,
+    effectiveness: f32,
+    overflow: &mut Overflow,
+) -> ConsumeAttempt {
+    let Some(item) = registry.get(item_id).filter(|item| item.consumable) else {
+        return ConsumeAttempt::default();
+    };
+
+    let before = needs.clone();
+    let removed = inventory.remove(item_id, quantity);
+    apply_item(needs, item, removed, effectiveness, overflow);
+
+    let need_reduction = (before.thirst - needs.thirst)
+        + (before.hunger - needs.hunger)
+        + (before.tiredness - needs.tiredness);
+    ConsumeAttempt {
+        consumed: removed,
+        need_reduction,
+        success: removed > 0,
+    }
+}
+
+/// Consume held items for `entity`, using the world's `ItemRegistry`
+///
+/// Falls back to the default registry if the world has none. Overflow goes
+/// to the entity's `OverflowBuffer`, if any, and a `StarvationState` weakens
+/// the item's effect. Returns `None` if the entity is dead or lacks `Needs`
+/// or `Inventory`.
+pub(crate) fn consume_held(
+    world: &World,
+    entity: Entity,
+    item_id: &str,
+    quantity: u32,
+) -> Option<ConsumeAttempt> {
+    if !world.entities().is_alive(entity) {
+        return None;
+    }
+
+    let defaults;
+    let registry = world.try_fetch::<ItemRegistry>();
+    let registry = match &registry {
+        Some(registry) => &**registry,
+        None => {
+            defaults = ItemRegistry::with_defaults();
+            &defaults
+        }
+    };
+
+    let effectiveness = starvation_effectiveness(world, entity);
+    let config = world.try_fetch::<OverflowConfig>().map(|c| (*c).clone()).unwrap_or_default();
+    let mut buffers = (config.enabled && world.has_value::<MaskedStorage<OverflowBuffer>>())
+        .then(|| world.write_storage::<OverflowBuffer>());
+    let mut overflow = buffers
+        .as_mut()
+        .and_then(|b| b.get_mut(entity))
+        .map(|buffer| (buffer, config.capacity));
+
+    let mut needs = world.write_storage::<Needs>();
+    let mut inventories = world.write_storage::<Inventory>();
+    match (needs.get_mut(entity), inventories.get_mut(entity)) {
+        (Some(needs), Some(inventory)) => Some(consume_item_buffered(
+            needs,
+            inventory,
+            registry,
+            item_id,
+            quantity,
+            effectiveness,
+            &mut overflow,
+        )),
+        _ => None,
+    }
+}
+
+/// Execute an [`ActionType::ConsumeItem`] for the acting agent `entity`
+///
+/// Runs [`consume_item`] against the entity's needs and inventory. Returns
+/// `None` for any other action type (including `Consume`, which targets a
+/// world location the host owns) and for entities without `Needs` or
+/// `Inventory`. A failed attempt (item not held or not consumable) is
+/// returned as `Some` with `success: false`.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use libreconomy::decision::ActionType;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Needs>();
+/// world.register::<Inventory>();
+/// let mut inventory = Inventory::default();
+/// inventory.add("water", 2);
+/// let agent = world
+///     .create_entity()
+///     .with(Needs::new(80.0, 0.0, 0.0))
+///     .with(inventory)
+///     .build();
+///
+/// let drink = ActionType::ConsumeItem { item: "water".to_string(), quantity: 1 };
+/// let attempt = execute_consume(&world, agent, &drink).unwrap();
+/// assert!(attempt.success);
+/// assert_eq!(world.read_storage::<Inventory>().get(agent).unwrap().quantity("water"), 1);
+/// ```
+pub fn execute_consume(world: &World, entity: Entity, action: &ActionType) -> Option<ConsumeAttempt> {
+    match action {
+        ActionType::ConsumeItem { item, quantity } => consume_held(world, entity, item, *quantity),
+        _ => None,
+    }
+}
+
+/// Agents consume held items for pressing needs each tick
+///
+/// Reads the optional [`ConsumptionConfig`], `ItemRegistry` and
+/// [`OverflowConfig`] resources, falling back to their defaults. Needs are
+/// handled thirst, hunger, then tiredness. Agents with an [`OverflowBuffer`]
+/// bank any satisfaction past the floor, and agents with a
+/// [`StarvationState`] get less out of each item (see
+/// [`StarvationConfig`]).
+pub struct ConsumptionSystem;
+
+impl ConsumptionSystem {
+    /// Stable dispatcher name
+    pub const NAME: &'static str = "consumption";
+}
+
+impl<'a> System<'a> for ConsumptionSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Needs>,
+        WriteStorage<'a, Inventory>,
+        OptionalWriteStorage<'a, OverflowBuffer>,
+        OptionalReadStorage<'a, StarvationState>,
+        Option<Read<'a, ItemRegistry>>,
+        Option<Read<'a, ConsumptionConfig>>,
+        Option<Read<'a, OverflowConfig>>,
+        Option<Read<'a, StarvationConfig>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut needs,
+            mut inventories,
+            mut buffers,
+            starvation,
+            registry,
+            config,
+            overflow,
+            starvation_config,
+        ): Self::SystemData,
+    ) {
+        let defaults;
+        let registry = match &registry {
+            Some(registry) => &**registry,
+            None => {
+                defaults = ItemRegistry::with_defaults();
+                &defaults
+            }
+        };
+        let config = config.map(|c| (*c).clone()).unwrap_or_default();
+        let overflow_config = overflow.map(|c| (*c).clone()).unwrap_or_default();
+        let starvation_config = starvation_config.map(|c| (*c).clone()).unwrap_or_default();
+
+        for (entity, needs, inventory) in (&entities, &mut needs, &mut inventories).join() {
+            let mut overflow = buffers
+                .as_mut()
+                .and_then(|buffers| buffers.get_mut(entity))
+                .filter(|_| overflow_config.enabled)
+                .map(|buffer| (buffer, overflow_config.capacity));
+            let effectiveness = starvation
+                .as_ref()
+                .and_then(|starvation| starvation.get(entity))
+                .map_or(1.0, |state| state.effectiveness(&starvation_config));
+            for need in [NeedType::Thirst, NeedType::Hunger, NeedType::Tiredness] {
+                consume_for_need_buffered(
+                    needs,
+                    need,
+                    inventory,
+                    registry,
+                    &config,
+                    effectiveness,
+                    &mut overflow,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::ItemType;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    /// Registry with a snack that only takes 15 off hunger
+    fn snack_registry() -> ItemRegistry {
+        let mut registry = ItemRegistry::new();
+        let mut satisfies = HashMap::new();
+        satisfies.insert(NeedType::Hunger, -15.0);
+        registry.register(ItemType::new("snack".into(), satisfies, true));
+        registry
+    }
+
+    fn run_system(world: &mut World) {
+        let mut system = ConsumptionSystem;
+        system.run_now(world);
+        world.maintain();
+    }
+
+    fn setup_world(hunger: f32, snacks: u32, config: ConsumptionConfig) -> (World, Entity) {
+        let mut world = World::new();
+        world.register::<Needs>();
+        world.register::<Inventory>();
+        world.insert(snack_registry());
+        world.insert(config);
+        let mut inventory = Inventory::default();
+        inventory.add("snack", snacks);
+        let agent = world
+            .create_entity()
+            .with(Needs::new(0.0, hunger, 0.0))
+            .with(inventory)
+            .build();
+        (world, agent)
+    }
+
+    #[test]
+    fn test_hungry_agent_eats_several_and_stops_when_satisfied() {
+        let (mut world, agent) = setup_world(90.0, 10, ConsumptionConfig::multi(10));
+        run_system(&mut world);
+
+        // 90 -> 75 -> 60 -> 45 -> 30: stops at target
+        assert_eq!(world.read_storage::<Needs>().get(agent).unwrap().hunger, 30.0);
+        assert_eq!(world.read_storage::<Inventory>().get(agent).unwrap().quantity("snack"), 6);
+
+        // Below trigger now: nothing more is eaten
+        run_system(&mut world);
+        assert_eq!(world.read_storage::<Inventory>().get(agent).unwrap().quantity("snack"), 6);
+    }
+
+    #[test]
+    fn test_overflow_fills_buffer_and_delays_need_growth() {
+        use crate::agent::components::{NeedDecayRates, NeedDecaySystem};
+
+        let config = ConsumptionConfig { trigger: 0.0, target: 0.0, ..Default::default() };
+        let (mut world, agent) = setup_world(10.0, 1, config.clone());
+        world.register::<NeedDecayRates>();
+        world.register::<OverflowBuffer>();
+        world.write_storage::<OverflowBuffer>().insert(agent, OverflowBuffer::default()).unwrap();
+        let (mut unbuffered, plain) = setup_world(10.0, 1, config);
+
+        // A 15-point snack at hunger 10 overshoots the floor by 5
+        run_system(&mut world);
+        run_system(&mut unbuffered);
+        assert_eq!(world.read_storage::<Needs>().get(agent).unwrap().hunger, 0.0);
+        assert_eq!(world.read_storage::<OverflowBuffer>().get(agent).unwrap().hunger, 5.0);
+
+        unbuffered.register::<NeedDecayRates>();
+        for _ in 0..10 {
+            NeedDecaySystem.run_now(&world);
+            NeedDecaySystem.run_now(&unbuffered);
+        }
+        let needs = world.read_storage::<Needs>().get(agent).unwrap().clone();
+        // Hunger is still being paid from the buffer; thirst grows as usual
+        assert_eq!(needs.hunger, 0.0);
+        assert!(needs.thirst > 0.0);
+        assert!(unbuffered.read_storage::<Needs>().get(plain).unwrap().hunger > 0.0);
+        assert!(world.read_storage::<OverflowBuffer>().get(agent).unwrap().hunger < 5.0);
+
+        // Switched off, the buffer neither fills nor drains
+        world.insert(OverflowConfig { enabled: false, ..Default::default() });
+        let banked = world.read_storage::<OverflowBuffer>().get(agent).unwrap().hunger;
+        NeedDecaySystem.run_now(&world);
+        assert!(world.read_storage::<Needs>().get(agent).unwrap().hunger > 0.0);
+        assert_eq!(world.read_storage::<OverflowBuffer>().get(agent).unwrap().hunger, banked);
+    }
+
+    #[test]
+    fn test_consumption_respects_limits() {
+        // Per-tick cap
+        let (mut world, agent) = setup_world(90.0, 10, ConsumptionConfig::multi(2));
+        run_system(&mut world);
+        assert_eq!(world.read_storage::<Needs>().get(agent).unwrap().hunger, 60.0);
+
+        // Inventory runs out
+        let (mut world, agent) = setup_world(90.0, 2, ConsumptionConfig::multi(10));
+        run_system(&mut world);
+        assert_eq!(world.read_storage::<Needs>().get(agent).unwrap().hunger, 60.0);
+        assert_eq!(world.read_storage::<Inventory>().get(agent).unwrap().quantity("snack"), 0);
+
+        // Default config eats a single portion
+        let (mut world, agent) = setup_world(90.0, 10, ConsumptionConfig::default());
+        run_system(&mut world);
+        assert_eq!(world.read_storage::<Needs>().get(agent).unwrap().hunger, 75.0);
+    }
+
+    #[test]
+    fn test_plan_never_goes_below_floor() {
+        let mut inventory = Inventory::default();
+        inventory.add("snack", 10);
+        let config = ConsumptionConfig {
+            max_consume_per_tick: 10,
+            trigger: 0.0,
+            target: 0.0,
+        };
+        let plan = consume_plan(
+            &Needs::new(0.0, 20.0, 0.0),
+            NeedType::Hunger,
+            &inventory,
+            &snack_registry(),
+            &config,
+        );
+        // Two snacks reach the floor; a third would be wasted
+        assert_eq!(plan, vec![("snack".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_consume_item_reports_missing_item() {
+        let registry = snack_registry();
+        let mut needs = Needs::new(0.0, 40.0, 0.0);
+        let mut inventory = Inventory::default();
+        inventory.add("snack", 3);
+
+        let attempt = consume_item(&mut needs, &mut inventory, &registry, "snack", 2);
+        assert_eq!(attempt, ConsumeAttempt { consumed: 2, need_reduction: 30.0, success: true });
+        assert_eq!(needs.hunger, 10.0);
+
+        // Only one left, and it bottoms out at the floor
+        let attempt = consume_item(&mut needs, &mut inventory, &registry, "snack", 5);
+        assert_eq!(attempt, ConsumeAttempt { consumed: 1, need_reduction: 10.0, success: true });
+
+        let attempt = consume_item(&mut needs, &mut inventory, &registry, "snack", 1);
+        assert_eq!(attempt, ConsumeAttempt::default());
+        let attempt = consume_item(&mut needs, &mut inventory, &registry, "pebble", 1);
+        assert!(!attempt.success);
+    }
+
+    #[test]
+    fn test_execute_consume_item_action() {
+        let (world, agent) = setup_world(60.0, 3, ConsumptionConfig::default());
+        let eat = ActionType::ConsumeItem { item: "snack".to_string(), quantity: 2 };
+
+        let attempt = execute_consume(&world, agent, &eat).unwrap();
+        assert_eq!(attempt, ConsumeAttempt { consumed: 2, need_reduction: 30.0, success: true });
+        assert_eq!(world.read_storage::<Needs>().get(agent).unwrap().hunger, 30.0);
+        assert_eq!(world.read_storage::<Inventory>().get(agent).unwrap().quantity("snack"), 1);
+
+        // Location-based consumption is left to the host
+        let graze = ActionType::Consume { resource_location: (1.0, 2.0) };
+        assert_eq!(execute_consume(&world, agent, &graze), None);
+    }
+}
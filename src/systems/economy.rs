@@ -0,0 +1,471 @@
+//! Closed-economy policy and sanctioned currency movement
+//!
+//! `Wallet::deposit` creates currency out of nothing. For closed-economy
+//! research, insert an [`EconomyPolicy`] with `closed` set and move money only
+//! through these helpers: [`transfer_currency`] always moves currency between
+//! wallets, [`deposit_currency`] (unbacked credit) is refused, and
+//! [`mint_currency`] is the single sanctioned creation path, recorded in the
+//! [`CurrencyJournal`]. [`transfer_exact`] moves fixed-point `Currency`
+//! between `Wallet<Currency>` wallets for audits that must balance exactly.
+//! A [`TradePolicy`] adds a tax to every trade, paid to a treasury or burned,
+//! and a [`TradeEligibility`] bans agents whose reputation is too low.
+
+use crate::{trust_in, Agent, AgentId, Currency, CurrencyAmount, CurrentTick, ReputationKnowledge, Wallet};
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+/// World resource selecting open or closed economy rules
+///
+/// Defaults to open; worlds without the resource behave as open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EconomyPolicy {
+    /// Whether currency may only be created by [`mint_currency`]
+    pub closed: bool,
+}
+
+impl EconomyPolicy {
+    /// A closed-economy policy
+    pub fn closed() -> Self {
+        Self { closed: true }
+    }
+
+    /// Whether the world runs a closed economy
+    pub fn is_closed(world: &World) -> bool {
+        world.try_fetch::<EconomyPolicy>().is_some_and(|policy| policy.closed)
+    }
+}
+
+/// World resource taxing every executed trade
+///
+/// The buyer pays the price plus `tax_rate` times the price; the seller
+/// receives the price. The tax goes to the `treasury` entity's wallet, or is
+/// burned (recorded in the [`CurrencyJournal`]) when there is no treasury or
+/// it has no `Wallet`. Worlds without the resource trade tax-free.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TradePolicy {
+    /// Fraction of the trade value charged on top (0.05 = 5%)
+    pub tax_rate: f32,
+    /// Entity whose wallet collects the tax; `None` burns it
+    pub treasury: Option<Entity>,
+}
+
+impl TradePolicy {
+    /// Tax owed on a trade worth `total`, rounded to whole minor units
+    ///
+    /// Negative or non-finite rates and totals owe nothing.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libreconomy::TradePolicy;
+    ///
+    /// let policy = TradePolicy { tax_rate: 0.1, treasury: None };
+    /// assert_eq!(policy.tax_on(12.34), 1.23);
+    /// ```
+    pub fn tax_on(&self, total: f32) -> f32 {
+        if !total.is_finite() || total <= 0.0 {
+            return 0.0;
+        }
+        self.tax_on_exact(Currency::from_f32(total)).to_f32()
+    }
+
+    /// Tax owed on an exact fixed-point `total`, rounded to the nearest minor unit
+    pub fn tax_on_exact(&self, total: Currency) -> Currency {
+        if !self.tax_rate.is_finite() || self.tax_rate <= 0.0 || total <= Currency::ZERO {
+            return Currency::ZERO;
+        }
+        let minor = total.minor_units() as f64 * f64::from(self.tax_rate);
+        Currency::from_minor(minor.round() as i64)
+    }
+}
+
+/// Whose reputation view decides [`TradeEligibility`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ReputationJudge {
+    /// Each party is judged by the other party's `ReputationKnowledge`
+    #[default]
+    Counterparty,
+    /// Both parties are judged by this entity's `ReputationKnowledge`, a
+    /// shared view kept by a market, guild or exchange
+    Market(Entity),
+}
+
+/// World resource refusing trades with agents of low reputation
+///
+/// Before a trade executes, each party's trust score in the judge's eyes
+/// (see [`trust_in`](crate::trust_in), which judges strangers by their
+/// faction) must be at least `min_reputation`; otherwise the trade is
+/// refused. A judge without
+/// `ReputationKnowledge` has no opinion and refuses nobody. Worlds without
+/// the resource let anyone trade.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<ReputationKnowledge>();
+/// let mut rep = ReputationKnowledge::new();
+/// for tick in 0..5 {
+///     rep.update_reputation(AgentId(1), -1.0, tick);
+/// }
+/// let seller = world.create_entity().with(rep).build();
+///
+/// let floor = TradeEligibility::new(0.4);
+/// assert!(floor.refusal(&world, AgentId(1), seller).is_some());
+/// assert_eq!(floor.refusal(&world, AgentId(2), seller), None);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TradeEligibility {
+    /// Lowest trust score a party may have and still trade
+    pub min_reputation: f32,
+    /// Whose view the score comes from
+    pub judge: ReputationJudge,
+}
+
+impl TradeEligibility {
+    /// A floor judged by each trade's counterparty
+    pub fn new(min_reputation: f32) -> Self {
+        Self {
+            min_reputation,
+            judge: ReputationJudge::Counterparty,
+        }
+    }
+
+    /// Judge both parties by `market`'s shared reputation instead
+    pub fn judged_by(mut self, market: Entity) -> Self {
+        self.judge = ReputationJudge::Market(market);
+        self
+    }
+
+    /// Score that bars `agent` from trading with `counterparty`, if any
+    ///
+    /// `None` means `agent` may trade.
+    pub fn refusal(&self, world: &World, agent: AgentId, counterparty: Entity) -> Option<f32> {
+        if !world.has_value::<MaskedStorage<ReputationKnowledge>>() {
+            return None;
+        }
+        let judge = match self.judge {
+            ReputationJudge::Counterparty => counterparty,
+            ReputationJudge::Market(market) => market,
+        };
+        let score = trust_in(world, world.read_storage::<ReputationKnowledge>().get(judge)?, agent);
+        (score < self.min_reputation).then_some(score)
+    }
+}
+
+/// One sanctioned currency creation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MintRecord {
+    /// Agent credited, if the entity is an agent
+    pub agent: Option<AgentId>,
+    /// Amount created
+    pub amount: f32,
+    /// Tick of creation (`CurrentTick`, or 0 if absent)
+    pub tick: u64,
+}
+
+/// World resource recording every [`mint_currency`] call
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CurrencyJournal {
+    /// Mints in the order they happened
+    pub minted: Vec<MintRecord>,
+    /// Total currency destroyed by sinks (untreasuried trade tax)
+    #[serde(default)]
+    pub burned: f32,
+}
+
+impl CurrencyJournal {
+    /// Total currency created through minting
+    pub fn total_minted(&self) -> f32 {
+        self.minted.iter().map(|r| f64::from(r.amount)).sum::<f64>() as f32
+    }
+}
+
+/// Reasons a currency movement was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurrencyError {
+    /// An entity has no `Wallet`
+    MissingWallet,
+    /// The source wallet does not cover the amount
+    InsufficientFunds,
+    /// Amount is negative or not finite
+    InvalidAmount,
+    /// Unbacked credit was attempted in a closed economy
+    ClosedEconomy,
+}
+
+impl core::fmt::Display for CurrencyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CurrencyError::MissingWallet => write!(f, "entity has no wallet"),
+            CurrencyError::InsufficientFunds => write!(f, "source wallet does not cover the amount"),
+            CurrencyError::InvalidAmount => write!(f, "amount must be finite and non-negative"),
+            CurrencyError::ClosedEconomy => {
+                write!(f, "currency can only be minted in a closed economy")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CurrencyError {}
+
+fn check_amount(amount: f32) -> Result<(), CurrencyError> {
+    if amount.is_finite() && amount >= 0.0 {
+        Ok(())
+    } else {
+        Err(CurrencyError::InvalidAmount)
+    }
+}
+
+/// Move `amount` from one wallet to another
+///
+/// All or nothing: fails without changes if `from` cannot cover the amount.
+/// Conserves total currency under either policy.
+///
+/// # Errors
+/// [`CurrencyError::InvalidAmount`], [`CurrencyError::MissingWallet`] or
+/// [`CurrencyError::InsufficientFunds`]
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Wallet>();
+/// world.insert(EconomyPolicy::closed());
+/// let a = world.create_entity().with(Wallet::new(100.0)).build();
+/// let b = world.create_entity().with(Wallet::new(0.0)).build();
+///
+/// transfer_currency(&mut world, a, b, 40.0).unwrap();
+/// assert_eq!(total_currency(&world), 100.0);
+/// assert!(transfer_currency(&mut world, a, b, 500.0).is_err());
+/// ```
+pub fn transfer_currency(
+    world: &mut World,
+    from: Entity,
+    to: Entity,
+    amount: f32,
+) -> Result<(), CurrencyError> {
+    check_amount(amount)?;
+    transfer_between(world, from, to, amount)
+}
+
+/// Move an exact fixed-point `amount` between `Wallet<Currency>` wallets
+///
+/// Same rules as [`transfer_currency`], but on `Wallet<Currency>` storage,
+/// so totals are conserved exactly however many transfers are made.
+///
+/// # Errors
+/// [`CurrencyError::InvalidAmount`] for negative amounts,
+/// [`CurrencyError::MissingWallet`] or [`CurrencyError::InsufficientFunds`]
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Wallet<Currency>>();
+/// let a = world.create_entity().with(Wallet::with_balance(Currency::from_minor(100))).build();
+/// let b = world.create_entity().with(Wallet::with_balance(Currency::ZERO)).build();
+///
+/// for _ in 0..10 {
+///     transfer_exact(&mut world, a, b, Currency::from_f32(0.1)).unwrap();
+/// }
+/// assert_eq!(world.read_storage::<Wallet<Currency>>().get(a).unwrap().currency, Currency::ZERO);
+/// assert_eq!(total_currency_exact(&world), Currency::from_minor(100));
+/// ```
+pub fn transfer_exact(
+    world: &mut World,
+    from: Entity,
+    to: Entity,
+    amount: Currency,
+) -> Result<(), CurrencyError> {
+    if amount < Currency::ZERO {
+        return Err(CurrencyError::InvalidAmount);
+    }
+    transfer_between(world, from, to, amount)
+}
+
+fn transfer_between<C: CurrencyAmount>(
+    world: &mut World,
+    from: Entity,
+    to: Entity,
+    amount: C,
+) -> Result<(), CurrencyError> {
+    let mut wallets = world.write_storage::<Wallet<C>>();
+    let funds = wallets.get(from).ok_or(CurrencyError::MissingWallet)?.currency;
+    if !wallets.contains(to) {
+        return Err(CurrencyError::MissingWallet);
+    }
+    if funds < amount {
+        return Err(CurrencyError::InsufficientFunds);
+    }
+
+    let moved = wallets.get_mut(from).map_or(C::default(), |w| w.withdraw(amount));
+    if let Some(wallet) = wallets.get_mut(to) {
+        wallet.deposit(moved);
+    }
+    Ok(())
+}
+
+/// Credit `amount` to a wallet without a matching withdrawal
+///
+/// For open economies (wages from outside, rewards). Refused when the world's
+/// [`EconomyPolicy`] is closed; use [`mint_currency`] there.
+///
+/// # Errors
+/// [`CurrencyError::ClosedEconomy`], [`CurrencyError::InvalidAmount`] or
+/// [`CurrencyError::MissingWallet`]
+pub fn deposit_currency(world: &mut World, to: Entity, amount: f32) -> Result<(), CurrencyError> {
+    check_amount(amount)?;
+    if EconomyPolicy::is_closed(world) {
+        return Err(CurrencyError::ClosedEconomy);
+    }
+    world
+        .write_storage::<Wallet>()
+        .get_mut(to)
+        .ok_or(CurrencyError::MissingWallet)?
+        .deposit(amount);
+    Ok(())
+}
+
+/// Create `amount` of new currency in an entity's wallet
+///
+/// The only sanctioned way to add currency in a closed economy. Every mint is
+/// appended to the [`CurrencyJournal`] resource (created if missing), which
+/// the conservation audit uses to tell minting apart from leaks.
+///
+/// # Errors
+/// [`CurrencyError::InvalidAmount`] or [`CurrencyError::MissingWallet`]
+pub fn mint_currency(world: &mut World, entity: Entity, amount: f32) -> Result<(), CurrencyError> {
+    check_amount(amount)?;
+    world
+        .write_storage::<Wallet>()
+        .get_mut(entity)
+        .ok_or(CurrencyError::MissingWallet)?
+        .deposit(amount);
+
+    let agent = if world.has_value::<MaskedStorage<Agent>>() {
+        world.read_storage::<Agent>().get(entity).map(|a| a.id)
+    } else {
+        None
+    };
+    let tick = world.try_fetch::<CurrentTick>().map_or(0, |t| t.0);
+    world
+        .entry::<CurrencyJournal>()
+        .or_insert_with(CurrencyJournal::default)
+        .minted
+        .push(MintRecord { agent, amount, tick });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::{total_currency, total_currency_exact};
+    use pretty_assertions::assert_eq;
+
+    fn setup_world(closed: bool) -> (World, Entity, Entity) {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Wallet>();
+        if closed {
+            world.insert(EconomyPolicy::closed());
+        }
+        let a = world
+            .create_entity()
+            .with(Agent { id: AgentId(1) })
+            .with(Wallet::new(100.0))
+            .build();
+        let b = world
+            .create_entity()
+            .with(Agent { id: AgentId(2) })
+            .with(Wallet::new(20.0))
+            .build();
+        (world, a, b)
+    }
+
+    #[test]
+    fn test_closed_transfer_conserves_currency() {
+        let (mut world, a, b) = setup_world(true);
+
+        assert_eq!(transfer_currency(&mut world, a, b, 60.0), Ok(()));
+        assert_eq!(
+            transfer_currency(&mut world, b, a, 1000.0),
+            Err(CurrencyError::InsufficientFunds)
+        );
+        assert_eq!(total_currency(&world), 120.0);
+
+        let wallets = world.read_storage::<Wallet>();
+        assert_eq!(wallets.get(a).unwrap().currency, 40.0);
+        assert_eq!(wallets.get(b).unwrap().currency, 80.0);
+    }
+
+    #[test]
+    fn test_closed_economy_refuses_unbacked_deposit() {
+        let (mut world, a, _) = setup_world(true);
+        assert_eq!(
+            deposit_currency(&mut world, a, 10.0),
+            Err(CurrencyError::ClosedEconomy)
+        );
+        assert_eq!(total_currency(&world), 120.0);
+
+        let (mut open, a, _) = setup_world(false);
+        assert_eq!(deposit_currency(&mut open, a, 10.0), Ok(()));
+        assert_eq!(total_currency(&open), 130.0);
+    }
+
+    #[test]
+    fn test_fixed_point_transfers_conserve_exactly() {
+        let mut world = World::new();
+        world.register::<Wallet>();
+        world.register::<Wallet<Currency>>();
+        let a = world
+            .create_entity()
+            .with(Wallet::new(1000.0))
+            .with(Wallet::with_balance(Currency::from_minor(100_000)))
+            .build();
+        let b = world
+            .create_entity()
+            .with(Wallet::new(0.37))
+            .with(Wallet::with_balance(Currency::from_minor(37)))
+            .build();
+
+        for i in 0..20_000 {
+            let (from, to) = if i % 3 == 0 { (b, a) } else { (a, b) };
+            let _ = transfer_currency(&mut world, from, to, 0.01);
+            let _ = transfer_exact(&mut world, from, to, Currency::from_f32(0.01));
+        }
+
+        // f32 balances drift away from the starting total...
+        assert_ne!(total_currency(&world), 1000.37);
+        // ...fixed-point balances never do
+        assert_eq!(total_currency_exact(&world), Currency::from_minor(100_037));
+        assert_eq!(
+            transfer_exact(&mut world, a, b, Currency::from_minor(-1)),
+            Err(CurrencyError::InvalidAmount)
+        );
+    }
+
+    #[test]
+    fn test_minting_is_journaled() {
+        let (mut world, a, b) = setup_world(true);
+        world.insert(CurrentTick(7));
+
+        assert_eq!(mint_currency(&mut world, a, 30.0), Ok(()));
+        assert_eq!(mint_currency(&mut world, b, 5.0), Ok(()));
+        assert_eq!(mint_currency(&mut world, b, -1.0), Err(CurrencyError::InvalidAmount));
+
+        assert_eq!(total_currency(&world), 155.0);
+        let journal = world.read_resource::<CurrencyJournal>();
+        assert_eq!(journal.total_minted(), 35.0);
+        assert_eq!(
+            journal.minted[0],
+            MintRecord { agent: Some(AgentId(1)), amount: 30.0, tick: 7 }
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! Passive need relief from an agent's surroundings
+//!
+//! Standing near water, an agent sips now and then without deciding to, so
+//! its thirst grows more slowly than in a desert. [`EnvironmentEffectSystem`]
+//! models this: each tick it lowers a need by a small amount for every
+//! configured [`EnvironmentEffect`] whose resource the host's [`WorldQuery`]
+//! reports within range. The relief is applied on top of `NeedDecaySystem`,
+//! not instead of it, and the system does nothing until the world holds an
+//! [`EnvironmentConfig`] with at least one effect.
+
+use crate::items::NeedType;
+use crate::world_query::WorldQuery;
+use crate::{Agent, Needs};
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+
+/// One need eased by being close to one resource type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentEffect {
+    /// Resource type to look for, as passed to `get_nearby_resources`
+    pub resource_type: String,
+    /// Need that is eased
+    pub need: NeedType,
+    /// Farthest the resource can be (world units)
+    pub radius: f32,
+    /// Amount the need drops each tick while in range
+    pub relief_per_tick: f32,
+}
+
+impl EnvironmentEffect {
+    /// Ease `need` by `relief_per_tick` within `radius` of `resource_type`
+    pub fn new(resource_type: &str, need: NeedType, radius: f32, relief_per_tick: f32) -> Self {
+        Self {
+            resource_type: resource_type.to_string(),
+            need,
+            radius,
+            relief_per_tick,
+        }
+    }
+}
+
+/// World resource listing the [`EnvironmentEffect`]s in play
+///
+/// The default has no effects, so inserting it alone changes nothing.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let config = EnvironmentConfig::default()
+///     .with_effect(EnvironmentEffect::new("water", NeedType::Thirst, 5.0, 0.004));
+/// assert_eq!(config.effects.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvironmentConfig {
+    /// Effects applied each tick, in order
+    pub effects: Vec<EnvironmentEffect>,
+}
+
+impl EnvironmentConfig {
+    /// Add an effect
+    pub fn with_effect(mut self, effect: EnvironmentEffect) -> Self {
+        self.effects.push(effect);
+        self
+    }
+}
+
+/// Eases agents' needs while they are near matching resources
+///
+/// Resources come from the host's [`WorldQuery`]. Effects with a
+/// non-positive or NaN relief are ignored, and needs are clamped afterwards
+/// so relief never pushes them below zero. Reads the optional
+/// [`EnvironmentConfig`]; without one the system does nothing.
+pub struct EnvironmentEffectSystem<'q> {
+    query: &'q dyn WorldQuery,
+}
+
+impl<'q> EnvironmentEffectSystem<'q> {
+    /// Stable dispatcher name
+    pub const NAME: &'static str = "environment_effect";
+
+    /// Look up nearby resources through `query`
+    pub fn new(query: &'q dyn WorldQuery) -> Self {
+        Self { query }
+    }
+}
+
+impl<'a> System<'a> for EnvironmentEffectSystem<'_> {
+    type SystemData = (
+        ReadStorage<'a, Agent>,
+        WriteStorage<'a, Needs>,
+        Option<Read<'a, EnvironmentConfig>>,
+    );
+
+    fn run(&mut self, (agents, mut needs, config): Self::SystemData) {
+        let Some(config) = config else {
+            return;
+        };
+        let effects: Vec<_> = config.effects.iter().filter(|e| e.relief_per_tick > 0.0).collect();
+        if effects.is_empty() {
+            return;
+        }
+
+        for (agent, needs) in (&agents, &mut needs).join() {
+            let mut eased = false;
+            for effect in &effects {
+                let nearby = self
+                    .query
+                    .get_nearby_resources(agent.id, &effect.resource_type, effect.radius);
+                if !nearby.is_empty() {
+                    *needs.get_mut(effect.need) -= effect.relief_per_tick;
+                    eased = true;
+                }
+            }
+            if eased {
+                needs.clamp();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::components::{NeedDecayRates, NeedDecaySystem};
+    use crate::world_query::GridWorldQuery;
+    use crate::AgentId;
+    use pretty_assertions::assert_eq;
+
+    /// Thirst of an agent by a spring and one in the desert after `ticks`
+    fn thirst_after(ticks: usize, config: Option<EnvironmentConfig>) -> (f32, f32) {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Needs>();
+        world.register::<NeedDecayRates>();
+        if let Some(config) = config {
+            world.insert(config);
+        }
+        let by_spring = world
+            .create_entity()
+            .with(Agent { id: AgentId(1) })
+            .with(Needs::new(20.0, 20.0, 20.0))
+            .build();
+        let in_desert = world
+            .create_entity()
+            .with(Agent { id: AgentId(2) })
+            .with(Needs::new(20.0, 20.0, 20.0))
+            .build();
+
+        let mut grid = GridWorldQuery::new(10.0);
+        grid.set_agent_position(AgentId(1), 0.0, 0.0);
+        grid.set_agent_position(AgentId(2), 500.0, 0.0);
+        grid.add_resource("water", "water", 3.0, 4.0);
+
+        for _ in 0..ticks {
+            NeedDecaySystem.run_now(&world);
+            EnvironmentEffectSystem::new(&grid).run_now(&world);
+        }
+        let needs = world.read_storage::<Needs>();
+        (needs.get(by_spring).unwrap().thirst, needs.get(in_desert).unwrap().thirst)
+    }
+
+    #[test]
+    fn test_agent_near_water_gets_thirsty_more_slowly() {
+        let config = EnvironmentConfig::default()
+            .with_effect(EnvironmentEffect::new("water", NeedType::Thirst, 10.0, 0.004));
+        let (by_spring, in_desert) = thirst_after(100, Some(config));
+
+        // Decay still applies near water, just partly offset
+        assert!(by_spring > 20.0);
+        assert!(by_spring < in_desert);
+        assert!((in_desert - 21.0).abs() < 1e-3);
+        assert!((by_spring - 20.6).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_without_effects_nothing_changes() {
+        let (by_spring, in_desert) = thirst_after(50, None);
+        assert_eq!(by_spring, in_desert);
+        assert_eq!(thirst_after(50, Some(EnvironmentConfig::default())), (by_spring, in_desert));
+
+        // Out of range is the same as no water at all
+        let far = EnvironmentConfig::default()
+            .with_effect(EnvironmentEffect::new("water", NeedType::Thirst, 1.0, 0.004));
+        assert_eq!(thirst_after(50, Some(far)), (by_spring, in_desert));
+    }
+}
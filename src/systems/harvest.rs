@@ -0,0 +1,308 @@
+//! Agents harvesting from resource sources into their inventories
+//!
+//! `ResourceSource::harvest` only takes stock out of a source. [`harvest_into`]
+//! is the agent-side action: it checks the source's tool and skill
+//! prerequisites, scales the amount by the agent's productivity (see
+//! [`agent_productivity`]) and puts the harvest in the agent's `Inventory`.
+//! [`harvest_action`] does the same for one standard gather action, taking
+//! the source's `yield_per_harvest`. [`harvest_from_location`] harvests
+//! from a source found through a `WorldQuery`.
+
+use crate::agent::productivity::{agent_productivity, scale_yield};
+use crate::world_query::ResourceLocation;
+use crate::{Inventory, ResourceSource, Skills};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+/// Harvest up to `amount` from `source` into `agent`'s inventory
+///
+/// A well-rested agent takes the full `amount` (stock permitting); tired or
+/// drained agents take proportionally less, but at least one unit while they
+/// can work at all. Returns the units harvested, 0 if `source` has no
+/// `ResourceSource`, the agent doesn't meet its prerequisites or is dead.
+/// Agents without an `Inventory` get a fresh one.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// register_core_components(&mut world);
+/// world.register::<ResourceSource>();
+/// let agent = create_agent_with_needs(&mut world, Needs::new(50.0, 50.0, 0.0));
+/// let bush = world
+///     .create_entity()
+///     .with(ResourceSource::new("plant".into(), "berries".into(), 0.0, 20))
+///     .build();
+///
+/// assert_eq!(harvest_into(&mut world, agent, bush, 5), 5);
+/// assert_eq!(world.read_storage::<Inventory>().get(agent).unwrap().quantity("berries"), 5);
+///
+/// // Worn out, the same agent manages less
+/// world.write_storage::<Needs>().get_mut(agent).unwrap().tiredness = 75.0;
+/// assert_eq!(harvest_into(&mut world, agent, bush, 5), 3);
+/// ```
+pub fn harvest_into(world: &mut World, agent: Entity, source: Entity, amount: u32) -> u32 {
+    if !world.has_value::<MaskedStorage<ResourceSource>>() || !world.is_alive(agent) {
+        return 0;
+    }
+    if !world.has_value::<MaskedStorage<Inventory>>() {
+        world.register::<Inventory>();
+    }
+
+    let allowed = {
+        let sources = world.read_storage::<ResourceSource>();
+        let Some(resource) = sources.get(source) else {
+            return 0;
+        };
+        let inventories = world.read_storage::<Inventory>();
+        let skills = world
+            .has_value::<MaskedStorage<Skills>>()
+            .then(|| world.read_storage::<Skills>());
+        resource.can_harvest(inventories.get(agent), skills.as_ref().and_then(|s| s.get(agent)))
+    };
+    if !allowed {
+        return 0;
+    }
+
+    let amount = scale_yield(amount, agent_productivity(world, agent));
+    let (item, harvested) = {
+        let mut sources = world.write_storage::<ResourceSource>();
+        match sources.get_mut(source) {
+            Some(resource) => (resource.item_produced.clone(), resource.harvest(amount)),
+            None => return 0,
+        }
+    };
+
+    if harvested > 0 {
+        let mut inventories = world.write_storage::<Inventory>();
+        if let Ok(entry) = inventories.entry(agent) {
+            entry.or_insert_with(Inventory::default).add(&item, harvested);
+        }
+    }
+    harvested
+}
+
+/// One gather action by `agent` at `source`
+///
+/// Takes the source's `yield_per_harvest` through [`harvest_into`], so the
+/// yield is scaled by productivity and capped by the remaining stock.
+/// Returns the units harvested.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// register_core_components(&mut world);
+/// world.register::<ResourceSource>();
+/// let agent = create_agent_with_needs(&mut world, Needs::new(50.0, 50.0, 0.0));
+/// let orchard = world
+///     .create_entity()
+///     .with(ResourceSource::new("tree".into(), "apple".into(), 0.0, 7).with_yield_per_harvest(5))
+///     .build();
+///
+/// assert_eq!(harvest_action(&mut world, agent, orchard), 5);
+/// assert_eq!(harvest_action(&mut world, agent, orchard), 2);
+/// ```
+pub fn harvest_action(world: &mut World, agent: Entity, source: Entity) -> u32 {
+    let amount = if world.has_value::<MaskedStorage<ResourceSource>>() {
+        world
+            .read_storage::<ResourceSource>()
+            .get(source)
+            .map_or(0, |resource| resource.yield_per_harvest)
+    } else {
+        0
+    };
+    harvest_into(world, agent, source, amount)
+}
+
+/// Harvest up to `amount` from the source at `location` into `agent`'s
+/// inventory
+///
+/// Resolves the location's `entity_id` and `entity_generation` and goes
+/// through [`harvest_into`]. The world may have moved on since the query:
+/// returns 0 if the location doesn't name an entity, or the entity has since
+/// died, depleted or stopped being a `ResourceSource`. An entity created in
+/// the dead source's slot has a new generation and is not harvested.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// register_core_components(&mut world);
+/// world.register::<ResourceSource>();
+/// let agent = create_agent_with_needs(&mut world, Needs::new(50.0, 50.0, 0.0));
+/// let well = world
+///     .create_entity()
+///     .with(ResourceSource::new("well".into(), "water".into(), 0.0, 10))
+///     .build();
+///
+/// let mut grid = GridWorldQuery::new(10.0);
+/// grid.set_agent_position(AgentId(1), 0.0, 0.0);
+/// grid.sync_from_world(&world, |_entity| Some((3.0, 4.0)));
+/// let location = grid.get_nearby_resources(AgentId(1), "water", 10.0).remove(0);
+///
+/// assert_eq!(harvest_from_location(&mut world, agent, &location, 4), 4);
+/// assert_eq!(world.read_storage::<ResourceSource>().get(well).unwrap().current_stock, 6);
+/// ```
+pub fn harvest_from_location(
+    world: &mut World,
+    agent: Entity,
+    location: &ResourceLocation,
+    amount: u32,
+) -> u32 {
+    let (Some(id), Some(generation)) = (location.entity_id, location.entity_generation) else {
+        return 0;
+    };
+    // Slots that died or were reused since the query have another generation
+    let source = world.entities().entity(id);
+    if source.gen().id() != generation || !world.is_alive(source) {
+        return 0;
+    }
+    harvest_into(world, agent, source, amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world_query::{GridWorldQuery, WorldQuery};
+    use crate::{AgentId, EnergyComponent, Needs};
+    use pretty_assertions::assert_eq;
+
+    fn world_with_source(stock: u32) -> (World, Entity) {
+        let mut world = World::new();
+        world.register::<Needs>();
+        world.register::<EnergyComponent>();
+        world.register::<Inventory>();
+        world.register::<ResourceSource>();
+        let source = world
+            .create_entity()
+            .with(ResourceSource::new("plant".into(), "berries".into(), 0.0, stock))
+            .build();
+        (world, source)
+    }
+
+    fn worker(world: &mut World, tiredness: f32, energy: f32) -> Entity {
+        world
+            .create_entity()
+            .with(Needs::new(20.0, 20.0, tiredness))
+            .with(EnergyComponent::new(energy, 100.0))
+            .build()
+    }
+
+    #[test]
+    fn test_rested_agent_harvests_more_than_exhausted_one() {
+        let (mut world, source) = world_with_source(100);
+        let rested = worker(&mut world, 0.0, 100.0);
+        let exhausted = worker(&mut world, 95.0, 10.0);
+
+        let rested_yield = harvest_into(&mut world, rested, source, 10);
+        let exhausted_yield = harvest_into(&mut world, exhausted, source, 10);
+        assert_eq!(rested_yield, 10);
+        assert!(exhausted_yield > 0 && exhausted_yield < rested_yield);
+
+        let inventories = world.read_storage::<Inventory>();
+        assert_eq!(inventories.get(exhausted).unwrap().quantity("berries"), exhausted_yield);
+        let stock = world.read_storage::<ResourceSource>().get(source).unwrap().current_stock;
+        assert_eq!(stock, 100 - rested_yield - exhausted_yield);
+    }
+
+    #[test]
+    fn test_harvest_respects_stock_and_prerequisites() {
+        let (mut world, source) = world_with_source(3);
+        let agent = worker(&mut world, 0.0, 100.0);
+        assert_eq!(harvest_into(&mut world, agent, source, 10), 3);
+        assert_eq!(harvest_into(&mut world, agent, source, 10), 0);
+
+        let forest = world
+            .create_entity()
+            .with(ResourceSource::new("tree".into(), "wood".into(), 0.0, 50).with_required_item("axe"))
+            .build();
+        assert_eq!(harvest_into(&mut world, agent, forest, 5), 0);
+    }
+
+    #[test]
+    fn test_harvest_action_takes_one_actions_yield() {
+        let mut world = World::new();
+        world.register::<Needs>();
+        world.register::<EnergyComponent>();
+        world.register::<Inventory>();
+        world.register::<ResourceSource>();
+        let orchard = world
+            .create_entity()
+            .with(ResourceSource::new("tree".into(), "apple".into(), 0.0, 12).with_yield_per_harvest(5))
+            .build();
+        let rested = worker(&mut world, 0.0, 100.0);
+        let tired = worker(&mut world, 75.0, 100.0);
+
+        assert_eq!(harvest_action(&mut world, rested, orchard), 5);
+        // Half as productive: sqrt(0.25) = 0.5 of 5, rounded
+        assert_eq!(harvest_action(&mut world, tired, orchard), 3);
+        // Only 4 left
+        assert_eq!(harvest_action(&mut world, rested, orchard), 4);
+        assert_eq!(harvest_action(&mut world, rested, orchard), 0);
+
+        // Sources default to one unit per action
+        let (mut world, bush) = world_with_source(10);
+        let agent = worker(&mut world, 0.0, 100.0);
+        assert_eq!(harvest_action(&mut world, agent, bush), 1);
+    }
+
+    #[test]
+    fn test_harvest_from_located_source() {
+        let (mut world, bush) = world_with_source(8);
+        let agent = worker(&mut world, 0.0, 100.0);
+        let mut grid = GridWorldQuery::new(10.0);
+        grid.set_agent_position(AgentId(1), 0.0, 0.0);
+        grid.sync_from_world(&world, |_| Some((2.0, 0.0)));
+
+        let location = grid.get_nearby_resources(AgentId(1), "berries", 5.0).remove(0);
+        assert_eq!(location.entity_id, Some(bush.id()));
+        assert_eq!(harvest_from_location(&mut world, agent, &location, 5), 5);
+        assert_eq!(world.read_storage::<Inventory>().get(agent).unwrap().quantity("berries"), 5);
+
+        // A location without an entity can't be resolved
+        let unknown = ResourceLocation::new(2.0, 0.0, 2.0);
+        assert_eq!(harvest_from_location(&mut world, agent, &unknown, 5), 0);
+    }
+
+    #[test]
+    fn test_source_gone_since_query_yields_nothing() {
+        let (mut world, bush) = world_with_source(3);
+        let (mut other, well) = world_with_source(10);
+        let agent = worker(&mut world, 0.0, 100.0);
+        let location = ResourceLocation::new(0.0, 0.0, 0.0).with_entity(bush);
+
+        // Depleted by someone else after the query
+        let rival = worker(&mut world, 0.0, 100.0);
+        assert_eq!(harvest_into(&mut world, rival, bush, 3), 3);
+        assert_eq!(harvest_from_location(&mut world, agent, &location, 2), 0);
+
+        // Removed from the world altogether
+        world.delete_entity(bush).unwrap();
+        world.maintain();
+        assert_eq!(harvest_from_location(&mut world, agent, &location, 2), 0);
+        assert!(world.read_storage::<Inventory>().get(agent).is_none());
+
+        // A new source in the same slot is not the one that was located
+        let newcomer = world
+            .create_entity()
+            .with(ResourceSource::new("tree".into(), "wood".into(), 0.0, 20))
+            .build();
+        assert_eq!(newcomer.id(), bush.id());
+        assert_eq!(harvest_from_location(&mut world, agent, &location, 2), 0);
+        assert_eq!(world.read_storage::<ResourceSource>().get(newcomer).unwrap().current_stock, 20);
+
+        // The agent itself being dead also counts
+        let gone = worker(&mut other, 0.0, 100.0);
+        let source = ResourceLocation::new(0.0, 0.0, 0.0).with_entity(well);
+        other.delete_entity(gone).unwrap();
+        other.maintain();
+        assert_eq!(harvest_from_location(&mut other, gone, &source, 2), 0);
+    }
+}
@@ -0,0 +1,242 @@
+//! World invariant checks for debugging
+//!
+//! Components are plain data that any system can write, so a custom system
+//! can push a need below zero or a balance to NaN without tripping anything.
+//! [`check_invariants`] scans the world for such corruption; hosts typically
+//! `debug_assert!` that it comes back empty after each tick.
+
+use crate::{Agent, AgentId, NeedType, Needs, ReputationKnowledge, ResourceSource, Wallet};
+use crate::{MAX_NEEDS, MIN_NEEDS};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+/// One broken invariant found by [`check_invariants`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum InvariantViolation {
+    /// A need is NaN or outside [`MIN_NEEDS`], [`MAX_NEEDS`]
+    NeedOutOfBounds {
+        entity: Entity,
+        need: NeedType,
+        value: f32,
+    },
+    /// A wallet balance is NaN or infinite
+    NonFiniteCurrency { entity: Entity, value: f32 },
+    /// A reputation score is NaN or outside [0, 1]
+    ReputationOutOfBounds {
+        entity: Entity,
+        about: AgentId,
+        score: f32,
+    },
+    /// A resource source regenerates at a negative or non-finite rate
+    InvalidRegeneration { entity: Entity, rate: f32 },
+}
+
+impl InvariantViolation {
+    /// Entity holding the broken component
+    pub fn entity(&self) -> Entity {
+        match self {
+            InvariantViolation::NeedOutOfBounds { entity, .. }
+            | InvariantViolation::NonFiniteCurrency { entity, .. }
+            | InvariantViolation::ReputationOutOfBounds { entity, .. }
+            | InvariantViolation::InvalidRegeneration { entity, .. } => *entity,
+        }
+    }
+}
+
+impl core::fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            InvariantViolation::NeedOutOfBounds { entity, need, value } => write!(
+                f,
+                "entity {}: {:?} is {} (expected {} to {})",
+                entity.id(),
+                need,
+                value,
+                MIN_NEEDS,
+                MAX_NEEDS
+            ),
+            InvariantViolation::NonFiniteCurrency { entity, value } => {
+                write!(f, "entity {}: wallet holds {}", entity.id(), value)
+            }
+            InvariantViolation::ReputationOutOfBounds { entity, about, score } => write!(
+                f,
+                "entity {}: reputation of agent {} is {} (expected 0 to 1)",
+                entity.id(),
+                about.0,
+                score
+            ),
+            InvariantViolation::InvalidRegeneration { entity, rate } => write!(
+                f,
+                "entity {}: resource regenerates at {} per tick",
+                entity.id(),
+                rate
+            ),
+        }
+    }
+}
+
+fn in_bounds(value: f32, min: f32, max: f32) -> bool {
+    // NaN fails both comparisons
+    value >= min && value <= max
+}
+
+/// Every broken invariant in the world, grouped by component, in entity order
+///
+/// Checks `Needs`, `Wallet`, `ReputationKnowledge` and `ResourceSource`;
+/// unregistered components are skipped. Reputation views are reported in
+/// ascending order of the agent they describe.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// register_core_components(&mut world);
+/// let agent = create_agent(&mut world);
+/// assert!(check_invariants(&world).is_empty());
+///
+/// world.write_storage::<Wallet>().get_mut(agent).unwrap().currency = f32::NAN;
+/// assert_eq!(check_invariants(&world).len(), 1);
+/// ```
+pub fn check_invariants(world: &World) -> Vec<InvariantViolation> {
+    let mut violations = Vec::new();
+    let entities = world.entities();
+
+    if world.has_value::<MaskedStorage<Needs>>() {
+        for (entity, needs) in (&entities, &world.read_storage::<Needs>()).join() {
+            for need in [NeedType::Thirst, NeedType::Hunger, NeedType::Tiredness] {
+                let value = needs.get(need);
+                if !in_bounds(value, MIN_NEEDS, MAX_NEEDS) {
+                    violations.push(InvariantViolation::NeedOutOfBounds { entity, need, value });
+                }
+            }
+        }
+    }
+
+    if world.has_value::<MaskedStorage<Wallet>>() {
+        for (entity, wallet) in (&entities, &world.read_storage::<Wallet>()).join() {
+            if !wallet.currency.is_finite() {
+                violations.push(InvariantViolation::NonFiniteCurrency {
+                    entity,
+                    value: wallet.currency,
+                });
+            }
+        }
+    }
+
+    if world.has_value::<MaskedStorage<ReputationKnowledge>>() {
+        for (entity, knowledge) in (&entities, &world.read_storage::<ReputationKnowledge>()).join() {
+            let mut views: Vec<_> = knowledge.first_hand.iter().collect();
+            views.sort_by_key(|(about, _)| **about);
+            for (about, view) in views {
+                let score = view.score();
+                if !in_bounds(score, 0.0, 1.0) {
+                    violations.push(InvariantViolation::ReputationOutOfBounds {
+                        entity,
+                        about: *about,
+                        score,
+                    });
+                }
+            }
+        }
+    }
+
+    if world.has_value::<MaskedStorage<ResourceSource>>() {
+        for (entity, source) in (&entities, &world.read_storage::<ResourceSource>()).join() {
+            let rate = source.regeneration_rate;
+            if !(rate.is_finite() && rate >= 0.0) {
+                violations.push(InvariantViolation::InvalidRegeneration { entity, rate });
+            }
+        }
+    }
+
+    violations
+}
+
+/// Agent id of a violation's entity, if it is an agent
+///
+/// Convenience for reporting; returns `None` if `Agent` is not registered.
+pub fn violating_agent(world: &World, violation: &InvariantViolation) -> Option<AgentId> {
+    if !world.has_value::<MaskedStorage<Agent>>() {
+        return None;
+    }
+    world.read_storage::<Agent>().get(violation.entity()).map(|a| a.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{register_core_components, create_agent, ReputationView};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_clean_world_has_no_violations() {
+        let mut world = World::new();
+        register_core_components(&mut world);
+        world.register::<ResourceSource>();
+        for _ in 0..3 {
+            create_agent(&mut world);
+        }
+        world
+            .create_entity()
+            .with(ResourceSource::new("plant".into(), "grass".into(), 0.5, 10))
+            .build();
+
+        assert_eq!(check_invariants(&world), Vec::new());
+        assert!(check_invariants(&World::new()).is_empty());
+    }
+
+    #[test]
+    fn test_corrupted_world_reports_each_violation() {
+        let mut world = World::new();
+        register_core_components(&mut world);
+        world.register::<ResourceSource>();
+        world.register::<ReputationKnowledge>();
+        let agent = create_agent(&mut world);
+        let healthy = create_agent(&mut world);
+        let source = world
+            .create_entity()
+            .with(ResourceSource::new("plant".into(), "grass".into(), 0.5, 10))
+            .build();
+
+        {
+            let mut needs = world.write_storage::<Needs>();
+            let needs = needs.get_mut(agent).unwrap();
+            needs.hunger = -5.0;
+            needs.tiredness = f32::NAN;
+        }
+        world.write_storage::<Wallet>().get_mut(agent).unwrap().currency = f32::INFINITY;
+        let mut rep = ReputationKnowledge::new();
+        rep.first_hand.insert(AgentId(9), ReputationView::with_prior(-1.0, 2.0));
+        rep.first_hand.insert(AgentId(3), ReputationView::new());
+        world.write_storage::<ReputationKnowledge>().insert(agent, rep).unwrap();
+        world.write_storage::<ResourceSource>().get_mut(source).unwrap().regeneration_rate = -1.0;
+
+        let violations = check_invariants(&world);
+        assert_eq!(violations.len(), 5);
+        assert_eq!(
+            violations[0],
+            InvariantViolation::NeedOutOfBounds { entity: agent, need: NeedType::Hunger, value: -5.0 }
+        );
+        assert!(matches!(
+            violations[1],
+            InvariantViolation::NeedOutOfBounds { need: NeedType::Tiredness, .. }
+        ));
+        assert_eq!(
+            violations[2],
+            InvariantViolation::NonFiniteCurrency { entity: agent, value: f32::INFINITY }
+        );
+        assert_eq!(
+            violations[3],
+            InvariantViolation::ReputationOutOfBounds { entity: agent, about: AgentId(9), score: -1.0 }
+        );
+        assert_eq!(violations[4], InvariantViolation::InvalidRegeneration { entity: source, rate: -1.0 });
+
+        assert!(violations.iter().all(|v| v.entity() != healthy));
+        let agent_id = world.read_storage::<Agent>().get(agent).unwrap().id;
+        assert_eq!(violating_agent(&world, &violations[0]), Some(agent_id));
+        assert_eq!(violating_agent(&world, &violations[4]), None);
+        assert!(violations[2].to_string().contains("inf"));
+    }
+}
@@ -0,0 +1,233 @@
+//! Forgetting stale market knowledge
+//!
+//! Without forgetting, `Knowledge` grows with every item and partner an agent
+//! ever hears about. [`KnowledgeDecaySystem`] evicts prices and partners not
+//! refreshed within a time-to-live and caps how many of each an agent keeps,
+//! dropping the least recently refreshed first.
+
+use crate::{CurrentTick, Knowledge};
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use std::collections::HashMap;
+
+/// Configuration for [`KnowledgeDecaySystem`]
+///
+/// Insert into the world as a resource to override the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KnowledgeDecayConfig {
+    /// Ticks after its last observation at which a price is forgotten
+    pub price_ttl: u64,
+    /// Ticks after the last meeting at which a trade partner is forgotten
+    pub partner_ttl: u64,
+    /// Most prices an agent keeps (most recently observed win)
+    pub max_prices: usize,
+    /// Most trade partners an agent keeps (most recently met win)
+    pub max_partners: usize,
+}
+
+impl Default for KnowledgeDecayConfig {
+    fn default() -> Self {
+        Self {
+            price_ttl: 5000,
+            partner_ttl: 10000,
+            max_prices: 64,
+            max_partners: 32,
+        }
+    }
+}
+
+/// Evicts stale prices and trade partners from every agent's `Knowledge`
+///
+/// An entry is refreshed by [`Knowledge::observe_price`] or
+/// [`Knowledge::meet_partner`]. Entries written without a tick (by
+/// `LearningSystem::update` or [`Knowledge::record_quote`]) count as
+/// observed the next time this system sees them. Quotes from `partner_quotes` go with their item's price.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Knowledge>();
+/// world.insert(KnowledgeDecayConfig { price_ttl: 100, ..Default::default() });
+///
+/// let mut knowledge = Knowledge::default();
+/// knowledge.observe_price("water", 2.0, 0);
+/// let agent = world.create_entity().with(knowledge).build();
+///
+/// world.insert(CurrentTick(150));
+/// KnowledgeDecaySystem.run_now(&world);
+/// assert!(world.read_storage::<Knowledge>().get(agent).unwrap().known_prices.is_empty());
+/// ```
+pub struct KnowledgeDecaySystem;
+
+impl KnowledgeDecaySystem {
+    /// Stable dispatcher name
+    pub const NAME: &'static str = "knowledge_decay";
+}
+
+impl<'a> System<'a> for KnowledgeDecaySystem {
+    type SystemData = (
+        WriteStorage<'a, Knowledge>,
+        Option<Read<'a, KnowledgeDecayConfig>>,
+        Option<Read<'a, CurrentTick>>,
+    );
+
+    fn run(&mut self, (mut knowledge_storage, config, current_tick): Self::SystemData) {
+        let config = config.map(|c| (*c).clone()).unwrap_or_default();
+        let now = current_tick.map_or(0, |t| t.0);
+        for knowledge in (&mut knowledge_storage).join() {
+            let prices: Vec<String> = knowledge.known_prices.keys().cloned().collect();
+            let kept = retain_fresh(
+                &mut knowledge.price_ticks,
+                prices,
+                now,
+                config.price_ttl,
+                config.max_prices,
+            );
+            knowledge.known_prices.retain(|item, _| kept.contains_key(item));
+            knowledge.partner_quotes.retain(|quote| kept.contains_key(&quote.item));
+
+            let partners = knowledge.trade_partners.clone();
+            let kept = retain_fresh(
+                &mut knowledge.partner_ticks,
+                partners,
+                now,
+                config.partner_ttl,
+                config.max_partners,
+            );
+            knowledge.trade_partners.retain(|partner| kept.contains_key(partner));
+        }
+    }
+}
+
+/// Stamp untimed `keys`, then drop expired and excess ones from `ticks`
+///
+/// Returns the surviving keys (the updated `ticks`).
+fn retain_fresh(
+    ticks: &mut HashMap<String, u64>,
+    keys: Vec<String>,
+    now: u64,
+    ttl: u64,
+    max: usize,
+) -> &HashMap<String, u64> {
+    let mut present: Vec<(String, u64)> = keys
+        .into_iter()
+        .map(|key| {
+            let tick = *ticks.entry(key.clone()).or_insert(now);
+            (key, tick)
+        })
+        .filter(|(_, tick)| now.saturating_sub(*tick) <= ttl)
+        .collect();
+
+    // Most recent first, ties by key so eviction is deterministic
+    present.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    present.truncate(max);
+
+    *ticks = present.into_iter().collect();
+    ticks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AgentId, LearningSystem};
+    use pretty_assertions::assert_eq;
+
+    fn world_with(knowledge: Knowledge, config: KnowledgeDecayConfig) -> (World, Entity) {
+        let mut world = World::new();
+        world.register::<Knowledge>();
+        world.insert(config);
+        let agent = world.create_entity().with(knowledge).build();
+        (world, agent)
+    }
+
+    fn decay_at(world: &mut World, tick: u64) {
+        world.insert(CurrentTick(tick));
+        KnowledgeDecaySystem.run_now(world);
+    }
+
+    fn sorted(mut keys: Vec<String>) -> Vec<String> {
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn test_stale_price_is_forgotten_after_ttl() {
+        let mut knowledge = Knowledge::default();
+        knowledge.record_quote("water", AgentId(7), 2.0);
+        knowledge.observe_price("water", 2.0, 0);
+        knowledge.observe_price("bread", 5.0, 0);
+        let config = KnowledgeDecayConfig { price_ttl: 100, ..Default::default() };
+        let (mut world, agent) = world_with(knowledge, config);
+
+        decay_at(&mut world, 100);
+        assert_eq!(world.read_storage::<Knowledge>().get(agent).unwrap().known_prices.len(), 2);
+
+        // Bread is bought again; water is not
+        world
+            .write_storage::<Knowledge>()
+            .get_mut(agent)
+            .unwrap()
+            .observe_price("bread", 6.0, 120);
+        decay_at(&mut world, 150);
+
+        let storage = world.read_storage::<Knowledge>();
+        let knowledge = storage.get(agent).unwrap();
+        assert_eq!(sorted(knowledge.known_prices.keys().cloned().collect()), vec!["bread"]);
+        assert!(knowledge.partner_quotes.is_empty());
+        assert_eq!(knowledge.price_ticks.get("bread"), Some(&120));
+    }
+
+    #[test]
+    fn test_requoted_price_survives_its_ttl() {
+        let mut knowledge = Knowledge::default();
+        knowledge.observe_price("water", 2.0, 0);
+        let config = KnowledgeDecayConfig { price_ttl: 100, ..Default::default() };
+        let (mut world, agent) = world_with(knowledge, config);
+
+        // Quoted again at tick 90, well after the first observation
+        world
+            .write_storage::<Knowledge>()
+            .get_mut(agent)
+            .unwrap()
+            .record_quote("water", AgentId(7), 3.0);
+        decay_at(&mut world, 90);
+        decay_at(&mut world, 150);
+
+        let storage = world.read_storage::<Knowledge>();
+        let knowledge = storage.get(agent).unwrap();
+        assert_eq!(knowledge.known_prices.get("water"), Some(&3.0));
+        assert_eq!(knowledge.partner_quotes.len(), 1);
+        assert_eq!(knowledge.price_ticks.get("water"), Some(&90));
+    }
+
+    #[test]
+    fn test_caps_keep_most_recent_and_untimed_entries_start_fresh() {
+        let mut knowledge = Knowledge::default();
+        for (tick, partner) in ["ann", "bob", "cat"].iter().enumerate() {
+            knowledge.meet_partner(partner, tick as u64);
+        }
+        knowledge.meet_partner("ann", 3);
+        LearningSystem::update(&mut knowledge, "salt", 1.0);
+        let config = KnowledgeDecayConfig {
+            price_ttl: 10,
+            max_partners: 2,
+            ..Default::default()
+        };
+        let (mut world, agent) = world_with(knowledge, config);
+
+        decay_at(&mut world, 5);
+        {
+            let storage = world.read_storage::<Knowledge>();
+            let knowledge = storage.get(agent).unwrap();
+            assert_eq!(knowledge.trade_partners, vec!["ann", "cat"]);
+            assert_eq!(knowledge.price_ticks.get("salt"), Some(&5));
+        }
+
+        decay_at(&mut world, 16);
+        assert!(world.read_storage::<Knowledge>().get(agent).unwrap().known_prices.is_empty());
+    }
+}
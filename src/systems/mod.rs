@@ -3,7 +3,50 @@
 //! This module contains systems that process game logic each tick.
 
 pub mod reputation;
+pub mod conservation;
+pub mod invariants;
+pub mod economy;
+pub mod consumption;
+pub mod stock;
+pub mod environment;
+pub mod harvest;
+pub mod knowledge;
+pub mod regen;
+pub mod rest;
+pub mod savings;
+pub mod tips;
+pub mod trust;
+pub mod builder;
+pub mod optional;
 
 pub use reputation::{
-    ReputationUpdateSystem, ReputationDecaySystem, ReputationDecayConfig, CurrentTick,
+    ReputationUpdateSystem, ReputationUpdateConfig, ReputationDecaySystem, ReputationDecayConfig,
+    CurrentTick, OutcomeFn, rebuild_reputation,
 };
+pub use conservation::{
+    total_currency, total_currency_exact, ConservationGuard, ConservationAction, CurrencyLeak,
+    ConservationSnapshotSystem, ConservationCheckSystem,
+};
+pub use invariants::{check_invariants, violating_agent, InvariantViolation};
+pub use economy::{
+    transfer_currency, transfer_exact, deposit_currency, mint_currency, EconomyPolicy, CurrencyJournal,
+    MintRecord, CurrencyError, ReputationJudge, TradeEligibility, TradePolicy,
+};
+pub use consumption::{
+    consume_plan, consume_for_need, consume_item, execute_consume, ConsumeAttempt,
+    ConsumptionConfig, ConsumptionSystem,
+};
+pub use environment::{EnvironmentConfig, EnvironmentEffect, EnvironmentEffectSystem};
+pub use harvest::{harvest_action, harvest_from_location, harvest_into};
+pub use knowledge::{KnowledgeDecayConfig, KnowledgeDecaySystem};
+pub use stock::{total_resource_stock, resource_source_count, resource_sources, resource_forecast, ResourceForecast};
+pub use regen::{current_season, ResourceRegenSystem, Season, SeasonConfig};
+pub use rest::{apply_rest, RestConfig, RestSystem, Resting};
+pub use savings::{
+    deposit_savings, withdraw_savings, total_savings, InterestConfig, InterestSystem,
+    SavingsAccount, SavingsBank,
+};
+pub use tips::{ResourceTipSystem, TipConfig};
+pub use trust::{ReputationThresholdSystem, TrustThresholds};
+pub use builder::{simulate_until, step, SimulationBuilder};
+pub use optional::{OptionalReadStorage, OptionalWriteStorage};
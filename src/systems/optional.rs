@@ -0,0 +1,73 @@
+//! Storages for opt-in components
+//!
+//! A system's `ReadStorage`/`WriteStorage` panics under `run_now` when the
+//! component was never registered. [`OptionalReadStorage`] and
+//! [`OptionalWriteStorage`] fetch `None` instead, like `Option<Read<..>>`
+//! does for resources, so systems can honor opt-in components without every
+//! world having to register them.
+
+use specs::prelude::*;
+use specs::shred::ResourceId;
+use specs::storage::MaskedStorage;
+use specs::world::EntitiesRes;
+use std::ops::{Deref, DerefMut};
+
+/// `ReadStorage` that is `None` while `T` is unregistered
+pub struct OptionalReadStorage<'a, T: Component>(pub Option<ReadStorage<'a, T>>);
+
+impl<'a, T: Component> Deref for OptionalReadStorage<'a, T> {
+    type Target = Option<ReadStorage<'a, T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, T: Component> SystemData<'a> for OptionalReadStorage<'a, T> {
+    fn setup(_: &mut World) {}
+
+    fn fetch(world: &'a World) -> Self {
+        Self(world.has_value::<MaskedStorage<T>>().then(|| world.read_storage::<T>()))
+    }
+
+    fn reads() -> Vec<ResourceId> {
+        vec![ResourceId::new::<EntitiesRes>(), ResourceId::new::<MaskedStorage<T>>()]
+    }
+
+    fn writes() -> Vec<ResourceId> {
+        vec![]
+    }
+}
+
+/// `WriteStorage` that is `None` while `T` is unregistered
+pub struct OptionalWriteStorage<'a, T: Component>(pub Option<WriteStorage<'a, T>>);
+
+impl<'a, T: Component> Deref for OptionalWriteStorage<'a, T> {
+    type Target = Option<WriteStorage<'a, T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, T: Component> DerefMut for OptionalWriteStorage<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, T: Component> SystemData<'a> for OptionalWriteStorage<'a, T> {
+    fn setup(_: &mut World) {}
+
+    fn fetch(world: &'a World) -> Self {
+        Self(world.has_value::<MaskedStorage<T>>().then(|| world.write_storage::<T>()))
+    }
+
+    fn reads() -> Vec<ResourceId> {
+        vec![ResourceId::new::<EntitiesRes>()]
+    }
+
+    fn writes() -> Vec<ResourceId> {
+        vec![ResourceId::new::<MaskedStorage<T>>()]
+    }
+}
@@ -0,0 +1,196 @@
+//! Resource regeneration and seasons
+//!
+//! [`ResourceRegenSystem`] regenerates every `ResourceSource` once per tick.
+//! With a [`SeasonConfig`] resource in the world, each source's
+//! `regeneration_rate` is first scaled by the multiplier for the current
+//! season, so winter can slow plant regrowth while water keeps flowing. The
+//! season follows from `CurrentTick` alone, so replaying the same ticks
+//! always gives the same seasons.
+
+use crate::agent::components::ResourceSource;
+use crate::systems::CurrentTick;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use std::collections::HashMap;
+
+/// One quarter of the seasonal cycle, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    /// All seasons in cycle order
+    pub const ALL: [Season; 4] = [Season::Spring, Season::Summer, Season::Autumn, Season::Winter];
+
+    /// Position in the cycle (spring = 0)
+    pub fn index(self) -> usize {
+        match self {
+            Season::Spring => 0,
+            Season::Summer => 1,
+            Season::Autumn => 2,
+            Season::Winter => 3,
+        }
+    }
+}
+
+/// World resource describing the seasonal cycle
+///
+/// Multipliers are listed in [`Season::ALL`] order. The default multiplies
+/// every season by 1.0, so adding it changes nothing until tuned.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let mut seasons = SeasonConfig::default();
+/// seasons.resource_multipliers.insert("plant".into(), [1.2, 1.5, 0.8, 0.2]);
+///
+/// assert_eq!(seasons.multiplier(Season::Winter, "plant"), 0.2);
+/// assert_eq!(seasons.multiplier(Season::Winter, "water"), 1.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeasonConfig {
+    /// Ticks in a full year; each season lasts a quarter of it
+    pub cycle_length: u64,
+    /// Regeneration multiplier per season for every resource type
+    pub multipliers: [f32; 4],
+    /// Per-season multipliers for specific resource types, replacing
+    /// `multipliers` for them
+    #[serde(serialize_with = "crate::snapshot::sorted_map")]
+    pub resource_multipliers: HashMap<String, [f32; 4]>,
+}
+
+impl Default for SeasonConfig {
+    fn default() -> Self {
+        Self {
+            cycle_length: 400,
+            multipliers: [1.0; 4],
+            resource_multipliers: HashMap::new(),
+        }
+    }
+}
+
+impl SeasonConfig {
+    /// Regeneration multiplier for `resource_type` during `season`
+    ///
+    /// Negative or NaN multipliers count as 0 (no regeneration).
+    pub fn multiplier(&self, season: Season, resource_type: &str) -> f32 {
+        let table = self
+            .resource_multipliers
+            .get(resource_type)
+            .unwrap_or(&self.multipliers);
+        let multiplier = table[season.index()];
+        if multiplier > 0.0 {
+            multiplier
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Season in effect at `tick`
+///
+/// Spring starts at tick 0 and the cycle repeats every `cycle_length`
+/// ticks. A `cycle_length` of 0 means no seasons: always spring.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let seasons = SeasonConfig { cycle_length: 100, ..Default::default() };
+/// assert_eq!(current_season(0, &seasons), Season::Spring);
+/// assert_eq!(current_season(25, &seasons), Season::Summer);
+/// assert_eq!(current_season(99, &seasons), Season::Winter);
+/// assert_eq!(current_season(100, &seasons), Season::Spring);
+/// ```
+pub fn current_season(tick: u64, config: &SeasonConfig) -> Season {
+    if config.cycle_length == 0 {
+        return Season::Spring;
+    }
+    let position = u128::from(tick % config.cycle_length);
+    let quarter = position * 4 / u128::from(config.cycle_length);
+    Season::ALL[quarter as usize]
+}
+
+/// Regenerates every [`ResourceSource`] once per tick
+///
+/// Reads the optional [`SeasonConfig`] and [`CurrentTick`] resources;
+/// without a `SeasonConfig` sources regenerate at their plain rate.
+pub struct ResourceRegenSystem;
+
+impl ResourceRegenSystem {
+    /// Stable dispatcher name
+    pub const NAME: &'static str = "resource_regen";
+}
+
+impl<'a> System<'a> for ResourceRegenSystem {
+    type SystemData = (
+        WriteStorage<'a, ResourceSource>,
+        Option<Read<'a, SeasonConfig>>,
+        Option<Read<'a, CurrentTick>>,
+    );
+
+    fn run(&mut self, (mut sources, seasons, tick): Self::SystemData) {
+        let Some(seasons) = seasons else {
+            for source in (&mut sources).join() {
+                source.regenerate();
+            }
+            return;
+        };
+        let season = current_season(tick.map_or(0, |t| t.0), &seasons);
+        for source in (&mut sources).join() {
+            let multiplier = seasons.multiplier(season, &source.resource_type);
+            source.regenerate_scaled(multiplier);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Stock gained by a fresh rate-4 plant source over one tick at `tick`
+    fn regrowth_at(tick: u64, seasons: Option<SeasonConfig>) -> u32 {
+        let mut world = World::new();
+        world.register::<ResourceSource>();
+        world.insert(CurrentTick(tick));
+        if let Some(seasons) = seasons {
+            world.insert(seasons);
+        }
+        let source = world
+            .create_entity()
+            .with(ResourceSource::new("plant".into(), "grass".into(), 4.0, 10))
+            .build();
+
+        ResourceRegenSystem.run_now(&world);
+        let stock = world.read_storage::<ResourceSource>().get(source).unwrap().current_stock;
+        stock - 10
+    }
+
+    #[test]
+    fn test_source_regrows_faster_in_summer_than_winter() {
+        let mut seasons = SeasonConfig { cycle_length: 40, ..Default::default() };
+        seasons.resource_multipliers.insert("plant".into(), [1.0, 2.0, 1.0, 0.25]);
+
+        let summer = regrowth_at(15, Some(seasons.clone()));
+        let winter = regrowth_at(35, Some(seasons.clone()));
+        assert_eq!((summer, winter), (8, 1));
+        // Next year's summer matches this one
+        assert_eq!(regrowth_at(55, Some(seasons)), summer);
+    }
+
+    #[test]
+    fn test_default_seasons_change_nothing() {
+        let plain = regrowth_at(0, None);
+        assert_eq!(plain, 4);
+        for tick in [0, 150, 250, 399] {
+            assert_eq!(regrowth_at(tick, Some(SeasonConfig::default())), plain);
+        }
+    }
+}
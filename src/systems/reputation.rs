@@ -2,9 +2,10 @@
 //!
 //! This system processes transaction events and updates agent reputation knowledge.
 
-use crate::events::TransactionLog;
-use crate::{Agent, ReputationKnowledge};
+use crate::events::{TransactionEvent, TransactionLog};
+use crate::{Agent, AgentId, ReputationKnowledge};
 use specs::prelude::*;
+use std::collections::HashMap;
 
 /// System that processes transaction events and updates reputation
 ///
@@ -23,6 +24,27 @@ use specs::prelude::*;
 /// 3. Update last_interaction_tick and interaction_count
 /// 4. Symmetric update for agent2's view of agent1
 ///
+/// # Custom Outcomes
+///
+/// Set an [`OutcomeFn`] with [`with_outcome_fn`](Self::with_outcome_fn) to
+/// score events yourself. It returns `(alpha_delta, beta_delta)`, applied to
+/// both agents' views instead of the outcome weight.
+///
+/// ```
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// // Large deals matter more
+/// let system = ReputationUpdateSystem::default().with_outcome_fn(|event| {
+///     let scale = 1.0 + event.price.unwrap_or(0.0) / 100.0;
+///     match event.outcome {
+///         Outcome::Positive(w) => (w * scale, 0.0),
+///         Outcome::Negative(w) => (0.0, w * scale),
+///         Outcome::Neutral => (0.0, 0.0),
+///     }
+/// });
+/// ```
+///
 /// # Example
 ///
 /// ```
@@ -30,37 +52,141 @@ use specs::prelude::*;
 /// use specs::prelude::*;
 ///
 /// let mut world = World::new();
+/// world.register::<Agent>();
 /// world.register::<ReputationKnowledge>();
 /// world.insert(TransactionLog::new());
 ///
-/// let mut system = ReputationUpdateSystem;
+/// let mut system = ReputationUpdateSystem::default();
 /// system.run_now(&world);
 /// ```
-pub struct ReputationUpdateSystem;
+///
+/// # Smoothing
+///
+/// Insert a [`ReputationUpdateConfig`] resource to cap how much evidence one
+/// event can add to a view per run. The excess is carried over and applied,
+/// at most `max_single_delta` per run, on later runs, so a single huge event
+/// erodes trust gradually but still ends up with the same long-run score.
+#[derive(Default)]
+pub struct ReputationUpdateSystem {
+    outcome_fn: Option<OutcomeFn>,
+    /// Evidence still to apply, keyed by (observer, subject)
+    pending: HashMap<(AgentId, AgentId), (f32, f32)>,
+}
 
-impl<'a> System<'a> for ReputationUpdateSystem {
-    type SystemData = (
-        Entities<'a>,
-        ReadStorage<'a, Agent>,
-        WriteStorage<'a, ReputationKnowledge>,
-        Write<'a, TransactionLog>,
-    );
+/// Custom scoring of a transaction event as `(alpha_delta, beta_delta)`
+pub type OutcomeFn = Box<dyn Fn(&TransactionEvent) -> (f32, f32) + Send + Sync>;
 
-    fn run(
+/// Configuration for reputation updates
+///
+/// Defaults to uncapped, which applies every event in full immediately.
+#[derive(Debug, Clone, Copy)]
+pub struct ReputationUpdateConfig {
+    /// Most alpha or beta one view can gain from a single event in one run
+    ///
+    /// Excess evidence is applied on subsequent runs. Non-finite or
+    /// non-positive values mean uncapped.
+    pub max_single_delta: f32,
+}
+
+impl Default for ReputationUpdateConfig {
+    fn default() -> Self {
+        Self {
+            max_single_delta: f32::INFINITY,
+        }
+    }
+}
+
+impl ReputationUpdateConfig {
+    /// Cap each event's contribution per run at `max_single_delta`
+    pub fn capped(max_single_delta: f32) -> Self {
+        Self { max_single_delta }
+    }
+
+    /// The cap, if one is in effect
+    fn cap(&self) -> Option<f32> {
+        Some(self.max_single_delta).filter(|cap| cap.is_finite() && *cap > 0.0)
+    }
+}
+
+impl ReputationUpdateSystem {
+    /// Stable dispatcher name
+    pub const NAME: &'static str = "reputation_update";
+
+    /// Create a system using the default `Outcome::weight()` update
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Score events with a custom closure instead of `Outcome::weight()`
+    pub fn with_outcome_fn<F>(mut self, outcome_fn: F) -> Self
+    where
+        F: Fn(&TransactionEvent) -> (f32, f32) + Send + Sync + 'static,
+    {
+        self.outcome_fn = Some(Box::new(outcome_fn));
+        self
+    }
+
+    /// Whether smoothed evidence is still waiting to be applied
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Apply up to `cap` of each view's carried-over evidence
+    ///
+    /// Each step counts as an interaction at `tick`, or at the view's last
+    /// interaction if that is later.
+    fn apply_pending(
         &mut self,
-        (entities, agents, mut reputation_storage, mut transaction_log): Self::SystemData,
+        cap: Option<f32>,
+        tick: u64,
+        entities: &Entities,
+        agents: &ReadStorage<Agent>,
+        reputation_storage: &mut WriteStorage<ReputationKnowledge>,
     ) {
-        // Drain events from the log (process and clear)
-        let events = transaction_log.drain();
+        let step = cap.unwrap_or(f32::INFINITY);
+        let by_id: HashMap<AgentId, Entity> = (entities, agents)
+            .join()
+            .map(|(entity, agent)| (agent.id, entity))
+            .collect();
+
+        self.pending.retain(|(observer, subject), (alpha, beta)| {
+            let rep = match by_id
+                .get(observer)
+                .and_then(|entity| reputation_storage.get_mut(*entity))
+            {
+                Some(rep) => rep,
+                // Observer is gone; drop its backlog
+                None => return false,
+            };
+
+            let (alpha_step, beta_step) = (alpha.min(step), beta.min(step));
+            let last = rep.first_hand.get(subject).map_or(0, |view| view.last_interaction_tick);
+            rep.add_evidence(*subject, alpha_step, beta_step, tick.max(last));
+            *alpha -= alpha_step;
+            *beta -= beta_step;
+            *alpha > 0.0 || *beta > 0.0
+        });
+    }
+}
 
+impl ReputationUpdateSystem {
+    /// Apply events to both parties' views, in order
+    fn apply_events(
+        &mut self,
+        events: &[TransactionEvent],
+        entities: &Entities,
+        agents: &ReadStorage<Agent>,
+        reputation_storage: &mut WriteStorage<ReputationKnowledge>,
+        cap: Option<f32>,
+    ) {
         for event in events {
-            let weight = event.outcome.weight();
+            let custom_delta = self.outcome_fn.as_ref().map(|f| f(event));
 
             // Find entities for both agents
             let mut agent1_entity = None;
             let mut agent2_entity = None;
 
-            for (entity, agent) in (&entities, &agents).join() {
+            for (entity, agent) in (entities, agents).join() {
                 if agent.id == event.agent1 {
                     agent1_entity = Some(entity);
                 }
@@ -75,18 +201,151 @@ impl<'a> System<'a> for ReputationUpdateSystem {
             // Update agent1's view of agent2
             if let Some(entity1) = agent1_entity {
                 if let Some(rep1) = reputation_storage.get_mut(entity1) {
-                    rep1.update_reputation(event.agent2, weight, event.tick);
+                    apply_event(
+                        rep1,
+                        event.agent1,
+                        event.agent2,
+                        event,
+                        custom_delta,
+                        cap,
+                        &mut self.pending,
+                    );
                 }
             }
 
             // Symmetric update: agent2's view of agent1
             if let Some(entity2) = agent2_entity {
                 if let Some(rep2) = reputation_storage.get_mut(entity2) {
-                    rep2.update_reputation(event.agent1, weight, event.tick);
+                    apply_event(
+                        rep2,
+                        event.agent2,
+                        event.agent1,
+                        event,
+                        custom_delta,
+                        cap,
+                        &mut self.pending,
+                    );
                 }
             }
         }
     }
+
+    /// Recompute every agent's reputation from a recorded log
+    ///
+    /// Clears all first-hand views (keeping each agent's `trust_level`) and
+    /// replays `log` in order through the same logic as [`run`](System::run),
+    /// including this system's [`OutcomeFn`]. Smoothing from
+    /// [`ReputationUpdateConfig`] is not applied: the result is the fully
+    /// settled evidence. The world's own `TransactionLog` is left untouched.
+    pub fn rebuild(&mut self, world: &World, log: &TransactionLog) {
+        let (entities, agents, mut reputation_storage) = world.system_data::<(
+            Entities,
+            ReadStorage<Agent>,
+            WriteStorage<ReputationKnowledge>,
+        )>();
+
+        for knowledge in (&mut reputation_storage).join() {
+            knowledge.first_hand.clear();
+        }
+        self.pending.clear();
+        self.apply_events(log.events(), &entities, &agents, &mut reputation_storage, None);
+    }
+}
+
+/// Recompute all reputation from a recorded `TransactionLog`
+///
+/// Event-sourced alternative to trusting live `ReputationKnowledge`: resets
+/// every view to its prior and replays `log` with the default update logic,
+/// giving the same scores as processing the events incrementally with
+/// [`ReputationUpdateSystem`]. Use [`ReputationUpdateSystem::rebuild`] to
+/// replay with a custom [`OutcomeFn`].
+///
+/// The live system drains the world's log each run, so keep your own copy of
+/// the events you want to audit.
+///
+/// # Example
+///
+/// ```
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Agent>();
+/// world.register::<ReputationKnowledge>();
+///
+/// let mut recorded = TransactionLog::new();
+/// recorded.add(TransactionEvent::positive_interaction(AgentId(1), AgentId(2), 1.0, 0));
+///
+/// rebuild_reputation(&world, &recorded);
+/// ```
+pub fn rebuild_reputation(world: &World, log: &TransactionLog) {
+    ReputationUpdateSystem::new().rebuild(world, log);
+}
+
+/// Apply one event to `observer`'s view of `subject`, deferring any excess over `cap`
+fn apply_event(
+    rep: &mut ReputationKnowledge,
+    observer: AgentId,
+    subject: AgentId,
+    event: &TransactionEvent,
+    custom_delta: Option<(f32, f32)>,
+    cap: Option<f32>,
+    pending: &mut HashMap<(AgentId, AgentId), (f32, f32)>,
+) {
+    let (alpha_delta, beta_delta) = match custom_delta {
+        Some(delta) => delta,
+        None => {
+            let weight = event.outcome.weight();
+            (weight.max(0.0), (-weight).max(0.0))
+        }
+    };
+
+    let cap = match cap {
+        Some(cap) if alpha_delta > cap || beta_delta > cap => cap,
+        // Within the cap (or uncapped): apply exactly as before
+        _ => {
+            match custom_delta {
+                Some((a, b)) => rep.add_evidence(subject, a, b, event.tick),
+                None => rep.update_reputation(subject, event.outcome.weight(), event.tick),
+            }
+            return;
+        }
+    };
+
+    let (alpha_now, beta_now) = (alpha_delta.max(0.0).min(cap), beta_delta.max(0.0).min(cap));
+    rep.add_evidence(subject, alpha_now, beta_now, event.tick);
+
+    let carry = pending.entry((observer, subject)).or_insert((0.0, 0.0));
+    carry.0 += alpha_delta.max(0.0) - alpha_now;
+    carry.1 += beta_delta.max(0.0) - beta_now;
+}
+
+impl<'a> System<'a> for ReputationUpdateSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Agent>,
+        WriteStorage<'a, ReputationKnowledge>,
+        Write<'a, TransactionLog>,
+        Option<Read<'a, ReputationUpdateConfig>>,
+        Option<Read<'a, CurrentTick>>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, agents, mut reputation_storage, mut transaction_log, config, tick): Self::SystemData,
+    ) {
+        let cap = config.and_then(|config| config.cap());
+
+        // Carried-over evidence from earlier oversized events goes first
+        if !self.pending.is_empty() {
+            let tick = tick.map_or(0, |t| t.0);
+            self.apply_pending(cap, tick, &entities, &agents, &mut reputation_storage);
+        }
+
+        // Drain events from the log (process and clear)
+        let events = transaction_log.drain();
+        self.apply_events(&events, &entities, &agents, &mut reputation_storage, cap);
+    }
 }
 
 /// System that applies reputation decay over time
@@ -107,7 +366,7 @@ impl<'a> System<'a> for ReputationUpdateSystem {
 ///
 /// let mut world = World::new();
 /// world.register::<ReputationKnowledge>();
-/// world.insert(ReputationDecayConfig { decay_rate: 0.0001 });
+/// world.insert(ReputationDecayConfig { decay_rate: 0.0001, ..Default::default() });
 /// world.insert(CurrentTick(1000));
 ///
 /// let mut system = ReputationDecaySystem;
@@ -115,17 +374,45 @@ impl<'a> System<'a> for ReputationUpdateSystem {
 /// ```
 pub struct ReputationDecaySystem;
 
+impl ReputationDecaySystem {
+    /// Stable dispatcher name
+    pub const NAME: &'static str = "reputation_decay";
+}
+
 /// Configuration for reputation decay
+///
+/// A view is rebalanced (its decayed score folded back into alpha/beta and
+/// its evidence normalized) once either `rebalance_after_ticks` have passed
+/// since its last interaction or its total evidence exceeds
+/// `rebalance_evidence_threshold`.
+///
+/// With a non-zero `confidence_decay_rate`, views not updated this tick also
+/// lose evidence: on every run, the evidence above a fresh view's prior
+/// (alpha + beta = 2) shrinks by that fraction, with alpha and beta scaled
+/// alike so the score is unchanged. Old relationships thus become less
+/// certain, and new evidence moves their score further.
 #[derive(Debug, Clone, Copy)]
 pub struct ReputationDecayConfig {
     /// Rate of decay per tick (0.0001 - 0.001 typical)
     pub decay_rate: f32,
+    /// Ticks without interaction after which a view is rebalanced
+    pub rebalance_after_ticks: u64,
+    /// Total evidence (alpha + beta) above which a view is rebalanced
+    pub rebalance_evidence_threshold: f32,
+    /// Fraction of excess evidence forgotten per run by stale views (0.0 = off)
+    pub confidence_decay_rate: f32,
 }
 
+/// Evidence (alpha + beta) of a fresh view's uniform prior
+const PRIOR_EVIDENCE: f32 = 2.0;
+
 impl Default for ReputationDecayConfig {
     fn default() -> Self {
         Self {
             decay_rate: 0.0001,
+            rebalance_after_ticks: 10000,
+            rebalance_evidence_threshold: 1000.0,
+            confidence_decay_rate: 0.0,
         }
     }
 }
@@ -145,9 +432,21 @@ impl<'a> System<'a> for ReputationDecaySystem {
         &mut self,
         (mut reputation_storage, decay_config, current_tick): Self::SystemData,
     ) {
+        let confidence_decay = decay_config.confidence_decay_rate.clamp(0.0, 1.0);
         for reputation in (&mut reputation_storage).join() {
             // Apply decay to all first-hand reputation views
             for view in reputation.first_hand.values_mut() {
+                // Forget part of the evidence of views not refreshed this tick
+                let total = view.alpha + view.beta;
+                if confidence_decay > 0.0
+                    && view.last_interaction_tick < current_tick.0
+                    && total > PRIOR_EVIDENCE
+                {
+                    let kept = PRIOR_EVIDENCE + (total - PRIOR_EVIDENCE) * (1.0 - confidence_decay);
+                    view.alpha *= kept / total;
+                    view.beta *= kept / total;
+                }
+
                 // Decay is applied by updating the view's effective score
                 // The decay happens automatically when score_with_decay is called,
                 // but we can also periodically rebalance alpha/beta to prevent overflow
@@ -157,7 +456,9 @@ impl<'a> System<'a> for ReputationDecaySystem {
                 // Only rebalance if we have significant time passed or high values
                 let ticks_since =
                     current_tick.0.saturating_sub(view.last_interaction_tick);
-                if ticks_since > 10000 || (view.alpha + view.beta) > 1000.0 {
+                if ticks_since > decay_config.rebalance_after_ticks
+                    || (view.alpha + view.beta) > decay_config.rebalance_evidence_threshold
+                {
                     // Rebalance to prevent overflow while preserving the decayed score
                     let total = view.alpha + view.beta;
                     let new_alpha = current_score * total;
@@ -219,7 +520,7 @@ mod tests {
         }
 
         // Run system
-        let mut system = ReputationUpdateSystem;
+        let mut system = ReputationUpdateSystem::default();
         system.run_now(&world);
         world.maintain();
 
@@ -253,7 +554,7 @@ mod tests {
         }
 
         // Run system
-        let mut system = ReputationUpdateSystem;
+        let mut system = ReputationUpdateSystem::default();
         system.run_now(&world);
         world.maintain();
 
@@ -298,7 +599,7 @@ mod tests {
         }
 
         // Run system
-        let mut system = ReputationUpdateSystem;
+        let mut system = ReputationUpdateSystem::default();
         system.run_now(&world);
         world.maintain();
 
@@ -321,6 +622,7 @@ mod tests {
         // Setup decay config
         world.insert(ReputationDecayConfig {
             decay_rate: 0.001,
+            ..Default::default()
         });
         world.insert(CurrentTick(1000));
 
@@ -336,7 +638,7 @@ mod tests {
         }
 
         // Update reputation
-        let mut update_system = ReputationUpdateSystem;
+        let mut update_system = ReputationUpdateSystem::default();
         update_system.run_now(&world);
         world.maintain();
 
@@ -393,7 +695,7 @@ mod tests {
         }
 
         // Run system
-        let mut system = ReputationUpdateSystem;
+        let mut system = ReputationUpdateSystem::default();
         system.run_now(&world);
         world.maintain();
 
@@ -401,4 +703,311 @@ mod tests {
         let log = world.read_resource::<TransactionLog>();
         assert_eq!(log.len(), 0);
     }
+
+    fn seed_view(world: &mut World, weight: f32, tick: u64) {
+        let mut log = world.write_resource::<TransactionLog>();
+        log.add(TransactionEvent::positive_interaction(
+            AgentId(1),
+            AgentId(2),
+            weight,
+            tick,
+        ));
+        drop(log);
+        ReputationUpdateSystem::default().run_now(world);
+        world.maintain();
+    }
+
+    fn view_of_agent2(world: &World, agent1: Entity) -> crate::ReputationView {
+        let reputation_storage = world.read_storage::<ReputationKnowledge>();
+        reputation_storage
+            .get(agent1)
+            .unwrap()
+            .first_hand
+            .get(&AgentId(2))
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn test_default_rebalance_thresholds_match_previous_constants() {
+        let config = ReputationDecayConfig::default();
+        assert_eq!(config.rebalance_after_ticks, 10000);
+        assert_eq!(config.rebalance_evidence_threshold, 1000.0);
+    }
+
+    #[test]
+    fn test_default_config_does_not_rebalance_recent_view() {
+        let (mut world, agent1, _agent2) = create_test_world();
+        world.insert(ReputationDecayConfig::default());
+        world.insert(CurrentTick(500));
+        seed_view(&mut world, 20.0, 0);
+
+        let before = view_of_agent2(&world, agent1);
+        ReputationDecaySystem.run_now(&world);
+        let after = view_of_agent2(&world, agent1);
+
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_lower_rebalance_after_ticks_normalizes_earlier() {
+        let (mut world, agent1, _agent2) = create_test_world();
+        world.insert(ReputationDecayConfig {
+            rebalance_after_ticks: 100,
+            ..Default::default()
+        });
+        world.insert(CurrentTick(500));
+        seed_view(&mut world, 20.0, 0);
+
+        ReputationDecaySystem.run_now(&world);
+        let view = view_of_agent2(&world, agent1);
+
+        assert!((view.alpha + view.beta - 10.0).abs() < 1e-3);
+        assert_eq!(view.last_interaction_tick, 500);
+    }
+
+    #[test]
+    fn test_lower_evidence_threshold_normalizes_earlier() {
+        let (mut world, agent1, _agent2) = create_test_world();
+        world.insert(ReputationDecayConfig {
+            rebalance_evidence_threshold: 15.0,
+            ..Default::default()
+        });
+        world.insert(CurrentTick(0));
+        seed_view(&mut world, 20.0, 0);
+
+        ReputationDecaySystem.run_now(&world);
+        let view = view_of_agent2(&world, agent1);
+
+        assert!((view.alpha + view.beta - 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_custom_outcome_fn_overrides_weight() {
+        let trade = TransactionEvent::successful_trade(
+            AgentId(1),
+            AgentId(2),
+            "wheat".to_string(),
+            500.0,
+            100,
+        );
+
+        // Default: Positive(1.0) adds 1 to alpha
+        let (world, agent1, _agent2) = create_test_world();
+        world.write_resource::<TransactionLog>().add(trade.clone());
+        ReputationUpdateSystem::default().run_now(&world);
+        let default_view = view_of_agent2(&world, agent1);
+
+        // Custom: large deals count more, and also add a little doubt
+        let (world, agent1, agent2) = create_test_world();
+        world.write_resource::<TransactionLog>().add(trade);
+        ReputationUpdateSystem::new()
+            .with_outcome_fn(|event| (event.price.unwrap_or(0.0) / 100.0, 0.5))
+            .run_now(&world);
+        let custom_view = view_of_agent2(&world, agent1);
+
+        assert_eq!(default_view.alpha, 2.0);
+        assert_eq!(default_view.beta, 1.0);
+        assert_eq!(custom_view.alpha, 6.0);
+        assert_eq!(custom_view.beta, 1.5);
+        assert_eq!(custom_view.interaction_count, 1);
+
+        // Symmetric: agent2's view of agent1 gets the same deltas
+        let reputation_storage = world.read_storage::<ReputationKnowledge>();
+        let view = reputation_storage.get(agent2).unwrap().first_hand.get(&AgentId(1)).unwrap();
+        assert_eq!((view.alpha, view.beta), (6.0, 1.5));
+    }
+
+    #[test]
+    fn test_custom_outcome_fn_ignores_negative_deltas() {
+        let (world, agent1, _agent2) = create_test_world();
+        world
+            .write_resource::<TransactionLog>()
+            .add(TransactionEvent::positive_interaction(AgentId(1), AgentId(2), 1.0, 10));
+
+        ReputationUpdateSystem::new()
+            .with_outcome_fn(|_| (-5.0, 2.0))
+            .run_now(&world);
+
+        let view = view_of_agent2(&world, agent1);
+        assert_eq!((view.alpha, view.beta), (1.0, 3.0));
+    }
+
+    /// Agent1 trusts agent2 (alpha 11, beta 1), then `negatives` are logged
+    fn run_capped(cap: Option<f32>, negatives: &[f32]) -> (World, Entity, ReputationUpdateSystem) {
+        let (mut world, agent1, _agent2) = create_test_world();
+        let mut system = ReputationUpdateSystem::new();
+
+        world
+            .write_resource::<TransactionLog>()
+            .add(TransactionEvent::positive_interaction(AgentId(1), AgentId(2), 10.0, 0));
+        system.run_now(&world);
+
+        if let Some(cap) = cap {
+            world.insert(ReputationUpdateConfig::capped(cap));
+        }
+
+        {
+            let mut log = world.write_resource::<TransactionLog>();
+            for (tick, weight) in negatives.iter().enumerate() {
+                log.add(TransactionEvent::negative_interaction(
+                    AgentId(1),
+                    AgentId(2),
+                    *weight,
+                    tick as u64 + 1,
+                ));
+            }
+        }
+        system.run_now(&world);
+        (world, agent1, system)
+    }
+
+    #[test]
+    fn test_capped_update_damps_single_large_negative() {
+        let (uncapped, agent1, _) = run_capped(None, &[20.0]);
+        let (capped, capped_agent1, system) = run_capped(Some(2.0), &[20.0]);
+
+        let uncapped_view = view_of_agent2(&uncapped, agent1);
+        let capped_view = view_of_agent2(&capped, capped_agent1);
+
+        assert_eq!(uncapped_view.beta, 21.0);
+        assert_eq!(capped_view.beta, 3.0);
+        assert!(capped_view.score() > 0.75);
+        assert!(uncapped_view.score() < 0.4);
+        assert!(system.has_pending());
+    }
+
+    #[test]
+    fn test_capped_update_leaves_small_negatives_alone() {
+        let negatives = [1.0; 10];
+        let (uncapped, agent1, _) = run_capped(None, &negatives);
+        let (capped, capped_agent1, system) = run_capped(Some(2.0), &negatives);
+
+        let uncapped_view = view_of_agent2(&uncapped, agent1);
+        let capped_view = view_of_agent2(&capped, capped_agent1);
+
+        assert_eq!((capped_view.alpha, capped_view.beta), (uncapped_view.alpha, uncapped_view.beta));
+        assert_eq!(capped_view.interaction_count, uncapped_view.interaction_count);
+        assert!(!system.has_pending());
+    }
+
+    #[test]
+    fn test_capped_update_converges_to_uncapped_score() {
+        let (uncapped, agent1, _) = run_capped(None, &[20.0, 5.0]);
+        let (capped, capped_agent1, mut system) = run_capped(Some(2.0), &[20.0, 5.0]);
+
+        // Each further run applies at most 2.0 of the backlog
+        let mut runs = 0;
+        while system.has_pending() {
+            let before = view_of_agent2(&capped, capped_agent1).beta;
+            system.run_now(&capped);
+            assert!(view_of_agent2(&capped, capped_agent1).beta - before <= 2.0 + 1e-5);
+            runs += 1;
+            assert!(runs < 100);
+        }
+
+        let uncapped_view = view_of_agent2(&uncapped, agent1);
+        let capped_view = view_of_agent2(&capped, capped_agent1);
+        assert!((capped_view.score() - uncapped_view.score()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_carried_evidence_counts_as_interactions() {
+        let (mut world, agent1, mut system) = run_capped(Some(2.0), &[6.0]);
+        let before = view_of_agent2(&world, agent1);
+        assert_eq!((before.interaction_count, before.last_interaction_tick), (2, 1));
+
+        world.insert(CurrentTick(5));
+        system.run_now(&world);
+
+        let after = view_of_agent2(&world, agent1);
+        assert_eq!(after.beta, before.beta + 2.0);
+        assert_eq!((after.interaction_count, after.last_interaction_tick), (3, 5));
+        assert!(after.score() < before.score());
+    }
+
+    #[test]
+    fn test_rebuild_matches_incremental_updates() {
+        let (world, agent1, agent2) = create_test_world();
+        let mut recorded = TransactionLog::new();
+        let mut system = ReputationUpdateSystem::new();
+
+        // Process events live over several runs, recording a copy of each
+        let batches = [
+            vec![TransactionEvent::positive_interaction(AgentId(1), AgentId(2), 2.0, 1)],
+            vec![
+                TransactionEvent::negative_interaction(AgentId(2), AgentId(1), 0.5, 2),
+                TransactionEvent::successful_trade(AgentId(1), AgentId(2), "water".into(), 5.0, 3),
+            ],
+            vec![TransactionEvent::negative_interaction(AgentId(1), AgentId(2), 3.0, 4)],
+        ];
+        for batch in batches {
+            let mut log = world.write_resource::<TransactionLog>();
+            for event in batch {
+                recorded.add(event.clone());
+                log.add(event);
+            }
+            drop(log);
+            system.run_now(&world);
+        }
+
+        let live_1 = view_of_agent2(&world, agent1);
+        let live_2 = world.read_storage::<ReputationKnowledge>().get(agent2).unwrap().clone();
+
+        // Corrupt the live state, then rebuild from the recorded events
+        world
+            .write_storage::<ReputationKnowledge>()
+            .get_mut(agent1)
+            .unwrap()
+            .update_reputation(AgentId(2), 50.0, 9);
+        rebuild_reputation(&world, &recorded);
+
+        assert_eq!(view_of_agent2(&world, agent1), live_1);
+        let rebuilt_2 = world.read_storage::<ReputationKnowledge>().get(agent2).unwrap().clone();
+        assert_eq!(rebuilt_2, live_2);
+    }
+
+    #[test]
+    fn test_confidence_decays_for_stale_views_only() {
+        let (mut world, agent1, _agent2) = create_test_world();
+        world.insert(ReputationDecayConfig {
+            confidence_decay_rate: 0.05,
+            ..Default::default()
+        });
+        seed_view(&mut world, 20.0, 0);
+        let seeded = view_of_agent2(&world, agent1);
+
+        let mut confidences = Vec::new();
+        for tick in 1..=20 {
+            world.insert(CurrentTick(tick));
+            ReputationDecaySystem.run_now(&world);
+            confidences.push(view_of_agent2(&world, agent1).confidence());
+        }
+
+        // Un-refreshed: confidence falls every tick toward the prior...
+        assert!(confidences.windows(2).all(|w| w[1] < w[0]));
+        assert!(confidences[19] < seeded.confidence() / 2.0);
+        assert!(confidences[19] > 2.0);
+        // ...while the score ratio is kept
+        let stale = view_of_agent2(&world, agent1);
+        assert!((stale.score() - seeded.score()).abs() < 1e-4);
+
+        // A view updated this tick keeps its evidence
+        seed_view(&mut world, 20.0, 20);
+        let refreshed = view_of_agent2(&world, agent1).confidence();
+        ReputationDecaySystem.run_now(&world);
+        assert_eq!(view_of_agent2(&world, agent1).confidence(), refreshed);
+    }
+
+    #[test]
+    fn test_confidence_decay_off_by_default() {
+        let (mut world, agent1, _agent2) = create_test_world();
+        world.insert(ReputationDecayConfig::default());
+        seed_view(&mut world, 20.0, 0);
+        let before = view_of_agent2(&world, agent1);
+
+        world.insert(CurrentTick(50));
+        ReputationDecaySystem.run_now(&world);
+        assert_eq!(view_of_agent2(&world, agent1), before);
+    }
 }
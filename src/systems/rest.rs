@@ -0,0 +1,152 @@
+//! Resting: recovering tiredness and energy
+//!
+//! Decision-making emits `Intent::Rest`, but only the host knows when an agent
+//! actually lies down. The host adds the [`Resting`] marker while the agent
+//! rests and removes it when it gets up; [`RestSystem`] then lowers tiredness
+//! and restores energy each tick. Hunger and thirst are untouched.
+//!
+//! Need decay keeps raising tiredness while an agent rests, so the net change
+//! per tick is `tiredness_recovery - decay`. With the defaults rest easily
+//! outpaces [`NeedDecaySystem::BASE_RATE`]; custom configs must keep
+//! `tiredness_recovery` above the agent's tiredness decay to make progress.
+//!
+//! [`NeedDecaySystem::BASE_RATE`]: crate::NeedDecaySystem::BASE_RATE
+
+use crate::agent::components::{EnergyComponent, Needs};
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+
+/// Marker for agents that are currently resting (set by the host)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Resting;
+
+impl Component for Resting {
+    type Storage = NullStorage<Self>;
+}
+
+/// World resource tuning how effective rest is
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestConfig {
+    /// Tiredness removed per tick of rest
+    pub tiredness_recovery: f32,
+    /// Energy restored per tick of rest (capped at the agent's max)
+    pub energy_recovery: f32,
+}
+
+impl Default for RestConfig {
+    fn default() -> Self {
+        Self {
+            tiredness_recovery: 1.0,
+            energy_recovery: 1.0,
+        }
+    }
+}
+
+/// Apply one tick of rest
+///
+/// Lowers tiredness and restores energy (if given) by the config's rates,
+/// clamping both. Hunger and thirst are not changed.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let mut needs = Needs::new(40.0, 40.0, 80.0);
+/// let mut energy = EnergyComponent::new(50.0, 100.0);
+/// apply_rest(&mut needs, Some(&mut energy), &RestConfig::default());
+///
+/// assert_eq!(needs.tiredness, 79.0);
+/// assert_eq!(needs.hunger, 40.0);
+/// assert_eq!(energy.current, 51.0);
+/// ```
+pub fn apply_rest(needs: &mut Needs, energy: Option<&mut EnergyComponent>, config: &RestConfig) {
+    needs.tiredness -= config.tiredness_recovery.max(0.0);
+    needs.clamp();
+    if let Some(energy) = energy {
+        energy.current += config.energy_recovery.max(0.0);
+        energy.clamp();
+    }
+}
+
+/// Applies [`apply_rest`] to every agent with the [`Resting`] marker
+///
+/// Reads the optional [`RestConfig`] resource (defaults if absent).
+pub struct RestSystem;
+
+impl RestSystem {
+    /// Stable dispatcher name
+    pub const NAME: &'static str = "rest";
+}
+
+impl<'a> System<'a> for RestSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Resting>,
+        WriteStorage<'a, Needs>,
+        WriteStorage<'a, EnergyComponent>,
+        Option<Read<'a, RestConfig>>,
+    );
+
+    fn run(&mut self, (entities, resting, mut needs, mut energy, config): Self::SystemData) {
+        let config = config.map(|c| (*c).clone()).unwrap_or_default();
+        for (entity, _, needs) in (&entities, &resting, &mut needs).join() {
+            apply_rest(needs, energy.get_mut(entity), &config);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimulationBuilder;
+
+    #[test]
+    fn test_resting_agent_recovers_while_awake_agent_tires() {
+        let mut world = World::new();
+        let mut dispatcher = SimulationBuilder::new().with_need_decay().with_rest().build();
+        dispatcher.setup(&mut world);
+
+        let sleeper = world
+            .create_entity()
+            .with(Needs::new(50.0, 50.0, 80.0))
+            .with(EnergyComponent::new(20.0, 100.0))
+            .with(Resting)
+            .build();
+        let awake = world
+            .create_entity()
+            .with(Needs::new(50.0, 50.0, 80.0))
+            .with(EnergyComponent::new(20.0, 100.0))
+            .build();
+
+        for _ in 0..10 {
+            dispatcher.dispatch(&world);
+            world.maintain();
+        }
+
+        let needs = world.read_storage::<Needs>();
+        let energy = world.read_storage::<EnergyComponent>();
+        let (rested, tired) = (needs.get(sleeper).unwrap(), needs.get(awake).unwrap());
+        assert!(rested.tiredness < 71.0);
+        assert!(tired.tiredness > 80.0);
+        // Rest does not feed or water the agent
+        assert_eq!(rested.hunger, tired.hunger);
+        assert!(rested.thirst > 50.0);
+        assert_eq!(energy.get(sleeper).unwrap().current, 30.0);
+        assert_eq!(energy.get(awake).unwrap().current, 20.0);
+    }
+
+    #[test]
+    fn test_rest_clamps_at_bounds() {
+        let mut needs = Needs::new(0.0, 0.0, 0.5);
+        let mut energy = EnergyComponent::new(99.5, 100.0);
+        apply_rest(&mut needs, Some(&mut energy), &RestConfig::default());
+
+        assert_eq!(needs.tiredness, 0.0);
+        assert_eq!(energy.current, 100.0);
+
+        // Agents without energy still rest
+        apply_rest(&mut needs, None, &RestConfig::default());
+        assert_eq!(needs.tiredness, 0.0);
+    }
+}
@@ -0,0 +1,298 @@
+//! Savings accounts and interest
+//!
+//! Savers park surplus currency with a bank: an entity with a `Wallet`, named
+//! by the [`SavingsBank`] resource. [`deposit_savings`] moves currency from the
+//! saver's wallet into the bank's and credits the saver's [`SavingsAccount`];
+//! [`withdraw_savings`] reverses it. Account balances are claims on the bank,
+//! not currency, so `total_currency` and the conservation audit are unchanged.
+//!
+//! [`InterestSystem`] grows balances once per tick at [`InterestConfig::rate`].
+//! In an open economy the bank is credited with the interest it owes. In a
+//! closed economy nothing may be created, so interest is only paid out of the
+//! bank's reserves (its wallet beyond what it already owes savers) and stops
+//! when those run out.
+
+use super::economy::{CurrencyError, EconomyPolicy};
+use super::reputation::CurrentTick;
+use crate::Wallet;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+/// Currency an agent holds with the [`SavingsBank`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SavingsAccount {
+    /// Amount the bank owes the saver, interest included
+    pub balance: f32,
+    /// Last tick interest was applied; `None` until the first accrual
+    pub last_accrued_tick: Option<u64>,
+}
+
+impl Component for SavingsAccount {
+    type Storage = VecStorage<Self>;
+}
+
+/// World resource naming the entity whose wallet holds deposited savings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SavingsBank(pub Entity);
+
+/// World resource setting the interest rate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InterestConfig {
+    /// Interest per tick, compounded (0.001 = 0.1% per tick)
+    pub rate: f32,
+}
+
+impl Default for InterestConfig {
+    fn default() -> Self {
+        Self { rate: 0.001 }
+    }
+}
+
+/// Compound interest owed on `balance` after `ticks` ticks at `rate`
+///
+/// Computed in f64 so many small ticks don't drift from one long step.
+fn interest_over(balance: f32, rate: f32, ticks: u64) -> f32 {
+    let ticks = i32::try_from(ticks).unwrap_or(i32::MAX);
+    let growth = (1.0 + f64::from(rate)).powi(ticks) - 1.0;
+    (f64::from(balance) * growth) as f32
+}
+
+fn bank_entity(world: &World) -> Result<Entity, CurrencyError> {
+    world
+        .try_fetch::<SavingsBank>()
+        .map(|bank| bank.0)
+        .ok_or(CurrencyError::MissingWallet)
+}
+
+/// Move `amount` from `saver`'s wallet into its savings account
+///
+/// The currency goes to the [`SavingsBank`]'s wallet, so wallet totals are
+/// conserved under either policy. Opens an account if `saver` has none.
+///
+/// # Errors
+/// [`CurrencyError::InvalidAmount`], [`CurrencyError::MissingWallet`] if
+/// there is no bank or either side lacks a wallet, or
+/// [`CurrencyError::InsufficientFunds`]
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Wallet>();
+/// let bank = world.create_entity().with(Wallet::new(0.0)).build();
+/// let saver = world.create_entity().with(Wallet::new(150.0)).build();
+/// world.insert(SavingsBank(bank));
+///
+/// deposit_savings(&mut world, saver, 100.0).unwrap();
+/// assert_eq!(world.read_storage::<SavingsAccount>().get(saver).unwrap().balance, 100.0);
+/// assert_eq!(total_currency(&world), 150.0);
+/// ```
+pub fn deposit_savings(world: &mut World, saver: Entity, amount: f32) -> Result<(), CurrencyError> {
+    let bank = bank_entity(world)?;
+    super::transfer_currency(world, saver, bank, amount)?;
+
+    if !world.has_value::<MaskedStorage<SavingsAccount>>() {
+        world.register::<SavingsAccount>();
+    }
+    let mut accounts = world.write_storage::<SavingsAccount>();
+    if let Ok(entry) = accounts.entry(saver) {
+        entry.or_insert_with(SavingsAccount::default).balance += amount;
+    }
+    Ok(())
+}
+
+/// Move `amount` from `saver`'s savings account back into its wallet
+///
+/// All or nothing: fails without changes if the account or the bank's
+/// wallet cannot cover the amount.
+///
+/// # Errors
+/// [`CurrencyError::InvalidAmount`], [`CurrencyError::MissingWallet`] if
+/// there is no bank or either side lacks a wallet, or
+/// [`CurrencyError::InsufficientFunds`]
+pub fn withdraw_savings(world: &mut World, saver: Entity, amount: f32) -> Result<(), CurrencyError> {
+    let bank = bank_entity(world)?;
+    let saved = if world.has_value::<MaskedStorage<SavingsAccount>>() {
+        world.read_storage::<SavingsAccount>().get(saver).map_or(0.0, |a| a.balance)
+    } else {
+        0.0
+    };
+    if amount.is_finite() && saved < amount {
+        return Err(CurrencyError::InsufficientFunds);
+    }
+
+    super::transfer_currency(world, bank, saver, amount)?;
+    if let Some(account) = world.write_storage::<SavingsAccount>().get_mut(saver) {
+        account.balance = (account.balance - amount).max(0.0);
+    }
+    Ok(())
+}
+
+/// Sum of all savings balances, or 0 if no accounts are registered
+pub fn total_savings(world: &World) -> f32 {
+    if !world.has_value::<MaskedStorage<SavingsAccount>>() {
+        return 0.0;
+    }
+    let accounts = world.read_storage::<SavingsAccount>();
+    accounts.join().map(|a| f64::from(a.balance)).sum::<f64>() as f32
+}
+
+/// Applies compound interest to every [`SavingsAccount`]
+///
+/// Each account accrues for the ticks since it was last accrued, so running
+/// the system twice in one tick, or skipping ticks, gives the same balances.
+/// An account's first run only stamps the tick. Reads the optional
+/// [`InterestConfig`] (defaults if absent), [`CurrentTick`] and
+/// [`EconomyPolicy`]; does nothing without a [`SavingsBank`].
+///
+/// Accounts accrue in entity order, so in a closed economy whose bank runs
+/// short the same accounts are paid first on every run.
+pub struct InterestSystem;
+
+impl InterestSystem {
+    /// Stable dispatcher name
+    pub const NAME: &'static str = "interest";
+}
+
+impl<'a> System<'a> for InterestSystem {
+    type SystemData = (
+        WriteStorage<'a, SavingsAccount>,
+        WriteStorage<'a, Wallet>,
+        Option<Read<'a, SavingsBank>>,
+        Option<Read<'a, InterestConfig>>,
+        Option<Read<'a, CurrentTick>>,
+        Option<Read<'a, EconomyPolicy>>,
+    );
+
+    fn run(&mut self, (mut accounts, mut wallets, bank, config, tick, policy): Self::SystemData) {
+        let bank = match bank {
+            Some(bank) => bank.0,
+            None => return,
+        };
+        let rate = config.map_or(InterestConfig::default().rate, |c| c.rate);
+        let now = tick.map_or(0, |t| t.0);
+        let closed = policy.is_some_and(|p| p.closed);
+
+        // Closed economies pay interest only from currency the bank doesn't owe
+        let owed = (&accounts).join().map(|a| f64::from(a.balance)).sum::<f64>();
+        let held = wallets.get(bank).map_or(0.0, |w| f64::from(w.currency));
+        let mut reserves = (held - owed).max(0.0) as f32;
+
+        for account in (&mut accounts).join() {
+            let elapsed = account.last_accrued_tick.map_or(0, |last| now.saturating_sub(last));
+            account.last_accrued_tick = Some(now.max(account.last_accrued_tick.unwrap_or(0)));
+            let interest = interest_over(account.balance, rate, elapsed);
+            if !interest.is_finite() || interest <= 0.0 {
+                continue;
+            }
+
+            let paid = if closed {
+                let paid = interest.min(reserves);
+                reserves -= paid;
+                paid
+            } else {
+                if let Some(wallet) = wallets.get_mut(bank) {
+                    wallet.deposit(interest);
+                }
+                interest
+            };
+            account.balance += paid;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::total_currency;
+    use pretty_assertions::assert_eq;
+
+    fn world_with_saver(bank_funds: f32, saved: f32, rate: f32) -> (World, Entity, Entity) {
+        let mut world = World::new();
+        world.register::<Wallet>();
+        world.register::<SavingsAccount>();
+        let bank = world.create_entity().with(Wallet::new(bank_funds)).build();
+        let saver = world.create_entity().with(Wallet::new(saved)).build();
+        world.insert(SavingsBank(bank));
+        world.insert(InterestConfig { rate });
+        world.insert(CurrentTick(0));
+        deposit_savings(&mut world, saver, saved).unwrap();
+        (world, bank, saver)
+    }
+
+    fn run_ticks(world: &mut World, ticks: u64) {
+        for _ in 0..ticks {
+            InterestSystem.run_now(world);
+            world.write_resource::<CurrentTick>().0 += 1;
+        }
+        InterestSystem.run_now(world);
+    }
+
+    fn balance(world: &World, saver: Entity) -> f32 {
+        world.read_storage::<SavingsAccount>().get(saver).unwrap().balance
+    }
+
+    #[test]
+    fn test_savings_grow_by_compound_interest() {
+        let (mut world, bank, saver) = world_with_saver(0.0, 1000.0, 0.01);
+        run_ticks(&mut world, 10);
+
+        // 1000 * 1.01^10
+        assert!((balance(&world, saver) - 1104.622).abs() < 1e-2);
+        // The bank is credited with what it owes
+        let bank_funds = world.read_storage::<Wallet>().get(bank).unwrap().currency;
+        assert!((bank_funds - balance(&world, saver)).abs() < 1e-2);
+
+        // Running again in the same tick changes nothing
+        let before = balance(&world, saver);
+        InterestSystem.run_now(&world);
+        assert_eq!(balance(&world, saver), before);
+    }
+
+    #[test]
+    fn test_skipped_ticks_accrue_the_same_interest() {
+        let (mut stepped, _, saver) = world_with_saver(0.0, 500.0, 0.002);
+        run_ticks(&mut stepped, 50);
+
+        let (mut jumped, _, other) = world_with_saver(0.0, 500.0, 0.002);
+        InterestSystem.run_now(&jumped);
+        jumped.insert(CurrentTick(50));
+        InterestSystem.run_now(&jumped);
+
+        assert!((balance(&stepped, saver) - balance(&jumped, other)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_closed_economy_pays_interest_only_from_reserves() {
+        let (mut world, bank, saver) = world_with_saver(15.0, 1000.0, 0.01);
+        world.insert(EconomyPolicy::closed());
+        let total = total_currency(&world);
+
+        run_ticks(&mut world, 5);
+
+        // 10.00 for the first tick, then only the remaining 5.00
+        assert!((balance(&world, saver) - 1015.0).abs() < 1e-3);
+        assert_eq!(total_currency(&world), total);
+
+        // Every claim is still backed and can be withdrawn
+        let saved = balance(&world, saver);
+        withdraw_savings(&mut world, saver, saved).unwrap();
+        assert!(world.read_storage::<Wallet>().get(bank).unwrap().currency < 1e-3);
+        assert_eq!(total_currency(&world), total);
+    }
+
+    #[test]
+    fn test_withdraw_more_than_saved_is_refused() {
+        let (mut world, _, saver) = world_with_saver(100.0, 40.0, 0.0);
+        assert_eq!(
+            withdraw_savings(&mut world, saver, 50.0),
+            Err(CurrencyError::InsufficientFunds)
+        );
+        assert_eq!(balance(&world, saver), 40.0);
+        assert_eq!(total_savings(&world), 40.0);
+    }
+}
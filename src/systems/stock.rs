@@ -0,0 +1,251 @@
+//! World-wide resource stock totals
+//!
+//! Sums over every `ResourceSource` for ecology balancing, scarcity signals
+//! and metrics. A source matches a query if either its `resource_type` or its
+//! `item_produced` equals the requested type, the same rule
+//! `EcsPositionQuery` uses.
+
+use crate::ResourceSource;
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+
+fn matches(source: &ResourceSource, resource_type: &str) -> bool {
+    source.resource_type == resource_type || source.item_produced == resource_type
+}
+
+/// Total stock and number of sources for a resource type, in one pass
+fn stock_and_count(world: &World, resource_type: &str) -> (u64, usize) {
+    world
+        .read_storage::<ResourceSource>()
+        .join()
+        .filter(|source| matches(source, resource_type))
+        .fold((0, 0), |(stock, count), source| {
+            (stock + u64::from(source.current_stock), count + 1)
+        })
+}
+
+/// Sum of `current_stock` over all sources of a resource type
+///
+/// # Panics
+/// Panics if `ResourceSource` is not registered in the world
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<ResourceSource>();
+/// for stock in [30, 12] {
+///     world
+///         .create_entity()
+///         .with(ResourceSource::new("water".into(), "water".into(), 0.0, stock))
+///         .build();
+/// }
+///
+/// assert_eq!(total_resource_stock(&world, "water"), 42);
+/// assert_eq!(resource_source_count(&world, "water"), 2);
+/// ```
+pub fn total_resource_stock(world: &World, resource_type: &str) -> u64 {
+    stock_and_count(world, resource_type).0
+}
+
+/// Number of sources of a resource type, including depleted ones
+///
+/// # Panics
+/// Panics if `ResourceSource` is not registered in the world
+pub fn resource_source_count(world: &World, resource_type: &str) -> usize {
+    stock_and_count(world, resource_type).1
+}
+
+/// Every resource source in the world, in entity order
+///
+/// Includes depleted sources. Returns an empty list if `ResourceSource` is
+/// not registered.
+pub fn resource_sources(world: &World) -> Vec<(Entity, ResourceSource)> {
+    if !world.has_value::<MaskedStorage<ResourceSource>>() {
+        return Vec::new();
+    }
+    let entities = world.entities();
+    let sources = world.read_storage::<ResourceSource>();
+    (&entities, &sources)
+        .join()
+        .map(|(entity, source)| (entity, source.clone()))
+        .collect()
+}
+
+/// Stock, regrowth and time to depletion for one resource type
+///
+/// Returned by [`resource_forecast`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceForecast {
+    /// Sum of `current_stock` over all matching sources
+    pub total_stock: u64,
+    /// Sum of the positive `regeneration_rate`s of matching sources
+    pub regen_per_tick: f32,
+    /// Ticks until the stock runs out at the given harvest rate
+    ///
+    /// `None` when regrowth keeps up with harvesting (or nothing is
+    /// harvested); `Some(0)` when there is nothing left to harvest.
+    pub ticks_to_depletion: Option<u64>,
+}
+
+impl ResourceForecast {
+    /// Whether regrowth keeps up with harvesting indefinitely
+    pub fn is_sustainable(&self) -> bool {
+        self.ticks_to_depletion.is_none()
+    }
+}
+
+/// Forecast how long a resource type lasts if harvested at a steady rate
+///
+/// Fractional regeneration rates are counted at their full value, i.e. the
+/// long-run average regrowth rather than the whole units a single
+/// `ResourceSource::regenerate` call adds. A negative or non-finite
+/// `harvest_rate_per_tick` counts as no harvesting. A world without
+/// `ResourceSource` registered has no sources.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<ResourceSource>();
+/// world
+///     .create_entity()
+///     .with(ResourceSource::new("plant".into(), "grass".into(), 2.0, 100))
+///     .build();
+///
+/// assert!(resource_forecast(&world, "grass", 1.5).is_sustainable());
+/// assert_eq!(resource_forecast(&world, "grass", 6.0).ticks_to_depletion, Some(25));
+/// ```
+pub fn resource_forecast(
+    world: &World,
+    resource_type: &str,
+    harvest_rate_per_tick: f32,
+) -> ResourceForecast {
+    let (total_stock, regen) = if world.has_value::<MaskedStorage<ResourceSource>>() {
+        world
+            .read_storage::<ResourceSource>()
+            .join()
+            .filter(|source| matches(source, resource_type))
+            .fold((0u64, 0f64), |(stock, regen), source| {
+                let rate = if source.regeneration_rate > 0.0 {
+                    f64::from(source.regeneration_rate)
+                } else {
+                    0.0
+                };
+                (stock + u64::from(source.current_stock), regen + rate)
+            })
+    } else {
+        (0, 0.0)
+    };
+
+    let harvest = if harvest_rate_per_tick.is_finite() {
+        f64::from(harvest_rate_per_tick.max(0.0))
+    } else {
+        0.0
+    };
+    let net_drain = harvest - regen;
+    let ticks_to_depletion = if net_drain <= 0.0 {
+        None
+    } else {
+        Some((total_stock as f64 / net_drain).ceil() as u64)
+    };
+
+    ResourceForecast {
+        total_stock,
+        regen_per_tick: regen as f32,
+        ticks_to_depletion,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn add_source(world: &mut World, resource_type: &str, item: &str, stock: u32) {
+        world
+            .create_entity()
+            .with(ResourceSource::new(resource_type.into(), item.into(), 0.0, stock))
+            .build();
+    }
+
+    #[test]
+    fn test_totals_per_resource_type() {
+        let mut world = World::new();
+        world.register::<ResourceSource>();
+        add_source(&mut world, "plant", "grass", 100);
+        add_source(&mut world, "plant", "grass", 50);
+        add_source(&mut world, "plant", "grass", 0);
+        add_source(&mut world, "water", "water", 30);
+        add_source(&mut world, "water", "water", u32::MAX);
+
+        assert_eq!(total_resource_stock(&world, "grass"), 150);
+        assert_eq!(resource_source_count(&world, "grass"), 3);
+        // Matched by resource_type as well as item
+        assert_eq!(total_resource_stock(&world, "plant"), 150);
+        // Summed in u64, so huge sources don't overflow
+        assert_eq!(total_resource_stock(&world, "water"), 30 + u64::from(u32::MAX));
+        assert_eq!(resource_source_count(&world, "water"), 2);
+
+        assert_eq!(total_resource_stock(&world, "stone"), 0);
+        assert_eq!(resource_source_count(&world, "stone"), 0);
+
+        let listed = resource_sources(&world);
+        assert_eq!(listed.len(), 5);
+        assert_eq!(listed[2].1.current_stock, 0);
+        assert!(resource_sources(&World::new()).is_empty());
+    }
+
+    fn add_renewable(world: &mut World, item: &str, rate: f32, stock: u32) {
+        world
+            .create_entity()
+            .with(ResourceSource::new("plant".into(), item.into(), rate, stock))
+            .build();
+    }
+
+    #[test]
+    fn test_forecast_sustainable_harvest() {
+        let mut world = World::new();
+        world.register::<ResourceSource>();
+        add_renewable(&mut world, "grass", 0.5, 40);
+        add_renewable(&mut world, "grass", 0.25, 10);
+        add_renewable(&mut world, "grass", 0.25, 0);
+
+        let forecast = resource_forecast(&world, "grass", 1.0);
+        assert_eq!(forecast.total_stock, 50);
+        // Fractional rates add up instead of truncating to zero
+        assert_eq!(forecast.regen_per_tick, 1.0);
+        assert_eq!(forecast.ticks_to_depletion, None);
+        assert!(forecast.is_sustainable());
+        assert!(resource_forecast(&world, "grass", f32::NAN).is_sustainable());
+    }
+
+    #[test]
+    fn test_forecast_unsustainable_harvest() {
+        let mut world = World::new();
+        world.register::<ResourceSource>();
+        add_renewable(&mut world, "berries", 0.5, 10);
+        add_renewable(&mut world, "ore", 0.0, 30);
+        add_renewable(&mut world, "ore", -1.0, 0);
+        add_renewable(&mut world, "flint", 0.0, 0);
+
+        // 10 stock, net drain 2.0 - 0.5 = 1.5 per tick: gone during tick 7
+        assert_eq!(resource_forecast(&world, "berries", 2.0).ticks_to_depletion, Some(7));
+
+        // Non-renewable: negative rates don't count as regrowth
+        let ore = resource_forecast(&world, "ore", 4.0);
+        assert_eq!(ore.regen_per_tick, 0.0);
+        assert_eq!(ore.ticks_to_depletion, Some(8));
+
+        assert_eq!(resource_forecast(&world, "flint", 1.0).ticks_to_depletion, Some(0));
+        assert_eq!(resource_forecast(&world, "flint", 0.0).ticks_to_depletion, None);
+        // No sources at all: nothing to harvest
+        assert_eq!(resource_forecast(&world, "gold", 1.0).ticks_to_depletion, Some(0));
+        // Nor without ResourceSource registered
+        assert_eq!(resource_forecast(&World::new(), "gold", 1.0).ticks_to_depletion, Some(0));
+    }
+}
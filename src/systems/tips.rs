@@ -0,0 +1,251 @@
+//! Resource tips: agents telling trusted neighbors where they found things
+//!
+//! An agent that recently saw a resource first-hand passes the location on
+//! to nearby agents it trusts, straight into their [`SpatialMemory`]. Trusted
+//! networks therefore find water and food faster than loners.
+//!
+//! Tips carry the tick of the original sighting and are only passed on while
+//! that sighting is younger than [`TipConfig::ttl`]. Listeners keep
+//! first-hand or fresher knowledge over a tip, and only first-hand sightings
+//! are shared, so rumors don't travel further than one hop.
+
+use super::reputation::CurrentTick;
+use crate::{Agent, AgentId, AgentIndex, ReputationKnowledge, SpatialMemory};
+use crate::world_query::WorldQuery;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+
+/// World resource tuning [`ResourceTipSystem`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TipConfig {
+    /// Score the teller must hold of a listener (0-1) to share with it
+    pub trust_threshold: f32,
+    /// Farthest a listener can be (world units)
+    pub radius: f32,
+    /// Most listeners per teller per tick, closest first
+    pub max_listeners: usize,
+    /// Oldest sighting (in ticks) that is still passed on
+    pub ttl: u64,
+}
+
+impl Default for TipConfig {
+    fn default() -> Self {
+        Self {
+            trust_threshold: 0.6,
+            radius: 50.0,
+            max_listeners: 5,
+            ttl: 100,
+        }
+    }
+}
+
+/// One location to pass on
+struct Tip {
+    listener: Entity,
+    resource_type: String,
+    x: f32,
+    y: f32,
+    tick: u64,
+    from: AgentId,
+}
+
+/// Shares agents' recent first-hand sightings with trusted neighbors
+///
+/// Neighbors come from the host's [`WorldQuery`]; the teller's
+/// `ReputationKnowledge` decides who is trusted, so agents without one tell
+/// no one. Listeners without a `SpatialMemory` get a default one. Reads the
+/// optional [`TipConfig`] (defaults if absent) and [`CurrentTick`].
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Agent>();
+/// world.register::<ReputationKnowledge>();
+/// world.register::<SpatialMemory>();
+/// world.insert(CurrentTick(10));
+///
+/// let mut trust = ReputationKnowledge::new();
+/// for tick in 0..10 {
+///     trust.update_reputation(AgentId(2), 1.0, tick);
+/// }
+/// let mut memory = SpatialMemory::default();
+/// memory.remember("water", 3.0, 4.0, 9);
+/// world.create_entity().with(Agent { id: AgentId(1) }).with(trust).with(memory).build();
+/// let friend = world.create_entity().with(Agent { id: AgentId(2) }).build();
+///
+/// let mut grid = GridWorldQuery::new(10.0);
+/// grid.set_agent_position(AgentId(1), 0.0, 0.0);
+/// grid.set_agent_position(AgentId(2), 5.0, 0.0);
+/// ResourceTipSystem::new(&grid).run_now(&world);
+///
+/// let memories = world.read_storage::<SpatialMemory>();
+/// assert_eq!(memories.get(friend).unwrap().recall("water", 10).unwrap().tipped_by, Some(AgentId(1)));
+/// ```
+pub struct ResourceTipSystem<'q> {
+    query: &'q dyn WorldQuery,
+}
+
+impl<'q> ResourceTipSystem<'q> {
+    /// Stable dispatcher name
+    pub const NAME: &'static str = "resource_tips";
+
+    /// Share tips between the neighbors `query` reports
+    pub fn new(query: &'q dyn WorldQuery) -> Self {
+        Self { query }
+    }
+}
+
+impl<'a> System<'a> for ResourceTipSystem<'_> {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Agent>,
+        ReadStorage<'a, ReputationKnowledge>,
+        WriteStorage<'a, SpatialMemory>,
+        Option<Read<'a, TipConfig>>,
+        Option<Read<'a, CurrentTick>>,
+    );
+
+    fn run(&mut self, (entities, agents, knowledge, mut memories, config, tick): Self::SystemData) {
+        let config = config.map(|c| c.clone()).unwrap_or_default();
+        let now = tick.map_or(0, |t| t.0);
+
+        let mut index = AgentIndex::new();
+        for (entity, agent) in (&entities, &agents).join() {
+            index.insert(agent.id, entity);
+        }
+
+        // Gather every tip first so this tick's tips aren't passed on again
+        let mut tips = Vec::new();
+        for (agent, trust, memory) in (&agents, &knowledge, &memories).join() {
+            let recent: Vec<_> = memory
+                .entries
+                .iter()
+                .filter(|e| e.tipped_by.is_none() && now.saturating_sub(e.tick) <= config.ttl)
+                .collect();
+            if recent.is_empty() {
+                continue;
+            }
+
+            let neighbors =
+                self.query
+                    .get_nearby_agents_within(agent.id, config.max_listeners, config.radius);
+            for neighbor in neighbors {
+                if trust.get_score(neighbor) < config.trust_threshold {
+                    continue;
+                }
+                let Some(listener) = index.get(neighbor) else {
+                    continue;
+                };
+                tips.extend(recent.iter().map(|sighting| Tip {
+                    listener,
+                    resource_type: sighting.resource_type.clone(),
+                    x: sighting.x,
+                    y: sighting.y,
+                    tick: sighting.tick,
+                    from: agent.id,
+                }));
+            }
+        }
+
+        for tip in tips {
+            if let Ok(entry) = memories.entry(tip.listener) {
+                entry.or_insert_with(SpatialMemory::default).remember_tip(
+                    &tip.resource_type,
+                    tip.x,
+                    tip.y,
+                    tip.tick,
+                    tip.from,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decision::{DecisionOutput, Intent, UtilityMaximizer};
+    use crate::world_query::GridWorldQuery;
+    use crate::{AgentIdAllocator, EnergyComponent, Needs};
+    use pretty_assertions::assert_eq;
+
+    /// A teller at the origin who trusts agent 2 and distrusts agent 3
+    fn setup() -> (World, GridWorldQuery, Entity, Entity) {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<Needs>();
+        world.register::<EnergyComponent>();
+        world.register::<ReputationKnowledge>();
+        world.register::<SpatialMemory>();
+        world.insert(AgentIdAllocator::new());
+        world.insert(CurrentTick(20));
+
+        let mut trust = ReputationKnowledge::new();
+        for tick in 0..10 {
+            trust.update_reputation(AgentId(2), 1.0, tick);
+            trust.update_reputation(AgentId(3), -1.0, tick);
+        }
+        let mut memory = SpatialMemory::default();
+        memory.remember("water", 30.0, 40.0, 15);
+        world
+            .create_entity()
+            .with(Agent { id: AgentId(1) })
+            .with(Needs::new(10.0, 10.0, 10.0))
+            .with(trust)
+            .with(memory)
+            .build();
+
+        let mut thirsty = |id| {
+            world
+                .create_entity()
+                .with(Agent { id: AgentId(id) })
+                .with(Needs::new(85.0, 10.0, 10.0))
+                .build()
+        };
+        let friend = thirsty(2);
+        let stranger = thirsty(3);
+
+        let mut grid = GridWorldQuery::new(10.0);
+        grid.set_agent_position(AgentId(1), 0.0, 0.0);
+        grid.set_agent_position(AgentId(2), 3.0, 0.0);
+        grid.set_agent_position(AgentId(3), 0.0, 3.0);
+        (world, grid, friend, stranger)
+    }
+
+    #[test]
+    fn test_trusted_neighbor_gets_tip_and_navigates_to_it() {
+        let (world, grid, friend, stranger) = setup();
+        ResourceTipSystem::new(&grid).run_now(&world);
+
+        // No water in sight, so the friend heads for the tipped location
+        let decision = UtilityMaximizer::default().decide(friend, &world, &grid);
+        assert_eq!(
+            decision,
+            DecisionOutput::Intent(Intent::MoveTo { x: 30.0, y: 40.0, item_type: "water".to_string() })
+        );
+
+        let memories = world.read_storage::<SpatialMemory>();
+        assert!(memories.get(stranger).is_none());
+        assert_eq!(memories.get(friend).unwrap().recall("water", 20).unwrap().tick, 15);
+    }
+
+    #[test]
+    fn test_stale_sightings_and_tips_are_not_passed_on() {
+        let (mut world, grid, friend, stranger) = setup();
+        world.insert(TipConfig { ttl: 3, ..Default::default() });
+        ResourceTipSystem::new(&grid).run_now(&world);
+        assert!(world.read_storage::<SpatialMemory>().get(friend).is_none());
+
+        // The friend trusts everyone, but only passes on what it saw itself
+        world.insert(TipConfig::default());
+        ResourceTipSystem::new(&grid).run_now(&world);
+        let trusting = ReputationKnowledge::with_trust_level(1.0);
+        world.write_storage::<ReputationKnowledge>().insert(friend, trusting).unwrap();
+        ResourceTipSystem::new(&grid).run_now(&world);
+        assert!(world.read_storage::<SpatialMemory>().get(stranger).is_none());
+    }
+}
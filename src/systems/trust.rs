@@ -0,0 +1,219 @@
+//! Trust milestone events
+//!
+//! [`ReputationThresholdSystem`] watches every first-hand reputation view and
+//! publishes [`SimEvent::TrustGained`] / [`SimEvent::TrustLost`] to the
+//! `EventBus` when a view's score crosses the configured thresholds.
+//!
+//! Crossings are edge-triggered with hysteresis: a view becomes trusted once
+//! its score reaches [`TrustThresholds::gain_at`] and only stops being trusted
+//! when it falls below [`TrustThresholds::lose_below`]. A score hovering at
+//! the boundary therefore emits one event, not one per tick.
+
+use crate::events::{EventBus, SimEvent};
+use crate::{Agent, AgentId, ReputationKnowledge};
+use specs::prelude::*;
+use std::collections::HashSet;
+
+/// World resource configuring [`ReputationThresholdSystem`]
+///
+/// `lose_below` should be at most `gain_at`; the gap between them is the
+/// hysteresis band.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrustThresholds {
+    /// Score at or above which a view becomes trusted
+    pub gain_at: f32,
+    /// Score below which a trusted view stops being trusted
+    pub lose_below: f32,
+}
+
+impl Default for TrustThresholds {
+    fn default() -> Self {
+        Self {
+            gain_at: 0.7,
+            lose_below: 0.6,
+        }
+    }
+}
+
+/// Publishes trust milestone events when reputation scores cross thresholds
+///
+/// Tracks which (observer, target) views are currently trusted between runs.
+/// Reads the optional [`TrustThresholds`] resource (defaults if absent) and
+/// publishes to the `EventBus` if the host has inserted one; without a bus
+/// the state is still tracked, so adding one later does not replay old
+/// crossings. Scores are the plain `ReputationView::score()`, without decay.
+/// Events from one run are ordered by observer, then target id.
+///
+/// A view seen for the first time counts as previously untrusted, so one
+/// created above `gain_at` emits `TrustGained` on its first run.
+///
+/// # Example
+///
+/// ```
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// world.register::<Agent>();
+/// world.register::<ReputationKnowledge>();
+/// world.insert(EventBus::new());
+///
+/// let mut knowledge = ReputationKnowledge::new();
+/// knowledge.update_reputation(AgentId(2), 5.0, 0);
+/// world.create_entity().with(Agent { id: AgentId(1) }).with(knowledge).build();
+///
+/// let mut system = ReputationThresholdSystem::default();
+/// system.run_now(&world);
+/// system.run_now(&world);
+///
+/// assert_eq!(
+///     world.write_resource::<EventBus>().poll(),
+///     vec![SimEvent::TrustGained { observer: AgentId(1), target: AgentId(2) }]
+/// );
+/// ```
+#[derive(Debug, Default)]
+pub struct ReputationThresholdSystem {
+    /// Views trusted as of the last run, as (observer, target)
+    trusted: HashSet<(AgentId, AgentId)>,
+}
+
+impl ReputationThresholdSystem {
+    /// Stable dispatcher name
+    pub const NAME: &'static str = "reputation_threshold";
+
+    /// Create a system with no views trusted yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `observer` currently trusts `target`, as of the last run
+    pub fn is_trusted(&self, observer: AgentId, target: AgentId) -> bool {
+        self.trusted.contains(&(observer, target))
+    }
+}
+
+impl<'a> System<'a> for ReputationThresholdSystem {
+    type SystemData = (
+        ReadStorage<'a, Agent>,
+        ReadStorage<'a, ReputationKnowledge>,
+        Option<Read<'a, TrustThresholds>>,
+        Option<Write<'a, EventBus>>,
+    );
+
+    fn run(&mut self, (agents, knowledge, thresholds, bus): Self::SystemData) {
+        let thresholds = thresholds.map(|t| *t).unwrap_or_default();
+
+        let mut trusted = HashSet::new();
+        let mut crossings = Vec::new();
+        for (agent, knowledge) in (&agents, &knowledge).join() {
+            for (&target, view) in &knowledge.first_hand {
+                let key = (agent.id, target);
+                let was_trusted = self.trusted.contains(&key);
+                let score = view.score();
+                let now_trusted = if was_trusted {
+                    score >= thresholds.lose_below
+                } else {
+                    score >= thresholds.gain_at
+                };
+
+                if now_trusted {
+                    trusted.insert(key);
+                }
+                if now_trusted != was_trusted {
+                    crossings.push((agent.id, target, now_trusted));
+                }
+            }
+        }
+        // Views that disappeared (agent removed, view forgotten) drop silently
+        self.trusted = trusted;
+
+        if let Some(mut bus) = bus {
+            crossings.sort_by_key(|(observer, target, _)| (observer.0, target.0));
+            for (observer, target, gained) in crossings {
+                bus.publish(if gained {
+                    SimEvent::TrustGained { observer, target }
+                } else {
+                    SimEvent::TrustLost { observer, target }
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn setup() -> (World, Entity) {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<ReputationKnowledge>();
+        world.insert(EventBus::new());
+        let observer = world
+            .create_entity()
+            .with(Agent { id: AgentId(1) })
+            .with(ReputationKnowledge::new())
+            .build();
+        (world, observer)
+    }
+
+    /// Set the observer's view of agent 2 to exactly `score`
+    fn set_score(world: &mut World, observer: Entity, score: f32) {
+        let mut knowledge = world.write_storage::<ReputationKnowledge>();
+        let view = knowledge
+            .get_mut(observer)
+            .unwrap()
+            .first_hand
+            .entry(AgentId(2))
+            .or_default();
+        view.alpha = score * 10.0;
+        view.beta = (1.0 - score) * 10.0;
+    }
+
+    fn gained() -> SimEvent {
+        SimEvent::TrustGained {
+            observer: AgentId(1),
+            target: AgentId(2),
+        }
+    }
+
+    #[test]
+    fn test_rising_score_emits_one_trust_gained() {
+        let (mut world, observer) = setup();
+        let mut system = ReputationThresholdSystem::new();
+
+        for score in [0.5, 0.6, 0.69, 0.71, 0.75, 0.8] {
+            set_score(&mut world, observer, score);
+            system.run_now(&world);
+        }
+
+        assert_eq!(world.write_resource::<EventBus>().poll(), vec![gained()]);
+        assert!(system.is_trusted(AgentId(1), AgentId(2)));
+    }
+
+    #[test]
+    fn test_hovering_at_boundary_does_not_repeat() {
+        let (mut world, observer) = setup();
+        let mut system = ReputationThresholdSystem::new();
+
+        for score in [0.71, 0.69, 0.71, 0.65, 0.72, 0.61, 0.7] {
+            set_score(&mut world, observer, score);
+            system.run_now(&world);
+        }
+        assert_eq!(world.write_resource::<EventBus>().poll(), vec![gained()]);
+
+        // Falling through the lower threshold is a real loss, once
+        for score in [0.55, 0.5, 0.62] {
+            set_score(&mut world, observer, score);
+            system.run_now(&world);
+        }
+        assert_eq!(
+            world.write_resource::<EventBus>().poll(),
+            vec![SimEvent::TrustLost {
+                observer: AgentId(1),
+                target: AgentId(2),
+            }]
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! Test utilities for downstream simulations (`testing` feature)
+//!
+//! [`assert_deterministic`] runs a scenario twice from scratch and compares
+//! [`WorldSnapshot`]s after every step, so a system that draws from an
+//! unseeded RNG, iterates a `HashMap` into its results or sorts unstably is
+//! caught the moment it is added.
+
+use crate::snapshot::WorldSnapshot;
+use serde_json::Value;
+use specs::prelude::*;
+
+/// First difference between two runs of the same scenario
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// Steps completed when the runs differed (0 = straight after building)
+    pub step: u64,
+    /// Path of the differing field, e.g. `agents[2].needs.thirst`
+    pub path: String,
+    /// Value in the first run (JSON)
+    pub first: String,
+    /// Value in the second run (JSON)
+    pub second: String,
+}
+
+impl core::fmt::Display for Divergence {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "runs diverged after step {} at {}: {} != {}",
+            self.step, self.path, self.first, self.second
+        )
+    }
+}
+
+impl std::error::Error for Divergence {}
+
+/// First field where two snapshots differ, in snapshot field order
+///
+/// Returns the path and both values as JSON, or `None` if they are equal.
+pub fn first_difference(a: &WorldSnapshot, b: &WorldSnapshot) -> Option<(String, String, String)> {
+    let a = serde_json::to_value(a).unwrap_or(Value::Null);
+    let b = serde_json::to_value(b).unwrap_or(Value::Null);
+    diff_values(&a, &b, String::new())
+}
+
+fn diff_values(a: &Value, b: &Value, path: String) -> Option<(String, String, String)> {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match (a, b) {
+        (Value::Object(left), Value::Object(right)) => {
+            for (key, value) in left {
+                let other = right.get(key).unwrap_or(&Value::Null);
+                if let Some(found) = diff_values(value, other, join(key)) {
+                    return Some(found);
+                }
+            }
+            right
+                .iter()
+                .find(|(key, _)| !left.contains_key(*key))
+                .map(|(key, value)| (join(key), "null".to_string(), value.to_string()))
+        }
+        (Value::Array(left), Value::Array(right)) => {
+            for (i, (x, y)) in left.iter().zip(right).enumerate() {
+                if let Some(found) = diff_values(x, y, format!("{}[{}]", path, i)) {
+                    return Some(found);
+                }
+            }
+            (left.len() != right.len()).then(|| {
+                (format!("{}.len()", path), left.len().to_string(), right.len().to_string())
+            })
+        }
+        _ => (a != b).then(|| (path, a.to_string(), b.to_string())),
+    }
+}
+
+/// Run a scenario twice and report where the runs first diverge
+///
+/// `build` creates a fresh world and a step function; each run calls the
+/// step `steps` times, capturing a snapshot before the first step and after
+/// every step.
+///
+/// # Errors
+/// The first [`Divergence`] between the two runs
+pub fn check_deterministic<B, S>(build: B, steps: u64) -> Result<(), Divergence>
+where
+    B: Fn() -> (World, S),
+    S: FnMut(&mut World),
+{
+    let (mut first, mut step_first) = build();
+    let (mut second, mut step_second) = build();
+
+    for step in 0..=steps {
+        if step > 0 {
+            step_first(&mut first);
+            step_second(&mut second);
+        }
+        let a = WorldSnapshot::capture(&first);
+        let b = WorldSnapshot::capture(&second);
+        if let Some((path, first, second)) = first_difference(&a, &b) {
+            return Err(Divergence { step, path, first, second });
+        }
+    }
+    Ok(())
+}
+
+/// Assert that a scenario evolves identically on every run
+///
+/// See [`check_deterministic`].
+///
+/// # Panics
+/// Panics with the first divergent field if the runs differ
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use libreconomy::testing::assert_deterministic;
+/// use specs::prelude::*;
+///
+/// assert_deterministic(
+///     || {
+///         let mut world = World::new();
+///         register_core_components(&mut world);
+///         world.register::<NeedDecayRates>();
+///         create_agents(&mut world, 5, &AgentTemplate::default());
+///         (world, |world: &mut World| NeedDecaySystem.run_now(world))
+///     },
+///     10,
+/// );
+/// ```
+pub fn assert_deterministic<B, S>(build: B, steps: u64)
+where
+    B: Fn() -> (World, S),
+    S: FnMut(&mut World),
+{
+    if let Err(divergence) = check_deterministic(build, steps) {
+        panic!("{}", divergence);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        create_agents, register_core_components, AgentIdAllocator, AgentTemplate, NeedDecayRates,
+        NeedDecaySystem, Needs,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn village() -> World {
+        let mut world = World::new();
+        register_core_components(&mut world);
+        world.register::<NeedDecayRates>();
+        create_agents(&mut world, 4, &AgentTemplate::default());
+        world
+    }
+
+    #[test]
+    fn test_deterministic_scenario_passes() {
+        assert_deterministic(|| (village(), |world: &mut World| NeedDecaySystem.run_now(world)), 20);
+    }
+
+    #[test]
+    fn test_random_scenario_fails_at_first_divergent_field() {
+        let result = check_deterministic(
+            || {
+                (village(), |world: &mut World| {
+                    for needs in (&mut world.write_storage::<Needs>()).join() {
+                        needs.thirst = rand::random::<f32>() * 100.0;
+                    }
+                })
+            },
+            3,
+        );
+        let divergence = result.unwrap_err();
+        assert_eq!((divergence.step, divergence.path.as_str()), (1, "agents[0].needs.thirst"));
+    }
+
+    #[test]
+    #[should_panic(expected = "runs diverged after step 1 at next_agent_id")]
+    fn test_assert_deterministic_panics_on_divergence() {
+        // Each run hands out a different number of ids, like a seed-dependent system would
+        let runs = std::cell::Cell::new(0);
+        assert_deterministic(
+            || {
+                runs.set(runs.get() + 1);
+                let extra = runs.get();
+                (village(), move |world: &mut World| {
+                    world.write_resource::<AgentIdAllocator>().allocate_many(extra).unwrap();
+                })
+            },
+            1,
+        );
+    }
+}
@@ -1,14 +1,17 @@
 //! WASM decision system bindings
 //!
 //! Provides JavaScript-friendly interface to the decision-making system.
+//! A decision maker can also report each decision to a JS callback, so web
+//! demos can show why agents act.
 
+use js_sys::Function;
 use wasm_bindgen::prelude::*;
 use serde_wasm_bindgen;
 use specs::WorldExt;
 
 use crate::{
     UtilityMaximizer, DecisionThresholds, UtilityWeights,
-    WorldQuery, ResourceLocation, AgentId, DecisionOutput, Intent,
+    WorldQuery, ResourceLocation, AgentId, DecisionOutput, Intent, HOME_ITEM_TYPE,
 };
 use super::world::WasmWorld;
 
@@ -79,6 +82,7 @@ impl<'a> WorldQuery for JsWorldQueryAdapter<'a> {
 #[wasm_bindgen]
 pub struct WasmDecisionMaker {
     inner: UtilityMaximizer,
+    log_callback: Option<Function>,
 }
 
 #[wasm_bindgen]
@@ -88,6 +92,7 @@ impl WasmDecisionMaker {
     pub fn new() -> Self {
         Self {
             inner: UtilityMaximizer::default(),
+            log_callback: None,
         }
     }
 
@@ -112,6 +117,7 @@ impl WasmDecisionMaker {
             high_hunger,
             critical_tiredness,
             high_tiredness,
+            ..Default::default()
         };
 
         let weights = UtilityWeights {
@@ -121,14 +127,30 @@ impl WasmDecisionMaker {
         };
 
         Self {
-            inner: UtilityMaximizer {
-                thresholds,
-                weights,
-                resource_search_radius: search_radius,
-            },
+            inner: UtilityMaximizer::new(thresholds, weights, search_radius),
+            log_callback: None,
         }
     }
 
+    /// Report every decision to `cb`
+    ///
+    /// `cb` is called as `cb(agentId, intentType, utility, reason)` after each
+    /// `decide` or `decide_libreterra`, with the fields `decide_libreterra`
+    /// returns. `agentId` is the agent's `AgentId`, or the entity id for
+    /// entities without one. Exceptions thrown by `cb` are swallowed so a
+    /// faulty logger can't break the simulation. Replaces any earlier
+    /// callback.
+    #[wasm_bindgen(js_name = setLogCallback)]
+    pub fn set_log_callback(&mut self, cb: Function) {
+        self.log_callback = Some(cb);
+    }
+
+    /// Stop reporting decisions
+    #[wasm_bindgen(js_name = clearLogCallback)]
+    pub fn clear_log_callback(&mut self) {
+        self.log_callback = None;
+    }
+
     /// Make a decision for an agent
     ///
     /// # Arguments
@@ -151,7 +173,12 @@ impl WasmDecisionMaker {
         let query_adapter = JsWorldQueryAdapter { js_query: world_query };
 
         // Make decision
-        let decision = self.inner.decide(entity, world.get_world(), &query_adapter);
+        let (decision, utility, reason) =
+            self.inner.decide_explained(entity, world.get_world(), &query_adapter);
+        if self.log_callback.is_some() {
+            let result = JsDecisionResult::explained(&decision, utility, reason);
+            self.log_decision(world, entity_id, &result);
+        }
 
         // Serialize to JsValue
         serde_wasm_bindgen::to_value(&decision).unwrap_or(JsValue::NULL)
@@ -177,9 +204,56 @@ impl WasmDecisionMaker {
     ) -> JsDecisionResult {
         let entity = world.get_world().entities().entity(entity_id);
         let query_adapter = JsWorldQueryAdapter { js_query: world_query };
-        let decision = self.inner.decide(entity, world.get_world(), &query_adapter);
+        let (decision, utility, reason) =
+            self.inner.decide_explained(entity, world.get_world(), &query_adapter);
+
+        let result = JsDecisionResult::explained(&decision, utility, reason);
+        self.log_decision(world, entity_id, &result);
+        result
+    }
+}
+
+impl WasmDecisionMaker {
+    /// Pass a decision to the log callback, if one is set
+    fn log_decision(&self, world: &WasmWorld, entity_id: u32, result: &JsDecisionResult) {
+        let Some(cb) = &self.log_callback else {
+            return;
+        };
+        let agent_id = world.agent_id_of(entity_id).map_or(entity_id as f64, |id| id.0 as f64);
+        // A throwing callback must not take the decision down with it
+        let _ = cb.call4(
+            &JsValue::NULL,
+            &JsValue::from(agent_id),
+            &JsValue::from(result.intent_type.as_str()),
+            &JsValue::from(result.utility),
+            &JsValue::from(result.reason.as_str()),
+        );
+    }
+}
 
-        // Convert DecisionOutput to JsDecisionResult
+/// JavaScript-friendly decision result
+///
+/// Converts Rust DecisionOutput to a format matching libreconomy-stub.js
+#[wasm_bindgen]
+pub struct JsDecisionResult {
+    intent_type: String,
+    target_x: f32,
+    target_y: f32,
+    has_target: bool,
+    target_entity: u32,
+    has_target_entity: bool,
+    utility: f32,
+    reason: String,
+}
+
+impl JsDecisionResult {
+    /// Flatten a decision, reporting the utility and reason it was chosen for
+    fn explained(decision: &DecisionOutput, utility: f32, reason: String) -> Self {
+        Self { utility, reason, ..Self::from_decision(decision) }
+    }
+
+    /// Flatten a decision into the libreconomy-stub.js shape
+    fn from_decision(decision: &DecisionOutput) -> Self {
         match decision {
             DecisionOutput::Intent(intent) => match intent {
                 Intent::SeekItem { item_type, urgency } => {
@@ -196,10 +270,48 @@ impl WasmDecisionMaker {
                         has_target: false,
                         target_entity: 0,
                         has_target_entity: false,
-                        utility: urgency,
+                        utility: *urgency,
                         reason: format!("Seeking {} (urgency: {:.2})", item_type, urgency),
                     }
                 }
+                Intent::MoveTo { x, y, item_type } if item_type == HOME_ITEM_TYPE => JsDecisionResult {
+                    intent_type: "RETURN_HOME".to_string(),
+                    target_x: *x,
+                    target_y: *y,
+                    has_target: true,
+                    target_entity: 0,
+                    has_target_entity: false,
+                    utility: 0.1,
+                    reason: "Returning home".to_string(),
+                },
+                Intent::MoveTo { x, y, item_type } => {
+                    let intent_type = if item_type == "water" {
+                        "SEEK_WATER".to_string()
+                    } else {
+                        "SEEK_FOOD".to_string()
+                    };
+
+                    JsDecisionResult {
+                        intent_type,
+                        target_x: *x,
+                        target_y: *y,
+                        has_target: true,
+                        target_entity: 0,
+                        has_target_entity: false,
+                        utility: 0.5,
+                        reason: format!("Heading to remembered {}", item_type),
+                    }
+                }
+                Intent::Migrate { toward_region } => JsDecisionResult {
+                    intent_type: "MIGRATE".to_string(),
+                    target_x: 0.0,
+                    target_y: 0.0,
+                    has_target: false,
+                    target_entity: 0,
+                    has_target_entity: false,
+                    utility: 1.0,
+                    reason: format!("Migrating toward region {}", toward_region),
+                },
                 Intent::Rest => JsDecisionResult {
                     intent_type: "REST".to_string(),
                     target_x: 0.0,
@@ -210,6 +322,39 @@ impl WasmDecisionMaker {
                     utility: 0.5,
                     reason: "Resting to recover".to_string(),
                 },
+                Intent::SeekTrade { buying, item_type } => JsDecisionResult {
+                    intent_type: "SEEK_TRADE".to_string(),
+                    target_x: 0.0,
+                    target_y: 0.0,
+                    has_target: false,
+                    target_entity: 0,
+                    has_target_entity: false,
+                    utility: 0.5,
+                    reason: format!("Looking to {} {}", if *buying { "buy" } else { "sell" }, item_type),
+                },
+                Intent::Invest { amount } => JsDecisionResult {
+                    intent_type: "INVEST".to_string(),
+                    target_x: 0.0,
+                    target_y: 0.0,
+                    has_target: false,
+                    target_entity: 0,
+                    has_target_entity: false,
+                    utility: 0.2,
+                    reason: format!("Saving surplus: {:.0}", amount),
+                },
+                Intent::WanderHeading { heading, persistence } => JsDecisionResult {
+                    intent_type: "WANDER".to_string(),
+                    target_x: 0.0,
+                    target_y: 0.0,
+                    has_target: false,
+                    target_entity: 0,
+                    has_target_entity: false,
+                    utility: 0.1,
+                    reason: format!(
+                        "Exploring along heading {:.2} rad ({} ticks left)",
+                        heading, persistence
+                    ),
+                },
                 Intent::Wander => JsDecisionResult {
                     intent_type: "WANDER".to_string(),
                     target_x: 0.0,
@@ -245,21 +390,6 @@ impl WasmDecisionMaker {
     }
 }
 
-/// JavaScript-friendly decision result
-///
-/// Converts Rust DecisionOutput to a format matching libreconomy-stub.js
-#[wasm_bindgen]
-pub struct JsDecisionResult {
-    intent_type: String,
-    target_x: f32,
-    target_y: f32,
-    has_target: bool,
-    target_entity: u32,
-    has_target_entity: bool,
-    utility: f32,
-    reason: String,
-}
-
 #[wasm_bindgen]
 impl JsDecisionResult {
     #[wasm_bindgen(getter)]
@@ -305,3 +435,50 @@ impl JsDecisionResult {
 
 // Note: WasmWorld needs a method to access the inner World
 // This will be added to world.rs
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Calling into JS needs a wasm32 host
+    #[cfg(target_arch = "wasm32")]
+    #[test]
+    fn test_log_callback_sees_every_decision() {
+        use wasm_bindgen::JsCast;
+
+        let mut world = WasmWorld::new();
+        let thirsty = world.create_agent_with_needs(90.0, 10.0, 10.0);
+        let content = world.create_agent_with_needs(10.0, 10.0, 10.0);
+        let query: JsWorldQuery = js_sys::eval(
+            "({ getNearbyAgents: () => [], getNearbyResources: () => [], canInteract: () => false })",
+        )
+        .unwrap()
+        .unchecked_into();
+        let logged = || js_sys::eval("globalThis.decisionLog").unwrap();
+        js_sys::eval("globalThis.decisionLog = []").unwrap();
+
+        let mut maker = WasmDecisionMaker::new();
+        // Nothing is reported before a callback is set
+        maker.decide_libreterra(&world, thirsty, &query);
+        assert_eq!(js_sys::Array::from(&logged()).length(), 0);
+
+        maker.set_log_callback(Function::new_with_args(
+            "agentId, intentType, utility, reason",
+            "globalThis.decisionLog.push([agentId, intentType, utility, reason])",
+        ));
+        let result = maker.decide_libreterra(&world, thirsty, &query);
+        maker.decide(&world, content, &query);
+        let log = js_sys::Array::from(&logged());
+        assert_eq!(log.length(), 2);
+        let first = js_sys::Array::from(&log.get(0));
+        assert_eq!(first.get(1).as_string(), Some(result.intent_type()));
+        assert_eq!(first.get(3).as_string(), Some(result.reason()));
+
+        // A throwing callback is ignored
+        maker.set_log_callback(Function::new_no_args("throw new Error('logger broke')"));
+        maker.decide_libreterra(&world, thirsty, &query);
+        maker.clear_log_callback();
+        maker.decide(&world, content, &query);
+        assert_eq!(js_sys::Array::from(&logged()).length(), 2);
+    }
+}
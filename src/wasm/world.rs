@@ -9,9 +9,18 @@ use serde_wasm_bindgen;
 use crate::{
     Agent, Needs, Inventory, Wallet, ResourceSource, SpeciesComponent, Species,
     AgentIdAllocator, create_agent, create_agent_with_needs,
-    create_agent_with_wallet, create_agent_custom, remove_agent,
-    ItemRegistry, NeedType, EnergyComponent,
+    create_agent_with_wallet, create_agent_custom, try_remove_agent,
+    ItemRegistry, NeedType, EnergyComponent, AgentId, ReputationKnowledge, inspect_agent,
+    consume_item, ConsumeAttempt, Market, OrderId, Side,
 };
+use serde::Serialize;
+
+/// One entry of `WasmWorld::most_trusted`
+#[derive(Serialize)]
+struct TrustEntry {
+    id: u32,
+    score: f32,
+}
 
 /// WASM wrapper for the ECS World
 ///
@@ -48,6 +57,7 @@ impl WasmWorld {
         world.register::<Wallet>();
         world.register::<ResourceSource>();
         world.register::<SpeciesComponent>();
+        world.register::<ReputationKnowledge>();
 
         // Insert AgentId allocator resource
         world.insert(AgentIdAllocator::new());
@@ -98,17 +108,18 @@ impl WasmWorld {
     }
 
     /// Remove an agent from the world
-    /// Returns true on success
-    ///
-    /// # Safety
-    /// The entity_id must be valid and currently alive.
-    /// Calling this with an invalid or already-deleted entity ID may panic.
+    /// Returns true on success, false if the entity is dead or not an agent
     pub fn remove_agent(&mut self, entity_id: u32) -> bool {
-        // Note: entity() will panic if the entity doesn't exist
-        // This is a limitation of the specs API
+        self.try_remove_agent(entity_id).is_ok()
+    }
+
+    /// Remove an agent from the world, returning its (now released) agent id
+    /// Throws "entity is already dead" or "entity is not an agent" otherwise
+    pub fn try_remove_agent(&mut self, entity_id: u32) -> Result<f64, String> {
         let entity = self.world.entities().entity(entity_id);
-        remove_agent(&mut self.world, entity);
-        true
+        try_remove_agent(&mut self.world, entity)
+            .map(|id| id.0 as f64)
+            .map_err(|err| err.to_string())
     }
 
     /// Get the total number of agents
@@ -214,6 +225,14 @@ impl WasmWorld {
         }
     }
 
+    /// Consume up to `quantity` units of an item from agent's inventory
+    /// Returns JSON `{consumed, need_reduction, success}`; `success` is false
+    /// and `consumed` 0 if the agent doesn't hold the item
+    pub fn consume_item(&mut self, entity_id: u32, item_id: &str, quantity: u32) -> JsValue {
+        let attempt = self.consume_attempt(entity_id, item_id, quantity);
+        serde_wasm_bindgen::to_value(&attempt).unwrap_or(JsValue::NULL)
+    }
+
     /// Get agent wallet as JSON
     /// Returns null if entity doesn't exist
     pub fn get_wallet(&self, entity_id: u32) -> JsValue {
@@ -421,6 +440,109 @@ impl WasmWorld {
             None => false,
         }
     }
+
+    /// Record an interaction in observer's view of target
+    ///
+    /// Positive weights build trust, negative weights erode it. The observer
+    /// gets a `ReputationKnowledge` component on first use.
+    /// Returns false if either entity is not a live agent.
+    pub fn update_reputation(&mut self, observer_id: u32, target_id: u32, weight: f32, tick: u32) -> bool {
+        let target = match (self.agent_id_of(observer_id), self.agent_id_of(target_id)) {
+            (Some(_), Some(target)) => target,
+            _ => return false,
+        };
+
+        let entity = self.world.entities().entity(observer_id);
+        let mut knowledge = self.world.write_storage::<ReputationKnowledge>();
+        if !knowledge.contains(entity) {
+            knowledge.insert(entity, ReputationKnowledge::new()).ok();
+        }
+        match knowledge.get_mut(entity) {
+            Some(knowledge) => {
+                knowledge.update_reputation(target, weight, tick as u64);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Observer's trust score for target (0.0-1.0)
+    /// Returns 0.5 (neutral) if there is no opinion or either entity is unknown
+    pub fn get_reputation_score(&self, observer_id: u32, target_id: u32) -> f32 {
+        let target = match self.agent_id_of(target_id) {
+            Some(target) => target,
+            None => return 0.5,
+        };
+
+        let entity = self.world.entities().entity(observer_id);
+        let knowledge = self.world.read_storage::<ReputationKnowledge>();
+        knowledge
+            .get(entity)
+            .map(|knowledge| knowledge.get_score(target))
+            .unwrap_or(0.5)
+    }
+
+    /// Full economic state of an agent as JSON (see `AgentReport`)
+    /// Returns null if the entity is not alive
+    pub fn inspect_agent(&self, entity_id: u32) -> JsValue {
+        let entity = self.world.entities().entity(entity_id);
+        if !self.world.is_alive(entity) {
+            return JsValue::NULL;
+        }
+        let report = inspect_agent(&self.world, entity, &self.item_registry);
+        serde_wasm_bindgen::to_value(&report).unwrap_or(JsValue::NULL)
+    }
+
+    /// Observer's most trusted agents
+    /// Returns JSON array of `{id, score}`, highest score first
+    pub fn most_trusted(&self, observer_id: u32, max: u32) -> JsValue {
+        let entries: Vec<TrustEntry> = self
+            .most_trusted_entities(observer_id, max)
+            .into_iter()
+            .map(|(id, score)| TrustEntry { id, score })
+            .collect();
+        serde_wasm_bindgen::to_value(&entries).unwrap_or(JsValue::NULL)
+    }
+
+    /// Post a limit order for an agent
+    /// Returns the order id; throws if the agent is unknown or the order invalid
+    pub fn post_order(
+        &mut self,
+        entity_id: u32,
+        item_id: &str,
+        is_bid: bool,
+        price: f32,
+        quantity: u32,
+    ) -> Result<f64, String> {
+        let agent = self
+            .agent_id_of(entity_id)
+            .ok_or_else(|| format!("entity {} is not an agent", entity_id))?;
+        let side = if is_bid { Side::Bid } else { Side::Ask };
+        let mut market = self.world.entry::<Market>().or_insert_with(Market::new);
+        market
+            .post(agent, item_id, side, price, quantity)
+            .map(|id| id.0 as f64)
+            .map_err(|err| err.to_string())
+    }
+
+    /// Fill up to `quantity` units of a resting order
+    /// Returns the units filled (0 if the order does not exist)
+    pub fn fill_order(&mut self, order_id: f64, quantity: u32) -> u32 {
+        match self.world.get_mut::<Market>() {
+            Some(market) => market.fill(OrderId(order_id as u64), quantity).unwrap_or(0),
+            None => 0,
+        }
+    }
+
+    /// Aggregated order book for an item as JSON (see `BookSnapshot`)
+    /// Levels are `{price, quantity, orders}`, best price first
+    pub fn get_book_snapshot(&self, item_id: &str) -> JsValue {
+        let book = match self.world.try_fetch::<Market>() {
+            Some(market) => market.book_snapshot(item_id),
+            None => Market::new().book_snapshot(item_id),
+        };
+        serde_wasm_bindgen::to_value(&book).unwrap_or(JsValue::NULL)
+    }
 }
 
 // Non-WASM-bindgen methods (for internal use)
@@ -429,6 +551,53 @@ impl WasmWorld {
     pub(crate) fn get_world(&self) -> &World {
         &self.world
     }
+
+    /// AgentId of a live agent entity
+    pub(crate) fn agent_id_of(&self, entity_id: u32) -> Option<AgentId> {
+        let entity = self.world.entities().entity(entity_id);
+        if !self.world.is_alive(entity) {
+            return None;
+        }
+        self.world.read_storage::<Agent>().get(entity).map(|agent| agent.id)
+    }
+
+    /// Consume items for `consume_item`, without the JS conversion
+    pub(crate) fn consume_attempt(&mut self, entity_id: u32, item_id: &str, quantity: u32) -> ConsumeAttempt {
+        let entity = self.world.entities().entity(entity_id);
+        if !self.world.is_alive(entity) {
+            return ConsumeAttempt::default();
+        }
+        let mut needs_storage = self.world.write_storage::<Needs>();
+        let mut inventory_storage = self.world.write_storage::<Inventory>();
+        match (needs_storage.get_mut(entity), inventory_storage.get_mut(entity)) {
+            (Some(needs), Some(inventory)) => {
+                consume_item(needs, inventory, &self.item_registry, item_id, quantity)
+            }
+            _ => ConsumeAttempt::default(),
+        }
+    }
+
+    /// Observer's most trusted agents as (entity id, score), highest first
+    pub(crate) fn most_trusted_entities(&self, observer_id: u32, max: u32) -> Vec<(u32, f32)> {
+        let entity = self.world.entities().entity(observer_id);
+        let knowledge = self.world.read_storage::<ReputationKnowledge>();
+        let trusted = match knowledge.get(entity) {
+            Some(knowledge) => knowledge.get_most_trusted(max as usize),
+            None => return Vec::new(),
+        };
+
+        let entities = self.world.entities();
+        let agents = self.world.read_storage::<Agent>();
+        trusted
+            .into_iter()
+            .filter_map(|(id, score)| {
+                (&entities, &agents)
+                    .join()
+                    .find(|(_, agent)| agent.id == id)
+                    .map(|(e, _)| (e.id(), score))
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -498,6 +667,23 @@ mod tests {
         assert!(!world.add_item(9999, "water", 1));
     }
 
+    #[test]
+    fn test_consume_item() {
+        let mut world = WasmWorld::new();
+        let id = world.create_agent_with_needs(80.0, 60.0, 40.0);
+        assert!(world.add_item(id, "water", 1));
+
+        let attempt = world.consume_attempt(id, "water", 1);
+        assert!(attempt.success);
+        assert_eq!(attempt.consumed, 1);
+        assert_eq!(attempt.need_reduction, 30.0);
+
+        // The only water is gone
+        let attempt = world.consume_attempt(id, "water", 1);
+        assert!(!attempt.success);
+        assert_eq!(attempt.consumed, 0);
+    }
+
     #[test]
     fn test_wallet_operations() {
         let mut world = WasmWorld::new();
@@ -562,5 +748,36 @@ mod tests {
         // Remove second agent
         assert!(world.remove_agent(id2));
         assert_eq!(world.get_agent_count(), 0);
+
+        // Removing again reports why instead of panicking
+        assert!(!world.remove_agent(id2));
+        assert_eq!(world.try_remove_agent(id2), Err("entity is already dead".to_string()));
+
+        // Released ids go to the next agents created
+        let id3 = world.create_agent();
+        assert_eq!(world.try_remove_agent(id3), Ok(1.0));
+    }
+
+    #[test]
+    fn test_reputation_update_and_score() {
+        let mut world = WasmWorld::new();
+        let observer = world.create_agent();
+        let honest = world.create_agent();
+        let cheat = world.create_agent();
+
+        assert_eq!(world.get_reputation_score(observer, honest), 0.5);
+
+        assert!(world.update_reputation(observer, honest, 3.0, 1));
+        assert!(world.update_reputation(observer, cheat, -2.0, 2));
+
+        assert!(world.get_reputation_score(observer, honest) > 0.5);
+        assert!(world.get_reputation_score(observer, cheat) < 0.5);
+
+        let trusted = world.most_trusted_entities(observer, 1);
+        assert_eq!(trusted.len(), 1);
+        assert_eq!(trusted[0].0, honest);
+
+        // Unknown entities are rejected
+        assert!(!world.update_reputation(observer, 9999, 1.0, 3));
     }
 }
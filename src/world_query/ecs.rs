@@ -0,0 +1,398 @@
+// ECS-backed WorldQuery adapter
+//
+// Many applications keep entity positions as a component inside the same
+// specs `World` libreconomy uses. `EcsPositionQuery` implements `WorldQuery`
+// directly on top of that storage, so there is no separate position map to
+// keep in sync with the ECS.
+
+use super::{ResourceDetail, ResourceLocation, WorldQuery};
+use crate::agent::{AgentId, AgentIndex};
+use crate::{Agent, ResourceSource};
+use specs::prelude::*;
+use std::marker::PhantomData;
+
+/// Component types that carry a 2D world position
+///
+/// Implement this for your application's position component so
+/// [`EcsPositionQuery`] can read it.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::Positioned;
+/// use specs::prelude::*;
+///
+/// struct Position { x: f32, y: f32 }
+///
+/// impl Component for Position {
+///     type Storage = VecStorage<Self>;
+/// }
+///
+/// impl Positioned for Position {
+///     fn position(&self) -> (f32, f32) {
+///         (self.x, self.y)
+///     }
+/// }
+/// ```
+pub trait Positioned {
+    /// World-space (x, y) coordinates
+    fn position(&self) -> (f32, f32);
+}
+
+/// `WorldQuery` implementation reading positions from an ECS component
+///
+/// Agents are entities with an [`Agent`] and a `P` component. Resources are
+/// entities with a [`ResourceSource`] and a `P` component; a resource matches
+/// a query if either its `resource_type` or `item_produced` equals the
+/// requested type. Detailed results carry the source's harvest prerequisites.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use specs::prelude::*;
+///
+/// struct Position { x: f32, y: f32 }
+/// impl Component for Position { type Storage = VecStorage<Self>; }
+/// impl Positioned for Position {
+///     fn position(&self) -> (f32, f32) { (self.x, self.y) }
+/// }
+///
+/// let mut world = World::new();
+/// world.register::<Agent>();
+/// world.register::<ResourceSource>();
+/// world.register::<Position>();
+///
+/// world.create_entity()
+///     .with(Agent { id: AgentId(1) })
+///     .with(Position { x: 0.0, y: 0.0 })
+///     .build();
+/// world.create_entity()
+///     .with(ResourceSource::new("water".into(), "water".into(), 0.0, 10))
+///     .with(Position { x: 3.0, y: 4.0 })
+///     .build();
+///
+/// let query = EcsPositionQuery::<Position>::new(&world, 10.0);
+/// let water = query.get_nearby_resources(AgentId(1), "water", 100.0);
+/// assert_eq!(water[0].distance, 5.0);
+/// ```
+///
+/// # Panics
+/// Query methods panic if `Agent`, `ResourceSource` or `P` is not registered.
+pub struct EcsPositionQuery<'w, P> {
+    world: &'w World,
+    index: AgentIndex,
+    interaction_distance: f32,
+    _position: PhantomData<P>,
+}
+
+impl<'w, P> EcsPositionQuery<'w, P>
+where
+    P: Component + Positioned,
+{
+    /// Create an adapter over `world`, indexing all current agents
+    ///
+    /// # Arguments
+    /// * `world` - ECS world holding agents, resources and positions
+    /// * `interaction_distance` - Maximum distance for `can_interact`
+    pub fn new(world: &'w World, interaction_distance: f32) -> Self {
+        Self::with_index(world, AgentIndex::from_world(world), interaction_distance)
+    }
+
+    /// Create an adapter reusing an existing [`AgentIndex`]
+    pub fn with_index(world: &'w World, index: AgentIndex, interaction_distance: f32) -> Self {
+        Self {
+            world,
+            index,
+            interaction_distance,
+            _position: PhantomData,
+        }
+    }
+
+    /// The agent index used to resolve ids to entities
+    pub fn index(&self) -> &AgentIndex {
+        &self.index
+    }
+
+    /// Position of an agent, if it is indexed and has a `P` component
+    pub fn agent_position(&self, agent: AgentId) -> Option<(f32, f32)> {
+        let entity = self.index.get(agent)?;
+        let positions = self.world.read_storage::<P>();
+        positions.get(entity).map(|p| p.position())
+    }
+
+    /// Other agents within `max_radius` matching `predicate`, closest first
+    fn agents_sorted_by_distance(
+        &self,
+        agent: AgentId,
+        max_count: usize,
+        max_radius: f32,
+        predicate: &dyn Fn(AgentId) -> bool,
+    ) -> Vec<AgentId> {
+        let origin = match self.agent_position(agent) {
+            Some(pos) => pos,
+            None => return Vec::new(),
+        };
+
+        let agents = self.world.read_storage::<Agent>();
+        let positions = self.world.read_storage::<P>();
+
+        let mut nearby: Vec<(AgentId, f32)> = (&agents, &positions)
+            .join()
+            .filter(|(a, _)| a.id != agent && predicate(a.id))
+            .map(|(a, p)| (a.id, distance(origin, p.position())))
+            .filter(|(_, d)| *d <= max_radius)
+            .collect();
+
+        // Sort by distance, ties broken by id for determinism
+        nearby.sort_by(|a, b| {
+            a.1.partial_cmp(&b.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.0 .0.cmp(&b.0 .0))
+        });
+
+        nearby.into_iter().take(max_count).map(|(id, _)| id).collect()
+    }
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt()
+}
+
+impl<'w, P> WorldQuery for EcsPositionQuery<'w, P>
+where
+    P: Component + Positioned,
+{
+    fn get_nearby_agents(&self, agent: AgentId, max_count: usize) -> Vec<AgentId> {
+        self.get_nearby_agents_within(agent, max_count, f32::INFINITY)
+    }
+
+    fn get_nearby_agents_within(
+        &self,
+        agent: AgentId,
+        max_count: usize,
+        max_radius: f32,
+    ) -> Vec<AgentId> {
+        self.agents_sorted_by_distance(agent, max_count, max_radius, &|_| true)
+    }
+
+    fn get_nearby_agents_filtered(
+        &self,
+        agent: AgentId,
+        max_count: usize,
+        predicate: &dyn Fn(AgentId) -> bool,
+    ) -> Vec<AgentId> {
+        self.agents_sorted_by_distance(agent, max_count, f32::INFINITY, predicate)
+    }
+
+    fn get_nearby_resources(
+        &self,
+        agent: AgentId,
+        resource_type: &str,
+        max_radius: f32,
+    ) -> Vec<ResourceLocation> {
+        self.get_nearby_resources_detailed(agent, resource_type, max_radius)
+            .into_iter()
+            .map(|detail| detail.location)
+            .collect()
+    }
+
+    /// Reports each source's `current_stock`; contention is always 0.
+    fn get_nearby_resources_detailed(
+        &self,
+        agent: AgentId,
+        resource_type: &str,
+        max_radius: f32,
+    ) -> Vec<ResourceDetail> {
+        let origin = match self.agent_position(agent) {
+            Some(pos) => pos,
+            None => return Vec::new(),
+        };
+
+        let entities = self.world.entities();
+        let sources = self.world.read_storage::<ResourceSource>();
+        let positions = self.world.read_storage::<P>();
+
+        let mut nearby: Vec<ResourceDetail> = (&entities, &sources, &positions)
+            .join()
+            .filter(|(_, s, _)| s.resource_type == resource_type || s.item_produced == resource_type)
+            .map(|(entity, s, p)| {
+                let (x, y) = p.position();
+                ResourceDetail::new(ResourceLocation::new(x, y, distance(origin, (x, y))).with_entity(entity))
+                    .with_stock(s.current_stock)
+                    .with_requirements(s.requires_item.clone(), s.requires_skill.clone())
+            })
+            .filter(|detail| detail.location.distance <= max_radius)
+            .collect();
+
+        nearby.sort_by(|a, b| {
+            a.location
+                .distance
+                .partial_cmp(&b.location.distance)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        nearby
+    }
+
+    fn can_interact(&self, agent1: AgentId, agent2: AgentId) -> bool {
+        match (self.agent_position(agent1), self.agent_position(agent2)) {
+            (Some(a), Some(b)) => distance(a, b) <= self.interaction_distance,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    struct Position {
+        x: f32,
+        y: f32,
+    }
+
+    impl Component for Position {
+        type Storage = VecStorage<Self>;
+    }
+
+    impl Positioned for Position {
+        fn position(&self) -> (f32, f32) {
+            (self.x, self.y)
+        }
+    }
+
+    fn create_test_world() -> World {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<ResourceSource>();
+        world.register::<Position>();
+        world
+    }
+
+    fn add_agent(world: &mut World, id: u64, x: f32, y: f32) -> Entity {
+        world
+            .create_entity()
+            .with(Agent { id: AgentId(id) })
+            .with(Position { x, y })
+            .build()
+    }
+
+    fn add_resource(world: &mut World, item: &str, x: f32, y: f32) -> Entity {
+        world
+            .create_entity()
+            .with(ResourceSource::new("plant".to_string(), item.to_string(), 0.0, 10))
+            .with(Position { x, y })
+            .build()
+    }
+
+    #[test]
+    fn test_get_nearby_agents_sorted_by_distance() {
+        let mut world = create_test_world();
+        add_agent(&mut world, 1, 0.0, 0.0);
+        add_agent(&mut world, 2, 10.0, 0.0);
+        add_agent(&mut world, 3, 5.0, 0.0);
+        add_agent(&mut world, 4, 50.0, 0.0);
+
+        let query = EcsPositionQuery::<Position>::new(&world, 10.0);
+
+        let nearby = query.get_nearby_agents(AgentId(1), 10);
+        assert_eq!(nearby, vec![AgentId(3), AgentId(2), AgentId(4)]);
+
+        let limited = query.get_nearby_agents(AgentId(1), 2);
+        assert_eq!(limited, vec![AgentId(3), AgentId(2)]);
+    }
+
+    #[test]
+    fn test_get_nearby_agents_within_radius() {
+        let mut world = create_test_world();
+        add_agent(&mut world, 1, 0.0, 0.0);
+        add_agent(&mut world, 2, 10.0, 0.0);
+        add_agent(&mut world, 3, 5.0, 0.0);
+        add_agent(&mut world, 4, 50.0, 0.0);
+
+        let query = EcsPositionQuery::<Position>::new(&world, 10.0);
+
+        let close = query.get_nearby_agents_within(AgentId(1), 10, 10.0);
+        assert_eq!(close, vec![AgentId(3), AgentId(2)]);
+    }
+
+    #[test]
+    fn test_get_nearby_resources_matches_item_and_radius() {
+        let mut world = create_test_world();
+        add_agent(&mut world, 1, 0.0, 0.0);
+        add_resource(&mut world, "water", 10.0, 0.0);
+        add_resource(&mut world, "water", 5.0, 0.0);
+        add_resource(&mut world, "grass", 3.0, 0.0);
+        add_resource(&mut world, "water", 100.0, 0.0);
+
+        let query = EcsPositionQuery::<Position>::new(&world, 10.0);
+
+        let water = query.get_nearby_resources(AgentId(1), "water", 50.0);
+        assert_eq!(water.len(), 2);
+        assert_eq!(water[0].distance, 5.0);
+        assert_eq!(water[1].distance, 10.0);
+
+        // resource_type also matches
+        let plants = query.get_nearby_resources(AgentId(1), "plant", 50.0);
+        assert_eq!(plants.len(), 3);
+    }
+
+    #[test]
+    fn test_get_nearby_resources_detailed_reports_stock() {
+        let mut world = create_test_world();
+        add_agent(&mut world, 1, 0.0, 0.0);
+        add_resource(&mut world, "water", 5.0, 0.0);
+
+        let query = EcsPositionQuery::<Position>::new(&world, 10.0);
+
+        let water = query.get_nearby_resources_detailed(AgentId(1), "water", 50.0);
+        assert_eq!(water.len(), 1);
+        assert_eq!(water[0].stock, Some(10));
+        assert_eq!(water[0].contention, 0);
+    }
+
+    #[test]
+    fn test_can_interact_uses_component_positions() {
+        let mut world = create_test_world();
+        add_agent(&mut world, 1, 0.0, 0.0);
+        add_agent(&mut world, 2, 5.0, 0.0);
+        add_agent(&mut world, 3, 20.0, 0.0);
+
+        let query = EcsPositionQuery::<Position>::new(&world, 10.0);
+
+        assert!(query.can_interact(AgentId(1), AgentId(2)));
+        assert!(!query.can_interact(AgentId(1), AgentId(3)));
+        assert!(!query.can_interact(AgentId(1), AgentId(99)));
+    }
+
+    #[test]
+    fn test_position_updates_visible_without_rebuild() {
+        let mut world = create_test_world();
+        let mover = add_agent(&mut world, 1, 0.0, 0.0);
+        add_agent(&mut world, 2, 30.0, 0.0);
+
+        {
+            let query = EcsPositionQuery::<Position>::new(&world, 10.0);
+            assert!(!query.can_interact(AgentId(1), AgentId(2)));
+        }
+
+        world
+            .write_storage::<Position>()
+            .insert(mover, Position { x: 25.0, y: 0.0 })
+            .unwrap();
+
+        let query = EcsPositionQuery::<Position>::new(&world, 10.0);
+        assert!(query.can_interact(AgentId(1), AgentId(2)));
+    }
+
+    #[test]
+    fn test_agent_without_position_finds_nothing() {
+        let mut world = create_test_world();
+        world.create_entity().with(Agent { id: AgentId(1) }).build();
+        add_resource(&mut world, "water", 1.0, 0.0);
+
+        let query = EcsPositionQuery::<Position>::new(&world, 10.0);
+        assert!(query.get_nearby_resources(AgentId(1), "water", 50.0).is_empty());
+        assert!(query.get_nearby_agents(AgentId(1), 5).is_empty());
+    }
+}
@@ -0,0 +1,419 @@
+// Standalone positional WorldQuery
+//
+// Hosts without their own spatial layer (scenarios, tools, small demos) can
+// keep agent and resource positions in a `GridWorldQuery` instead of writing
+// a `WorldQuery` of their own. Positions live outside the ECS, so the host
+// moves agents by calling `relocate_agent`, which also tells any caches
+// keyed on position (such as `ResourceQueryCache`) that the agent moved.
+
+use super::{InteractionMatrix, ResourceLocation, WorldQuery};
+use crate::agent::AgentId;
+use crate::systems::resource_sources;
+use specs::prelude::*;
+use std::collections::HashMap;
+
+/// One resource placed on the grid
+#[derive(Debug, Clone, PartialEq)]
+struct PlacedResource {
+    resource_type: String,
+    item_produced: String,
+    position: (f32, f32),
+    /// Source entity, for resources synced from the world
+    entity: Option<Entity>,
+}
+
+/// `WorldQuery` over positions stored in the query itself
+///
+/// A resource matches a query if either its resource type or the item it
+/// produces equals the requested type. Distances are Euclidean.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let mut grid = GridWorldQuery::new(10.0);
+/// grid.set_agent_position(AgentId(1), 0.0, 0.0);
+/// grid.set_agent_position(AgentId(2), 6.0, 8.0);
+/// grid.add_resource("well", "water", 3.0, 4.0);
+///
+/// assert_eq!(grid.get_nearby_resources(AgentId(1), "water", 100.0)[0].distance, 5.0);
+/// assert!(grid.can_interact(AgentId(1), AgentId(2)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GridWorldQuery {
+    agents: HashMap<AgentId, (f32, f32)>,
+    resources: Vec<PlacedResource>,
+    interaction_distance: f32,
+}
+
+impl GridWorldQuery {
+    /// Create an empty grid
+    ///
+    /// # Arguments
+    /// * `interaction_distance` - Maximum distance for `can_interact`
+    pub fn new(interaction_distance: f32) -> Self {
+        Self {
+            interaction_distance,
+            ..Default::default()
+        }
+    }
+
+    /// Place or move an agent
+    pub fn set_agent_position(&mut self, agent: AgentId, x: f32, y: f32) {
+        self.agents.insert(agent, (x, y));
+    }
+
+    /// Forget an agent's position
+    pub fn remove_agent(&mut self, agent: AgentId) {
+        self.agents.remove(&agent);
+    }
+
+    /// Position of an agent, if placed
+    pub fn agent_position(&self, agent: AgentId) -> Option<(f32, f32)> {
+        self.agents.get(&agent).copied()
+    }
+
+    /// Place a resource
+    pub fn add_resource(&mut self, resource_type: &str, item_produced: &str, x: f32, y: f32) {
+        self.resources.push(PlacedResource {
+            resource_type: resource_type.to_string(),
+            item_produced: item_produced.to_string(),
+            position: (x, y),
+            entity: None,
+        });
+    }
+
+    /// Replace every placed resource with the world's `ResourceSource`s
+    ///
+    /// Each source with stock left is placed where `position` says; sources
+    /// it returns `None` for, and depleted ones, are left out. Their
+    /// locations carry the source entity. Call it each tick (or whenever
+    /// sources change) instead of mirroring every `ResourceSource` by hand
+    /// with [`add_resource`](Self::add_resource).
+    ///
+    /// # Example
+    /// ```rust
+    /// use libreconomy::*;
+    /// use specs::prelude::*;
+    ///
+    /// let mut world = World::new();
+    /// world.register::<ResourceSource>();
+    /// world.create_entity().with(ResourceSource::new("well".into(), "water".into(), 0.0, 5)).build();
+    ///
+    /// let mut grid = GridWorldQuery::new(10.0);
+    /// grid.set_agent_position(AgentId(1), 0.0, 0.0);
+    /// grid.sync_from_world(&world, |_entity| Some((3.0, 4.0)));
+    /// assert_eq!(grid.get_nearby_resources(AgentId(1), "water", 10.0)[0].distance, 5.0);
+    /// ```
+    pub fn sync_from_world(&mut self, world: &World, position: impl Fn(Entity) -> Option<(f32, f32)>) {
+        self.resources = resource_sources(world)
+            .into_iter()
+            .filter(|(_, source)| source.current_stock > 0)
+            .filter_map(|(entity, source)| {
+                position(entity).map(|position| PlacedResource {
+                    resource_type: source.resource_type,
+                    item_produced: source.item_produced,
+                    position,
+                    entity: Some(entity),
+                })
+            })
+            .collect();
+    }
+
+    /// Number of placed agents
+    pub fn agent_count(&self) -> usize {
+        self.agents.len()
+    }
+
+    /// Number of placed resources
+    pub fn resource_count(&self) -> usize {
+        self.resources.len()
+    }
+
+    /// Every ordered pair of placed agents within interaction distance
+    ///
+    /// Buckets agents into cells and only compares neighbouring cells, so it
+    /// costs about O(agents + pairs) instead of a `can_interact` call per
+    /// pair. Insert the result into the world once per tick to speed up
+    /// decisions (see [`InteractionMatrix`]).
+    pub fn interaction_matrix(&self) -> InteractionMatrix {
+        let range = self.interaction_distance;
+        let mut matrix = InteractionMatrix::new();
+        if !(range > 0.0 && range.is_finite()) {
+            // No usable cell size; compare every pair
+            for (&a, &pa) in &self.agents {
+                for (&b, &pb) in &self.agents {
+                    if a != b && distance(pa, pb) <= range {
+                        matrix.insert(a, b);
+                    }
+                }
+            }
+            return matrix;
+        }
+
+        // Cells twice the range wide: an in-range pair is at most one cell apart
+        let cell_size = f64::from(range) * 2.0;
+        let cell_of = |(x, y): (f32, f32)| {
+            (
+                (f64::from(x) / cell_size).floor() as i64,
+                (f64::from(y) / cell_size).floor() as i64,
+            )
+        };
+        let mut cells: HashMap<(i64, i64), Vec<PlacedAgent>> = HashMap::new();
+        for (&id, &pos) in &self.agents {
+            cells.entry(cell_of(pos)).or_default().push((id, pos));
+        }
+
+        for (&(cx, cy), members) in &cells {
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    let Some(neighbours) = cells.get(&(cx.saturating_add(dx), cy.saturating_add(dy)))
+                    else {
+                        continue;
+                    };
+                    for &(a, pa) in members {
+                        for &(b, pb) in neighbours {
+                            if a != b && distance(pa, pb) <= range {
+                                matrix.insert(a, b);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        matrix
+    }
+}
+
+/// Something that has to hear when an agent moves on a [`GridWorldQuery`]
+///
+/// Implemented by `ResourceQueryCache`, which forgets the agent's memoized
+/// results.
+pub trait RelocationHook {
+    /// `agent` moved from `from` (`None` if it was not placed) to `to`
+    fn agent_relocated(&self, agent: AgentId, from: Option<(f32, f32)>, to: (f32, f32));
+}
+
+/// Move an agent on the grid and notify every hook
+///
+/// The position is replaced outright, so later queries, including the
+/// bucketed [`GridWorldQuery::interaction_matrix`], only see the agent at
+/// its new place. Hooks run after the move, in order. Returns the previous
+/// position, if the agent was placed.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+///
+/// let mut grid = GridWorldQuery::new(5.0);
+/// grid.set_agent_position(AgentId(1), 0.0, 0.0);
+/// grid.add_resource("well", "water", 50.0, 0.0);
+/// let cache = ResourceQueryCache::new(10);
+///
+/// let from = relocate_agent(&mut grid, AgentId(1), 48.0, 0.0, &[&cache]);
+/// assert_eq!(from, Some((0.0, 0.0)));
+/// assert_eq!(grid.get_nearby_resources(AgentId(1), "water", 5.0)[0].distance, 2.0);
+/// ```
+pub fn relocate_agent(
+    query: &mut GridWorldQuery,
+    agent: AgentId,
+    x: f32,
+    y: f32,
+    hooks: &[&dyn RelocationHook],
+) -> Option<(f32, f32)> {
+    let from = query.agents.insert(agent, (x, y));
+    for hook in hooks {
+        hook.agent_relocated(agent, from, (x, y));
+    }
+    from
+}
+
+/// An agent id with its position
+type PlacedAgent = (AgentId, (f32, f32));
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt()
+}
+
+impl WorldQuery for GridWorldQuery {
+    fn get_nearby_agents(&self, agent: AgentId, max_count: usize) -> Vec<AgentId> {
+        self.get_nearby_agents_within(agent, max_count, f32::INFINITY)
+    }
+
+    fn get_nearby_agents_within(
+        &self,
+        agent: AgentId,
+        max_count: usize,
+        max_radius: f32,
+    ) -> Vec<AgentId> {
+        let origin = match self.agent_position(agent) {
+            Some(pos) => pos,
+            None => return Vec::new(),
+        };
+
+        let mut nearby: Vec<(AgentId, f32)> = self
+            .agents
+            .iter()
+            .filter(|(id, _)| **id != agent)
+            .map(|(id, pos)| (*id, distance(origin, *pos)))
+            .filter(|(_, d)| *d <= max_radius)
+            .collect();
+
+        // Sort by distance, ties broken by id for determinism
+        nearby.sort_by(|a, b| {
+            a.1.partial_cmp(&b.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.0.cmp(&b.0))
+        });
+
+        nearby.into_iter().take(max_count).map(|(id, _)| id).collect()
+    }
+
+    fn get_nearby_resources(
+        &self,
+        agent: AgentId,
+        resource_type: &str,
+        max_radius: f32,
+    ) -> Vec<ResourceLocation> {
+        let origin = match self.agent_position(agent) {
+            Some(pos) => pos,
+            None => return Vec::new(),
+        };
+
+        let mut nearby: Vec<ResourceLocation> = self
+            .resources
+            .iter()
+            .filter(|r| r.resource_type == resource_type || r.item_produced == resource_type)
+            .map(|r| {
+                let location = ResourceLocation::new(r.position.0, r.position.1, distance(origin, r.position));
+                match r.entity {
+                    Some(entity) => location.with_entity(entity),
+                    None => location,
+                }
+            })
+            .filter(|loc| loc.distance <= max_radius)
+            .collect();
+
+        nearby.sort_by(|a, b| {
+            a.distance
+                .partial_cmp(&b.distance)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        nearby
+    }
+
+    fn travel_cost(&self, agent: AgentId, target_x: f32, target_y: f32) -> Option<f32> {
+        self.agent_position(agent)
+            .map(|origin| distance(origin, (target_x, target_y)))
+    }
+
+    fn can_interact(&self, agent1: AgentId, agent2: AgentId) -> bool {
+        match (self.agent_position(agent1), self.agent_position(agent2)) {
+            (Some(a), Some(b)) => distance(a, b) <= self.interaction_distance,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_grid_queries_sort_by_distance() {
+        let mut grid = GridWorldQuery::new(5.0);
+        grid.set_agent_position(AgentId(1), 0.0, 0.0);
+        grid.set_agent_position(AgentId(2), 10.0, 0.0);
+        grid.set_agent_position(AgentId(3), 3.0, 0.0);
+        grid.add_resource("well", "water", 8.0, 0.0);
+        grid.add_resource("spring", "water", 2.0, 0.0);
+        grid.add_resource("bush", "food", 1.0, 0.0);
+
+        assert_eq!(grid.get_nearby_agents(AgentId(1), 5), vec![AgentId(3), AgentId(2)]);
+        assert_eq!(grid.get_nearby_agents_within(AgentId(1), 5, 5.0), vec![AgentId(3)]);
+
+        let water = grid.get_nearby_resources(AgentId(1), "water", 100.0);
+        assert_eq!(water.iter().map(|r| r.x).collect::<Vec<_>>(), vec![2.0, 8.0]);
+        assert_eq!(grid.get_nearby_resources(AgentId(1), "spring", 100.0).len(), 1);
+
+        assert!(grid.can_interact(AgentId(1), AgentId(3)));
+        assert!(!grid.can_interact(AgentId(1), AgentId(2)));
+        assert!(!grid.can_interact(AgentId(1), AgentId(99)));
+    }
+
+    #[test]
+    fn test_sync_tracks_created_and_depleted_sources() {
+        use crate::ResourceSource;
+
+        let mut world = World::new();
+        world.register::<ResourceSource>();
+        let mut spawn = |world: &mut World, stock| {
+            world
+                .create_entity()
+                .with(ResourceSource::new("well".into(), "water".into(), 0.0, stock))
+                .build()
+        };
+        let near = spawn(&mut world, 10);
+        spawn(&mut world, 10);
+        let at = |entity: Entity| Some((if entity == near { 2.0 } else { 9.0 }, 0.0));
+
+        let mut grid = GridWorldQuery::new(5.0);
+        grid.set_agent_position(AgentId(1), 0.0, 0.0);
+        grid.add_resource("stale", "water", 1.0, 0.0);
+        grid.sync_from_world(&world, at);
+        let xs = |grid: &GridWorldQuery| {
+            grid.get_nearby_resources(AgentId(1), "water", 100.0)
+                .iter()
+                .map(|r| r.x)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(xs(&grid), vec![2.0, 9.0]);
+
+        // The near well runs dry and a new one opens beside the far one
+        world.write_storage::<ResourceSource>().get_mut(near).unwrap().current_stock = 0;
+        spawn(&mut world, 3);
+        grid.sync_from_world(&world, at);
+        assert_eq!(xs(&grid), vec![9.0, 9.0]);
+        assert_eq!(grid.resource_count(), 2);
+    }
+
+    #[test]
+    fn test_relocated_agent_is_only_found_at_new_position() {
+        use std::cell::RefCell;
+
+        struct Recorder(RefCell<Vec<(AgentId, Option<(f32, f32)>, (f32, f32))>>);
+        impl RelocationHook for Recorder {
+            fn agent_relocated(&self, agent: AgentId, from: Option<(f32, f32)>, to: (f32, f32)) {
+                self.0.borrow_mut().push((agent, from, to));
+            }
+        }
+
+        let mut grid = GridWorldQuery::new(5.0);
+        grid.set_agent_position(AgentId(1), 0.0, 0.0);
+        grid.set_agent_position(AgentId(2), 3.0, 0.0);
+        grid.set_agent_position(AgentId(3), 100.0, 0.0);
+        grid.add_resource("well", "water", 1.0, 0.0);
+        grid.add_resource("lake", "water", 102.0, 0.0);
+        let recorder = Recorder(RefCell::new(Vec::new()));
+
+        let from = relocate_agent(&mut grid, AgentId(1), 99.0, 0.0, &[&recorder]);
+        assert_eq!(from, Some((0.0, 0.0)));
+        assert_eq!(*recorder.0.borrow(), vec![(AgentId(1), Some((0.0, 0.0)), (99.0, 0.0))]);
+        assert_eq!(grid.agent_count(), 3);
+
+        let water = grid.get_nearby_resources(AgentId(1), "water", 5.0);
+        assert_eq!(water.iter().map(|r| r.x).collect::<Vec<_>>(), vec![102.0]);
+        assert_eq!(grid.get_nearby_agents_within(AgentId(1), 5, 5.0), vec![AgentId(3)]);
+        // The old neighbour no longer sees it, by either query path
+        assert_eq!(grid.get_nearby_agents_within(AgentId(2), 5, 5.0), Vec::new());
+        let matrix = grid.interaction_matrix();
+        assert!(!matrix.can_interact(AgentId(2), AgentId(1)));
+        assert!(matrix.can_interact(AgentId(3), AgentId(1)));
+
+        // Placing an agent for the first time is a relocation from nowhere
+        assert_eq!(relocate_agent(&mut grid, AgentId(4), 1.0, 1.0, &[&recorder]), None);
+        assert_eq!(recorder.0.borrow().last().unwrap().1, None);
+    }
+}
@@ -0,0 +1,194 @@
+// Precomputed interaction ranges
+//
+// Deciding for every agent asks `can_interact` for each candidate partner,
+// re-walking the host's spatial structure every time. A host that can list
+// everyone within interaction range in one pass (as `GridWorldQuery` does)
+// inserts the result as an `InteractionMatrix` resource once per tick, and
+// decision-making answers `can_interact` from it instead.
+
+use super::{ResourceDetail, ResourceLocation, WorldQuery};
+use crate::agent::AgentId;
+use std::collections::HashSet;
+
+/// World resource listing which agents can interact this tick
+///
+/// Pairs are ordered, so a matrix built from a `WorldQuery` matches its
+/// `can_interact` even if that is not symmetric. While the resource is in
+/// the world, decisions consult it instead of the `WorldQuery`; rebuild it
+/// whenever agents move, or remove it to go back to per-pair queries.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use libreconomy::world_query::InteractionMatrix;
+///
+/// let mut grid = GridWorldQuery::new(5.0);
+/// grid.set_agent_position(AgentId(1), 0.0, 0.0);
+/// grid.set_agent_position(AgentId(2), 3.0, 4.0);
+/// grid.set_agent_position(AgentId(3), 50.0, 0.0);
+///
+/// let matrix = grid.interaction_matrix();
+/// assert!(matrix.can_interact(AgentId(1), AgentId(2)));
+/// assert!(!matrix.can_interact(AgentId(1), AgentId(3)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InteractionMatrix {
+    pairs: HashSet<(AgentId, AgentId)>,
+}
+
+impl InteractionMatrix {
+    /// An empty matrix (nobody can interact)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a matrix by asking `query` about every ordered pair of `agents`
+    ///
+    /// O(agents²) calls to `can_interact`; hosts with a spatial index should
+    /// collect in-range pairs themselves and [`insert`](Self::insert) them.
+    pub fn from_query(query: &dyn WorldQuery, agents: &[AgentId]) -> Self {
+        let mut matrix = Self::new();
+        for &a in agents {
+            for &b in agents {
+                if a != b && query.can_interact(a, b) {
+                    matrix.insert(a, b);
+                }
+            }
+        }
+        matrix
+    }
+
+    /// Record that `agent1` can interact with `agent2`
+    pub fn insert(&mut self, agent1: AgentId, agent2: AgentId) {
+        self.pairs.insert((agent1, agent2));
+    }
+
+    /// Whether `agent1` can interact with `agent2`
+    pub fn can_interact(&self, agent1: AgentId, agent2: AgentId) -> bool {
+        self.pairs.contains(&(agent1, agent2))
+    }
+
+    /// Number of ordered pairs in range
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Check if no pair is in range
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+/// `WorldQuery` adapter answering `can_interact` from an [`InteractionMatrix`]
+///
+/// Everything else passes straight through. Used internally when the world
+/// has an `InteractionMatrix` resource.
+pub struct MatrixQuery<'a> {
+    inner: &'a dyn WorldQuery,
+    matrix: &'a InteractionMatrix,
+}
+
+impl<'a> MatrixQuery<'a> {
+    /// Wrap an application query
+    pub fn new(inner: &'a dyn WorldQuery, matrix: &'a InteractionMatrix) -> Self {
+        Self { inner, matrix }
+    }
+}
+
+impl WorldQuery for MatrixQuery<'_> {
+    fn get_nearby_agents(&self, agent: AgentId, max_count: usize) -> Vec<AgentId> {
+        self.inner.get_nearby_agents(agent, max_count)
+    }
+
+    fn get_nearby_agents_within(
+        &self,
+        agent: AgentId,
+        max_count: usize,
+        max_radius: f32,
+    ) -> Vec<AgentId> {
+        self.inner.get_nearby_agents_within(agent, max_count, max_radius)
+    }
+
+    fn get_nearby_agents_filtered(
+        &self,
+        agent: AgentId,
+        max_count: usize,
+        predicate: &dyn Fn(AgentId) -> bool,
+    ) -> Vec<AgentId> {
+        self.inner.get_nearby_agents_filtered(agent, max_count, predicate)
+    }
+
+    fn get_nearby_resources(
+        &self,
+        agent: AgentId,
+        resource_type: &str,
+        max_radius: f32,
+    ) -> Vec<ResourceLocation> {
+        self.inner.get_nearby_resources(agent, resource_type, max_radius)
+    }
+
+    fn get_nearby_resources_detailed(
+        &self,
+        agent: AgentId,
+        resource_type: &str,
+        max_radius: f32,
+    ) -> Vec<ResourceDetail> {
+        self.inner
+            .get_nearby_resources_detailed(agent, resource_type, max_radius)
+    }
+
+    fn travel_cost(&self, agent: AgentId, target_x: f32, target_y: f32) -> Option<f32> {
+        self.inner.travel_cost(agent, target_x, target_y)
+    }
+
+    fn can_interact(&self, agent1: AgentId, agent2: AgentId) -> bool {
+        self.matrix.can_interact(agent1, agent2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world_query::GridWorldQuery;
+    use pretty_assertions::assert_eq;
+
+    fn scattered_grid() -> (GridWorldQuery, Vec<AgentId>) {
+        let mut grid = GridWorldQuery::new(4.0);
+        let ids: Vec<AgentId> = (1..=40).map(AgentId).collect();
+        for id in &ids {
+            // Deterministic scatter with clusters, cell edges and exact-range pairs
+            let x = ((id.0 * 37) % 23) as f32 * 1.7;
+            let y = ((id.0 * 11) % 13) as f32 * 2.0;
+            grid.set_agent_position(*id, x, y);
+        }
+        grid.set_agent_position(AgentId(41), -4.0, 0.0);
+        grid.set_agent_position(AgentId(42), 0.0, 0.0);
+        (grid, (1..=42).map(AgentId).collect())
+    }
+
+    #[test]
+    fn test_matrix_matches_per_pair_queries() {
+        let (grid, ids) = scattered_grid();
+        let precomputed = grid.interaction_matrix();
+        let brute_force = InteractionMatrix::from_query(&grid, &ids);
+        assert_eq!(precomputed, brute_force);
+        assert!(!precomputed.is_empty());
+
+        let wrapped = MatrixQuery::new(&grid, &precomputed);
+        for &a in &ids {
+            for &b in &ids {
+                if a != b {
+                    assert_eq!(wrapped.can_interact(a, b), grid.can_interact(a, b), "{:?}-{:?}", a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_unlimited_range_falls_back_to_all_pairs() {
+        let mut grid = GridWorldQuery::new(f32::INFINITY);
+        grid.set_agent_position(AgentId(1), 0.0, 0.0);
+        grid.set_agent_position(AgentId(2), 1e30, 0.0);
+        assert_eq!(grid.interaction_matrix().len(), 2);
+    }
+}
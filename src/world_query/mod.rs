@@ -49,8 +49,34 @@
 // }
 // ```
 
+//
+// # Distance Metric
+//
+// Distances and radii are in whatever units the application uses: tiles on a
+// grid, meters in a continuous world, hop counts or path costs on a graph.
+// libreconomy never interprets them on their own; it only compares a distance
+// against the radius it searched with, via `normalize_distance`. As long as a
+// `WorldQuery` reports `distance` in the same metric it applies `max_radius`
+// in, decision-making behaves the same in every kind of world. Configure
+// search radii (e.g. `UtilityMaximizer::resource_search_radius`) in that
+// metric too.
+
 use crate::agent::AgentId;
+use crate::agent::components::{harvest_requirements_met, Inventory, Skills};
 use serde::{Deserialize, Serialize};
+use specs::Entity;
+
+pub mod ecs;
+pub mod grid;
+pub mod interaction;
+pub mod species;
+pub mod test_mode;
+
+pub use ecs::{EcsPositionQuery, Positioned};
+pub use grid::{relocate_agent, GridWorldQuery, RelocationHook};
+pub use interaction::{InteractionMatrix, MatrixQuery};
+pub use species::{nearby_agents_of_species, nearest_agent_of_species, nearest_prey};
+pub use test_mode::{InRangeQuery, TestMode};
 
 /// Location of a resource in the world (x, y coordinates and distance)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -61,12 +87,136 @@ pub struct ResourceLocation {
     pub y: f32,
     /// Distance from the querying agent (for convenience)
     pub distance: f32,
+    /// Id of the `ResourceSource` entity here, when the query knows it
+    #[serde(default)]
+    pub entity_id: Option<u32>,
+    /// Generation of that entity, so a reused id isn't mistaken for it
+    #[serde(default)]
+    pub entity_generation: Option<i32>,
 }
 
 impl ResourceLocation {
     /// Create a new resource location
     pub fn new(x: f32, y: f32, distance: f32) -> Self {
-        Self { x, y, distance }
+        Self {
+            x,
+            y,
+            distance,
+            entity_id: None,
+            entity_generation: None,
+        }
+    }
+
+    /// Set the source entity, see [`harvest_from_location`](crate::harvest_from_location)
+    pub fn with_entity(mut self, entity: Entity) -> Self {
+        self.entity_id = Some(entity.id());
+        self.entity_generation = Some(entity.gen().id());
+        self
+    }
+
+    /// Whether this resource lies within `radius` (inclusive)
+    pub fn within(&self, radius: f32) -> bool {
+        self.distance <= radius
+    }
+
+    /// Distance as a fraction of `radius`, see [`normalize_distance`]
+    pub fn normalized_distance(&self, radius: f32) -> f32 {
+        normalize_distance(self.distance, radius)
+    }
+}
+
+/// Express `distance` as a 0-1 fraction of `radius`
+///
+/// 0.0 at the agent, 1.0 at the radius edge and beyond. Unit-agnostic: both
+/// arguments just need to use the same metric. An infinite radius makes every
+/// finite distance 0.0; a zero, negative or NaN radius makes everything but
+/// distance 0 count as 1.0.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::world_query::normalize_distance;
+///
+/// assert_eq!(normalize_distance(0.0, 50.0), 0.0);
+/// assert_eq!(normalize_distance(25.0, 50.0), 0.5);
+/// assert_eq!(normalize_distance(80.0, 50.0), 1.0);
+/// ```
+pub fn normalize_distance(distance: f32, radius: f32) -> f32 {
+    if distance.is_nan() {
+        return 1.0;
+    }
+    let distance = distance.max(0.0);
+    if radius.is_nan() || radius <= 0.0 {
+        return if distance == 0.0 { 0.0 } else { 1.0 };
+    }
+    (distance / radius).min(1.0)
+}
+
+/// A nearby resource with stock and contention information
+///
+/// Returned by [`WorldQuery::get_nearby_resources_detailed`]. `stock` is the
+/// amount left at the source, if known; `contention` is how many *other*
+/// agents are currently targeting it, as supplied by the host or a system.
+/// `requires_item`/`requires_skill` mirror the source's harvest
+/// prerequisites; decision-making skips sources the agent can't use.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResourceDetail {
+    /// Where the resource is
+    pub location: ResourceLocation,
+    /// Units remaining at the source (None if unknown)
+    pub stock: Option<u32>,
+    /// Number of other agents targeting this source
+    pub contention: u32,
+    /// Tool needed to harvest here
+    #[serde(default)]
+    pub requires_item: Option<String>,
+    /// Skill and minimum level needed to harvest here
+    #[serde(default)]
+    pub requires_skill: Option<(String, u32)>,
+}
+
+impl ResourceDetail {
+    /// Resource with unknown stock and no contention
+    pub fn new(location: ResourceLocation) -> Self {
+        Self {
+            location,
+            stock: None,
+            contention: 0,
+            requires_item: None,
+            requires_skill: None,
+        }
+    }
+
+    /// Copy a source's harvest prerequisites
+    pub fn with_requirements(
+        mut self,
+        requires_item: Option<String>,
+        requires_skill: Option<(String, u32)>,
+    ) -> Self {
+        self.requires_item = requires_item;
+        self.requires_skill = requires_skill;
+        self
+    }
+
+    /// Whether an agent with this inventory and skills may harvest here
+    pub fn can_harvest(&self, inventory: Option<&Inventory>, skills: Option<&Skills>) -> bool {
+        harvest_requirements_met(
+            self.requires_item.as_deref(),
+            self.requires_skill.as_ref().map(|(s, l)| (s.as_str(), *l)),
+            inventory,
+            skills,
+        )
+    }
+
+    /// Set the remaining stock
+    pub fn with_stock(mut self, stock: u32) -> Self {
+        self.stock = Some(stock);
+        self
+    }
+
+    /// Set the number of other agents targeting this source
+    pub fn with_contention(mut self, contention: u32) -> Self {
+        self.contention = contention;
+        self
     }
 }
 
@@ -118,6 +268,62 @@ pub trait WorldQuery {
     /// ```
     fn get_nearby_agents(&self, agent: AgentId, max_count: usize) -> Vec<AgentId>;
 
+    /// Get agents near the given agent, limited to `max_radius` world units
+    ///
+    /// Same contract as [`get_nearby_agents`](Self::get_nearby_agents), but
+    /// agents farther than `max_radius` are excluded. Used by decision-making
+    /// to keep trading partners local even when resources are searched for
+    /// over a much larger area.
+    ///
+    /// The default implementation has no distance information and simply
+    /// delegates to `get_nearby_agents`, ignoring the radius. Implementations
+    /// that know agent positions should override it.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let partners = world_query.get_nearby_agents_within(my_agent, 5, 50.0);
+    /// // Returns up to 5 closest agents no farther than 50 units away
+    /// ```
+    fn get_nearby_agents_within(
+        &self,
+        agent: AgentId,
+        max_count: usize,
+        max_radius: f32,
+    ) -> Vec<AgentId> {
+        let _ = max_radius;
+        self.get_nearby_agents(agent, max_count)
+    }
+
+    /// Get agents near the given agent that satisfy `predicate`
+    ///
+    /// Same contract as [`get_nearby_agents`](Self::get_nearby_agents), but
+    /// agents for which `predicate` returns false are skipped before
+    /// `max_count` is applied. Lets callers filter by species, faction or any
+    /// other crate-side property without the host knowing about it.
+    ///
+    /// The default implementation requests every nearby agent and filters the
+    /// result. Implementations with a spatial index may override it to stop
+    /// early.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let odd = world_query.get_nearby_agents_filtered(my_agent, 3, &|id| id.0 % 2 == 1);
+    /// ```
+    fn get_nearby_agents_filtered(
+        &self,
+        agent: AgentId,
+        max_count: usize,
+        predicate: &dyn Fn(AgentId) -> bool,
+    ) -> Vec<AgentId> {
+        self.get_nearby_agents(agent, usize::MAX)
+            .into_iter()
+            .filter(|id| predicate(*id))
+            .take(max_count)
+            .collect()
+    }
+
     /// Get resources of a specific type near the given agent
     ///
     /// Returns a list of resource locations sorted by distance (closest first).
@@ -150,6 +356,60 @@ pub trait WorldQuery {
         max_radius: f32,
     ) -> Vec<ResourceLocation>;
 
+    /// Get resources near the given agent along with stock and contention
+    ///
+    /// Same contract as [`get_nearby_resources`](Self::get_nearby_resources).
+    /// Decision-making uses the extra information to discount sources that
+    /// many agents are competing for, so agents spread out when forage is
+    /// scarce.
+    ///
+    /// The default implementation wraps `get_nearby_resources` with unknown
+    /// stock and no contention, which leaves decisions unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for detail in world_query.get_nearby_resources_detailed(my_agent, "grass", 100.0) {
+    ///     println!("grass at distance {} with {} rivals",
+    ///              detail.location.distance, detail.contention);
+    /// }
+    /// ```
+    fn get_nearby_resources_detailed(
+        &self,
+        agent: AgentId,
+        resource_type: &str,
+        max_radius: f32,
+    ) -> Vec<ResourceDetail> {
+        self.get_nearby_resources(agent, resource_type, max_radius)
+            .into_iter()
+            .map(ResourceDetail::new)
+            .collect()
+    }
+
+    /// Cost for an agent to travel to a point, in the units of resource distances
+    ///
+    /// Decision-making ranks resources by this cost instead of their
+    /// straight-line `distance`, so a host that knows about walls, rivers or
+    /// roads can steer agents away from sources that are close but hard to
+    /// reach. Report unreachable points as `f32::INFINITY` (decisions then
+    /// ignore the source); `None` means the cost is unknown and the
+    /// straight-line distance is used.
+    ///
+    /// The default implementation returns `None`, so the straight-line
+    /// distance reported with each resource is used unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn travel_cost(&self, agent: AgentId, x: f32, y: f32) -> Option<f32> {
+    ///     self.navmesh.path_length(self.position(agent)?, (x, y))
+    /// }
+    /// ```
+    fn travel_cost(&self, agent: AgentId, target_x: f32, target_y: f32) -> Option<f32> {
+        let _ = (agent, target_x, target_y);
+        None
+    }
+
     /// Check if two agents can interact
     ///
     /// Returns true if the agents are close enough to interact (trade,
@@ -290,6 +550,27 @@ mod tests {
         assert_eq!(loc.distance, 5.0);
     }
 
+    #[test]
+    fn test_normalized_distance_bounds() {
+        let radius = 40.0;
+        assert_eq!(ResourceLocation::new(0.0, 0.0, 0.0).normalized_distance(radius), 0.0);
+        assert_eq!(ResourceLocation::new(40.0, 0.0, 40.0).normalized_distance(radius), 1.0);
+        assert_eq!(ResourceLocation::new(10.0, 0.0, 10.0).normalized_distance(radius), 0.25);
+        // Beyond the edge stays at 1
+        assert_eq!(normalize_distance(100.0, radius), 1.0);
+        // Degenerate radii
+        assert_eq!(normalize_distance(5.0, f32::INFINITY), 0.0);
+        assert_eq!(normalize_distance(0.0, 0.0), 0.0);
+        assert_eq!(normalize_distance(5.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn test_resource_location_within() {
+        let loc = ResourceLocation::new(3.0, 4.0, 5.0);
+        assert!(loc.within(5.0));
+        assert!(!loc.within(4.9));
+    }
+
     #[test]
     fn test_get_nearby_agents() {
         let mut world = MockWorldQuery::new();
@@ -361,6 +642,23 @@ mod tests {
         assert!(!world.can_interact(agent1, agent3));
     }
 
+    #[test]
+    fn test_get_nearby_agents_filtered_default() {
+        let mut world = MockWorldQuery::new();
+        world.add_agent(AgentId(0), 0.0, 0.0);
+        world.add_agent(AgentId(1), 1.0, 0.0);
+        world.add_agent(AgentId(2), 2.0, 0.0);
+        world.add_agent(AgentId(3), 3.0, 0.0);
+        world.add_agent(AgentId(4), 4.0, 0.0);
+
+        // Filter applies before max_count
+        let even = world.get_nearby_agents_filtered(AgentId(0), 1, &|id| id.0 % 2 == 0);
+        assert_eq!(even, vec![AgentId(2)]);
+
+        let even = world.get_nearby_agents_filtered(AgentId(0), 10, &|id| id.0 % 2 == 0);
+        assert_eq!(even, vec![AgentId(2), AgentId(4)]);
+    }
+
     #[test]
     fn test_get_nearby_agents_excludes_self() {
         let mut world = MockWorldQuery::new();
@@ -0,0 +1,210 @@
+// Species-aware neighbor queries
+//
+// `WorldQuery` only knows agent ids and positions; species lives in the ECS
+// as `SpeciesComponent`. These helpers join the two so predators can find
+// prey and flocks can find their own kind without the host resolving each
+// id's species.
+
+use super::WorldQuery;
+use crate::agent::AgentId;
+use crate::{Agent, Species, SpeciesComponent};
+use specs::prelude::*;
+use specs::storage::MaskedStorage;
+use std::collections::HashMap;
+
+/// Species of every agent that has a `SpeciesComponent`
+fn species_by_id(world: &World) -> HashMap<AgentId, Species> {
+    if !world.has_value::<MaskedStorage<Agent>>()
+        || !world.has_value::<MaskedStorage<SpeciesComponent>>()
+    {
+        return HashMap::new();
+    }
+    let agents = world.read_storage::<Agent>();
+    let species = world.read_storage::<SpeciesComponent>();
+    (&agents, &species)
+        .join()
+        .map(|(a, s)| (a.id, s.species))
+        .collect()
+}
+
+/// Nearby agents whose species is one of `species`, closest first
+///
+/// Agents without a `SpeciesComponent` never match.
+///
+/// # Example
+/// ```rust
+/// use libreconomy::*;
+/// use libreconomy::world_query::nearby_agents_of_species;
+/// use specs::prelude::*;
+///
+/// struct Position { x: f32, y: f32 }
+/// impl Component for Position { type Storage = VecStorage<Self>; }
+/// impl Positioned for Position {
+///     fn position(&self) -> (f32, f32) { (self.x, self.y) }
+/// }
+///
+/// let mut world = World::new();
+/// world.register::<Agent>();
+/// world.register::<ResourceSource>();
+/// world.register::<SpeciesComponent>();
+/// world.register::<Position>();
+///
+/// for (id, x, species) in [(1, 0.0, SpeciesComponent::human()),
+///                          (2, 1.0, SpeciesComponent::human()),
+///                          (3, 2.0, SpeciesComponent::rabbit())] {
+///     world.create_entity()
+///         .with(Agent { id: AgentId(id) })
+///         .with(Position { x, y: 0.0 })
+///         .with(species)
+///         .build();
+/// }
+///
+/// let query = EcsPositionQuery::<Position>::new(&world, 10.0);
+/// let rabbits = nearby_agents_of_species(&world, &query, AgentId(1), &[Species::Rabbit], 5);
+/// assert_eq!(rabbits, vec![AgentId(3)]);
+/// ```
+pub fn nearby_agents_of_species(
+    world: &World,
+    world_query: &dyn WorldQuery,
+    agent: AgentId,
+    species: &[Species],
+    max_count: usize,
+) -> Vec<AgentId> {
+    let known = species_by_id(world);
+    world_query.get_nearby_agents_filtered(agent, max_count, &|id| {
+        known.get(&id).is_some_and(|s| species.contains(s))
+    })
+}
+
+/// Closest nearby agent of the given species
+pub fn nearest_agent_of_species(
+    world: &World,
+    world_query: &dyn WorldQuery,
+    agent: AgentId,
+    species: Species,
+) -> Option<AgentId> {
+    nearby_agents_of_species(world, world_query, agent, &[species], 1)
+        .into_iter()
+        .next()
+}
+
+/// Closest nearby agent the given agent's diet allows it to hunt
+///
+/// Returns None if the agent has no `SpeciesComponent` or its diet has no
+/// prey.
+pub fn nearest_prey(
+    world: &World,
+    world_query: &dyn WorldQuery,
+    agent: AgentId,
+) -> Option<AgentId> {
+    if !world.has_value::<MaskedStorage<Agent>>()
+        || !world.has_value::<MaskedStorage<SpeciesComponent>>()
+    {
+        return None;
+    }
+    let diet = {
+        let agents = world.read_storage::<Agent>();
+        let species = world.read_storage::<SpeciesComponent>();
+        (&agents, &species)
+            .join()
+            .find(|(a, _)| a.id == agent)
+            .map(|(_, s)| s.diet.clone())?
+    };
+
+    let known = species_by_id(world);
+    world_query
+        .get_nearby_agents_filtered(agent, 1, &|id| {
+            known.get(&id).is_some_and(|s| diet.can_hunt(*s))
+        })
+        .into_iter()
+        .next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world_query::{EcsPositionQuery, Positioned};
+    use crate::ResourceSource;
+    use pretty_assertions::assert_eq;
+
+    struct Position {
+        x: f32,
+        y: f32,
+    }
+
+    impl Component for Position {
+        type Storage = VecStorage<Self>;
+    }
+
+    impl Positioned for Position {
+        fn position(&self) -> (f32, f32) {
+            (self.x, self.y)
+        }
+    }
+
+    fn create_test_world() -> World {
+        let mut world = World::new();
+        world.register::<Agent>();
+        world.register::<ResourceSource>();
+        world.register::<SpeciesComponent>();
+        world.register::<Position>();
+        world
+    }
+
+    fn add_agent(world: &mut World, id: u64, x: f32, species: Option<SpeciesComponent>) {
+        let mut builder = world
+            .create_entity()
+            .with(Agent { id: AgentId(id) })
+            .with(Position { x, y: 0.0 });
+        if let Some(species) = species {
+            builder = builder.with(species);
+        }
+        builder.build();
+    }
+
+    /// Human predator at 0 with humans, rabbits and an untyped agent around
+    fn mixed_group() -> World {
+        let mut world = create_test_world();
+        add_agent(&mut world, 1, 0.0, Some(SpeciesComponent::human()));
+        add_agent(&mut world, 2, 1.0, Some(SpeciesComponent::human()));
+        add_agent(&mut world, 3, 2.0, None);
+        add_agent(&mut world, 4, 8.0, Some(SpeciesComponent::rabbit()));
+        add_agent(&mut world, 5, 4.0, Some(SpeciesComponent::rabbit()));
+        world
+    }
+
+    #[test]
+    fn test_predator_finds_nearest_prey() {
+        let world = mixed_group();
+        let query = EcsPositionQuery::<Position>::new(&world, 10.0);
+
+        assert_eq!(nearest_prey(&world, &query, AgentId(1)), Some(AgentId(5)));
+        assert_eq!(
+            nearest_agent_of_species(&world, &query, AgentId(1), Species::Rabbit),
+            Some(AgentId(5))
+        );
+    }
+
+    #[test]
+    fn test_nearby_agents_of_species_sorted_and_limited() {
+        let world = mixed_group();
+        let query = EcsPositionQuery::<Position>::new(&world, 10.0);
+
+        let rabbits = nearby_agents_of_species(&world, &query, AgentId(1), &[Species::Rabbit], 10);
+        assert_eq!(rabbits, vec![AgentId(5), AgentId(4)]);
+
+        // Flocking: own species only, excluding self
+        let humans = nearby_agents_of_species(&world, &query, AgentId(1), &[Species::Human], 10);
+        assert_eq!(humans, vec![AgentId(2)]);
+    }
+
+    #[test]
+    fn test_herbivore_has_no_prey() {
+        let world = mixed_group();
+        let query = EcsPositionQuery::<Position>::new(&world, 10.0);
+
+        assert_eq!(nearest_prey(&world, &query, AgentId(5)), None);
+        // Untyped agent has no diet
+        assert_eq!(nearest_prey(&world, &query, AgentId(3)), None);
+    }
+}
@@ -0,0 +1,172 @@
+// Distance-free test mode
+//
+// Lets tests exercise economic behavior (need satisfaction, trading,
+// teaching) without building spatial glue: when the `TestMode` resource is
+// enabled, decision-making and action executors see every resource and agent
+// as in range.
+
+use super::{ResourceDetail, ResourceLocation, WorldQuery};
+use crate::agent::AgentId;
+use specs::prelude::*;
+
+/// World resource that switches off spatial constraints
+///
+/// Defaults to off; worlds without the resource behave normally. Intended for
+/// tests only.
+///
+/// # Example
+///
+/// ```rust
+/// use libreconomy::world_query::TestMode;
+/// use specs::prelude::*;
+///
+/// let mut world = World::new();
+/// assert!(!TestMode::is_enabled(&world));
+///
+/// world.insert(TestMode::enabled());
+/// assert!(TestMode::is_enabled(&world));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TestMode {
+    /// Whether spatial constraints are ignored
+    pub enabled: bool,
+}
+
+impl TestMode {
+    /// An enabled test mode
+    pub fn enabled() -> Self {
+        Self { enabled: true }
+    }
+
+    /// Whether the world has test mode switched on
+    pub fn is_enabled(world: &World) -> bool {
+        world.try_fetch::<TestMode>().is_some_and(|mode| mode.enabled)
+    }
+}
+
+/// `WorldQuery` adapter that places everything in range
+///
+/// Searches ignore their radius, every resource is reported at distance 0
+/// and travel cost 0, and any two agents can interact. Used internally when
+/// [`TestMode`] is enabled.
+pub struct InRangeQuery<'a> {
+    inner: &'a dyn WorldQuery,
+}
+
+impl<'a> InRangeQuery<'a> {
+    /// Wrap an application query
+    pub fn new(inner: &'a dyn WorldQuery) -> Self {
+        Self { inner }
+    }
+}
+
+impl WorldQuery for InRangeQuery<'_> {
+    fn get_nearby_agents(&self, agent: AgentId, max_count: usize) -> Vec<AgentId> {
+        self.inner.get_nearby_agents(agent, max_count)
+    }
+
+    fn get_nearby_agents_within(
+        &self,
+        agent: AgentId,
+        max_count: usize,
+        _max_radius: f32,
+    ) -> Vec<AgentId> {
+        self.inner
+            .get_nearby_agents_within(agent, max_count, f32::INFINITY)
+    }
+
+    fn get_nearby_agents_filtered(
+        &self,
+        agent: AgentId,
+        max_count: usize,
+        predicate: &dyn Fn(AgentId) -> bool,
+    ) -> Vec<AgentId> {
+        self.inner
+            .get_nearby_agents_filtered(agent, max_count, predicate)
+    }
+
+    fn get_nearby_resources(
+        &self,
+        agent: AgentId,
+        resource_type: &str,
+        _max_radius: f32,
+    ) -> Vec<ResourceLocation> {
+        self.inner
+            .get_nearby_resources(agent, resource_type, f32::INFINITY)
+            .into_iter()
+            .map(|loc| ResourceLocation::new(loc.x, loc.y, 0.0))
+            .collect()
+    }
+
+    fn get_nearby_resources_detailed(
+        &self,
+        agent: AgentId,
+        resource_type: &str,
+        _max_radius: f32,
+    ) -> Vec<ResourceDetail> {
+        self.inner
+            .get_nearby_resources_detailed(agent, resource_type, f32::INFINITY)
+            .into_iter()
+            .map(|mut detail| {
+                detail.location.distance = 0.0;
+                detail
+            })
+            .collect()
+    }
+
+    fn travel_cost(&self, _agent: AgentId, _target_x: f32, _target_y: f32) -> Option<f32> {
+        Some(0.0)
+    }
+
+    fn can_interact(&self, _agent1: AgentId, _agent2: AgentId) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    struct FarWorldQuery;
+
+    impl WorldQuery for FarWorldQuery {
+        fn get_nearby_agents(&self, _agent: AgentId, _max_count: usize) -> Vec<AgentId> {
+            vec![AgentId(2)]
+        }
+
+        fn get_nearby_resources(
+            &self,
+            _agent: AgentId,
+            _resource_type: &str,
+            max_radius: f32,
+        ) -> Vec<ResourceLocation> {
+            let far = ResourceLocation::new(900.0, 0.0, 900.0);
+            if far.distance <= max_radius {
+                vec![far]
+            } else {
+                Vec::new()
+            }
+        }
+
+        fn can_interact(&self, _agent1: AgentId, _agent2: AgentId) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_default_is_off() {
+        let world = World::new();
+        assert!(!TestMode::default().enabled);
+        assert!(!TestMode::is_enabled(&world));
+    }
+
+    #[test]
+    fn test_in_range_query_ignores_distance() {
+        let query = InRangeQuery::new(&FarWorldQuery);
+
+        let water = query.get_nearby_resources(AgentId(1), "water", 10.0);
+        assert_eq!(water, vec![ResourceLocation::new(900.0, 0.0, 0.0)]);
+        assert!(query.can_interact(AgentId(1), AgentId(2)));
+    }
+}
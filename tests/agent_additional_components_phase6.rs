@@ -4,7 +4,7 @@ use pretty_assertions::assert_eq;
 use specs::prelude::*;
 
 use libreconomy::{
-    Agent, AgentIdAllocator,
+    Agent, AgentId, AgentIdAllocator,
     Skills, Knowledge, Employment, Preferences, UtilityFunctionType,
 };
 
@@ -65,15 +65,15 @@ fn employment_component_defaults_and_basic_usage() {
 
     // Assign values
     e.job_status = Some("employed".into());
-    e.employer = Some("Acme Inc".into());
-    e.employees.push("WorkerA".into());
-    e.employees.push("WorkerB".into());
+    e.employer = Some(AgentId(1));
+    e.employees.push(AgentId(2));
+    e.employees.push(AgentId(3));
 
     assert_eq!(e.job_status.as_deref(), Some("employed"));
-    assert_eq!(e.employer.as_deref(), Some("Acme Inc"));
+    assert_eq!(e.employer, Some(AgentId(1)));
     assert_eq!(e.employees.len(), 2);
-    assert_eq!(e.employees[0], "WorkerA");
-    assert_eq!(e.employees[1], "WorkerB");
+    assert_eq!(e.employees[0], AgentId(2));
+    assert_eq!(e.employees[1], AgentId(3));
 }
 
 #[test]
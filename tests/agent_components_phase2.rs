@@ -4,7 +4,7 @@ use libreconomy::agent::components::{Inventory, Needs, Wallet, MAX_NEEDS, MIN_NE
 
 #[test]
 fn needs_new_clamps_values() {
-    let n = Needs::new(-5.0, 150.0);
+    let n = Needs::new(-5.0, 150.0, 50.0);
     assert!(n.thirst >= MIN_NEEDS && n.thirst <= MAX_NEEDS);
     assert!(n.hunger >= MIN_NEEDS && n.hunger <= MAX_NEEDS);
     assert_eq!(n.thirst, MIN_NEEDS);
@@ -13,7 +13,7 @@ fn needs_new_clamps_values() {
 
 #[test]
 fn needs_clamp_in_place() {
-    let mut n = Needs { thirst: -1.0, hunger: 200.0 };
+    let mut n = Needs { thirst: -1.0, hunger: 200.0, tiredness: 50.0 };
     n.clamp();
     assert_eq!(n.thirst, MIN_NEEDS);
     assert_eq!(n.hunger, MAX_NEEDS);
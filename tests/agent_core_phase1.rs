@@ -13,7 +13,7 @@ fn phase5_register_and_query_agent_components() {
 
     // Create several agents
     let agent1 = libreconomy::create_agent(&mut world);
-    let agent2 = libreconomy::create_agent_with_needs(&mut world, Needs::new(80.0, 20.0));
+    let agent2 = libreconomy::create_agent_with_needs(&mut world, Needs::new(80.0, 20.0, 50.0));
     let agent3 = libreconomy::create_agent_with_wallet(&mut world, Wallet::new(500.0));
 
     // Query all Agent entities
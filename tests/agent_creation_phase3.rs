@@ -117,7 +117,7 @@ fn test_create_agent_with_custom_needs() {
     world.register::<Wallet>();
     world.insert(AgentIdAllocator::new());
 
-    let custom_needs = Needs::new(75.0, 25.0);
+    let custom_needs = Needs::new(75.0, 25.0, 50.0);
 
     // Act: Create agent with custom needs
     let entity = libreconomy::create_agent_with_needs(&mut world, custom_needs);
@@ -160,7 +160,7 @@ fn test_create_agent_fully_custom() {
     world.register::<Wallet>();
     world.insert(AgentIdAllocator::new());
 
-    let custom_needs = Needs::new(80.0, 30.0);
+    let custom_needs = Needs::new(80.0, 30.0, 50.0);
     let custom_wallet = Wallet::new(250.0);
     let mut custom_inventory = Inventory::default();
     custom_inventory.add("water", 5);
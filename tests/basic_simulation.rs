@@ -11,7 +11,7 @@ fn test_create_world_with_agents() {
     world.register::<Skills>();
 
     let agent = world.create_entity()
-        .with(Needs::new(0.5, 0.8))
+        .with(Needs::new(0.5, 0.8, 0.5))
         .with(Inventory::default())
         .with(Wallet::new(100.0))
         .with(Skills::default())
@@ -41,7 +41,7 @@ fn test_example_agent_creation_pattern() {
     // Create one Agent entity with the allocated id (use helpers)
     let e = world
         .create_entity()
-        .with(Needs::new(-1.0, 200.0))
+        .with(Needs::new(-1.0, 200.0, 50.0))
         .with(Inventory::default())
         .with(Wallet::new(-10.0))
         .with(Skills::default())
@@ -103,7 +103,7 @@ mod ecs_missing_components_tests {
     #[test]
     fn test_knowledge_component_creation() {
         // Should fail: Knowledge not implemented yet
-        let _k = Knowledge { known_prices: std::collections::HashMap::new(), trade_partners: vec![] };
+        let _k = Knowledge { known_prices: std::collections::HashMap::new(), trade_partners: vec![], partner_quotes: vec![], price_ticks: std::collections::HashMap::new(), partner_ticks: std::collections::HashMap::new() };
     }
 
     #[test]
@@ -120,14 +120,15 @@ mod ecs_missing_components_tests {
 
     #[test]
     fn test_need_decay_system() {
-        let mut needs = Needs { thirst: 0.2, hunger: 0.3 };
+        let mut needs = Needs { thirst: 0.2, hunger: 0.3, tiredness: 0.1 };
         NeedDecaySystem::tick(&mut needs);
         assert!(needs.thirst > 0.2 && needs.hunger > 0.3);
+        assert!(needs.tiredness > 0.1);
     }
 
     #[test]
     fn test_learning_system() {
-        let mut knowledge = Knowledge { known_prices: std::collections::HashMap::new(), trade_partners: vec![] };
+        let mut knowledge = Knowledge { known_prices: std::collections::HashMap::new(), trade_partners: vec![], partner_quotes: vec![], price_ticks: std::collections::HashMap::new(), partner_ticks: std::collections::HashMap::new() };
         LearningSystem::update(&mut knowledge, "water", 1.5);
         assert_eq!(knowledge.known_prices.get("water"), Some(&1.5));
     }
@@ -175,7 +176,7 @@ mod phase3_agent_creation_tests {
         let agent1 = create_agent(&mut world);
 
         // Create agent with custom needs (thirsty)
-        let agent2 = create_agent_with_needs(&mut world, Needs::new(90.0, 50.0));
+        let agent2 = create_agent_with_needs(&mut world, Needs::new(90.0, 50.0, 50.0));
 
         // Create agent with custom wallet (rich)
         let agent3 = create_agent_with_wallet(&mut world, Wallet::new(1000.0));
@@ -186,7 +187,7 @@ mod phase3_agent_creation_tests {
         inventory.add("food", 5);
         let agent4 = create_agent_custom(
             &mut world,
-            Needs::new(20.0, 30.0),
+            Needs::new(20.0, 30.0, 50.0),
             inventory,
             Wallet::new(500.0),
         );
@@ -20,7 +20,9 @@ const EXPECTED_API_EXPORTS: &[(&str, &str, &str)] = &[
     ("agent::creation", "create_agent_with_needs", "create_agent_with_needs"),
     ("agent::creation", "create_agent_with_wallet", "create_agent_with_wallet"),
     ("agent::creation", "create_agent_custom", "create_agent_full"),
+    ("agent::creation", "try_create_agent_custom", "create_agent_full"),
     ("agent::creation", "remove_agent", "remove_agent"),
+    ("agent::creation", "try_remove_agent", "remove_agent"),
     
     // World management (FFI-specific)
     ("ffi", "create_world", "create_world"),
@@ -28,6 +30,12 @@ const EXPECTED_API_EXPORTS: &[(&str, &str, &str)] = &[
     
     // Utility functions
     ("ffi", "get_agent_count", "get_agent_count"),
+
+    // Simulation clock
+    ("ffi", "get_current_tick", "get_current_tick"),
+    ("ffi", "set_current_tick", "set_current_tick"),
+    ("ffi", "advance_tick", "advance_tick"),
+    ("ffi", "simulate_ticks", "simulate_ticks"),
 ];
 
 /// Core FFI functions that must always be present
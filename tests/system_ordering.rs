@@ -0,0 +1,110 @@
+//! System ordering tests
+//! SimulationBuilder wires crate systems so one dispatch applies them in the intended order
+
+use pretty_assertions::assert_eq;
+use specs::prelude::*;
+
+use libreconomy::*;
+
+fn setup(world: &mut World, dispatcher: &mut Dispatcher) -> (Entity, Entity) {
+    dispatcher.setup(world);
+    world.register::<Agent>();
+    world.register::<Needs>();
+    world.register::<Inventory>();
+    world.register::<Wallet>();
+    world.register::<ReputationKnowledge>();
+    world.register::<NeedDecayRates>();
+    world.insert(AgentIdAllocator::new());
+
+    let a = create_agent(world);
+    let b = create_agent(world);
+    {
+        let mut knowledge = world.write_storage::<ReputationKnowledge>();
+        knowledge.insert(a, ReputationKnowledge::new()).unwrap();
+        knowledge.insert(b, ReputationKnowledge::new()).unwrap();
+    }
+    (a, b)
+}
+
+fn agent_id(world: &World, entity: Entity) -> AgentId {
+    world.read_storage::<Agent>().get(entity).unwrap().id
+}
+
+#[test]
+fn test_same_tick_transaction_is_applied_before_decay() {
+    let mut world = World::new();
+    let mut dispatcher = SimulationBuilder::new()
+        .with_reputation(ReputationUpdateSystem::default())
+        .build();
+    let (a, b) = setup(&mut world, &mut dispatcher);
+    let (id_a, id_b) = (agent_id(&world, a), agent_id(&world, b));
+
+    // Rebalance any view holding more than 10 units of evidence
+    world.insert(ReputationDecayConfig {
+        rebalance_evidence_threshold: 10.0,
+        ..Default::default()
+    });
+    world.insert(CurrentTick(5));
+    world
+        .write_resource::<TransactionLog>()
+        .add(TransactionEvent::positive_interaction(id_a, id_b, 20.0, 5));
+
+    dispatcher.dispatch(&world);
+
+    // Update ran first (alpha 21, beta 1), then decay normalized it to 10
+    let knowledge = world.read_storage::<ReputationKnowledge>();
+    let view = &knowledge.get(a).unwrap().first_hand[&id_b];
+    assert!((view.alpha + view.beta - 10.0).abs() < 1e-4);
+    assert!(view.score() > 0.9);
+}
+
+/// Records the thirst it sees, standing in for a decision system
+struct ObserveThirst(std::sync::Arc<std::sync::Mutex<Vec<f32>>>);
+
+impl<'a> System<'a> for ObserveThirst {
+    type SystemData = ReadStorage<'a, Needs>;
+
+    fn run(&mut self, needs: Self::SystemData) {
+        let mut seen = self.0.lock().unwrap();
+        seen.extend(needs.join().map(|n| n.thirst));
+    }
+}
+
+#[test]
+fn test_need_decay_runs_before_decision_systems() {
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let mut world = World::new();
+    let mut dispatcher = SimulationBuilder::new()
+        .with_need_decay()
+        .with_reputation(ReputationUpdateSystem::default())
+        .with_decision_system(ObserveThirst(seen.clone()), "observe_thirst")
+        .build();
+    let (a, _) = setup(&mut world, &mut dispatcher);
+
+    let start = world.read_storage::<Needs>().get(a).unwrap().thirst;
+    dispatcher.dispatch(&world);
+
+    let expected = start + NeedDecaySystem::BASE_RATE;
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 2);
+    assert!(seen.iter().all(|t| (t - expected).abs() < 1e-5));
+}
+
+#[test]
+fn test_need_decay_system_applies_rates() {
+    let mut world = World::new();
+    let mut dispatcher = SimulationBuilder::new().with_need_decay().build();
+    let (a, b) = setup(&mut world, &mut dispatcher);
+    world
+        .write_storage::<NeedDecayRates>()
+        .insert(b, NeedDecayRates::new(1.0, 3.0, 1.0))
+        .unwrap();
+
+    dispatcher.dispatch(&world);
+
+    let needs = world.read_storage::<Needs>();
+    let hunger_a = needs.get(a).unwrap().hunger;
+    let hunger_b = needs.get(b).unwrap().hunger;
+    assert!((hunger_b - hunger_a - 2.0 * NeedDecaySystem::BASE_RATE).abs() < 1e-5);
+}
@@ -0,0 +1,87 @@
+//! Tiredness decay tests
+//! A default agent grows tired over time and eventually chooses to rest
+
+use pretty_assertions::assert_eq;
+use specs::prelude::*;
+
+use libreconomy::agent::components::NeedDecaySystem;
+use libreconomy::agent::creation::DEFAULT_TIREDNESS;
+use libreconomy::world_query::{ResourceLocation, WorldQuery};
+use libreconomy::*;
+
+/// World with no resources and no other agents
+struct EmptyWorldQuery;
+
+impl WorldQuery for EmptyWorldQuery {
+    fn get_nearby_agents(&self, _agent: AgentId, _max_count: usize) -> Vec<AgentId> {
+        Vec::new()
+    }
+
+    fn get_nearby_resources(
+        &self,
+        _agent: AgentId,
+        _resource_type: &str,
+        _max_radius: f32,
+    ) -> Vec<ResourceLocation> {
+        Vec::new()
+    }
+
+    fn can_interact(&self, _agent1: AgentId, _agent2: AgentId) -> bool {
+        false
+    }
+}
+
+fn setup_world() -> World {
+    let mut world = World::new();
+    world.register::<Agent>();
+    world.register::<Needs>();
+    world.register::<Inventory>();
+    world.register::<Wallet>();
+    world.register::<EnergyComponent>();
+    world.register::<SpeciesComponent>();
+    world.insert(AgentIdAllocator::new());
+    world
+}
+
+#[test]
+fn test_default_agent_starts_with_default_tiredness() {
+    let mut world = setup_world();
+    let agent = create_agent(&mut world);
+
+    let needs = world.read_storage::<Needs>();
+    assert_eq!(needs.get(agent).unwrap().tiredness, DEFAULT_TIREDNESS);
+}
+
+#[test]
+fn test_default_agent_eventually_rests() {
+    let mut world = setup_world();
+    let agent = create_agent(&mut world);
+
+    let dm = UtilityMaximizer::default();
+    let world_query = EmptyWorldQuery;
+
+    let mut rested_at = None;
+    for tick in 0..5000 {
+        {
+            let mut needs_storage = world.write_storage::<Needs>();
+            let needs = needs_storage.get_mut(agent).unwrap();
+            NeedDecaySystem::tick(needs);
+            // The agent keeps eating and drinking; only tiredness accumulates
+            needs.thirst = 20.0;
+            needs.hunger = 20.0;
+        }
+
+        if dm.preview_decision(agent, &world, &world_query)
+            == DecisionOutput::Intent(Intent::Rest)
+        {
+            rested_at = Some(tick);
+            break;
+        }
+    }
+
+    let tick = rested_at.expect("agent never chose to rest");
+    assert!(tick > 0, "agent should not start out tired");
+
+    let needs = world.read_storage::<Needs>();
+    assert!(needs.get(agent).unwrap().tiredness > dm.thresholds.high_tiredness);
+}